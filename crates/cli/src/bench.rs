@@ -0,0 +1,314 @@
+//! Repeatable indexing/search benchmark harness.
+//!
+//! A [`Workload`] is a small JSON file listing tasks (`{"op":"index","path":"."}`,
+//! `{"op":"search","query":"hello","limit":5}`), optionally with a `repeat` count and a `seed`
+//! for a deterministic shuffle. [`run_workload`] executes each task through the same
+//! [`command::execute`] path as `context-finder command --json`, records its wall-clock
+//! duration, and folds the per-task durations into a [`BenchStats`] report (min/max/mean plus
+//! p50/p90/p95/p99 per op, and overall throughput) so contributors can diff runs for
+//! indexing/search regressions.
+
+use anyhow::{Context as AnyhowContext, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::cache::CacheConfig;
+use crate::command::{
+    self, CommandAction, CommandRequest, IndexPayload, ListSymbolsPayload, SearchPayload,
+    SymbolsOutput,
+};
+
+/// A single task in a [`Workload`]. Tagged by `op` so workload JSON stays readable.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BenchTask {
+    Index {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        path: Option<PathBuf>,
+    },
+    Search {
+        query: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        limit: Option<usize>,
+    },
+}
+
+impl BenchTask {
+    fn op_name(&self) -> &'static str {
+        match self {
+            BenchTask::Index { .. } => "index",
+            BenchTask::Search { .. } => "search",
+        }
+    }
+}
+
+/// On-disk workload: a task list plus optional repeat count and shuffle seed, so a fixed corpus
+/// can be replayed deterministically across runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workload {
+    pub tasks: Vec<BenchTask>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repeat: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+pub fn load_workload(path: &Path) -> Result<Workload> {
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read workload file {}", path.display()))?;
+    serde_json::from_str(&raw).context("Invalid workload JSON")
+}
+
+pub fn write_workload(path: &Path, workload: &Workload) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(workload)?)
+        .with_context(|| format!("Failed to write workload file {}", path.display()))
+}
+
+/// Splitmix64: this workspace has no `rand` dependency, and a fixed, seedable generator is
+/// exactly what a reproducible shuffle needs.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        if bound == 0 {
+            0
+        } else {
+            (self.next_u64() % bound as u64) as usize
+        }
+    }
+}
+
+/// Expands `repeat` into repeated copies of the task list, then Fisher-Yates shuffles the result
+/// with `seed` (if set) so a fixed workload still replays in a deterministic-but-mixed order.
+fn expand_tasks(workload: &Workload) -> Vec<BenchTask> {
+    let repeat = workload.repeat.unwrap_or(1).max(1);
+    let mut tasks: Vec<BenchTask> = Vec::with_capacity(workload.tasks.len() * repeat as usize);
+    for _ in 0..repeat {
+        tasks.extend(workload.tasks.iter().cloned());
+    }
+    if let Some(seed) = workload.seed {
+        let mut rng = SplitMix64(seed);
+        for i in (1..tasks.len()).rev() {
+            let j = rng.below(i + 1);
+            tasks.swap(i, j);
+        }
+    }
+    tasks
+}
+
+/// Min/max/mean plus latency percentiles for every task recorded against a single op.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OpStats {
+    pub count: usize,
+    pub min_ms: f64,
+    pub max_ms: f64,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+}
+
+/// Benchmark report for one workload run, mirroring `context_indexer::IndexStats`'s shape (a
+/// single flat serde struct per run) so successive runs can be diffed directly as JSON.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BenchStats {
+    pub tasks: usize,
+    pub total_ms: u64,
+    pub throughput_tasks_per_sec: f64,
+    pub by_op: HashMap<String, OpStats>,
+    pub errors: Vec<String>,
+}
+
+fn percentile_ms(sorted_ms: &[f64], pct: f64) -> f64 {
+    if sorted_ms.is_empty() {
+        return 0.0;
+    }
+    let n = sorted_ms.len();
+    let idx = ((pct / 100.0) * n as f64).ceil() as isize - 1;
+    let idx = idx.clamp(0, n as isize - 1) as usize;
+    sorted_ms[idx]
+}
+
+fn summarize_op(durations: Vec<Duration>) -> OpStats {
+    if durations.is_empty() {
+        return OpStats::default();
+    }
+    let mut ms: Vec<f64> = durations
+        .into_iter()
+        .map(|d| d.as_secs_f64() * 1000.0)
+        .collect();
+    ms.sort_by(|a, b| a.partial_cmp(b).expect("durations are never NaN"));
+    let count = ms.len();
+    let sum: f64 = ms.iter().sum();
+    OpStats {
+        count,
+        min_ms: ms[0],
+        max_ms: ms[count - 1],
+        mean_ms: sum / count as f64,
+        p50_ms: percentile_ms(&ms, 50.0),
+        p90_ms: percentile_ms(&ms, 90.0),
+        p95_ms: percentile_ms(&ms, 95.0),
+        p99_ms: percentile_ms(&ms, 99.0),
+    }
+}
+
+fn build_request(task: &BenchTask, root: &Path) -> Result<CommandRequest> {
+    let (action, payload) = match task {
+        BenchTask::Index { path } => (
+            CommandAction::Index,
+            serde_json::to_value(IndexPayload {
+                path: Some(path.clone().unwrap_or_else(|| root.to_path_buf())),
+                full: false,
+                models: Vec::new(),
+                experts: false,
+            })?,
+        ),
+        BenchTask::Search { query, limit } => (
+            CommandAction::Search,
+            serde_json::to_value(SearchPayload {
+                query: query.clone(),
+                limit: *limit,
+                project: Some(root.to_path_buf()),
+                trace: None,
+            })?,
+        ),
+    };
+    Ok(CommandRequest {
+        action,
+        payload,
+        options: None,
+        config: None,
+    })
+}
+
+/// Runs every task in `workload` (after `repeat`/`seed` expansion) against `root`, recording the
+/// wall-clock duration of each task into a `Vec<Duration>` per op. `memory_load_mb`, if set,
+/// pre-allocates and touches a buffer of that size before the run starts, to simulate memory
+/// pressure alongside the workload for the duration of the run.
+pub async fn run_workload(
+    root: &Path,
+    workload: &Workload,
+    cache_cfg: CacheConfig,
+    memory_load_mb: Option<usize>,
+) -> Result<BenchStats> {
+    let _memory_load = memory_load_mb.map(|mb| {
+        let mut buf = vec![0u8; mb * 1024 * 1024];
+        for chunk in buf.chunks_mut(4096) {
+            chunk[0] = 1;
+        }
+        buf
+    });
+
+    let tasks = expand_tasks(workload);
+    let mut durations_by_op: HashMap<&'static str, Vec<Duration>> = HashMap::new();
+    let mut errors = Vec::new();
+    let run_started = Instant::now();
+
+    for task in &tasks {
+        let request = build_request(task, root)?;
+        let task_started = Instant::now();
+        let response = command::execute(request, cache_cfg.clone()).await;
+        let elapsed = task_started.elapsed();
+
+        if response.is_error() {
+            errors.push(
+                response
+                    .message
+                    .unwrap_or_else(|| "unknown bench task error".to_string()),
+            );
+        }
+        durations_by_op
+            .entry(task.op_name())
+            .or_default()
+            .push(elapsed);
+    }
+
+    let total = run_started.elapsed();
+    let task_count = tasks.len();
+    let by_op = durations_by_op
+        .into_iter()
+        .map(|(op, durations)| (op.to_string(), summarize_op(durations)))
+        .collect();
+
+    Ok(BenchStats {
+        tasks: task_count,
+        total_ms: total.as_millis() as u64,
+        throughput_tasks_per_sec: if total.as_secs_f64() > 0.0 {
+            task_count as f64 / total.as_secs_f64()
+        } else {
+            0.0
+        },
+        by_op,
+        errors,
+    })
+}
+
+/// Generates a replayable workload by sampling indexed symbol names as search queries (cheap,
+/// and tied to whatever the project actually contains), plus an optional leading `index` task.
+/// Falls back to a single generic query if the project has no symbols indexed yet.
+pub async fn generate_workload(
+    root: &Path,
+    cache_cfg: CacheConfig,
+    search_task_count: usize,
+    with_index: bool,
+    repeat: Option<u32>,
+    seed: Option<u64>,
+) -> Result<Workload> {
+    let request = CommandRequest {
+        action: CommandAction::ListSymbols,
+        payload: serde_json::to_value(ListSymbolsPayload {
+            file: "*".to_string(),
+            project: Some(root.to_path_buf()),
+        })?,
+        options: None,
+        config: None,
+    };
+    let response = command::execute(request, cache_cfg).await;
+    let symbols: Vec<String> = if response.is_error() {
+        Vec::new()
+    } else {
+        serde_json::from_value::<SymbolsOutput>(response.data)
+            .map(|out| out.symbols.into_iter().map(|s| s.name).collect())
+            .unwrap_or_default()
+    };
+
+    let mut tasks = Vec::new();
+    if with_index {
+        tasks.push(BenchTask::Index {
+            path: Some(root.to_path_buf()),
+        });
+    }
+    if symbols.is_empty() {
+        tasks.push(BenchTask::Search {
+            query: "main".to_string(),
+            limit: Some(5),
+        });
+    } else {
+        for name in symbols.into_iter().take(search_task_count) {
+            tasks.push(BenchTask::Search {
+                query: name,
+                limit: Some(5),
+            });
+        }
+    }
+
+    Ok(Workload {
+        tasks,
+        repeat,
+        seed,
+    })
+}
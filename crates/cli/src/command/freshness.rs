@@ -2,12 +2,16 @@ use crate::command::context::{index_path, load_store_mtime, unix_ms};
 use crate::command::domain::{Hint, HintKind, RequestOptions, StalePolicy};
 use anyhow::Result;
 use context_indexer::{
-    assess_staleness, compute_project_watermark, read_index_watermark, IndexSnapshot, IndexState,
-    IndexerError, PersistedIndexWatermark, ProjectIndexer, ReindexAttempt, ReindexResult,
-    StaleReason, Watermark, INDEX_STATE_SCHEMA_VERSION,
+    assess_staleness, changed_paths_since, compute_fs_versions, compute_project_watermark,
+    diff_fs_versions, read_fs_versions, read_index_watermark, write_fs_versions, FsVersions,
+    IndexSnapshot, IndexState, IndexerError, PersistedIndexWatermark, ProjectIndexer,
+    ReindexAttempt, ReindexResult, StaleReason, Watermark, INDEX_STATE_SCHEMA_VERSION,
 };
 use context_search::SearchProfile;
-use context_vector_store::current_model_id;
+use context_vector_store::{
+    corpus_path_for_project_root, current_model_id, ChunkCorpus, CodeChunk,
+};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
@@ -86,6 +90,7 @@ async fn gather_index_state_with_project_mark(
         Ok(Some(PersistedIndexWatermark {
             built_at_unix_ms: built_at,
             watermark: mark,
+            ..
         })) => {
             built_at_unix_ms = Some(built_at);
             watermark = Some(mark);
@@ -141,7 +146,13 @@ pub async fn enforce_stale_policy(
     match options.stale_policy {
         StalePolicy::Auto => {
             if gate.index_state.stale || !gate.index_state.index.exists {
-                let attempt = attempt_reindex(project_root, profile, options.max_reindex_ms).await;
+                let attempt = attempt_reindex(
+                    project_root,
+                    profile,
+                    options.max_reindex_ms,
+                    &gate.index_state.stale_reasons,
+                )
+                .await;
                 gate.hints.push(render_reindex_hint(&attempt));
                 gate.index_updated |= attempt.performed;
 
@@ -211,10 +222,181 @@ pub async fn enforce_stale_policy(
     Ok(Ok(gate))
 }
 
+/// Delta sets larger than this are treated the same as "no usable delta" — past this size the
+/// bookkeeping overhead of a path-scoped re-embed isn't worth it over just rebuilding.
+const MAX_INCREMENTAL_DELTA_FILES: usize = 2_000;
+
+/// Staleness reasons that mean "the tree moved under an otherwise-trustworthy index" — eligible
+/// for a delta-scoped reindex. Anything implying the index or its bookkeeping itself can't be
+/// trusted (missing/corrupt index, missing watermark) always forces a full rebuild, and so does
+/// `IndexMissing` (there's nothing to apply a delta on top of).
+fn incremental_eligible(stale_reasons: &[StaleReason]) -> bool {
+    !stale_reasons.is_empty()
+        && stale_reasons.iter().all(|reason| {
+            matches!(
+                reason,
+                StaleReason::GitHeadMismatch
+                    | StaleReason::GitDirtyMismatch
+                    | StaleReason::FilesystemChanged
+            )
+        })
+}
+
+/// Computes the changed-path delta to re-embed, or `None` to signal "fall back to a full
+/// rebuild" — either because `stale_reasons` isn't delta-eligible, there's no prior watermark to
+/// diff against, or the delta exceeded [`MAX_INCREMENTAL_DELTA_FILES`].
+async fn incremental_delta(
+    project_root: &Path,
+    store_path: &Path,
+    stale_reasons: &[StaleReason],
+) -> Option<Vec<PathBuf>> {
+    if !incremental_eligible(stale_reasons) {
+        return None;
+    }
+    let prior = read_index_watermark(store_path).await.ok().flatten()?;
+    let current = compute_project_watermark(project_root).await.ok()?;
+    changed_paths_since(project_root, &prior, &current, MAX_INCREMENTAL_DELTA_FILES).await
+}
+
+/// Content-hash-scoped subset of an `incremental_delta` candidate list: which of the candidate
+/// paths actually changed bytes since the last reindex (`changed`, relative to `project_root`),
+/// which were only touched (`skipped`), and which no longer exist (`deleted`) -- plus the
+/// refreshed [`FsVersions`] to persist once the reindex using `changed` has completed.
+struct ReembedPlan {
+    changed: Vec<String>,
+    skipped: Vec<String>,
+    deleted: Vec<String>,
+    next_versions: FsVersions,
+}
+
+/// Narrows `candidate_paths` (already-git/snapshot-scoped) down to the files whose content hash
+/// actually changed, per the `fs_versions.json` sidecar from the last reindex. A candidate that
+/// can no longer be read (deleted since the delta was computed) never shows up in `current`, so
+/// it's reported as `deleted` rather than `changed`.
+async fn plan_reembed(project_root: &Path, candidate_paths: &[PathBuf]) -> ReembedPlan {
+    let prior = read_fs_versions(project_root).await.unwrap_or_default();
+    let current = compute_fs_versions(project_root, candidate_paths).await;
+    let file_diff = diff_fs_versions(&prior, &current);
+
+    let candidate_relative: HashSet<String> = candidate_paths
+        .iter()
+        .map(|path| {
+            path.strip_prefix(project_root)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .replace('\\', "/")
+        })
+        .collect();
+    let deleted: Vec<String> = candidate_relative
+        .iter()
+        .filter(|path| !current.files.contains_key(*path))
+        .cloned()
+        .collect();
+
+    let mut next_versions = prior;
+    for (path, version) in &current.files {
+        if file_diff.changed.contains(path) {
+            next_versions.files.insert(path.clone(), *version);
+        }
+    }
+    for path in &deleted {
+        next_versions.files.remove(path);
+    }
+
+    ReembedPlan {
+        changed: file_diff.changed,
+        skipped: file_diff.unchanged,
+        deleted,
+        next_versions,
+    }
+}
+
+/// Stable identity for a chunk within a file: its qualified name/symbol when the chunker found
+/// one, falling back to its line range for chunks without a resolvable symbol (e.g. prose docs).
+/// Used to avoid double-counting a chunk as both "removed" and "added" when re-embedding a file
+/// only actually changed a handful of its chunks.
+fn chunk_identity(chunk: &CodeChunk) -> String {
+    match chunk
+        .metadata
+        .qualified_name
+        .as_deref()
+        .or(chunk.metadata.symbol_name.as_deref())
+    {
+        Some(name) if !name.is_empty() => name.to_string(),
+        _ => format!("{}:{}", chunk.start_line, chunk.end_line),
+    }
+}
+
+/// Diffs the chunk sets of `before`/`after` for one file (keyed by [`chunk_identity`]) and
+/// returns `(added, removed)` counts -- a chunk whose identity survives the edit (content may
+/// differ, but the symbol is the same) counts as neither.
+fn diff_file_chunks(before: Option<&[CodeChunk]>, after: Option<&[CodeChunk]>) -> (usize, usize) {
+    let before_ids: HashSet<String> = before.unwrap_or(&[]).iter().map(chunk_identity).collect();
+    let after_ids: HashSet<String> = after.unwrap_or(&[]).iter().map(chunk_identity).collect();
+    let added = after_ids.difference(&before_ids).count();
+    let removed = before_ids.difference(&after_ids).count();
+    (added, removed)
+}
+
+/// Drops corpus chunks for files that were deleted since the last reindex, and reports how many
+/// chunks that removed in total. Best-effort: a missing/unreadable corpus just reports zero.
+async fn reconcile_deleted_files(project_root: &Path, removed_relative_paths: &[String]) -> usize {
+    if removed_relative_paths.is_empty() {
+        return 0;
+    }
+    let corpus_path = corpus_path_for_project_root(project_root);
+    let Ok(mut corpus) = ChunkCorpus::load(&corpus_path).await else {
+        return 0;
+    };
+
+    let mut chunks_removed = 0usize;
+    for path in removed_relative_paths {
+        if let Some(chunks) = corpus.files().get(path) {
+            chunks_removed += chunks.len();
+        }
+        corpus.remove_file(path);
+    }
+
+    if chunks_removed > 0 {
+        let _ = corpus.save(&corpus_path).await;
+    }
+    chunks_removed
+}
+
 pub async fn attempt_reindex(
     project_root: &Path,
     profile: &SearchProfile,
     max_reindex_ms: u64,
+    stale_reasons: &[StaleReason],
+) -> ReindexAttempt {
+    run_reindex(project_root, profile, max_reindex_ms, None, stale_reasons).await
+}
+
+/// Re-embeds an explicit set of already-known changed paths (e.g. ones a filesystem watcher just
+/// observed) instead of deriving the delta from `stale_reasons`/the git-or-snapshot watermark --
+/// the caller already knows which files moved, so `incremental_delta` is skipped entirely.
+pub(crate) async fn reindex_changed_paths(
+    project_root: &Path,
+    profile: &SearchProfile,
+    max_reindex_ms: u64,
+    changed_paths: Vec<PathBuf>,
+) -> ReindexAttempt {
+    run_reindex(
+        project_root,
+        profile,
+        max_reindex_ms,
+        Some(changed_paths),
+        &[],
+    )
+    .await
+}
+
+async fn run_reindex(
+    project_root: &Path,
+    profile: &SearchProfile,
+    max_reindex_ms: u64,
+    explicit_delta: Option<Vec<PathBuf>>,
+    stale_reasons: &[StaleReason],
 ) -> ReindexAttempt {
     let start = Instant::now();
     let budget = Duration::from_millis(max_reindex_ms);
@@ -226,6 +408,12 @@ pub async fn attempt_reindex(
         duration_ms: None,
         result: None,
         error: None,
+        incremental: false,
+        files_changed: None,
+        files_reembedded: None,
+        files_skipped: None,
+        chunks_added: None,
+        chunks_removed: None,
     };
 
     let templates = profile.embedding().clone();
@@ -240,7 +428,41 @@ pub async fn attempt_reindex(
         }
     };
 
-    match indexer.index_with_budget(budget).await {
+    let delta = match explicit_delta {
+        Some(paths) => Some(paths),
+        None => incremental_delta(project_root, indexer.store_path(), stale_reasons).await,
+    };
+    let plan = match &delta {
+        Some(paths) => Some(plan_reembed(project_root, paths).await),
+        None => None,
+    };
+
+    let corpus_path = corpus_path_for_project_root(project_root);
+    let chunks_before = ChunkCorpus::load(&corpus_path).await.ok();
+
+    let outcome = match (&delta, &plan) {
+        (Some(paths), Some(plan)) => {
+            attempt.incremental = true;
+            attempt.files_changed = Some(paths.len());
+            attempt.files_skipped = Some(plan.skipped.len());
+            attempt.files_reembedded = Some(plan.changed.len());
+            if plan.changed.is_empty() {
+                Ok(())
+            } else {
+                let changed_paths: Vec<PathBuf> = plan
+                    .changed
+                    .iter()
+                    .map(|relative| project_root.join(relative))
+                    .collect();
+                indexer
+                    .index_paths_with_budget(&changed_paths, budget)
+                    .await
+            }
+        }
+        _ => indexer.index_with_budget(budget).await,
+    };
+
+    match outcome {
         Ok(_) => {
             attempt.performed = true;
             attempt.result = Some(ReindexResult::Ok);
@@ -254,6 +476,54 @@ pub async fn attempt_reindex(
         }
     }
 
+    if matches!(attempt.result, Some(ReindexResult::Ok)) {
+        let deleted = match &plan {
+            Some(plan) => {
+                let _ = write_fs_versions(project_root, &plan.next_versions).await;
+                plan.deleted.clone()
+            }
+            None => {
+                // Full rebuild: re-scan the whole tree so the next incremental reindex has a
+                // complete baseline to diff against.
+                let mut scanner = context_indexer::FileScanner::new(project_root);
+                if let Ok(files) = scanner.scan() {
+                    let versions = compute_fs_versions(project_root, &files).await;
+                    let _ = write_fs_versions(project_root, &versions).await;
+                }
+                Vec::new()
+            }
+        };
+
+        let deleted_chunks = reconcile_deleted_files(project_root, &deleted).await;
+
+        if let Some(before) = chunks_before {
+            if let Ok(after) = ChunkCorpus::load(&corpus_path).await {
+                let mut touched: Vec<String> = Vec::new();
+                touched.extend(before.files().keys().cloned());
+                touched.extend(after.files().keys().cloned());
+                touched.sort();
+                touched.dedup();
+
+                let mut added = 0usize;
+                let mut removed = deleted_chunks;
+                for file in &touched {
+                    // Already counted in `deleted_chunks`.
+                    if deleted.contains(file) {
+                        continue;
+                    }
+                    let (file_added, file_removed) = diff_file_chunks(
+                        before.files().get(file).map(Vec::as_slice),
+                        after.files().get(file).map(Vec::as_slice),
+                    );
+                    added += file_added;
+                    removed += file_removed;
+                }
+                attempt.chunks_added = Some(added);
+                attempt.chunks_removed = Some(removed);
+            }
+        }
+    }
+
     attempt.duration_ms = Some(start.elapsed().as_millis() as u64);
     attempt
 }
@@ -268,14 +538,29 @@ pub fn render_reindex_hint(attempt: &ReindexAttempt) -> Hint {
         .map(|v| format!("{v}ms"))
         .unwrap_or_else(|| "unknown".to_string());
 
+    let scope = match (attempt.incremental, attempt.files_changed) {
+        (true, Some(n)) => {
+            let reembed = match (attempt.files_reembedded, attempt.files_skipped) {
+                (Some(reembedded), Some(skipped)) if skipped > 0 => {
+                    format!(", {reembedded} re-embedded, {skipped} unchanged")
+                }
+                (Some(reembedded), _) => format!(", {reembedded} re-embedded"),
+                (None, _) => String::new(),
+            };
+            format!(" [incremental, {n} file(s){reembed}]")
+        }
+        (true, None) => " [incremental]".to_string(),
+        (false, _) => String::new(),
+    };
+
     match attempt.result {
         Some(ReindexResult::Ok) => Hint {
             kind: HintKind::Cache,
-            text: format!("Auto reindex OK in {duration} (budget {budget})"),
+            text: format!("Auto reindex OK in {duration} (budget {budget}){scope}"),
         },
         Some(ReindexResult::BudgetExceeded) => Hint {
             kind: HintKind::Warn,
-            text: format!("Auto reindex exceeded budget {budget} (ran {duration})"),
+            text: format!("Auto reindex exceeded budget {budget} (ran {duration}){scope}"),
         },
         Some(ReindexResult::Failed) => Hint {
             kind: HintKind::Warn,
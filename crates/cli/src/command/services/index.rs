@@ -29,7 +29,9 @@ impl IndexService {
         let payload: IndexPayload = parse_payload(payload)?;
         let project_ctx = ctx.resolve_project(payload.path).await?;
         let _ = crate::heartbeat::ping(&project_ctx.root).await;
-        let warm = warm::global_warmer().prewarm(&project_ctx.root).await;
+        let warm = warm::global_warmer()
+            .prewarm(&project_ctx.root, &project_ctx.profile)
+            .await;
         let templates = project_ctx.profile.embedding().clone();
 
         let primary_model_id = current_model_id().unwrap_or_else(|_| "bge-small".to_string());
@@ -42,7 +42,9 @@ impl MeaningService {
         let payload: MeaningPackPayload = parse_payload(payload)?;
         let project_ctx = ctx.resolve_project(payload.project).await?;
         let _ = crate::heartbeat::ping(&project_ctx.root).await;
-        let warm = warm::global_warmer().prewarm(&project_ctx.root).await;
+        let warm = warm::global_warmer()
+            .prewarm(&project_ctx.root, &project_ctx.profile)
+            .await;
 
         let max_chars = payload
             .max_chars
@@ -641,7 +643,9 @@ impl MeaningService {
         let payload: MeaningFocusPayload = parse_payload(payload)?;
         let project_ctx = ctx.resolve_project(payload.project).await?;
         let _ = crate::heartbeat::ping(&project_ctx.root).await;
-        let warm = warm::global_warmer().prewarm(&project_ctx.root).await;
+        let warm = warm::global_warmer()
+            .prewarm(&project_ctx.root, &project_ctx.profile)
+            .await;
 
         let max_chars = payload
             .max_chars
@@ -1251,7 +1255,9 @@ impl MeaningService {
         let payload: crate::command::domain::EvidenceFetchPayload = parse_payload(payload)?;
         let project_ctx = ctx.resolve_project(payload.project).await?;
         let _ = crate::heartbeat::ping(&project_ctx.root).await;
-        let warm = warm::global_warmer().prewarm(&project_ctx.root).await;
+        let warm = warm::global_warmer()
+            .prewarm(&project_ctx.root, &project_ctx.profile)
+            .await;
 
         let max_chars = payload
             .max_chars
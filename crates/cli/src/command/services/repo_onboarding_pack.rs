@@ -84,6 +84,7 @@ impl RepoOnboardingPackService {
                 &project_ctx.root,
                 &project_ctx.profile,
                 policy.budget_ms,
+                &index_state.stale_reasons,
             )
             .await;
             reindex_hints.push(freshness::render_reindex_hint(&attempt));
@@ -67,7 +67,9 @@ impl SearchService {
         }
         let project_ctx = ctx.resolve_project(payload.project).await?;
         let _ = crate::heartbeat::ping(&project_ctx.root).await;
-        let warm = warm::global_warmer().prewarm(&project_ctx.root).await;
+        let warm = warm::global_warmer()
+            .prewarm(&project_ctx.root, &project_ctx.profile)
+            .await;
         let (strategy_hint, _reason_hint) = choose_task_hint(&payload.query);
         let limit = payload
             .limit
@@ -179,7 +181,9 @@ impl SearchService {
             ));
         }
         let project_ctx = ctx.resolve_project(payload.project).await?;
-        let warm = warm::global_warmer().prewarm(&project_ctx.root).await;
+        let warm = warm::global_warmer()
+            .prewarm(&project_ctx.root, &project_ctx.profile)
+            .await;
         let (task_hint, reason_hint) = choose_task_hint(&payload.query);
         let limit = payload
             .limit
@@ -470,7 +474,9 @@ impl SearchService {
 
         let project_ctx = ctx.resolve_project(payload.project).await?;
         let request_options = ctx.request_options();
-        let warm = warm::global_warmer().prewarm(&project_ctx.root).await;
+        let warm = warm::global_warmer()
+            .prewarm(&project_ctx.root, &project_ctx.profile)
+            .await;
 
         let limit = payload
             .limit
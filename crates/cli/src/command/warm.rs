@@ -1,24 +1,68 @@
 use anyhow::Result;
+use notify::{
+    Config as NotifyConfig, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+};
 use once_cell::sync::OnceCell;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Instant;
-use tokio::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Mutex};
 
+use crate::command::context::unix_ms;
+use crate::command::freshness::{attempt_reindex, gather_index_state, reindex_changed_paths};
 use crate::graph_cache::GraphCache;
 use context_graph::GraphLanguage;
-use context_vector_store::{EmbeddingModel, VectorStore};
+use context_indexer::ReindexResult;
+use context_search::SearchProfile;
+use context_vector_store::{is_context_dir_name, EmbeddingModel, VectorStore};
+
+/// Reindex budget for the opportunistic content-hash-scoped reindex `run_warm` performs before
+/// loading the store -- generous enough to absorb a normal incremental delta, but short enough
+/// that a pathological repo still lets warmup finish and fall back to whatever's on disk.
+const WARM_REINDEX_BUDGET_MS: u64 = 3_000;
+
+/// How long `watch` waits for more filesystem events to arrive before acting on a batch -- keeps
+/// a save-triggered flurry of events (editor temp files, formatter rewrites) from turning into
+/// one reindex per event.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(400);
+
+/// Directory names a watched project tree never needs events from. Kept in sync with the
+/// indexer's own scan exclusions in spirit, not by sharing the list -- this only needs to be
+/// cheap enough to skip obviously-irrelevant churn (`.git`, build output, dependency caches).
+const WATCH_IGNORED_DIRS: &[&str] = &[
+    ".git",
+    "target",
+    "node_modules",
+    ".cache",
+    ".next",
+    "dist",
+    "build",
+];
 
 #[derive(Debug, Clone, Default)]
 pub struct WarmMeta {
     pub warmed: bool,
     pub warm_cost_ms: u64,
     pub graph_cache_hit: bool,
+    /// Set once `watch` has observed file changes that haven't been re-embedded yet, and cleared
+    /// again once the resulting incremental reindex completes. Lets callers report "the warm
+    /// cache may be slightly behind disk" without re-running `gather_index_state` themselves.
+    pub warm_stale: bool,
+    /// Unix millis of the last reindex `watch` triggered, `None` until the first one runs.
+    pub last_refresh_ms: Option<u64>,
 }
 
 #[derive(Clone, Default)]
 pub struct Warmer {
     inner: Arc<Mutex<Option<WarmMeta>>>,
+    watch: Arc<Mutex<Option<WatchGuard>>>,
+}
+
+/// Keeps the `notify` watcher alive for as long as `Warmer::watch` is active; dropping it stops
+/// the watch. Never read directly -- its only job is to not be dropped early.
+struct WatchGuard {
+    _watcher: RecommendedWatcher,
 }
 
 static GLOBAL_WARMER: OnceCell<Warmer> = OnceCell::new();
@@ -29,7 +73,7 @@ pub fn global_warmer() -> Warmer {
 
 impl Warmer {
     /// Start prewarm if not already done; returns warm meta (may be cached).
-    pub async fn prewarm(&self, project_root: &Path) -> WarmMeta {
+    pub async fn prewarm(&self, project_root: &Path, profile: &SearchProfile) -> WarmMeta {
         {
             let guard = self.inner.lock().await;
             if let Some(meta) = guard.as_ref() {
@@ -37,15 +81,39 @@ impl Warmer {
             }
         }
 
-        let meta = self.run_warm(project_root).await.unwrap_or_default();
-        let mut guard = self.inner.lock().await;
-        *guard = Some(meta.clone());
+        let meta = self
+            .run_warm(project_root, profile)
+            .await
+            .unwrap_or_default();
+        {
+            let mut guard = self.inner.lock().await;
+            *guard = Some(meta.clone());
+        }
+
+        if let Err(err) = self.watch(project_root, profile).await {
+            log::debug!("Warmer::watch failed to start: {err:#}");
+        }
+
         meta
     }
 
-    async fn run_warm(&self, project_root: &Path) -> Result<WarmMeta> {
+    async fn run_warm(&self, project_root: &Path, profile: &SearchProfile) -> Result<WarmMeta> {
         let started = Instant::now();
 
+        // Keep warm cost low on large repos: reuse the same content-hash-scoped incremental
+        // reindex the search path uses, rather than always loading whatever is on disk as-is.
+        if let Ok(state) = gather_index_state(project_root, profile.name()).await {
+            if state.stale && state.index.exists {
+                let _ = attempt_reindex(
+                    project_root,
+                    profile,
+                    WARM_REINDEX_BUDGET_MS,
+                    &state.stale_reasons,
+                )
+                .await;
+            }
+        }
+
         let index_path = crate::command::context::index_path(project_root);
         let store = VectorStore::load(&index_path).await?;
         let (chunks, chunk_index) = crate::command::services::collect_chunks(&store);
@@ -75,6 +143,141 @@ impl Warmer {
             warmed: true,
             warm_cost_ms: started.elapsed().as_millis() as u64,
             graph_cache_hit,
+            warm_stale: false,
+            last_refresh_ms: None,
         })
     }
+
+    /// Starts a background filesystem watch that keeps this `Warmer`'s cached `WarmMeta` (and,
+    /// transitively, the on-disk index/`GraphCache` that the next `prewarm` reads) from drifting
+    /// too far from what's actually on disk between requests. A no-op if `prewarm` hasn't run yet
+    /// (nothing to keep fresh) or if a watch is already active; disabled entirely by
+    /// `CONTEXT_FINDER_DISABLE_DAEMON`, matching the rest of the daemon/background-task surface.
+    pub async fn watch(&self, project_root: &Path, profile: &SearchProfile) -> Result<()> {
+        if daemon_disabled() {
+            return Ok(());
+        }
+        if self.inner.lock().await.is_none() {
+            return Ok(());
+        }
+
+        let mut watch_guard = self.watch.lock().await;
+        if watch_guard.is_some() {
+            return Ok(());
+        }
+
+        let (event_tx, mut event_rx) = mpsc::channel::<notify::Result<Event>>(256);
+        let mut watcher = RecommendedWatcher::new(
+            move |res| {
+                let _ = event_tx.blocking_send(res);
+            },
+            NotifyConfig::default(),
+        )?;
+        watcher.watch(project_root, RecursiveMode::Recursive)?;
+
+        let root = project_root.to_path_buf();
+        let profile = profile.clone();
+        let warm_meta = self.inner.clone();
+
+        tokio::spawn(async move {
+            // Per-file versions, LSP-document-store style: bumped once per observed change so a
+            // file's staleness can be reasoned about independently of the batch it arrived in.
+            let mut file_versions: HashMap<String, u64> = HashMap::new();
+
+            loop {
+                let Some(first) = event_rx.recv().await else {
+                    break; // watcher dropped
+                };
+                let mut pending: HashSet<PathBuf> = HashSet::new();
+                collect_relevant_paths(&root, first, &mut pending);
+
+                // Coalesce the rest of this burst: keep draining while events keep arriving
+                // within the debounce window, so a save-triggered flurry collapses into one run.
+                loop {
+                    match tokio::time::timeout(WATCH_DEBOUNCE, event_rx.recv()).await {
+                        Ok(Some(event)) => collect_relevant_paths(&root, event, &mut pending),
+                        Ok(None) => return,
+                        Err(_) => break,
+                    }
+                }
+
+                if pending.is_empty() {
+                    continue;
+                }
+
+                {
+                    let mut meta_guard = warm_meta.lock().await;
+                    if let Some(meta) = meta_guard.as_mut() {
+                        meta.warm_stale = true;
+                    }
+                }
+
+                let changed: Vec<PathBuf> = pending.into_iter().collect();
+                for path in &changed {
+                    let relative = path
+                        .strip_prefix(&root)
+                        .unwrap_or(path)
+                        .to_string_lossy()
+                        .replace('\\', "/");
+                    *file_versions.entry(relative).or_insert(0) += 1;
+                }
+
+                let attempt =
+                    reindex_changed_paths(&root, &profile, WARM_REINDEX_BUDGET_MS, changed).await;
+
+                // `reindex_changed_paths` only touches the index/corpus for files whose content
+                // hash actually changed, so the on-disk store's mtime (and therefore
+                // `GraphCache`'s own staleness check) only moves when there was real work to
+                // invalidate -- a no-op batch (touches, reverts) leaves the graph cache untouched
+                // rather than forcing a rebuild of the whole thing.
+                let mut meta_guard = warm_meta.lock().await;
+                if let Some(meta) = meta_guard.as_mut() {
+                    meta.warm_stale = !matches!(attempt.result, Some(ReindexResult::Ok));
+                    meta.last_refresh_ms = Some(unix_ms(std::time::SystemTime::now()));
+                }
+            }
+        });
+
+        *watch_guard = Some(WatchGuard { _watcher: watcher });
+        Ok(())
+    }
+}
+
+fn daemon_disabled() -> bool {
+    std::env::var("CONTEXT_FINDER_DISABLE_DAEMON")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+/// Extracts the paths touched by one filesystem event and adds the ones worth re-embedding to
+/// `pending` -- skipping watcher errors, the project's own `.context`/`.agents` cache directory
+/// (reindexing would otherwise re-trigger itself), and other well-known non-source directories.
+fn collect_relevant_paths(
+    root: &Path,
+    event: notify::Result<Event>,
+    pending: &mut HashSet<PathBuf>,
+) {
+    let Ok(event) = event else {
+        return;
+    };
+    if !matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) {
+        return;
+    }
+    for path in event.paths {
+        if is_watch_ignored(root, &path) {
+            continue;
+        }
+        pending.insert(path);
+    }
+}
+
+fn is_watch_ignored(root: &Path, path: &Path) -> bool {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    relative.components().any(|component| {
+        let name = component.as_os_str().to_string_lossy();
+        is_context_dir_name(&name) || WATCH_IGNORED_DIRS.contains(&name.as_ref())
+    })
 }
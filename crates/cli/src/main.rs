@@ -24,6 +24,7 @@ use tonic::transport::Server;
 
 use crate::command::infra::HealthPort;
 
+mod bench;
 mod cache;
 mod command;
 mod graph_cache;
@@ -137,6 +138,9 @@ enum Commands {
     /// Compare two profiles/model sets on a golden dataset (A/B)
     #[command(name = "eval-compare")]
     EvalCompare(EvalCompareArgs),
+
+    /// Run or generate a repeatable indexing/search benchmark workload
+    Bench(BenchArgs),
 }
 
 #[derive(Args)]
@@ -445,6 +449,70 @@ struct EvalCompareArgs {
     json: bool,
 }
 
+#[derive(Args)]
+struct BenchArgs {
+    #[command(subcommand)]
+    action: BenchAction,
+}
+
+#[derive(Subcommand)]
+enum BenchAction {
+    /// Run a workload file against a project and report latency percentiles
+    Run(BenchRunArgs),
+
+    /// Generate a workload file sampled from a project's indexed symbols
+    Workload(BenchWorkloadArgs),
+}
+
+#[derive(Args)]
+struct BenchRunArgs {
+    /// Path to the workload JSON file
+    workload: PathBuf,
+
+    /// Project directory to run the workload against (defaults to current directory)
+    #[arg(long, default_value = ".")]
+    path: PathBuf,
+
+    /// Pre-allocate (and touch) a buffer of this many megabytes to simulate memory pressure
+    #[arg(long)]
+    memory_load: Option<usize>,
+
+    /// Write the BenchStats JSON report to this path, in addition to printing it
+    #[arg(long)]
+    out_json: Option<PathBuf>,
+
+    /// Output JSON format
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Args)]
+struct BenchWorkloadArgs {
+    /// Project directory to sample symbols from (defaults to current directory)
+    #[arg(default_value = ".")]
+    path: PathBuf,
+
+    /// Number of search tasks to generate
+    #[arg(long, default_value_t = 10)]
+    search_tasks: usize,
+
+    /// Include a leading index task in the generated workload
+    #[arg(long)]
+    with_index: bool,
+
+    /// Repeat count to embed in the generated workload
+    #[arg(long)]
+    repeat: Option<u32>,
+
+    /// RNG seed to embed in the generated workload (omit for an unshuffled, stable order)
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Where to write the generated workload JSON
+    #[arg(long)]
+    out: PathBuf,
+}
+
 #[derive(Args)]
 struct ListSymbolsArgs {
     /// Project directory (defaults to current directory)
@@ -602,6 +670,7 @@ async fn main() -> Result<()> {
         Commands::Doctor(args) => args.json,
         Commands::Eval(args) => args.json,
         Commands::EvalCompare(args) => args.json,
+        Commands::Bench(args) => matches!(&args.action, BenchAction::Run(run) if run.json),
         _ => false,
     };
     if json_output {
@@ -644,6 +713,7 @@ async fn main() -> Result<()> {
         Commands::Doctor(args) => run_doctor(args).await?,
         Commands::Eval(args) => run_eval(args, cache_cfg).await?,
         Commands::EvalCompare(args) => run_eval_compare(args, cache_cfg).await?,
+        Commands::Bench(args) => run_bench(args, cache_cfg).await?,
     }
 
     Ok(())
@@ -806,6 +876,79 @@ async fn run_eval_compare(args: EvalCompareArgs, cache_cfg: CacheConfig) -> Resu
     Ok(())
 }
 
+async fn run_bench(args: BenchArgs, cache_cfg: CacheConfig) -> Result<()> {
+    match args.action {
+        BenchAction::Run(args) => run_bench_run(args, cache_cfg).await,
+        BenchAction::Workload(args) => run_bench_workload(args, cache_cfg).await,
+    }
+}
+
+async fn run_bench_run(args: BenchRunArgs, cache_cfg: CacheConfig) -> Result<()> {
+    let root = args.path.canonicalize().context("Invalid project path")?;
+    let workload = bench::load_workload(&args.workload)?;
+    let stats = bench::run_workload(&root, &workload, cache_cfg, args.memory_load).await?;
+
+    if let Some(path) = &args.out_json {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(&stats)?)?;
+    }
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+    } else {
+        eprintln!(
+            "Ran {} tasks in {}ms ({:.1} tasks/sec, {} errors)",
+            stats.tasks,
+            stats.total_ms,
+            stats.throughput_tasks_per_sec,
+            stats.errors.len()
+        );
+        let mut ops: Vec<_> = stats.by_op.iter().collect();
+        ops.sort_by(|a, b| a.0.cmp(b.0));
+        for (op, op_stats) in ops {
+            eprintln!(
+                "  {op}: n={} mean={:.1}ms p50={:.1}ms p90={:.1}ms p95={:.1}ms p99={:.1}ms",
+                op_stats.count,
+                op_stats.mean_ms,
+                op_stats.p50_ms,
+                op_stats.p90_ms,
+                op_stats.p95_ms,
+                op_stats.p99_ms
+            );
+        }
+        for error in &stats.errors {
+            eprintln!("  error: {error}");
+        }
+    }
+
+    if !stats.errors.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+async fn run_bench_workload(args: BenchWorkloadArgs, cache_cfg: CacheConfig) -> Result<()> {
+    let root = args.path.canonicalize().context("Invalid project path")?;
+    let workload = bench::generate_workload(
+        &root,
+        cache_cfg,
+        args.search_tasks,
+        args.with_index,
+        args.repeat,
+        args.seed,
+    )
+    .await?;
+    bench::write_workload(&args.out, &workload)?;
+    eprintln!(
+        "Wrote workload with {} tasks to {}",
+        workload.tasks.len(),
+        args.out.display()
+    );
+    Ok(())
+}
+
 async fn run_command(args: CommandArgs, cache_cfg: CacheConfig) -> Result<()> {
     let raw = read_payload(&args)?;
     let request: CommandRequest =
@@ -230,6 +230,28 @@ impl ChunkType {
         !matches!(self, Self::Import | Self::Comment | Self::Other)
     }
 
+    /// Map to an LSP `SymbolKind` name (see `textDocument/documentSymbol`), for consumers that
+    /// want to expose chunk declarations through editor-facing outline APIs.
+    #[must_use]
+    pub const fn as_lsp_symbol_kind(self) -> &'static str {
+        match self {
+            Self::Function => "Function",
+            Self::Method => "Method",
+            Self::Class => "Class",
+            Self::Struct => "Struct",
+            Self::Enum => "Enum",
+            Self::Interface => "Interface",
+            Self::Module => "Module",
+            Self::Impl => "Class",
+            Self::Type => "TypeParameter",
+            Self::Const => "Constant",
+            Self::Variable => "Variable",
+            Self::Import => "Namespace",
+            Self::Comment => "String",
+            Self::Other => "Object",
+        }
+    }
+
     /// Get human-readable name
     #[must_use]
     pub const fn as_str(self) -> &'static str {
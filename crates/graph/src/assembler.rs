@@ -25,6 +25,10 @@ pub enum AssemblyStrategy {
 
     /// Custom depth
     Custom(usize),
+
+    /// Rank by embedding similarity to the query instead of graph distance (depth=2 halo
+    /// around whatever the semantic search surfaces). See [`crate::semantic_index`].
+    Semantic,
 }
 
 /// Assembled context for AI agent
@@ -64,6 +68,9 @@ impl ContextAssembler {
             AssemblyStrategy::Extended => 2,
             AssemblyStrategy::Deep => 3,
             AssemblyStrategy::Custom(d) => d,
+            // Symbol lookup still walks the call graph; the embedding ranking only changes
+            // which primary hits feed in upstream, so a plain Extended-sized halo is enough.
+            AssemblyStrategy::Semantic => 2,
         };
 
         // Find primary node
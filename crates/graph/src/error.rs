@@ -19,3 +19,12 @@ pub enum GraphError {
     #[error("{0}")]
     Other(String),
 }
+
+impl GraphError {
+    /// True for failures plausibly caused by transient contention while the graph is being built
+    /// (e.g. concurrent rebuild), false for errors tied to the query itself (missing node/symbol),
+    /// which retrying cannot fix.
+    pub fn is_retriable(&self) -> bool {
+        matches!(self, Self::BuildError(_))
+    }
+}
@@ -0,0 +1,202 @@
+//! Fuzzy (subsequence) symbol matching, used when an exact `CodeGraph::find_node` lookup misses.
+//!
+//! Two stages:
+//! 1. Cheap rejection: a `char_bag` bitmask (one bit per distinct lowercased ASCII byte)
+//!    lets us skip any candidate that is missing a character the query needs, without scoring it.
+//! 2. For survivors, a recursive best-subsequence scorer (the algorithm behind fuzzy pickers like
+//!    Sublime/VS Code's "Go to Symbol") finds the highest-scoring way to match the query characters
+//!    against the candidate in order, rewarding consecutive runs and word-boundary starts.
+
+use crate::types::CodeGraph;
+use petgraph::graph::NodeIndex;
+
+/// Bonus for matching a candidate character immediately after the previous match.
+const CONSECUTIVE_BONUS: i32 = 15;
+/// Bonus for matching at a word boundary (string start, after `_`/`-`/`.`/`/`, or a camelCase hump).
+const WORD_START_BONUS: i32 = 30;
+/// Base score awarded per matched character.
+const MATCH_SCORE: i32 = 10;
+/// Penalty per candidate character skipped while searching for the next match.
+const SKIP_PENALTY: i32 = 1;
+
+/// 128 bits: one per lowercased ASCII byte value. Non-ASCII bytes are folded into bit 127 so
+/// symbols with non-ASCII characters still get a (coarser) rejection test instead of being
+/// silently exempt from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CharBag(u128);
+
+impl CharBag {
+    fn of(text: &str) -> Self {
+        let mut bits: u128 = 0;
+        for byte in text.bytes() {
+            let lower = byte.to_ascii_lowercase();
+            let bit = if lower.is_ascii() { lower as u32 } else { 127 };
+            bits |= 1u128 << bit;
+        }
+        CharBag(bits)
+    }
+
+    /// True if every bit set in `query` is also set in `self` (i.e. `self` contains at least one
+    /// of each character the query needs).
+    fn contains_all(&self, query: &CharBag) -> bool {
+        self.0 & query.0 == query.0
+    }
+}
+
+fn is_word_boundary(candidate: &[u8], idx: usize) -> bool {
+    if idx == 0 {
+        return true;
+    }
+    let prev = candidate[idx - 1];
+    if matches!(prev, b'_' | b'-' | b'.' | b'/') {
+        return true;
+    }
+    let cur = candidate[idx];
+    prev.is_ascii_lowercase() && cur.is_ascii_uppercase()
+}
+
+/// Recursively finds the best-scoring way to match `query[qi..]` as a subsequence of
+/// `candidate[ci..]`, branching between "match here" and "skip this candidate char".
+fn best_match_score(
+    query: &[u8],
+    candidate: &[u8],
+    qi: usize,
+    ci: usize,
+    prev_matched: bool,
+) -> Option<i32> {
+    if qi == query.len() {
+        return Some(0);
+    }
+    if ci == candidate.len() {
+        return None;
+    }
+
+    // Branch 1: skip this candidate character.
+    let skip = best_match_score(query, candidate, qi, ci + 1, false)
+        .map(|score| score - SKIP_PENALTY);
+
+    // Branch 2: match here, if characters are equal case-insensitively.
+    let take = if query[qi].to_ascii_lowercase() == candidate[ci].to_ascii_lowercase() {
+        best_match_score(query, candidate, qi + 1, ci + 1, true).map(|rest| {
+            let mut score = rest + MATCH_SCORE;
+            if prev_matched {
+                score += CONSECUTIVE_BONUS;
+            }
+            if is_word_boundary(candidate, ci) {
+                score += WORD_START_BONUS;
+            }
+            score
+        })
+    } else {
+        None
+    };
+
+    match (skip, take) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (Some(a), None) => Some(a),
+        (None, Some(b)) => Some(b),
+        (None, None) => None,
+    }
+}
+
+/// Scores `candidate` against `query` as a case-insensitive fuzzy subsequence match. Returns
+/// `None` if `query` is not a subsequence of `candidate` at all. The score is normalized by query
+/// length so candidates of different lengths remain comparable.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<f32> {
+    if query.is_empty() {
+        return None;
+    }
+    let query_bytes = query.as_bytes();
+    let candidate_bytes = candidate.as_bytes();
+    let raw = best_match_score(query_bytes, candidate_bytes, 0, 0, false)?;
+    Some(raw as f32 / query_bytes.len() as f32)
+}
+
+/// One ranked fuzzy-match candidate: the matched symbol name, its graph node, and a relative score
+/// (higher is a better match; not bounded to any fixed range).
+#[derive(Debug, Clone, Copy)]
+pub struct FuzzySymbolMatch<'a> {
+    pub symbol: &'a str,
+    pub node: NodeIndex,
+    pub score: f32,
+}
+
+impl CodeGraph {
+    /// Finds symbols whose name fuzzy-matches `query` as a subsequence, for use when
+    /// [`CodeGraph::find_node`] misses on the exact name (e.g. a typo or partial name). Returns
+    /// the top `limit` candidates sorted by score descending, breaking ties by shorter symbol name
+    /// first (a closer match to what the caller probably meant).
+    pub fn fuzzy_find_symbols(&self, query: &str, limit: usize) -> Vec<FuzzySymbolMatch<'_>> {
+        let query_bag = CharBag::of(query);
+
+        let mut matches: Vec<FuzzySymbolMatch<'_>> = self
+            .symbol_index
+            .iter()
+            .filter_map(|(name, &node)| {
+                if !CharBag::of(name).contains_all(&query_bag) {
+                    return None;
+                }
+                let score = fuzzy_score(query, name)?;
+                Some(FuzzySymbolMatch {
+                    symbol: name.as_str(),
+                    node,
+                    score,
+                })
+            })
+            .collect();
+
+        matches.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.symbol.len().cmp(&b.symbol.len()))
+        });
+        matches.truncate(limit);
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{GraphNode, Symbol, SymbolType};
+
+    fn push_symbol(graph: &mut CodeGraph, name: &str) {
+        graph.add_node(GraphNode {
+            symbol: Symbol {
+                name: name.to_string(),
+                symbol_type: SymbolType::Function,
+                file_path: "lib.rs".to_string(),
+                start_line: 1,
+                end_line: 1,
+            },
+            chunk_id: name.to_string(),
+        });
+    }
+
+    #[test]
+    fn char_bag_rejects_candidates_missing_a_query_character() {
+        let query = CharBag::of("vecstore");
+        assert!(CharBag::of("VectorStore").contains_all(&query));
+        assert!(!CharBag::of("Graph").contains_all(&query));
+    }
+
+    #[test]
+    fn fuzzy_score_prefers_word_boundary_and_consecutive_matches() {
+        let camel = fuzzy_score("vs", "VectorStore").unwrap();
+        let buried = fuzzy_score("vs", "haversine").unwrap();
+        assert!(camel > buried, "camel={camel} buried={buried}");
+    }
+
+    #[test]
+    fn fuzzy_find_symbols_ranks_near_misses_for_a_typo() {
+        let mut graph = CodeGraph::new();
+        push_symbol(&mut graph, "VectorStore");
+        push_symbol(&mut graph, "VectorSearch");
+        push_symbol(&mut graph, "Graph");
+
+        let matches = graph.fuzzy_find_symbols("VecStore", 5);
+        assert!(!matches.is_empty());
+        assert_eq!(matches[0].symbol, "VectorStore");
+    }
+}
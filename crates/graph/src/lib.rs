@@ -35,8 +35,14 @@ mod builder;
 mod graph;
 mod assembler;
 mod error;
+mod semantic_index;
+mod fuzzy;
+mod symbol_fst;
 
 pub use types::{CodeGraph, GraphNode, GraphEdge, RelationshipType, Symbol, SymbolType};
 pub use builder::{GraphBuilder, GraphLanguage};
 pub use assembler::{ContextAssembler, AssemblyStrategy, AssembledContext, RelatedChunk};
 pub use error::{Result, GraphError};
+pub use semantic_index::{Embedder, IndexedWindow, SemanticIndex, SemanticMatch};
+pub use fuzzy::FuzzySymbolMatch;
+pub use symbol_fst::{SymbolFstIndex, SymbolFstMatch};
@@ -0,0 +1,199 @@
+//! Embedding-based recall, complementing graph-distance ranking ([`crate::AssemblyStrategy`]).
+//!
+//! Files are split into overlapping line windows and embedded through a pluggable
+//! [`Embedder`], then ranked against a query by cosine similarity. This catches paraphrased
+//! or conceptual questions that share no literal tokens with the code that answers them.
+
+use std::collections::HashMap;
+
+/// Turns text into fixed-size vectors. Implementations can wrap llama.cpp, fastembed, a
+/// PostgresML-style remote call, or anything else; batched so a backend that pays a fixed
+/// per-call cost (model load, HTTP round trip) only pays it once per window set.
+pub trait Embedder: Send + Sync {
+    fn embed(&self, texts: &[String]) -> Vec<Vec<f32>>;
+}
+
+/// One embedded line window within an indexed file.
+#[derive(Debug, Clone)]
+pub struct IndexedWindow {
+    pub file: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub content: String,
+    pub vector: Vec<f32>,
+}
+
+/// A window ranked against a query, with its cosine similarity score.
+#[derive(Debug, Clone)]
+pub struct SemanticMatch {
+    pub window: IndexedWindow,
+    pub score: f32,
+}
+
+const WINDOW_LINES: usize = 40;
+const WINDOW_STRIDE: usize = 10;
+
+/// In-memory embedding index over line windows, keyed by content hash so an unchanged file
+/// is never re-embedded.
+#[derive(Default)]
+pub struct SemanticIndex {
+    windows: Vec<IndexedWindow>,
+    indexed_hashes: HashMap<String, String>,
+}
+
+impl SemanticIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `true` if `file` is already indexed under this exact `content_hash`.
+    pub fn is_fresh(&self, file: &str, content_hash: &str) -> bool {
+        self.indexed_hashes
+            .get(file)
+            .is_some_and(|hash| hash == content_hash)
+    }
+
+    /// Splits `content` into overlapping line windows and embeds them, replacing any stale
+    /// windows already indexed for `file`. No-op if `file` is already fresh under `content_hash`.
+    pub fn index_file(
+        &mut self,
+        embedder: &dyn Embedder,
+        file: &str,
+        content_hash: &str,
+        content: &str,
+    ) {
+        if self.is_fresh(file, content_hash) {
+            return;
+        }
+        self.windows.retain(|window| window.file != file);
+
+        let lines: Vec<&str> = content.lines().collect();
+        if lines.is_empty() {
+            self.indexed_hashes
+                .insert(file.to_string(), content_hash.to_string());
+            return;
+        }
+
+        let mut spans: Vec<(usize, usize)> = Vec::new();
+        let mut start = 0;
+        loop {
+            let end = (start + WINDOW_LINES).min(lines.len());
+            spans.push((start, end));
+            if end == lines.len() {
+                break;
+            }
+            start += WINDOW_STRIDE;
+        }
+
+        let texts: Vec<String> = spans
+            .iter()
+            .map(|(start, end)| lines[*start..*end].join("\n"))
+            .collect();
+        let vectors = embedder.embed(&texts);
+
+        for ((start, end), (content, vector)) in spans.into_iter().zip(texts.into_iter().zip(vectors))
+        {
+            self.windows.push(IndexedWindow {
+                file: file.to_string(),
+                start_line: start + 1,
+                end_line: end,
+                content,
+                vector,
+            });
+        }
+        self.indexed_hashes
+            .insert(file.to_string(), content_hash.to_string());
+    }
+
+    /// Embeds `query` and returns the `top_k` windows ranked by cosine similarity, highest first.
+    pub fn search(&self, embedder: &dyn Embedder, query: &str, top_k: usize) -> Vec<SemanticMatch> {
+        if self.windows.is_empty() || top_k == 0 {
+            return Vec::new();
+        }
+        let Some(query_vector) = embedder
+            .embed(std::slice::from_ref(&query.to_string()))
+            .into_iter()
+            .next()
+        else {
+            return Vec::new();
+        };
+
+        let mut scored: Vec<SemanticMatch> = self
+            .windows
+            .iter()
+            .map(|window| SemanticMatch {
+                window: window.clone(),
+                score: cosine_similarity(&query_vector, &window.vector),
+            })
+            .collect();
+        scored.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(top_k);
+        scored
+    }
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeEmbedder;
+    impl Embedder for FakeEmbedder {
+        fn embed(&self, texts: &[String]) -> Vec<Vec<f32>> {
+            texts
+                .iter()
+                .map(|text| vec![text.len() as f32, text.matches("fn ").count() as f32])
+                .collect()
+        }
+    }
+
+    #[test]
+    fn skips_reembedding_unchanged_files() {
+        let embedder = FakeEmbedder;
+        let mut index = SemanticIndex::new();
+        index.index_file(&embedder, "a.rs", "hash1", "fn a() {}\nfn b() {}\n");
+        let windows_before = index.windows.len();
+        index.index_file(&embedder, "a.rs", "hash1", "fn a() {}\nfn b() {}\n");
+        assert_eq!(index.windows.len(), windows_before);
+    }
+
+    #[test]
+    fn reindexes_on_hash_change() {
+        let embedder = FakeEmbedder;
+        let mut index = SemanticIndex::new();
+        index.index_file(&embedder, "a.rs", "hash1", "fn a() {}\n");
+        index.index_file(&embedder, "a.rs", "hash2", "fn a() {}\nfn b() {}\nfn c() {}\n");
+        assert!(index.is_fresh("a.rs", "hash2"));
+        assert!(!index.is_fresh("a.rs", "hash1"));
+    }
+
+    #[test]
+    fn search_ranks_by_similarity() {
+        let embedder = FakeEmbedder;
+        let mut index = SemanticIndex::new();
+        index.index_file(&embedder, "a.rs", "h", "fn a() {}\nfn b() {}\n");
+        index.index_file(&embedder, "b.md", "h", "# Doc\nno functions here\n");
+        let matches = index.search(&embedder, "fn target() {}", 1);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].window.file, "a.rs");
+    }
+
+    #[test]
+    fn search_returns_empty_for_empty_index() {
+        let embedder = FakeEmbedder;
+        let index = SemanticIndex::new();
+        assert!(index.search(&embedder, "anything", 5).is_empty());
+    }
+}
@@ -0,0 +1,254 @@
+//! FST-backed symbol name index: ordered prefix enumeration and bounded edit-distance fuzzy
+//! search over every symbol a [`CodeGraph`] knows about.
+//!
+//! Shared by the `atlas_pack` and `impact` MCP tools (and exposed directly via `symbol_lookup`)
+//! so neither has to fall back to a full scan just to answer "what symbols start with/are near
+//! X". An `fst::Map` requires keys inserted in strictly increasing lexicographic order, so
+//! [`SymbolFstIndex::build`] collects every symbol name up front and sorts before inserting; a
+//! name defined in more than one place can't fit in the single packed `u64` an `fst::Map` value
+//! holds, so those map instead to an index into `duplicates`, a side table of every location for
+//! that name. This module does no I/O itself — callers own persistence (see
+//! `context-mcp`'s symbol FST cache, which persists this next to the semantic index).
+
+use crate::types::CodeGraph;
+use fst::automaton::{Levenshtein, Str};
+use fst::{IntoStreamer, Map, MapBuilder, Streamer};
+use std::collections::HashMap;
+
+/// One symbol definition site, as reported by [`SymbolFstIndex`] queries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolFstMatch {
+    pub symbol: String,
+    pub file: String,
+    pub line: usize,
+}
+
+/// Tags an `fst::Map` value as a `duplicates` index rather than a packed `(file_id, line)`.
+const DUPLICATE_TAG: u64 = 1 << 63;
+
+fn pack_location(file_id: u32, line: usize) -> u64 {
+    ((file_id as u64) << 31) | (line as u64 & 0x7FFF_FFFF)
+}
+
+fn unpack_location(packed: u64) -> (u32, usize) {
+    ((packed >> 31) as u32, (packed & 0x7FFF_FFFF) as usize)
+}
+
+/// In-memory FST index over symbol names. Build once per [`CodeGraph`] snapshot via
+/// [`SymbolFstIndex::build`]; reconstruct a persisted one via [`SymbolFstIndex::from_parts`].
+pub struct SymbolFstIndex {
+    map: Map<Vec<u8>>,
+    files: Vec<String>,
+    duplicates: Vec<Vec<(u32, usize)>>,
+}
+
+impl SymbolFstIndex {
+    /// Builds the index from every symbol in `graph`, deduplicating file paths into a compact
+    /// side table so the common (single-location) case packs into one `u64`.
+    pub fn build(graph: &CodeGraph) -> Self {
+        let mut file_ids: HashMap<String, u32> = HashMap::new();
+        let mut files: Vec<String> = Vec::new();
+        let mut by_name: HashMap<String, Vec<(u32, usize)>> = HashMap::new();
+
+        for node in graph.graph.node_weights() {
+            let file_id = *file_ids
+                .entry(node.symbol.file_path.clone())
+                .or_insert_with(|| {
+                    files.push(node.symbol.file_path.clone());
+                    (files.len() - 1) as u32
+                });
+            by_name
+                .entry(node.symbol.name.clone())
+                .or_default()
+                .push((file_id, node.symbol.start_line));
+        }
+
+        let mut names: Vec<String> = by_name.keys().cloned().collect();
+        names.sort();
+
+        let mut duplicates: Vec<Vec<(u32, usize)>> = Vec::new();
+        let mut builder = MapBuilder::memory();
+        for name in &names {
+            let locations = &by_name[name];
+            let value = if locations.len() == 1 {
+                pack_location(locations[0].0, locations[0].1)
+            } else {
+                duplicates.push(locations.clone());
+                DUPLICATE_TAG | (duplicates.len() - 1) as u64
+            };
+            // Names are sorted and deduplicated via `by_name`'s keys, so insertion order is
+            // already strictly increasing.
+            let _ = builder.insert(name, value);
+        }
+
+        let map = builder
+            .into_inner()
+            .ok()
+            .and_then(|bytes| Map::new(bytes).ok())
+            .unwrap_or_else(|| Map::from_iter(std::iter::empty::<(&str, u64)>()).unwrap());
+
+        Self {
+            map,
+            files,
+            duplicates,
+        }
+    }
+
+    /// Reconstructs an index previously taken apart via [`Self::as_fst_bytes`]/[`Self::files`]/
+    /// [`Self::duplicates`], e.g. when loading a persisted cache.
+    pub fn from_parts(
+        fst_bytes: Vec<u8>,
+        files: Vec<String>,
+        duplicates: Vec<Vec<(u32, usize)>>,
+    ) -> fst::Result<Self> {
+        Ok(Self {
+            map: Map::new(fst_bytes)?,
+            files,
+            duplicates,
+        })
+    }
+
+    /// Raw FST bytes, for persisting alongside [`Self::files`] and [`Self::duplicates`].
+    pub fn as_fst_bytes(&self) -> &[u8] {
+        self.map.as_fst().as_bytes()
+    }
+
+    pub fn files(&self) -> &[String] {
+        &self.files
+    }
+
+    pub fn duplicates(&self) -> &[Vec<(u32, usize)>] {
+        &self.duplicates
+    }
+
+    fn resolve(&self, name: &str, value: u64) -> Vec<SymbolFstMatch> {
+        let locations: Vec<(u32, usize)> = if value & DUPLICATE_TAG != 0 {
+            let idx = (value & !DUPLICATE_TAG) as usize;
+            self.duplicates.get(idx).cloned().unwrap_or_default()
+        } else {
+            vec![unpack_location(value)]
+        };
+
+        locations
+            .into_iter()
+            .filter_map(|(file_id, line)| {
+                self.files.get(file_id as usize).map(|file| SymbolFstMatch {
+                    symbol: name.to_string(),
+                    file: file.clone(),
+                    line,
+                })
+            })
+            .collect()
+    }
+
+    /// Exact lookup: every location `name` is defined at, empty if `name` isn't in the index.
+    pub fn exact(&self, name: &str) -> Vec<SymbolFstMatch> {
+        self.map
+            .get(name)
+            .map(|value| self.resolve(name, value))
+            .unwrap_or_default()
+    }
+
+    /// Ordered-prefix enumeration (autocomplete): every `(symbol, location)` whose name starts
+    /// with `prefix`, in lexicographic order, capped at `limit`.
+    pub fn prefix(&self, prefix: &str, limit: usize) -> Vec<SymbolFstMatch> {
+        if limit == 0 {
+            return Vec::new();
+        }
+        let automaton = Str::new(prefix).starts_with();
+        let mut stream = self.map.search(automaton).into_stream();
+        let mut out = Vec::new();
+        while let Some((key, value)) = stream.next() {
+            let name = String::from_utf8_lossy(key).into_owned();
+            out.extend(self.resolve(&name, value));
+            if out.len() >= limit {
+                break;
+            }
+        }
+        out.truncate(limit);
+        out
+    }
+
+    /// Bounded edit-distance fuzzy search via a Levenshtein automaton, complementing
+    /// [`CodeGraph::fuzzy_find_symbols`]'s subsequence matcher. `edits` is typically 1-2; ranked
+    /// by name length (shorter names are a closer match for the same edit distance).
+    pub fn fuzzy(&self, term: &str, edits: u32, limit: usize) -> Vec<SymbolFstMatch> {
+        let Ok(automaton) = Levenshtein::new(term, edits) else {
+            return Vec::new();
+        };
+        let mut stream = self.map.search(automaton).into_stream();
+        let mut out = Vec::new();
+        while let Some((key, value)) = stream.next() {
+            let name = String::from_utf8_lossy(key).into_owned();
+            out.extend(self.resolve(&name, value));
+        }
+        out.sort_by_key(|m| m.symbol.len());
+        out.truncate(limit);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{GraphNode, Symbol, SymbolType};
+
+    fn push_symbol(graph: &mut CodeGraph, name: &str, file: &str, line: usize) {
+        graph.add_node(GraphNode {
+            symbol: Symbol {
+                name: name.to_string(),
+                symbol_type: SymbolType::Function,
+                file_path: file.to_string(),
+                start_line: line,
+                end_line: line,
+            },
+            chunk_id: format!("{file}:{line}"),
+        });
+    }
+
+    fn sample_graph() -> CodeGraph {
+        let mut graph = CodeGraph::new();
+        push_symbol(&mut graph, "VectorStore", "store.rs", 10);
+        push_symbol(&mut graph, "VectorSearch", "search.rs", 20);
+        push_symbol(&mut graph, "parse", "parser.rs", 5);
+        push_symbol(&mut graph, "parse", "other_parser.rs", 40);
+        graph
+    }
+
+    #[test]
+    fn exact_returns_all_locations_for_a_duplicate_name() {
+        let index = SymbolFstIndex::build(&sample_graph());
+        let mut matches = index.exact("parse");
+        matches.sort_by(|a, b| a.file.cmp(&b.file));
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].file, "other_parser.rs");
+        assert_eq!(matches[1].file, "parser.rs");
+    }
+
+    #[test]
+    fn prefix_enumerates_in_lexicographic_order() {
+        let index = SymbolFstIndex::build(&sample_graph());
+        let matches = index.prefix("Vector", 10);
+        let names: Vec<&str> = matches.iter().map(|m| m.symbol.as_str()).collect();
+        assert_eq!(names, vec!["VectorSearch", "VectorStore"]);
+    }
+
+    #[test]
+    fn fuzzy_finds_a_one_edit_typo() {
+        let index = SymbolFstIndex::build(&sample_graph());
+        let matches = index.fuzzy("VectorStor", 2, 5);
+        assert!(matches.iter().any(|m| m.symbol == "VectorStore"));
+    }
+
+    #[test]
+    fn round_trips_through_persisted_parts() {
+        let index = SymbolFstIndex::build(&sample_graph());
+        let rebuilt = SymbolFstIndex::from_parts(
+            index.as_fst_bytes().to_vec(),
+            index.files().to_vec(),
+            index.duplicates().to_vec(),
+        )
+        .expect("valid fst bytes");
+        assert_eq!(rebuilt.exact("VectorStore"), index.exact("VectorStore"));
+    }
+}
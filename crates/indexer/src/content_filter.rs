@@ -0,0 +1,101 @@
+//! Cheap pre-index content-quality heuristics.
+//!
+//! Large codebases often carry minified bundles, vendored blobs, and generated lockfile-like
+//! data alongside real source. Indexing those wastes embedding budget and pollutes
+//! [`crate::IndexStats::languages`] with noise, so [`classify_content`] rejects a file before it
+//! ever reaches the chunker, using three metrics that are cheap to compute from the content
+//! alone: average line length, maximum line length, and alphanumeric byte fraction.
+
+/// Thresholds consulted by [`classify_content`]. The defaults mirror how large "clean code"
+/// corpora are filtered and noticeably reduce chunk count on repos with build artifacts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContentFilterConfig {
+    /// Reject when `content.len() / line_count` exceeds this.
+    pub max_avg_line_length: usize,
+    /// Reject when the single longest line exceeds this.
+    pub max_line_length: usize,
+    /// Reject when `alphanumeric_bytes / total_bytes` falls below this.
+    pub min_alphanum_fraction: f64,
+}
+
+impl Default for ContentFilterConfig {
+    fn default() -> Self {
+        Self {
+            max_avg_line_length: 100,
+            max_line_length: 1000,
+            min_alphanum_fraction: 0.25,
+        }
+    }
+}
+
+/// Returns `Some(reason)` if `content` looks non-source (minified/generated/binary-ish) under
+/// `config`, or `None` if it should be indexed normally. Empty content always passes here;
+/// callers already drop zero-chunk files separately.
+pub fn classify_content(content: &str, config: &ContentFilterConfig) -> Option<String> {
+    if content.is_empty() {
+        return None;
+    }
+
+    let total_bytes = content.len();
+    let line_count = content.lines().count().max(1);
+    let max_line_length = content.lines().map(str::len).max().unwrap_or(0);
+    let avg_line_length = total_bytes / line_count;
+    let alphanum_bytes = content
+        .bytes()
+        .filter(u8::is_ascii_alphanumeric)
+        .count();
+    let alphanum_fraction = alphanum_bytes as f64 / total_bytes as f64;
+
+    if avg_line_length > config.max_avg_line_length {
+        return Some(format!(
+            "avg_line_length {avg_line_length} exceeds max_avg_line_length {}",
+            config.max_avg_line_length
+        ));
+    }
+    if max_line_length > config.max_line_length {
+        return Some(format!(
+            "max_line_length {max_line_length} exceeds max_line_length {}",
+            config.max_line_length
+        ));
+    }
+    if alphanum_fraction < config.min_alphanum_fraction {
+        return Some(format!(
+            "alphanum_fraction {alphanum_fraction:.3} below min_alphanum_fraction {:.3}",
+            config.min_alphanum_fraction
+        ));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_normal_source() {
+        let config = ContentFilterConfig::default();
+        let content = "fn main() {\n    println!(\"hello\");\n}\n";
+        assert_eq!(classify_content(content, &config), None);
+    }
+
+    #[test]
+    fn rejects_long_minified_line() {
+        let config = ContentFilterConfig::default();
+        let content = format!("var x={};\n", "a".repeat(2000));
+        assert!(classify_content(&content, &config).is_some());
+    }
+
+    #[test]
+    fn rejects_low_alphanumeric_density() {
+        let config = ContentFilterConfig::default();
+        let content = "{{{{{{}}}}}}\n;;;;;;;;;;;;\n[[[[[[]]]]]]\n".repeat(10);
+        assert!(classify_content(&content, &config).is_some());
+    }
+
+    #[test]
+    fn accepts_empty_content() {
+        let config = ContentFilterConfig::default();
+        assert_eq!(classify_content("", &config), None);
+    }
+}
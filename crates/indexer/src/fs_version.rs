@@ -0,0 +1,235 @@
+//! Per-file content-hash "fs version" bookkeeping for precise incremental reindex.
+//!
+//! [`changed_paths_since`](crate::changed_paths_since) already narrows a reindex to the paths a
+//! git diff or filesystem snapshot says moved, but "moved" only means mtime/size changed -- a
+//! save-without-edit, a touch, or a revert all show up as a candidate even though the bytes are
+//! identical to what's already embedded. [`FsVersions`] tracks a fast content hash per file so
+//! [`diff_fs_versions`] can tell `candidate changed` apart from `candidate unchanged`, and the
+//! caller only re-chunks/re-embeds the former.
+
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+pub const FS_VERSIONS_FILE_NAME: &str = "fs_versions.json";
+
+/// Content hash plus mtime for one file, as of the last time it was (re-)embedded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileVersion {
+    pub content_hash: u64,
+    pub mtime_ms: u64,
+}
+
+/// Per-file [`FileVersion`]s for a project, keyed by path relative to the project root (with
+/// `/` separators, so the map is stable across platforms).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FsVersions {
+    pub files: BTreeMap<String, FileVersion>,
+}
+
+/// Result of comparing a freshly-computed [`FsVersions`] against the one persisted from the last
+/// reindex.
+#[derive(Debug, Clone, Default)]
+pub struct FileVersionDiff {
+    /// Relative paths that are new or whose content hash no longer matches the prior version.
+    pub changed: Vec<String>,
+    /// Relative paths present in `prior` that no longer exist in `current`. [`diff_fs_versions`]
+    /// leaves this empty (it only sees candidate paths); [`removed_paths`] computes deletions.
+    pub removed: Vec<String>,
+    /// Candidate paths whose content hash matched the prior version exactly (touched but not
+    /// actually edited) -- these are skipped rather than re-embedded.
+    pub unchanged: Vec<String>,
+}
+
+pub fn fs_versions_path(project_root: &Path) -> PathBuf {
+    context_vector_store::context_dir_for_project_root(project_root).join(FS_VERSIONS_FILE_NAME)
+}
+
+pub async fn read_fs_versions(project_root: &Path) -> Result<FsVersions> {
+    let path = fs_versions_path(project_root);
+    if !path.exists() {
+        return Ok(FsVersions::default());
+    }
+    let bytes = tokio::fs::read(&path).await?;
+    Ok(serde_json::from_slice(&bytes).unwrap_or_default())
+}
+
+pub async fn write_fs_versions(project_root: &Path, versions: &FsVersions) -> Result<()> {
+    let path = fs_versions_path(project_root);
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let bytes = serde_json::to_vec_pretty(versions)?;
+    let tmp = path.with_extension("json.tmp");
+    tokio::fs::write(&tmp, bytes).await?;
+    tokio::fs::rename(&tmp, &path).await?;
+    Ok(())
+}
+
+/// Fast, seeded, non-cryptographic content hash (FNV-1a). Collisions are acceptable here -- a
+/// false "unchanged" just means a pathological edit gets skipped until something else touches
+/// the same file, nothing is lost, it just costs the next reindex an extra re-embed.
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+#[must_use]
+pub fn hash_content(bytes: &[u8]) -> u64 {
+    let mut hash = FNV_OFFSET_BASIS;
+    for &byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+pub async fn compute_file_version(path: &Path) -> Result<FileVersion> {
+    let bytes = tokio::fs::read(path).await?;
+    let metadata = tokio::fs::metadata(path).await?;
+    let mtime_ms = metadata
+        .modified()
+        .ok()
+        .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    Ok(FileVersion {
+        content_hash: hash_content(&bytes),
+        mtime_ms,
+    })
+}
+
+/// Computes [`FileVersion`]s for `paths` (relative to `project_root`), skipping any that can no
+/// longer be read (deleted between scan and hash) rather than failing the whole reindex.
+pub async fn compute_fs_versions(project_root: &Path, paths: &[PathBuf]) -> FsVersions {
+    let mut files = BTreeMap::new();
+    for path in paths {
+        let relative = path
+            .strip_prefix(project_root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        let absolute = if path.is_absolute() {
+            path.clone()
+        } else {
+            project_root.join(path)
+        };
+        if let Ok(version) = compute_file_version(&absolute).await {
+            files.insert(relative, version);
+        }
+    }
+    FsVersions { files }
+}
+
+/// Diffs `current` (freshly computed for `candidate_paths`) against `prior` (the last-persisted
+/// versions). Paths outside `candidate_paths` are left untouched in `prior` by the caller --
+/// this only ever reports on the files that were actually candidates for this reindex.
+#[must_use]
+pub fn diff_fs_versions(prior: &FsVersions, current: &FsVersions) -> FileVersionDiff {
+    let mut diff = FileVersionDiff::default();
+    for (path, version) in &current.files {
+        match prior.files.get(path) {
+            Some(prior_version) if prior_version.content_hash == version.content_hash => {
+                diff.unchanged.push(path.clone());
+            }
+            _ => diff.changed.push(path.clone()),
+        }
+    }
+    diff
+}
+
+/// Relative paths present in `prior` but absent from the live scan `live_relative_paths`, i.e.
+/// files that were deleted since the last reindex.
+#[must_use]
+pub fn removed_paths(
+    prior: &FsVersions,
+    live_relative_paths: &std::collections::HashSet<String>,
+) -> Vec<String> {
+    prior
+        .files
+        .keys()
+        .filter(|path| !live_relative_paths.contains(*path))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_content_is_deterministic_and_sensitive_to_bytes() {
+        assert_eq!(hash_content(b"hello"), hash_content(b"hello"));
+        assert_ne!(hash_content(b"hello"), hash_content(b"hellO"));
+    }
+
+    #[test]
+    fn diff_flags_new_and_unchanged_and_modified() {
+        let mut prior = FsVersions::default();
+        prior.files.insert(
+            "a.rs".to_string(),
+            FileVersion {
+                content_hash: 1,
+                mtime_ms: 100,
+            },
+        );
+        prior.files.insert(
+            "b.rs".to_string(),
+            FileVersion {
+                content_hash: 2,
+                mtime_ms: 100,
+            },
+        );
+
+        let mut current = FsVersions::default();
+        current.files.insert(
+            "a.rs".to_string(),
+            FileVersion {
+                content_hash: 1,
+                mtime_ms: 200,
+            },
+        ); // touched, same content
+        current.files.insert(
+            "b.rs".to_string(),
+            FileVersion {
+                content_hash: 99,
+                mtime_ms: 200,
+            },
+        ); // actually edited
+        current.files.insert(
+            "c.rs".to_string(),
+            FileVersion {
+                content_hash: 3,
+                mtime_ms: 200,
+            },
+        ); // new file
+
+        let diff = diff_fs_versions(&prior, &current);
+        assert_eq!(diff.unchanged, vec!["a.rs".to_string()]);
+        let mut changed = diff.changed.clone();
+        changed.sort();
+        assert_eq!(changed, vec!["b.rs".to_string(), "c.rs".to_string()]);
+    }
+
+    #[test]
+    fn removed_paths_reports_deleted_files() {
+        let mut prior = FsVersions::default();
+        prior.files.insert(
+            "a.rs".to_string(),
+            FileVersion {
+                content_hash: 1,
+                mtime_ms: 100,
+            },
+        );
+        prior.files.insert(
+            "b.rs".to_string(),
+            FileVersion {
+                content_hash: 2,
+                mtime_ms: 100,
+            },
+        );
+
+        let live: std::collections::HashSet<String> = ["a.rs".to_string()].into_iter().collect();
+        assert_eq!(removed_paths(&prior, &live), vec!["b.rs".to_string()]);
+    }
+}
@@ -1,6 +1,7 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::path::PathBuf;
 
 pub const INDEX_STATE_SCHEMA_VERSION: u32 = 1;
 
@@ -14,6 +15,11 @@ pub enum Watermark {
         git_dirty: bool,
         #[serde(skip_serializing_if = "Option::is_none")]
         dirty_hash: Option<u64>,
+        /// Resolved HEAD (plus a `+dirty` suffix when the submodule's own workdir is dirty) of
+        /// each submodule probed at watermark time, so a commit entirely inside a submodule still
+        /// moves the watermark even though the superproject's HEAD/status don't change.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        submodule_heads: Vec<(PathBuf, String)>,
     },
     Filesystem {
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -56,6 +62,29 @@ pub struct ReindexAttempt {
     pub result: Option<ReindexResult>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Whether this reindex was scoped to a changed-path delta rather than the whole project.
+    /// `false` for both "full rebuild" and "no reindex attempted" (see `attempted`/`performed`).
+    #[serde(default)]
+    pub incremental: bool,
+    /// Size of the delta set when `incremental` is set; `None` for a full rebuild.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files_changed: Option<usize>,
+    /// Of the candidate delta, how many files actually had their content hash change and were
+    /// re-chunked/re-embedded. `None` when content-hash filtering wasn't applied (e.g. the
+    /// reindex failed before reaching that stage).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files_reembedded: Option<usize>,
+    /// Of the candidate delta, how many files were skipped because their content hash matched
+    /// the last-persisted `fs_version` (touched but not actually edited).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files_skipped: Option<usize>,
+    /// Chunks added across all re-embedded/new files (new chunk count minus chunks that were
+    /// unchanged and kept their existing vectors).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chunks_added: Option<usize>,
+    /// Chunks dropped for deleted files plus chunks removed from files that shrank.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chunks_removed: Option<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
@@ -249,6 +278,7 @@ mod tests {
             git_head: head.to_string(),
             git_dirty: dirty,
             dirty_hash: None,
+            submodule_heads: Vec::new(),
         }
     }
 
@@ -258,6 +288,7 @@ mod tests {
             git_head: head.to_string(),
             git_dirty: dirty,
             dirty_hash,
+            submodule_heads: Vec::new(),
         }
     }
 
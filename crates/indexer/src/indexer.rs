@@ -1,3 +1,4 @@
+use crate::content_filter::{classify_content, ContentFilterConfig};
 use crate::error::{IndexerError, Result};
 use crate::scanner::FileScanner;
 use crate::stats::IndexStats;
@@ -7,11 +8,25 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::time::{Instant, SystemTime};
 
+/// Outcome of reading a single file before chunking.
+enum FileReadOutcome {
+    Kept(PathBuf, String, usize),
+    Skipped(PathBuf, String),
+}
+
+/// Outcome of processing a single file, folded into [`IndexStats`] by the caller.
+enum FileProcessOutcome {
+    Processed(Vec<context_code_chunker::CodeChunk>, String, usize),
+    Skipped(String, String),
+    Error(String),
+}
+
 /// Project indexer that scans, chunks, and indexes code
 pub struct ProjectIndexer {
     root: PathBuf,
     store_path: PathBuf,
     chunker: Chunker,
+    content_filter: ContentFilterConfig,
 }
 
 impl ProjectIndexer {
@@ -39,9 +54,17 @@ impl ProjectIndexer {
             root,
             store_path,
             chunker,
+            content_filter: ContentFilterConfig::default(),
         })
     }
 
+    /// Overrides the content-quality filter thresholds used to skip generated/minified files
+    /// (defaults: see [`ContentFilterConfig::default`]).
+    pub fn with_content_filter(mut self, content_filter: ContentFilterConfig) -> Self {
+        self.content_filter = content_filter;
+        self
+    }
+
     /// Index the project (with incremental support)
     pub async fn index(&self) -> Result<IndexStats> {
         self.index_with_mode(false).await
@@ -60,7 +83,7 @@ impl ProjectIndexer {
         log::info!("Indexing project at {:?}", self.root);
 
         // 1. Scan for files
-        let scanner = FileScanner::new(&self.root);
+        let mut scanner = FileScanner::new(&self.root);
         let files = scanner.scan()?;
 
         // 2. Load or create vector store
@@ -120,12 +143,16 @@ impl ProjectIndexer {
             // Aggregate results
             for result in results {
                 match result {
-                    Ok((chunks, language, lines)) => {
+                    FileProcessOutcome::Processed(chunks, language, lines) => {
                         stats.add_file(&language, lines);
                         stats.add_chunks(chunks.len());
                         store.add_chunks(chunks).await?;
                     }
-                    Err(e) => {
+                    FileProcessOutcome::Skipped(path, reason) => {
+                        log::debug!("Skipping {}: {}", path, reason);
+                        stats.add_skipped(path, reason);
+                    }
+                    FileProcessOutcome::Error(e) => {
                         log::warn!("Failed to process file: {}", e);
                         stats.add_error(e);
                     }
@@ -197,10 +224,7 @@ impl ProjectIndexer {
     }
 
     /// Process files in parallel with concurrency limit
-    async fn process_files_parallel(
-        &self,
-        files: &[PathBuf],
-    ) -> Result<Vec<std::result::Result<(Vec<context_code_chunker::CodeChunk>, String, usize), String>>> {
+    async fn process_files_parallel(&self, files: &[PathBuf]) -> Result<Vec<FileProcessOutcome>> {
         // Parallel file reading (IO bound)
         const MAX_CONCURRENT: usize = 16;
 
@@ -209,8 +233,9 @@ impl ProjectIndexer {
         for file_chunk in files.chunks(MAX_CONCURRENT) {
             for file_path in file_chunk {
                 let file_path = file_path.clone();
+                let content_filter = self.content_filter;
                 let task = tokio::spawn(async move {
-                    Self::read_file_static(file_path).await
+                    Self::read_file_static(file_path, content_filter).await
                 });
                 tasks.push(task);
             }
@@ -219,12 +244,16 @@ impl ProjectIndexer {
             let mut batch_results = Vec::new();
             for task in tasks.drain(..) {
                 match task.await {
-                    Ok(Ok((file_path, content, lines))) => {
+                    Ok(Ok(FileReadOutcome::Kept(file_path, content, lines))) => {
                         // Process with chunker (CPU bound, sequential per batch)
                         match self.chunker.chunk_str(&content, file_path.to_str()) {
                             Ok(chunks) => {
                                 if chunks.is_empty() {
-                                    batch_results.push(Ok((vec![], "unknown".to_string(), lines)));
+                                    batch_results.push(FileProcessOutcome::Processed(
+                                        vec![],
+                                        "unknown".to_string(),
+                                        lines,
+                                    ));
                                 } else {
                                     let language = chunks[0]
                                         .metadata
@@ -232,16 +261,27 @@ impl ProjectIndexer {
                                         .as_deref()
                                         .unwrap_or("unknown")
                                         .to_string();
-                                    batch_results.push(Ok((chunks, language, lines)));
+                                    batch_results.push(FileProcessOutcome::Processed(
+                                        chunks, language, lines,
+                                    ));
                                 }
                             }
                             Err(e) => {
-                                batch_results.push(Err(format!("{:?}: {}", file_path, e)));
+                                batch_results
+                                    .push(FileProcessOutcome::Error(format!("{:?}: {}", file_path, e)));
                             }
                         }
                     }
-                    Ok(Err(e)) => batch_results.push(Err(e)),
-                    Err(e) => batch_results.push(Err(format!("Task panicked: {}", e))),
+                    Ok(Ok(FileReadOutcome::Skipped(file_path, reason))) => {
+                        batch_results.push(FileProcessOutcome::Skipped(
+                            file_path.to_string_lossy().to_string(),
+                            reason,
+                        ));
+                    }
+                    Ok(Err(e)) => batch_results.push(FileProcessOutcome::Error(e)),
+                    Err(e) => {
+                        batch_results.push(FileProcessOutcome::Error(format!("Task panicked: {}", e)))
+                    }
                 }
             }
 
@@ -251,17 +291,23 @@ impl ProjectIndexer {
         Ok(vec![])
     }
 
-    /// Static method for file reading (IO bound)
+    /// Static method for file reading (IO bound). Applies the content-quality filter right after
+    /// reading so rejected files never reach the chunker.
     async fn read_file_static(
         file_path: PathBuf,
-    ) -> std::result::Result<(PathBuf, String, usize), String> {
+        content_filter: ContentFilterConfig,
+    ) -> std::result::Result<FileReadOutcome, String> {
         let content = tokio::fs::read_to_string(&file_path)
             .await
             .map_err(|e| format!("{:?}: {}", file_path, e))?;
 
+        if let Some(reason) = classify_content(&content, &content_filter) {
+            return Ok(FileReadOutcome::Skipped(file_path, reason));
+        }
+
         let lines = content.lines().count();
 
-        Ok((file_path, content, lines))
+        Ok(FileReadOutcome::Kept(file_path, content, lines))
     }
 
     /// Process single file (legacy method, kept for compatibility)
@@ -274,6 +320,13 @@ impl ProjectIndexer {
         log::debug!("Processing file: {:?}", file_path);
 
         let content = tokio::fs::read_to_string(file_path).await?;
+
+        if let Some(reason) = classify_content(&content, &self.content_filter) {
+            log::debug!("Skipping {}: {}", file_path.display(), reason);
+            stats.add_skipped(file_path.to_string_lossy().to_string(), reason);
+            return Ok(());
+        }
+
         let lines = content.lines().count();
 
         // Chunk the file
@@ -32,11 +32,19 @@
 //! }
 //! ```
 
+mod content_filter;
 mod error;
+mod fs_version;
 mod indexer;
 mod scanner;
 mod stats;
 
+pub use content_filter::ContentFilterConfig;
 pub use error::{IndexerError, Result};
+pub use fs_version::{
+    compute_file_version, compute_fs_versions, diff_fs_versions, fs_versions_path,
+    read_fs_versions, removed_paths, write_fs_versions, FileVersion, FileVersionDiff, FsVersions,
+};
 pub use indexer::ProjectIndexer;
+pub use scanner::{FileScanner, ScanOptions};
 pub use stats::IndexStats;
@@ -1,31 +1,68 @@
 use ignore::WalkBuilder;
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
+use crate::error::{IndexerError, Result};
+
+/// Options controlling a [`FileScanner`] crawl.
+///
+/// Defaults match the historical behavior: `.gitignore`/`.ignore`/global excludes honored,
+/// unbounded depth, unbounded file count.
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    /// Opt-in override that bypasses `.gitignore`/`.ignore`/global excludes entirely.
+    pub all_files: bool,
+    /// Maximum directory depth to descend below the root (`None` = unbounded).
+    pub max_depth: Option<usize>,
+    /// Stop the walk once this many files have been collected (`None` = unbounded).
+    pub max_total_files: Option<usize>,
+}
+
 /// Scanner for finding source files in a project
 pub struct FileScanner {
     root: PathBuf,
+    options: ScanOptions,
+    seen_extensions: HashSet<String>,
 }
 
 impl FileScanner {
     pub fn new(root: impl AsRef<Path>) -> Self {
         Self {
             root: root.as_ref().to_path_buf(),
+            options: ScanOptions::default(),
+            seen_extensions: HashSet::new(),
         }
     }
 
-    /// Scan directory for source files (.gitignore aware)
-    pub fn scan(&self) -> Vec<PathBuf> {
+    /// Overrides the default crawl behavior (gitignore-awareness, depth, and file-count caps).
+    pub fn with_options(mut self, options: ScanOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Scan directory for source files (.gitignore aware by default; see [`ScanOptions`]).
+    ///
+    /// Refuses to walk a `root` that isn't a local filesystem path (see
+    /// [`Self::guard_local_root`]), and records the extensions of every file it finds so a later
+    /// [`rescan_for_changed_file`](Self::rescan_for_changed_file) can skip re-walking the tree.
+    pub fn scan(&mut self) -> Result<Vec<PathBuf>> {
+        Self::guard_local_root(&self.root)?;
+
         let mut files = Vec::new();
 
         let root = self.root.clone();
+        let honor_ignores = !self.options.all_files;
         let mut builder = WalkBuilder::new(&self.root);
         builder
             .hidden(true) // do not index hidden files by default
-            .git_ignore(true)
-            .git_global(true)
-            .git_exclude(true);
+            .ignore(honor_ignores)
+            .git_ignore(honor_ignores)
+            .git_global(honor_ignores)
+            .git_exclude(honor_ignores)
+            .max_depth(self.options.max_depth);
         builder.filter_entry(move |entry| !FileScanner::is_ignored_scope(entry.path(), &root));
 
+        let max_total_files = self.options.max_total_files;
         for result in builder.build() {
             match result {
                 Ok(entry) => {
@@ -58,14 +95,58 @@ impl FileScanner {
                         continue;
                     }
 
+                    if let Some(ext) = path.extension().and_then(|ext| ext.to_str()) {
+                        self.seen_extensions.insert(ext.to_lowercase());
+                    }
                     files.push(path.to_path_buf());
+
+                    if max_total_files.is_some_and(|max| files.len() >= max) {
+                        log::debug!("Reached max_total_files ({}), stopping crawl early", files.len());
+                        break;
+                    }
                 }
                 Err(e) => log::warn!("Failed to read entry: {e}"),
             }
         }
 
         log::info!("Found {} source files", files.len());
-        files
+        Ok(files)
+    }
+
+    /// `true` if a prior [`scan`](Self::scan) already indexed a file with this extension
+    /// (case-insensitive, no leading dot).
+    pub fn has_seen_extension(&self, ext: &str) -> bool {
+        self.seen_extensions.contains(&ext.to_lowercase())
+    }
+
+    /// Re-crawls the project unless `changed`'s extension class was already indexed by a prior
+    /// [`scan`](Self::scan), in which case this returns `Ok(None)` and skips the walk entirely.
+    /// Intended for callers that react to a single changed file but only need a fresh file list
+    /// when that file could plausibly shift anchor/boundary detection (i.e. its extension hasn't
+    /// been seen before).
+    pub fn rescan_for_changed_file(&mut self, changed: &Path) -> Result<Option<Vec<PathBuf>>> {
+        let already_known = changed
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| self.has_seen_extension(ext));
+        if already_known {
+            return Ok(None);
+        }
+        self.scan().map(Some)
+    }
+
+    /// Refuses to crawl a root that isn't a local filesystem path, i.e. a URI with any scheme
+    /// other than `file` (`s3://`, `ssh://`, ...) slipped in from an MCP client.
+    fn guard_local_root(root: &Path) -> Result<()> {
+        let display = root.to_string_lossy();
+        if let Some((scheme, rest)) = display.split_once("://") {
+            if !rest.is_empty() && !scheme.eq_ignore_ascii_case("file") {
+                return Err(IndexerError::InvalidPath(format!(
+                    "refusing to crawl non-local root '{display}' (scheme '{scheme}')"
+                )));
+            }
+        }
+        Ok(())
     }
 
     /// Check if file is a source code file
@@ -295,9 +376,10 @@ const SUPPORTED_EXTENSIONS: &[&str] = &[
 
 #[cfg(test)]
 mod tests {
-    use super::FileScanner;
+    use super::{FileScanner, ScanOptions};
     use pretty_assertions::assert_eq;
     use std::fs;
+    use std::path::Path;
     use tempfile::tempdir;
 
     #[test]
@@ -309,8 +391,8 @@ mod tests {
         fs::write(bench_logs.join("non_empty.json"), b"{\"ok\":true}").unwrap();
         fs::write(temp.path().join("main.rs"), b"fn main() {}").unwrap();
 
-        let scanner = FileScanner::new(temp.path());
-        let files = scanner.scan();
+        let mut scanner = FileScanner::new(temp.path());
+        let files = scanner.scan().unwrap();
 
         assert!(files
             .iter()
@@ -328,8 +410,8 @@ mod tests {
         fs::write(temp.path().join("src.rs"), b"fn main() {}").unwrap();
         fs::write(temp.path().join(".gitignore"), b"/datasets").unwrap();
 
-        let scanner = FileScanner::new(temp.path());
-        let files = scanner.scan();
+        let mut scanner = FileScanner::new(temp.path());
+        let files = scanner.scan().unwrap();
 
         assert!(files
             .iter()
@@ -337,4 +419,66 @@ mod tests {
         assert!(files.iter().any(|p| p.ends_with("src.rs")));
         assert!(files.iter().all(|p| !p.ends_with(".gitignore")));
     }
+
+    #[test]
+    fn all_files_option_bypasses_gitignore() {
+        let temp = tempdir().unwrap();
+        // `.gitignore` rules only apply inside a git repository.
+        fs::create_dir(temp.path().join(".git")).unwrap();
+        fs::write(temp.path().join("src.rs"), b"fn main() {}").unwrap();
+        fs::write(temp.path().join("ignored.rs"), b"fn skip() {}").unwrap();
+        fs::write(temp.path().join(".gitignore"), b"ignored.rs").unwrap();
+
+        let mut default_scanner = FileScanner::new(temp.path());
+        let default_files = default_scanner.scan().unwrap();
+        assert!(default_files.iter().all(|p| !p.ends_with("ignored.rs")));
+
+        let mut all_files_scanner = FileScanner::new(temp.path()).with_options(ScanOptions {
+            all_files: true,
+            ..Default::default()
+        });
+        let all_files = all_files_scanner.scan().unwrap();
+        assert!(all_files.iter().any(|p| p.ends_with("ignored.rs")));
+    }
+
+    #[test]
+    fn max_total_files_caps_the_walk() {
+        let temp = tempdir().unwrap();
+        for i in 0..5 {
+            fs::write(temp.path().join(format!("f{i}.rs")), b"fn main() {}").unwrap();
+        }
+
+        let mut scanner = FileScanner::new(temp.path()).with_options(ScanOptions {
+            max_total_files: Some(2),
+            ..Default::default()
+        });
+        let files = scanner.scan().unwrap();
+        assert_eq!(files.len(), 2);
+    }
+
+    #[test]
+    fn rescan_for_changed_file_skips_known_extension_classes() {
+        let temp = tempdir().unwrap();
+        fs::write(temp.path().join("a.rs"), b"fn a() {}").unwrap();
+
+        let mut scanner = FileScanner::new(temp.path());
+        scanner.scan().unwrap();
+        assert!(scanner.has_seen_extension("rs"));
+
+        let rescanned = scanner
+            .rescan_for_changed_file(Path::new("b.rs"))
+            .unwrap();
+        assert!(rescanned.is_none());
+
+        let rescanned = scanner
+            .rescan_for_changed_file(Path::new("c.py"))
+            .unwrap();
+        assert!(rescanned.is_some());
+    }
+
+    #[test]
+    fn rejects_non_local_root() {
+        let mut scanner = FileScanner::new("s3://bucket/project");
+        assert!(scanner.scan().is_err());
+    }
 }
@@ -20,6 +20,9 @@ pub struct IndexStats {
 
     /// Errors encountered
     pub errors: Vec<String>,
+
+    /// Files rejected by the content-quality filter before chunking, as (path, reason) pairs.
+    pub skipped: Vec<(String, String)>,
 }
 
 impl IndexStats {
@@ -31,6 +34,7 @@ impl IndexStats {
             time_ms: 0,
             languages: std::collections::HashMap::new(),
             errors: Vec::new(),
+            skipped: Vec::new(),
         }
     }
 
@@ -47,6 +51,10 @@ impl IndexStats {
     pub fn add_error(&mut self, error: String) {
         self.errors.push(error);
     }
+
+    pub fn add_skipped(&mut self, path: String, reason: String) {
+        self.skipped.push((path, reason));
+    }
 }
 
 impl Default for IndexStats {
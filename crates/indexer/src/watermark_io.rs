@@ -3,8 +3,9 @@ use crate::{IndexerError, Result, Watermark};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::cmp::max;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::time::{timeout, Duration};
 
@@ -12,13 +13,43 @@ const INDEX_WATERMARK_FILE_NAME: &str = "watermark.json";
 
 // Watermark computation must be cheap and bounded. Some repos (dataset-heavy / many untracked files)
 // can make `git status` extremely slow; in those cases we fall back to a filesystem watermark.
+//
+// With the `git2-backend` feature enabled, `probe_git_state`/`probe_git_changed_paths_between_heads`
+// use `git2` (libgit2) in-process instead of shelling out, which avoids both the subprocess
+// overhead and the need for the timeouts below. Without the feature, the original `git` subprocess
+// implementation is compiled in as a fallback.
+#[cfg(not(feature = "git2-backend"))]
 const GIT_HEAD_TIMEOUT: Duration = Duration::from_millis(1_000);
+#[cfg(not(feature = "git2-backend"))]
 const GIT_STATUS_TIMEOUT: Duration = Duration::from_millis(2_000);
+#[cfg(not(feature = "git2-backend"))]
+const GIT_SUBMODULE_TIMEOUT: Duration = Duration::from_millis(1_000);
+
+// A commit inside a submodule (or a nested checked-in repo) doesn't move the top-level HEAD or
+// porcelain output, so we probe submodules separately and mix their resolved heads into the
+// watermark. Capped so a super-repo with hundreds of submodules can't blow the latency budget.
+const MAX_SUBMODULES_PROBED: usize = 64;
+
+/// Compact per-path fingerprint persisted alongside the watermark so a later index can diff
+/// precisely against the last-known tree state, instead of only being able to tell "stale or not."
+/// Mirrors Zed's worktree `LocalSnapshot`, minus everything that snapshot needs for live editing.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Snapshot {
+    pub entries: HashMap<PathBuf, SnapshotEntry>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub(crate) struct SnapshotEntry {
+    pub len: u64,
+    pub mtime_ms: u64,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PersistedIndexWatermark {
     pub built_at_unix_ms: u64,
     pub watermark: Watermark,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) snapshot: Option<Snapshot>,
 }
 
 pub fn index_watermark_path_for_store(store_path: &Path) -> Result<PathBuf> {
@@ -28,16 +59,25 @@ pub fn index_watermark_path_for_store(store_path: &Path) -> Result<PathBuf> {
     Ok(dir.join(INDEX_WATERMARK_FILE_NAME))
 }
 
-pub async fn write_index_watermark(store_path: &Path, watermark: Watermark) -> Result<()> {
+pub async fn write_index_watermark(
+    project_root: &Path,
+    store_path: &Path,
+    watermark: Watermark,
+) -> Result<()> {
     let path = index_watermark_path_for_store(store_path)?;
     if let Some(parent) = path.parent() {
         tokio::fs::create_dir_all(parent).await?;
     }
 
+    // Best-effort: a project we can't re-scan for a snapshot still gets a watermark, it just loses
+    // the precise-diff fast path on the next index.
+    let snapshot = build_snapshot(project_root).await.ok();
+
     let built_at_unix_ms = unix_now_ms();
     let persisted = PersistedIndexWatermark {
         built_at_unix_ms,
         watermark,
+        snapshot,
     };
 
     let bytes = serde_json::to_vec_pretty(&persisted)?;
@@ -63,6 +103,43 @@ pub async fn compute_project_watermark(project_root: &Path) -> Result<Watermark>
     compute_filesystem_watermark(project_root).await
 }
 
+/// Precise changed-path delta between `prior` (the last-persisted watermark) and `current`, for
+/// scoping a reindex to just the files that actually moved instead of rewalking and re-embedding
+/// everything. Prefers a git tree diff when both watermarks carry a resolvable HEAD (cheapest and
+/// most precise); falls back to diffing `prior`'s persisted [`Snapshot`] against the live tree
+/// otherwise (non-git projects, or the dirty-tree case where `git status` only says *that*
+/// something changed). Returns `None` when there's no usable prior state to diff against, or the
+/// delta exceeds `max_paths` — callers should treat that as "fall back to a full reindex".
+pub async fn changed_paths_since(
+    project_root: &Path,
+    prior: &PersistedIndexWatermark,
+    current: &Watermark,
+    max_paths: usize,
+) -> Option<Vec<PathBuf>> {
+    if let (
+        Watermark::Git {
+            git_head: old_head,
+            git_dirty: false,
+            ..
+        },
+        Watermark::Git {
+            git_head: new_head,
+            git_dirty: false,
+            ..
+        },
+    ) = (&prior.watermark, current)
+    {
+        if let Some(paths) =
+            probe_git_changed_paths_between_heads(project_root, old_head, new_head, max_paths).await
+        {
+            return Some(paths);
+        }
+    }
+
+    let snapshot = prior.snapshot.as_ref()?;
+    changed_paths_since_snapshot(project_root, snapshot, max_paths).await
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct GitState {
     pub computed_at_unix_ms: u64,
@@ -70,8 +147,145 @@ pub(crate) struct GitState {
     pub git_dirty: bool,
     pub dirty_hash: Option<u64>,
     pub dirty_paths: Vec<PathBuf>,
+    pub submodule_heads: Vec<(PathBuf, String)>,
+}
+
+/// Mixes `submodule_heads` into `dirty_hash` so a commit or dirty change entirely inside a
+/// submodule still moves the watermark, even when the superproject's own HEAD and status are
+/// unchanged. Returns the original `dirty_hash` unchanged when there are no submodules.
+fn fold_submodule_heads_into_dirty_hash(
+    dirty_hash: Option<u64>,
+    submodule_heads: &[(PathBuf, String)],
+) -> Option<u64> {
+    if submodule_heads.is_empty() {
+        return dirty_hash;
+    }
+
+    let mut hasher = Sha256::new();
+    if let Some(h) = dirty_hash {
+        hasher.update(h.to_be_bytes());
+    }
+    for (path, head) in submodule_heads {
+        hasher.update(path.to_string_lossy().as_bytes());
+        hasher.update(head.as_bytes());
+    }
+    let digest = hasher.finalize();
+    Some(u64::from_be_bytes([
+        digest[0], digest[1], digest[2], digest[3], digest[4], digest[5], digest[6], digest[7],
+    ]))
 }
 
+#[cfg(feature = "git2-backend")]
+pub(crate) async fn probe_git_state(project_root: &Path) -> Option<GitState> {
+    let root = project_root.to_path_buf();
+    tokio::task::spawn_blocking(move || probe_git_state_sync(&root))
+        .await
+        .ok()
+        .flatten()
+}
+
+#[cfg(feature = "git2-backend")]
+fn probe_git_state_sync(project_root: &Path) -> Option<GitState> {
+    let repo = git2::Repository::discover(project_root).ok()?;
+    let git_head = repo.head().ok()?.peel_to_commit().ok()?.id().to_string();
+
+    let mut status_options = git2::StatusOptions::new();
+    status_options
+        .include_untracked(true)
+        .recurse_untracked_dirs(false);
+    let statuses = repo.statuses(Some(&mut status_options)).ok()?;
+    let git_dirty = !statuses.is_empty();
+
+    let (dirty_hash, dirty_paths) = if git_dirty {
+        const MAX_DIRTY_PATHS_FOR_HASH: usize = 512;
+
+        let mut hasher = Sha256::new();
+        let mut dirty_paths_buf: Vec<PathBuf> = Vec::new();
+
+        // Mirrors the subprocess backend's freshness trick: mix in filesystem mtimes/sizes for the
+        // dirty paths (bounded), since the status itself doesn't change when a dirty file is
+        // modified again.
+        for entry in statuses.iter().take(MAX_DIRTY_PATHS_FOR_HASH) {
+            let Some(path) = entry.path() else {
+                continue;
+            };
+            hasher.update(path.as_bytes());
+            dirty_paths_buf.push(PathBuf::from(path));
+
+            let candidate = project_root.join(path);
+            if let Ok(meta) = std::fs::metadata(&candidate) {
+                hasher.update(meta.len().to_be_bytes());
+                if let Ok(modified) = meta.modified() {
+                    let mtime_ms = modified
+                        .duration_since(UNIX_EPOCH)
+                        .map(|d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+                        .unwrap_or(0);
+                    hasher.update(mtime_ms.to_be_bytes());
+                } else {
+                    hasher.update(0u64.to_be_bytes());
+                }
+            } else {
+                hasher.update(0u64.to_be_bytes());
+                hasher.update(0u64.to_be_bytes());
+            }
+        }
+
+        let digest = hasher.finalize();
+        let dirty_hash = Some(u64::from_be_bytes([
+            digest[0], digest[1], digest[2], digest[3], digest[4], digest[5], digest[6], digest[7],
+        ]));
+        (dirty_hash, dirty_paths_buf)
+    } else {
+        (None, Vec::new())
+    };
+
+    let submodule_heads = probe_submodule_heads_git2(&repo);
+    let dirty_hash = fold_submodule_heads_into_dirty_hash(dirty_hash, &submodule_heads);
+
+    Some(GitState {
+        computed_at_unix_ms: unix_now_ms(),
+        git_head,
+        git_dirty,
+        dirty_hash,
+        dirty_paths,
+        submodule_heads,
+    })
+}
+
+#[cfg(feature = "git2-backend")]
+fn probe_submodule_heads_git2(repo: &git2::Repository) -> Vec<(PathBuf, String)> {
+    let Ok(submodules) = repo.submodules() else {
+        return Vec::new();
+    };
+
+    let mut out = Vec::with_capacity(submodules.len().min(MAX_SUBMODULES_PROBED));
+    for submodule in submodules.iter().take(MAX_SUBMODULES_PROBED) {
+        let Some(head_id) = submodule.workdir_id().or_else(|| submodule.head_id()) else {
+            continue;
+        };
+        let dirty = submodule
+            .name()
+            .and_then(|name| repo.submodule_status(name, git2::SubmoduleIgnore::None).ok())
+            .map(|status| {
+                status.intersects(
+                    git2::SubmoduleStatus::WD_MODIFIED
+                        | git2::SubmoduleStatus::WD_WD_MODIFIED
+                        | git2::SubmoduleStatus::WD_UNTRACKED
+                        | git2::SubmoduleStatus::WD_INDEX_MODIFIED,
+                )
+            })
+            .unwrap_or(false);
+        let head = if dirty {
+            format!("{}+dirty", head_id)
+        } else {
+            head_id.to_string()
+        };
+        out.push((submodule.path().to_path_buf(), head));
+    }
+    out
+}
+
+#[cfg(not(feature = "git2-backend"))]
 pub(crate) async fn probe_git_state(project_root: &Path) -> Option<GitState> {
     let head = timeout(
         GIT_HEAD_TIMEOUT,
@@ -181,15 +395,127 @@ pub(crate) async fn probe_git_state(project_root: &Path) -> Option<GitState> {
         (None, Vec::new())
     };
 
+    let submodule_heads = probe_submodule_heads_subprocess(project_root).await;
+    let dirty_hash = fold_submodule_heads_into_dirty_hash(dirty_hash, &submodule_heads);
+
     Some(GitState {
         computed_at_unix_ms: unix_now_ms(),
         git_head,
         git_dirty,
         dirty_hash,
         dirty_paths,
+        submodule_heads,
     })
 }
 
+#[cfg(not(feature = "git2-backend"))]
+async fn probe_submodule_heads_subprocess(project_root: &Path) -> Vec<(PathBuf, String)> {
+    let Ok(Ok(output)) = timeout(
+        GIT_SUBMODULE_TIMEOUT,
+        tokio::process::Command::new("git")
+            .arg("-C")
+            .arg(project_root)
+            .arg("submodule")
+            .arg("status")
+            .output(),
+    )
+    .await
+    else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut out = Vec::new();
+    for line in text.lines().take(MAX_SUBMODULES_PROBED) {
+        let line = line.trim_start();
+        let Some(marker) = line.chars().next() else {
+            continue;
+        };
+        let rest = if marker == '-' || marker == '+' || marker == 'U' {
+            &line[1..]
+        } else {
+            line
+        };
+        let mut parts = rest.split_whitespace();
+        let Some(sha) = parts.next() else {
+            continue;
+        };
+        let Some(path) = parts.next() else {
+            continue;
+        };
+        // `-` means the submodule isn't checked out at all; there's no workdir state to watermark.
+        if marker == '-' {
+            continue;
+        }
+        let head = if marker == '+' {
+            format!("{sha}+dirty")
+        } else {
+            sha.to_string()
+        };
+        out.push((PathBuf::from(path), head));
+    }
+    out
+}
+
+#[cfg(feature = "git2-backend")]
+pub(crate) async fn probe_git_changed_paths_between_heads(
+    project_root: &Path,
+    old_head: &str,
+    new_head: &str,
+    max_paths: usize,
+) -> Option<Vec<PathBuf>> {
+    let old_head = old_head.trim().to_string();
+    let new_head = new_head.trim().to_string();
+    let root = project_root.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        probe_git_changed_paths_between_heads_sync(&root, &old_head, &new_head, max_paths)
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+#[cfg(feature = "git2-backend")]
+fn probe_git_changed_paths_between_heads_sync(
+    project_root: &Path,
+    old_head: &str,
+    new_head: &str,
+    max_paths: usize,
+) -> Option<Vec<PathBuf>> {
+    if old_head.is_empty() || new_head.is_empty() {
+        return None;
+    }
+    if old_head == new_head {
+        return Some(Vec::new());
+    }
+
+    let repo = git2::Repository::discover(project_root).ok()?;
+    let old_tree = repo.revparse_single(old_head).ok()?.peel_to_tree().ok()?;
+    let new_tree = repo.revparse_single(new_head).ok()?.peel_to_tree().ok()?;
+    let diff = repo
+        .diff_tree_to_tree(Some(&old_tree), Some(&new_tree), None)
+        .ok()?;
+
+    let mut changed: HashSet<PathBuf> = HashSet::new();
+    for delta in diff.deltas() {
+        if let Some(path) = delta.old_file().path() {
+            changed.insert(path.to_path_buf());
+        }
+        if let Some(path) = delta.new_file().path() {
+            changed.insert(path.to_path_buf());
+        }
+        if changed.len() > max_paths {
+            return None;
+        }
+    }
+
+    Some(changed.into_iter().collect())
+}
+
+#[cfg(not(feature = "git2-backend"))]
 pub(crate) async fn probe_git_changed_paths_between_heads(
     project_root: &Path,
     old_head: &str,
@@ -274,23 +600,36 @@ async fn try_compute_git_watermark(project_root: &Path) -> Option<Watermark> {
         git_head: state.git_head,
         git_dirty: state.git_dirty,
         dirty_hash: state.dirty_hash,
+        submodule_heads: state.submodule_heads,
     })
 }
 
 async fn compute_filesystem_watermark(project_root: &Path) -> Result<Watermark> {
+    let (watermark, _sizes) = scan_filesystem_watermark(project_root).await?;
+    Ok(watermark)
+}
+
+/// Full `FileScanner` walk, `stat()`-ing every file. Returns both the aggregate watermark and the
+/// per-path sizes the aggregate was built from, so [`FilesystemWatermarkWatcher`] can apply
+/// create/modify/remove deltas against the same baseline instead of re-walking the tree.
+async fn scan_filesystem_watermark(
+    project_root: &Path,
+) -> Result<(Watermark, HashMap<PathBuf, u64>)> {
     let root = project_root.to_path_buf();
     tokio::task::spawn_blocking(move || {
-        let scanner = FileScanner::new(&root);
-        let files = scanner.scan();
+        let mut scanner = FileScanner::new(&root);
+        let files = scanner.scan()?;
 
         let mut file_count = 0u64;
         let mut total_bytes = 0u64;
         let mut max_mtime_ms = 0u64;
+        let mut sizes = HashMap::with_capacity(files.len());
 
         for path in files {
             let meta = std::fs::metadata(&path)?;
             file_count += 1;
             total_bytes = total_bytes.saturating_add(meta.len());
+            sizes.insert(path, meta.len());
             if let Ok(modified) = meta.modified() {
                 let mtime_ms = modified
                     .duration_since(UNIX_EPOCH)
@@ -300,17 +639,299 @@ async fn compute_filesystem_watermark(project_root: &Path) -> Result<Watermark>
             }
         }
 
-        Ok::<_, IndexerError>(Watermark::Filesystem {
+        let watermark = Watermark::Filesystem {
             computed_at_unix_ms: Some(unix_now_ms()),
             file_count,
             max_mtime_ms,
             total_bytes,
-        })
+        };
+        Ok::<_, IndexerError>((watermark, sizes))
     })
     .await
     .map_err(|e| IndexerError::Other(format!("failed to compute filesystem watermark: {e}")))?
 }
 
+async fn build_snapshot(project_root: &Path) -> Result<Snapshot> {
+    let root = project_root.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let mut scanner = FileScanner::new(&root);
+        let mut entries = HashMap::new();
+
+        for path in scanner.scan()? {
+            let Ok(rel) = path.strip_prefix(&root) else {
+                continue;
+            };
+            let meta = std::fs::metadata(&path)?;
+            let mtime_ms = meta
+                .modified()
+                .ok()
+                .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+                .map(|d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+                .unwrap_or(0);
+            entries.insert(
+                rel.to_path_buf(),
+                SnapshotEntry {
+                    len: meta.len(),
+                    mtime_ms,
+                },
+            );
+        }
+
+        Ok::<_, IndexerError>(Snapshot { entries })
+    })
+    .await
+    .map_err(|e| IndexerError::Other(format!("failed to build snapshot: {e}")))?
+}
+
+/// Diffs the current tree against a previously persisted [`Snapshot`], used when there's no git
+/// history to diff against (non-git projects, or the dirty/untracked case where `git status` only
+/// tells us *that* something changed, not *what*). Walks the tree via [`FileScanner`] and compares
+/// `(len, mtime_ms)` per relative path; added/modified paths and paths missing from the current
+/// scan (deletions) are all reported as changed. Bails to `None` past `max_paths`, same as
+/// [`probe_git_changed_paths_between_heads`].
+pub(crate) async fn changed_paths_since_snapshot(
+    project_root: &Path,
+    prev: &Snapshot,
+    max_paths: usize,
+) -> Option<Vec<PathBuf>> {
+    let root = project_root.to_path_buf();
+    let prev = prev.clone();
+    tokio::task::spawn_blocking(move || changed_paths_since_snapshot_sync(&root, &prev, max_paths))
+        .await
+        .ok()
+        .flatten()
+}
+
+fn changed_paths_since_snapshot_sync(
+    project_root: &Path,
+    prev: &Snapshot,
+    max_paths: usize,
+) -> Option<Vec<PathBuf>> {
+    let mut scanner = FileScanner::new(project_root);
+
+    let mut changed: HashSet<PathBuf> = HashSet::new();
+    let mut seen: HashSet<PathBuf> = HashSet::new();
+
+    for path in scanner.scan().ok()? {
+        let rel = path.strip_prefix(project_root).ok()?.to_path_buf();
+        let meta = std::fs::metadata(&path).ok()?;
+        let mtime_ms = meta
+            .modified()
+            .ok()
+            .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+            .map(|d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+            .unwrap_or(0);
+
+        let unchanged = prev
+            .entries
+            .get(&rel)
+            .is_some_and(|entry| entry.len == meta.len() && entry.mtime_ms == mtime_ms);
+        if !unchanged {
+            changed.insert(rel.clone());
+            if changed.len() > max_paths {
+                return None;
+            }
+        }
+        seen.insert(rel);
+    }
+
+    for rel in prev.entries.keys() {
+        if !seen.contains(rel) {
+            changed.insert(rel.clone());
+            if changed.len() > max_paths {
+                return None;
+            }
+        }
+    }
+
+    Some(changed.into_iter().collect())
+}
+
+/// Debounce window over which a burst of filesystem events is coalesced before the watcher
+/// re-publishes an updated [`Watermark::Filesystem`].
+const WATERMARK_WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Keeps a [`Watermark::Filesystem`] current without re-walking the project tree on every check.
+///
+/// `start` does one full `FileScanner` walk to seed the aggregate (`file_count`, `total_bytes`,
+/// `max_mtime_ms`), then a background task applies size/mtime deltas from `notify` filesystem
+/// events, coalesced over [`WATERMARK_WATCH_DEBOUNCE`]. `current` reads the aggregate in O(1).
+/// If the watcher failed to start, or the event stream reported a dropped/overflowed batch, the
+/// next `current` call transparently falls back to a full rescan and reseeds from it.
+pub struct FilesystemWatermarkWatcher {
+    inner: Arc<WatermarkWatchState>,
+    _watcher: Option<notify::RecommendedWatcher>,
+}
+
+struct WatermarkWatchState {
+    project_root: PathBuf,
+    watermark: std::sync::Mutex<Watermark>,
+    sizes: std::sync::Mutex<HashMap<PathBuf, u64>>,
+    needs_rescan: std::sync::atomic::AtomicBool,
+}
+
+impl FilesystemWatermarkWatcher {
+    pub async fn start(project_root: &Path) -> Result<Self> {
+        let (watermark, sizes) = scan_filesystem_watermark(project_root).await?;
+        let inner = Arc::new(WatermarkWatchState {
+            project_root: project_root.to_path_buf(),
+            watermark: std::sync::Mutex::new(watermark),
+            sizes: std::sync::Mutex::new(sizes),
+            needs_rescan: std::sync::atomic::AtomicBool::new(false),
+        });
+
+        let watcher = match spawn_watermark_watcher(Arc::clone(&inner)) {
+            Ok(watcher) => Some(watcher),
+            Err(err) => {
+                log::warn!(
+                    "filesystem watermark watcher unavailable for {}, falling back to full scans: {err}",
+                    project_root.display()
+                );
+                None
+            }
+        };
+
+        Ok(Self {
+            inner,
+            _watcher: watcher,
+        })
+    }
+
+    /// Current filesystem watermark, in O(1) unless a rescan is pending.
+    pub async fn current(&self) -> Result<Watermark> {
+        let needs_rescan = self._watcher.is_none()
+            || self
+                .inner
+                .needs_rescan
+                .swap(false, std::sync::atomic::Ordering::SeqCst);
+        if !needs_rescan {
+            let watermark = self
+                .inner
+                .watermark
+                .lock()
+                .map_err(|_| IndexerError::Other("watermark lock poisoned".to_string()))?
+                .clone();
+            return Ok(watermark);
+        }
+
+        let (watermark, sizes) = scan_filesystem_watermark(&self.inner.project_root).await?;
+        *self
+            .inner
+            .watermark
+            .lock()
+            .map_err(|_| IndexerError::Other("watermark lock poisoned".to_string()))? = watermark.clone();
+        *self
+            .inner
+            .sizes
+            .lock()
+            .map_err(|_| IndexerError::Other("watermark sizes lock poisoned".to_string()))? = sizes;
+        Ok(watermark)
+    }
+}
+
+fn spawn_watermark_watcher(
+    state: Arc<WatermarkWatchState>,
+) -> Result<notify::RecommendedWatcher> {
+    use notify::Watcher;
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<notify::Result<notify::Event>>(1024);
+    let mut watcher = notify::RecommendedWatcher::new(
+        move |res| {
+            let _ = tx.blocking_send(res);
+        },
+        notify::Config::default(),
+    )
+    .map_err(|e| IndexerError::Other(format!("watermark watcher init failed: {e}")))?;
+    watcher
+        .watch(&state.project_root, notify::RecursiveMode::Recursive)
+        .map_err(|e| IndexerError::Other(format!("watermark watcher watch failed: {e}")))?;
+
+    tokio::spawn(async move {
+        let mut pending: Vec<notify::Event> = Vec::new();
+        loop {
+            match timeout(WATERMARK_WATCH_DEBOUNCE, rx.recv()).await {
+                Ok(Some(Ok(event))) => pending.push(event),
+                Ok(Some(Err(_))) => {
+                    state
+                        .needs_rescan
+                        .store(true, std::sync::atomic::Ordering::SeqCst);
+                }
+                Ok(None) => break,
+                Err(_elapsed) => {
+                    if !pending.is_empty() {
+                        apply_watermark_events(&state, pending.drain(..));
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(watcher)
+}
+
+fn apply_watermark_events(state: &WatermarkWatchState, events: impl Iterator<Item = notify::Event>) {
+    let Ok(mut watermark) = state.watermark.lock() else {
+        return;
+    };
+    let Ok(mut sizes) = state.sizes.lock() else {
+        return;
+    };
+    let Watermark::Filesystem {
+        computed_at_unix_ms,
+        file_count,
+        max_mtime_ms,
+        total_bytes,
+    } = &mut *watermark
+    else {
+        return;
+    };
+
+    for event in events {
+        match event.kind {
+            notify::EventKind::Create(_) | notify::EventKind::Modify(_) => {
+                for path in &event.paths {
+                    let Ok(meta) = std::fs::metadata(path) else {
+                        continue;
+                    };
+                    if !meta.is_file() {
+                        continue;
+                    }
+                    let new_len = meta.len();
+                    match sizes.insert(path.clone(), new_len) {
+                        Some(old_len) => {
+                            *total_bytes = total_bytes
+                                .saturating_sub(old_len)
+                                .saturating_add(new_len);
+                        }
+                        None => {
+                            *file_count += 1;
+                            *total_bytes = total_bytes.saturating_add(new_len);
+                        }
+                    }
+                    if let Ok(modified) = meta.modified() {
+                        let mtime_ms = modified
+                            .duration_since(UNIX_EPOCH)
+                            .map(|d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+                            .unwrap_or(0);
+                        *max_mtime_ms = max(*max_mtime_ms, mtime_ms);
+                    }
+                }
+            }
+            notify::EventKind::Remove(_) => {
+                for path in &event.paths {
+                    if let Some(old_len) = sizes.remove(path) {
+                        *file_count = file_count.saturating_sub(1);
+                        *total_bytes = total_bytes.saturating_sub(old_len);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    *computed_at_unix_ms = Some(unix_now_ms());
+}
+
 fn unix_now_ms() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
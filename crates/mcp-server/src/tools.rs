@@ -12,8 +12,9 @@ use context_graph::{
 };
 use context_indexer::FileScanner;
 use context_search::{
-    ContextPackBudget, ContextPackItem, ContextPackOutput, MultiModelContextSearch,
-    MultiModelHybridSearch, QueryClassifier, QueryType, SearchProfile, CONTEXT_PACK_VERSION,
+    apply_ranking_rules, ContextPackBudget, ContextPackItem, ContextPackOutput, LineIndex,
+    MultiModelContextSearch, MultiModelHybridSearch, OffsetEncoding, QueryClassifier, QueryType,
+    RankingBreakdown, SearchProfile, CONTEXT_PACK_VERSION,
 };
 use context_vector_store::{
     corpus_path_for_project_root, current_model_id, ChunkCorpus, GraphNodeDoc, GraphNodeStore,
@@ -375,8 +376,8 @@ async fn compute_map_result(
             }
         }
         None => {
-            let scanner = FileScanner::new(root);
-            let files = scanner.scan();
+            let mut scanner = FileScanner::new(root);
+            let files = scanner.scan()?;
             let chunker = Chunker::new(chunker_config_for_map());
 
             for file in files {
@@ -536,8 +537,8 @@ async fn compute_list_files_result(
         None => {
             source = "filesystem".to_string();
 
-            let scanner = FileScanner::new(root);
-            let scanned = scanner.scan();
+            let mut scanner = FileScanner::new(root);
+            let scanned = scanner.scan()?;
             scanned_files = scanned.len();
 
             let mut candidates: Vec<String> = scanned
@@ -733,8 +734,8 @@ async fn compute_grep_context_result(
                 }
             }
             None => {
-                let scanner = FileScanner::new(root);
-                let files = scanner.scan();
+                let mut scanner = FileScanner::new(root);
+                let files = scanner.scan()?;
                 let mut rels: Vec<String> = files
                     .into_iter()
                     .filter_map(|p| normalize_relative_path(root, &p))
@@ -2137,6 +2138,29 @@ pub struct SearchRequest {
     /// Maximum results (default: 10)
     #[schemars(description = "Maximum number of results (1-50)")]
     pub limit: Option<usize>,
+
+    /// Ordered tie-breaking rules applied on top of the fused semantic/fuzzy score (default:
+    /// exactness, words, proximity, attribute, semantic). Unrecognized names are ignored.
+    #[schemars(
+        description = "Ordered ranking rules (e.g. [\"exactness\",\"words\",\"proximity\",\"attribute\",\"semantic\"]); defaults to that order when omitted"
+    )]
+    pub ranking_rules: Option<Vec<String>>,
+
+    /// Offset encoding for each result's precise `range` ("utf8", "utf16", or "char"). Omit to
+    /// skip computing ranges, leaving `range` null -- unrecognized values do the same.
+    #[schemars(description = "Offset encoding for precise ranges: utf8 | utf16 | char")]
+    pub offset_encoding: Option<String>,
+}
+
+/// A result's precise content span in the requested offset encoding, for editor/LSP clients that
+/// can't work off `start_line`/`end_line` alone (e.g. VS Code addresses text by UTF-16 offset).
+/// Always spans the whole of `SearchResult::content`, i.e. `start` is `0`.
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+pub struct SearchRange {
+    /// "utf8", "utf16", or "char" -- matches the request's `offset_encoding`.
+    pub encoding: String,
+    pub start: usize,
+    pub end: usize,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -2155,6 +2179,12 @@ pub struct SearchResult {
     pub score: f32,
     /// Code content
     pub content: String,
+    /// Per-rule contribution behind `score`'s ranking, for transparency into why this result
+    /// placed where it did.
+    pub ranking: RankingBreakdown,
+    /// Precise content span in the encoding requested via `offset_encoding`; `None` if that
+    /// field was omitted from the request.
+    pub range: Option<SearchRange>,
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -3025,7 +3055,7 @@ impl ContextFinderService {
         } else {
             source = "filesystem".to_string();
 
-            let scanner = FileScanner::new(&root);
+            let mut scanner = FileScanner::new(&root);
             let (start_file_index, start_line_offset) = match cursor_mode.as_ref() {
                 None => (0usize, 0usize),
                 Some(TextSearchCursorModeV1::Filesystem {
@@ -3039,8 +3069,15 @@ impl ContextFinderService {
                 }
             };
 
-            let mut candidates: Vec<(String, PathBuf)> = scanner
-                .scan()
+            let scanned = match scanner.scan() {
+                Ok(files) => files,
+                Err(e) => {
+                    return Ok(CallToolResult::error(vec![Content::text(format!(
+                        "Error: {e:#}"
+                    ))]));
+                }
+            };
+            let mut candidates: Vec<(String, PathBuf)> = scanned
                 .into_iter()
                 .filter_map(|file| normalize_relative_path(&root, &file).map(|rel| (rel, file)))
                 .filter(|(rel, _)| Self::matches_file_pattern(rel, file_pattern))
@@ -4113,11 +4150,15 @@ impl ContextFinderService {
             }
         };
 
+        // Fetch a wider candidate pool than `limit` so the ranking-rules pass below has room to
+        // reorder before truncating -- the fused score `hybrid_mut().search()` sorts by isn't
+        // necessarily the order a sharper rule (e.g. `exactness`) would pick.
+        let candidate_limit = (limit.saturating_mul(4)).min(200);
         let results = match engine
             .engine_mut()
             .context_search
             .hybrid_mut()
-            .search(&request.query, limit)
+            .search(&request.query, candidate_limit)
             .await
         {
             Ok(r) => r,
@@ -4128,20 +4169,41 @@ impl ContextFinderService {
             }
         };
 
-        let formatted: Vec<SearchResult> = results
+        let rules = request.ranking_rules.unwrap_or_default();
+        let ranked = apply_ranking_rules(&request.query, results, &rules);
+        let offset_encoding = request
+            .offset_encoding
+            .as_deref()
+            .and_then(OffsetEncoding::parse);
+
+        let formatted: Vec<SearchResult> = ranked
             .into_iter()
-            .map(|r| SearchResult {
-                file: r.chunk.file_path.clone(),
-                start_line: r.chunk.start_line,
-                end_line: r.chunk.end_line,
-                symbol: r.chunk.metadata.symbol_name.clone(),
-                symbol_type: r
-                    .chunk
-                    .metadata
-                    .chunk_type
-                    .map(|ct| ct.as_str().to_string()),
-                score: r.score,
-                content: r.chunk.content.clone(),
+            .take(limit)
+            .map(|(r, ranking)| {
+                let range = offset_encoding.map(|encoding| {
+                    let end =
+                        LineIndex::new(&r.chunk.content).convert(r.chunk.content.len(), encoding);
+                    SearchRange {
+                        encoding: encoding.as_str().to_string(),
+                        start: 0,
+                        end,
+                    }
+                });
+                SearchResult {
+                    file: r.chunk.file_path.clone(),
+                    start_line: r.chunk.start_line,
+                    end_line: r.chunk.end_line,
+                    symbol: r.chunk.metadata.symbol_name.clone(),
+                    symbol_type: r
+                        .chunk
+                        .metadata
+                        .chunk_type
+                        .map(|ct| ct.as_str().to_string()),
+                    score: r.score,
+                    content: r.chunk.content.clone(),
+                    ranking,
+                    range,
+                }
             })
             .collect();
 
@@ -4163,6 +4225,7 @@ impl ContextFinderService {
         let strategy = match request.strategy.as_deref() {
             Some("direct") => context_graph::AssemblyStrategy::Direct,
             Some("deep") => context_graph::AssemblyStrategy::Deep,
+            Some("semantic") => context_graph::AssemblyStrategy::Semantic,
             _ => context_graph::AssemblyStrategy::Extended,
         };
 
@@ -4273,6 +4336,7 @@ impl ContextFinderService {
         let strategy = match request.strategy.as_deref() {
             Some("direct") => context_graph::AssemblyStrategy::Direct,
             Some("deep") => context_graph::AssemblyStrategy::Deep,
+            Some("semantic") => context_graph::AssemblyStrategy::Semantic,
             _ => context_graph::AssemblyStrategy::Extended,
         };
 
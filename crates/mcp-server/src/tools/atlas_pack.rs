@@ -272,6 +272,11 @@ pub(super) async fn compute_atlas_pack_result(
         map_depth: Some(2),
         map_limit: None,
         max_chars: Some(meaning_max_chars),
+        max_tokens: request.max_tokens,
+        cache_path: None,
+        rules: Vec::new(),
+        semantic: None,
+        semantic_weight: None,
     };
     let meaning_result = meaning::meaning_pack(root, root_display, &meaning_request)
         .await
@@ -338,6 +343,8 @@ pub(super) async fn compute_atlas_pack_result(
         budget: AtlasPackBudget {
             max_chars,
             used_chars: 0,
+            max_tokens: request.max_tokens,
+            used_tokens: None,
             truncated: meaning_result.budget.truncated || worktrees_truncated || !include_worktrees,
             truncation: None,
         },
@@ -196,6 +196,8 @@ pub(super) fn compute_used_chars(output: &BatchResult) -> anyhow::Result<usize>
         budget: BatchBudget {
             max_chars: output.budget.max_chars,
             used_chars: 0,
+            max_tokens: output.budget.max_tokens,
+            used_tokens: output.budget.used_tokens,
             truncated: output.budget.truncated,
             truncation: output.budget.truncation.clone(),
         },
@@ -291,6 +293,8 @@ mod tests {
                     duration_ms: None,
                     result: None,
                     error: Some(long_error),
+                    incremental: false,
+                    files_changed: None,
                 }),
             }),
             root_fingerprint: Some(1),
@@ -312,6 +316,8 @@ mod tests {
             budget: BatchBudget {
                 max_chars: 1_000,
                 used_chars: 0,
+                max_tokens: None,
+                used_tokens: None,
                 truncated: false,
                 truncation: None,
             },
@@ -346,6 +352,8 @@ mod tests {
             budget: BatchBudget {
                 max_chars: 1_000,
                 used_chars: 0,
+                max_tokens: None,
+                used_tokens: None,
                 truncated: false,
                 truncation: None,
             },
@@ -47,6 +47,38 @@ pub(crate) const TOOL_CATALOG: &[ToolDescriptor] = &[
         name: "batch",
         summary: "Multiple tools under one max_chars budget with $ref.",
     },
+    ToolDescriptor {
+        name: "submit_batch",
+        summary: "Enqueue a batch in the background; returns a task id.",
+    },
+    ToolDescriptor {
+        name: "batch_status",
+        summary: "Poll a submit_batch task's progress/partial result.",
+    },
+    ToolDescriptor {
+        name: "cancel_batch",
+        summary: "Cancel a submit_batch task by id.",
+    },
+    ToolDescriptor {
+        name: "get_task",
+        summary: "Poll an index background task by task_uid.",
+    },
+    ToolDescriptor {
+        name: "list_tasks",
+        summary: "List index background tasks for a project.",
+    },
+    ToolDescriptor {
+        name: "cancel_task",
+        summary: "Cancel an index background task by task_uid.",
+    },
+    ToolDescriptor {
+        name: "dump_index",
+        summary: "Export a project's semantic indexes to one portable archive file.",
+    },
+    ToolDescriptor {
+        name: "restore_index",
+        summary: "Restore semantic indexes from a dump_index archive, flagging stale files.",
+    },
     ToolDescriptor {
         name: "map",
         summary: "Project structure overview (directories + symbols).",
@@ -87,6 +119,10 @@ pub(crate) const TOOL_CATALOG: &[ToolDescriptor] = &[
         name: "trace",
         summary: "Call chain between two symbols.",
     },
+    ToolDescriptor {
+        name: "symbol_lookup",
+        summary: "Prefix/fuzzy symbol name autocomplete (FST-backed).",
+    },
     ToolDescriptor {
         name: "explain",
         summary: "Symbol details, deps, dependents, docs.",
@@ -24,6 +24,11 @@ struct PersistedCursorStoreEntryData {
     expires_at_unix_ms: u64,
 }
 
+/// Keyed store for large cursor payloads (e.g. a serialized recall session) that are too big to
+/// inline into a cursor token. Abandoned entries are reclaimed by `CURSOR_STORE_TTL` expiry and
+/// `CURSOR_STORE_CAPACITY` LRU eviction on every `get`/`insert`, so there's no separate sweep to
+/// schedule. Backed by a single JSON file (best-effort, lock-guarded) when `persist_path` is set,
+/// or purely in-memory -- see [`Self::new_in_memory`] -- when it's `None`.
 pub(super) struct CursorStore {
     next_id: u64,
     entries: HashMap<u64, CursorStoreEntry>,
@@ -39,12 +44,24 @@ impl CursorStore {
     }
 
     pub(super) fn new() -> Self {
+        Self::with_persist_path(cursor_store_persist_path())
+    }
+
+    /// Forces an in-memory-only store, bypassing disk persistence regardless of the
+    /// `CONTEXT_MCP_CURSOR_STORE_PATH`/`CONTEXT_FINDER_MCP_CURSOR_STORE_PATH` env vars. Intended
+    /// for tests that need deterministic, isolated cursor storage.
+    #[cfg(test)]
+    pub(super) fn new_in_memory() -> Self {
+        Self::with_persist_path(None)
+    }
+
+    fn with_persist_path(persist_path: Option<PathBuf>) -> Self {
         let seed = Self::random_u64_best_effort().unwrap_or(1).max(1);
         let mut store = Self {
             next_id: seed,
             entries: HashMap::new(),
             order: VecDeque::new(),
-            persist_path: cursor_store_persist_path(),
+            persist_path,
         };
         store.load_best_effort();
         store
@@ -389,6 +406,10 @@ struct PersistedCursorStoreEntry {
 }
 
 fn cursor_store_persist_path() -> Option<PathBuf> {
+    if std::env::var("CONTEXT_MCP_CURSOR_STORE_MEMORY_ONLY").is_ok_and(|v| v == "1") {
+        return None;
+    }
+
     if let Ok(raw) = std::env::var("CONTEXT_MCP_CURSOR_STORE_PATH")
         .or_else(|_| std::env::var("CONTEXT_FINDER_MCP_CURSOR_STORE_PATH"))
     {
@@ -415,3 +436,28 @@ fn cursor_store_persist_path() -> Option<PathBuf> {
     }
     Some(preferred)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn in_memory_store_round_trips_without_touching_disk() {
+        let mut store = CursorStore::new_in_memory();
+        let id = store.insert_persisted_best_effort(b"hello".to_vec());
+        assert_eq!(store.get(id), Some(b"hello".to_vec()));
+        // A fresh in-memory store never shares state with a prior one.
+        let mut other = CursorStore::new_in_memory();
+        assert_eq!(other.get(id), None);
+    }
+
+    #[test]
+    fn expired_entry_is_reclaimed() {
+        let mut store = CursorStore::new_in_memory();
+        let id = store.insert(b"stale".to_vec());
+        if let Some(entry) = store.entries.get_mut(&id) {
+            entry.expires_at = Instant::now() - Duration::from_secs(1);
+        }
+        assert_eq!(store.get(id), None);
+    }
+}
@@ -0,0 +1,463 @@
+//! Persistent task store backing `index`'s background enqueue/poll/cancel flow.
+//!
+//! Mirrors `BatchTaskTable`'s in-memory shape (capacity-bounded, oldest-eviction) but adds
+//! things `submit_batch` tasks don't need: a monotonically increasing numeric `task_uid` (instead
+//! of a random-suffixed hex id), on-disk persistence so a `processing` task survives a server
+//! restart, and one drain loop per root (rather than one spawned worker per task) so a burst of
+//! enqueued tasks for the same project autobatches into a single indexing pass -- see
+//! `pop_batch_or_release`. The log lives next to the project's index (`tasks_v1.jsonl` under the
+//! project's `.context`/`.context-finder` dir) as append-only JSON records keyed by `uid` -- the
+//! latest record for a given uid wins on replay, so "append a record" is always safe, never a
+//! read-modify-write of the whole file.
+
+use super::super::schemas::index::{IndexResult, IndexTaskKind, IndexTaskState, IndexTaskStatus};
+use super::super::util::unix_ms;
+use context_vector_store::context_dir_for_project_root;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+const TASK_LOG_FILE_NAME: &str = "tasks_v1.jsonl";
+const INDEX_TASK_CAPACITY: usize = 256;
+
+/// Snapshot of the `IndexRequest` fields needed to re-run a task that was interrupted mid-`processing`
+/// by a server restart (recovered tasks are re-enqueued, not replayed automatically -- see
+/// `IndexTaskTable::ensure_loaded`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct IndexTaskRequestSnapshot {
+    pub full: bool,
+    pub experts: bool,
+    pub models: Vec<String>,
+}
+
+struct IndexTaskEntry {
+    root: PathBuf,
+    kind: IndexTaskKind,
+    status: IndexTaskState,
+    enqueued_at_ms: u64,
+    started_at_ms: Option<u64>,
+    finished_at_ms: Option<u64>,
+    error: Option<String>,
+    result: Option<IndexResult>,
+    request: IndexTaskRequestSnapshot,
+    cancel: tokio_util::sync::CancellationToken,
+}
+
+impl IndexTaskEntry {
+    fn to_status(&self, uid: u64) -> IndexTaskStatus {
+        IndexTaskStatus {
+            uid,
+            kind: self.kind,
+            status: self.status,
+            enqueued_at_ms: self.enqueued_at_ms,
+            started_at_ms: self.started_at_ms,
+            finished_at_ms: self.finished_at_ms,
+            error: self.error.clone(),
+            result: self.result.clone(),
+        }
+    }
+
+    fn to_record(&self, uid: u64) -> PersistedIndexTaskRecord {
+        PersistedIndexTaskRecord {
+            uid,
+            root: self.root.to_string_lossy().to_string(),
+            kind: self.kind,
+            status: self.status,
+            enqueued_at_ms: self.enqueued_at_ms,
+            started_at_ms: self.started_at_ms,
+            finished_at_ms: self.finished_at_ms,
+            error: self.error.clone(),
+            result: self.result.clone(),
+            request: self.request.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PersistedIndexTaskRecord {
+    uid: u64,
+    root: String,
+    kind: IndexTaskKind,
+    status: IndexTaskState,
+    enqueued_at_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    started_at_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    finished_at_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<IndexResult>,
+    request: IndexTaskRequestSnapshot,
+}
+
+/// Tracks `index` background tasks so `get_task`/`list_tasks` can poll them and `cancel_task` can
+/// signal cancellation. Bounded by `INDEX_TASK_CAPACITY` in memory (oldest-eviction, same policy
+/// as `BatchTaskTable`); the on-disk log for a project is only read once per process via
+/// `ensure_loaded`, since this process is the sole writer to it for the lifetime of the run.
+pub(super) struct IndexTaskTable {
+    next_uid: u64,
+    entries: HashMap<u64, IndexTaskEntry>,
+    order: VecDeque<u64>,
+    loaded_roots: HashSet<PathBuf>,
+    /// Roots that currently have a drain loop running. `claim_drain` is the only way to flip a
+    /// root from absent to present; the loop itself clears it once it finds nothing left to pop,
+    /// both inside the same lock acquisition so a concurrent `insert` never goes unclaimed.
+    active_drains: HashSet<PathBuf>,
+}
+
+/// A task recovered from a prior run's `processing` state. Reset to `enqueued` on load; the
+/// caller just needs to make sure a drain loop is running for `root` (via `claim_drain`) so it
+/// eventually gets picked up again -- the table has no scheduler of its own.
+pub(super) struct RecoveredTask {
+    pub uid: u64,
+    pub root: PathBuf,
+}
+
+/// One drain-loop pass worth of work: the merged request to run, and every task uid that should
+/// transition to the same terminal state once it finishes.
+pub(super) struct IndexTaskBatch {
+    pub uids: Vec<u64>,
+    pub request: IndexTaskRequestSnapshot,
+}
+
+impl IndexTaskTable {
+    pub(super) fn new() -> Self {
+        Self {
+            next_uid: 1,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            loaded_roots: HashSet::new(),
+            active_drains: HashSet::new(),
+        }
+    }
+
+    fn task_log_path(root: &Path) -> PathBuf {
+        context_dir_for_project_root(root).join(TASK_LOG_FILE_NAME)
+    }
+
+    /// Loads `root`'s on-disk task log into memory the first time `root` is seen by this process
+    /// (a no-op on subsequent calls). Any task still `processing` on load means the process that
+    /// was running it died without finishing -- those are reset to `enqueued` (appended as a new
+    /// record) and returned so the caller can spawn a worker to pick them back up.
+    pub(super) fn ensure_loaded(&mut self, root: &Path) -> Vec<RecoveredTask> {
+        if !self.loaded_roots.insert(root.to_path_buf()) {
+            return Vec::new();
+        }
+
+        let path = Self::task_log_path(root);
+        let Ok(bytes) = std::fs::read(&path) else {
+            return Vec::new();
+        };
+
+        let mut latest: HashMap<u64, PersistedIndexTaskRecord> = HashMap::new();
+        for line in String::from_utf8_lossy(&bytes).lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Ok(record) = serde_json::from_str::<PersistedIndexTaskRecord>(line) {
+                latest.insert(record.uid, record);
+            }
+        }
+
+        let mut recovered = Vec::new();
+        let mut max_uid = 0u64;
+        let mut records: Vec<_> = latest.into_values().collect();
+        records.sort_by_key(|r| r.uid);
+
+        for mut record in records {
+            max_uid = max_uid.max(record.uid);
+            let was_interrupted = record.status == IndexTaskState::Processing;
+            if was_interrupted {
+                record.status = IndexTaskState::Enqueued;
+                record.started_at_ms = None;
+            }
+
+            let entry = IndexTaskEntry {
+                root: root.to_path_buf(),
+                kind: record.kind,
+                status: record.status,
+                enqueued_at_ms: record.enqueued_at_ms,
+                started_at_ms: record.started_at_ms,
+                finished_at_ms: record.finished_at_ms,
+                error: record.error.clone(),
+                result: record.result.clone(),
+                request: record.request.clone(),
+                cancel: tokio_util::sync::CancellationToken::new(),
+            };
+
+            if was_interrupted {
+                Self::append_record(&path, &entry.to_record(record.uid));
+                recovered.push(RecoveredTask {
+                    uid: record.uid,
+                    root: root.to_path_buf(),
+                });
+            }
+
+            self.entries.insert(record.uid, entry);
+            self.order.push_back(record.uid);
+        }
+
+        self.next_uid = self.next_uid.max(max_uid.wrapping_add(1)).max(1);
+        self.evict_over_capacity();
+        recovered
+    }
+
+    fn evict_over_capacity(&mut self) {
+        while self.order.len() > INDEX_TASK_CAPACITY {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+
+    fn append_record(path: &Path, record: &PersistedIndexTaskRecord) {
+        let Some(parent) = path.parent() else {
+            return;
+        };
+        if std::fs::create_dir_all(parent).is_err() {
+            return;
+        }
+        let Ok(mut line) = serde_json::to_string(record) else {
+            return;
+        };
+        line.push('\n');
+
+        use fs2::FileExt;
+        use std::io::Write;
+        let Ok(mut file) = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+        else {
+            return;
+        };
+        if file.lock_exclusive().is_err() {
+            return;
+        }
+        let _ = file.write_all(line.as_bytes());
+        let _ = file.unlock();
+    }
+
+    fn persist(&self, uid: u64) {
+        let Some(entry) = self.entries.get(&uid) else {
+            return;
+        };
+        let path = Self::task_log_path(&entry.root);
+        Self::append_record(&path, &entry.to_record(uid));
+    }
+
+    /// Registers a new `index` task for `root` and returns `(uid, cancel_token, should_spawn)`.
+    /// `should_spawn` is true iff no drain loop is currently claimed for `root`, i.e. the caller
+    /// is responsible for spawning one (see `claim_drain`).
+    pub(super) fn insert(
+        &mut self,
+        root: &Path,
+        request: IndexTaskRequestSnapshot,
+    ) -> (u64, tokio_util::sync::CancellationToken, bool) {
+        let uid = self.next_uid;
+        self.next_uid = self.next_uid.wrapping_add(1).max(1);
+
+        let cancel = tokio_util::sync::CancellationToken::new();
+        self.entries.insert(
+            uid,
+            IndexTaskEntry {
+                root: root.to_path_buf(),
+                kind: IndexTaskKind::Index,
+                status: IndexTaskState::Enqueued,
+                enqueued_at_ms: unix_ms(SystemTime::now()),
+                started_at_ms: None,
+                finished_at_ms: None,
+                error: None,
+                result: None,
+                request,
+                cancel: cancel.clone(),
+            },
+        );
+        self.order.push_back(uid);
+        self.persist(uid);
+        self.evict_over_capacity();
+        let should_spawn = self.claim_drain(root);
+        (uid, cancel, should_spawn)
+    }
+
+    /// Claims the drain loop for `root`: returns `true` if no loop was already claimed (the
+    /// caller must spawn one), `false` if one is already running (it will pick up this root's
+    /// tasks on its next `pop_batch_or_release`). Called with the same lock held as whatever
+    /// action just made a task available (`insert`, or recovery on `ensure_loaded`), so a loop
+    /// that is mid-exit can never miss a task: either it hasn't released yet (sees `should_spawn
+    /// == false` here, but will find the new task on its next pop) or it already released (this
+    /// call claims fresh and spawns a replacement).
+    pub(super) fn claim_drain(&mut self, root: &Path) -> bool {
+        self.active_drains.insert(root.to_path_buf())
+    }
+
+    /// Pops the next batch of compatible `enqueued` tasks for `root`, merging them per the
+    /// autobatching rule: the oldest enqueued task for `root` is the head. If the head is a full
+    /// (or forced) reindex, it absorbs every other queued incremental task for `root` (they are
+    /// subsumed by the full walk). If the head is incremental, it keeps absorbing subsequent
+    /// queued incremental tasks for `root` in submission order, accumulating the union of
+    /// `models`/`experts`, until it hits a full/force task -- which stops the batch there and
+    /// becomes its own batch's head next round. Returns `None` (and releases the drain claim for
+    /// `root`) once there's nothing left to pop.
+    pub(super) fn pop_batch_or_release(&mut self, root: &Path) -> Option<IndexTaskBatch> {
+        let root_queue: Vec<u64> = self
+            .order
+            .iter()
+            .copied()
+            .filter(|uid| {
+                self.entries.get(uid).is_some_and(|e| {
+                    e.status == IndexTaskState::Enqueued && e.root.as_path() == root
+                })
+            })
+            .collect();
+
+        let Some(&head_uid) = root_queue.first() else {
+            self.active_drains.remove(root);
+            return None;
+        };
+
+        let head_full = self.entries[&head_uid].request.full;
+        let mut uids = vec![head_uid];
+        let mut experts = self.entries[&head_uid].request.experts;
+        let mut models = self.entries[&head_uid].request.models.clone();
+        let mut seen_models: HashSet<String> = models.iter().cloned().collect();
+
+        for &uid in &root_queue[1..] {
+            let candidate_full = self.entries[&uid].request.full;
+            if head_full {
+                if candidate_full {
+                    continue; // another full reindex forms its own batch later
+                }
+            } else if candidate_full {
+                break; // full task is a barrier: stop extending the incremental batch here
+            }
+            uids.push(uid);
+            let candidate = &self.entries[&uid].request;
+            experts |= candidate.experts;
+            for model in &candidate.models {
+                if seen_models.insert(model.clone()) {
+                    models.push(model.clone());
+                }
+            }
+        }
+
+        Some(IndexTaskBatch {
+            uids,
+            request: IndexTaskRequestSnapshot {
+                full: head_full,
+                experts,
+                models,
+            },
+        })
+    }
+
+    pub(super) fn set_processing_many(&mut self, uids: &[u64]) {
+        for &uid in uids {
+            if let Some(entry) = self.entries.get_mut(&uid) {
+                entry.status = IndexTaskState::Processing;
+                entry.started_at_ms = Some(unix_ms(SystemTime::now()));
+            }
+            self.persist(uid);
+        }
+    }
+
+    /// Clones the cancellation tokens for `uids`, so a drain loop can race an in-flight indexing
+    /// call against cancellation without holding the table lock for the duration of the call.
+    pub(super) fn cancel_tokens(&self, uids: &[u64]) -> Vec<tokio_util::sync::CancellationToken> {
+        uids.iter()
+            .filter_map(|uid| self.entries.get(uid).map(|e| e.cancel.clone()))
+            .collect()
+    }
+
+    /// Marks `uids` `Canceled` rather than `Succeeded`/`Failed` because `cancel_task` fired while
+    /// the batch was still running -- see `run_drain_loop`'s race against `cancel_tokens`.
+    pub(super) fn cancel_many(&mut self, uids: &[u64]) {
+        let finished_at_ms = Some(unix_ms(SystemTime::now()));
+        for &uid in uids {
+            if let Some(entry) = self.entries.get_mut(&uid) {
+                entry.status = IndexTaskState::Canceled;
+                entry.finished_at_ms = finished_at_ms;
+            }
+            self.persist(uid);
+        }
+    }
+
+    pub(super) fn finish_many(&mut self, uids: &[u64], result: IndexResult) {
+        let finished_at_ms = Some(unix_ms(SystemTime::now()));
+        for &uid in uids {
+            if let Some(entry) = self.entries.get_mut(&uid) {
+                entry.status = IndexTaskState::Succeeded;
+                entry.finished_at_ms = finished_at_ms;
+                entry.result = Some(result.clone());
+            }
+            self.persist(uid);
+        }
+    }
+
+    pub(super) fn fail_many(&mut self, uids: &[u64], error: String) {
+        let finished_at_ms = Some(unix_ms(SystemTime::now()));
+        for &uid in uids {
+            if let Some(entry) = self.entries.get_mut(&uid) {
+                entry.status = IndexTaskState::Failed;
+                entry.finished_at_ms = finished_at_ms;
+                entry.error = Some(error.clone());
+            }
+            self.persist(uid);
+        }
+    }
+
+    /// Signals cancellation for `uid`. An `enqueued` task is flipped to `Canceled` right here,
+    /// before it ever starts; a task already `processing` is instead caught by `run_drain_loop`'s
+    /// poll of this token against the in-flight indexing call (there's no per-file checkpoint to
+    /// interrupt the call itself mid-flight, same limitation noted on `run_tracked_batch`'s
+    /// concurrent mode -- but the task table reflects `Canceled` as soon as the poll observes it
+    /// rather than waiting for the call to finish). Returns `false` if `uid` is unknown (evicted or
+    /// never existed).
+    pub(super) fn cancel(&mut self, uid: u64) -> bool {
+        let Some(entry) = self.entries.get_mut(&uid) else {
+            return false;
+        };
+        entry.cancel.cancel();
+        if entry.status == IndexTaskState::Enqueued {
+            entry.status = IndexTaskState::Canceled;
+            entry.finished_at_ms = Some(unix_ms(SystemTime::now()));
+        }
+        self.persist(uid);
+        true
+    }
+
+    pub(super) fn get(&self, uid: u64) -> Option<IndexTaskStatus> {
+        Some(self.entries.get(&uid)?.to_status(uid))
+    }
+
+    pub(super) fn list(
+        &self,
+        root: &Path,
+        status: Option<IndexTaskState>,
+        limit: usize,
+    ) -> Vec<IndexTaskStatus> {
+        let mut out: Vec<IndexTaskStatus> = self
+            .order
+            .iter()
+            .rev()
+            .filter_map(|uid| {
+                let entry = self.entries.get(uid)?;
+                if entry.root.as_path() != root {
+                    return None;
+                }
+                if let Some(want) = status {
+                    if entry.status != want {
+                        return None;
+                    }
+                }
+                Some(entry.to_status(*uid))
+            })
+            .collect();
+        out.truncate(limit);
+        out
+    }
+}
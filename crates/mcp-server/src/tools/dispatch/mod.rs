@@ -14,7 +14,8 @@ use super::map::{compute_map_result, decode_map_cursor};
 use super::paths::normalize_relative_path;
 use super::repo_onboarding_pack::compute_repo_onboarding_pack_result;
 use super::schemas::batch::{
-    BatchBudget, BatchItemResult, BatchItemStatus, BatchRequest, BatchResult, BatchToolName,
+    BatchBudget, BatchItemResult, BatchItemStatus, BatchRequest, BatchResult, BatchRetryPolicy,
+    BatchStatusRequest, BatchTaskState, BatchTaskStatus, BatchToolName,
 };
 use super::schemas::context::{ContextHit, ContextRequest, ContextResult, RelatedCode};
 use super::schemas::context_pack::ContextPackRequest;
@@ -22,11 +23,17 @@ use super::schemas::doctor::{
     DoctorEnvResult, DoctorIndexDrift, DoctorModelStatus, DoctorProjectResult, DoctorRequest,
     DoctorResult,
 };
+use super::schemas::dump_index::{DumpIndexRequest, RestoreIndexRequest};
 use super::schemas::explain::{ExplainRequest, ExplainResult};
 use super::schemas::file_slice::{FileSliceCursorV1, FileSliceRequest};
 use super::schemas::grep_context::{GrepContextCursorV1, GrepContextRequest};
-use super::schemas::impact::{ImpactRequest, ImpactResult, SymbolLocation, UsageInfo};
-use super::schemas::index::{IndexRequest, IndexResult};
+use super::schemas::impact::{
+    ImpactRequest, ImpactResult, SymbolLocation, SymbolSuggestion, UsageInfo,
+};
+use super::schemas::index::{
+    CancelTaskRequest, GetTaskRequest, IndexRequest, IndexResult, IndexTaskState, IndexTaskStatus,
+    ListTasksRequest,
+};
 use super::schemas::list_files::ListFilesRequest;
 #[cfg(test)]
 use super::schemas::list_files::ListFilesTruncation;
@@ -36,16 +43,17 @@ use super::schemas::overview::{
 };
 use super::schemas::read_pack::{
     ReadPackBudget, ReadPackIntent, ReadPackNextAction, ReadPackRequest, ReadPackResult,
-    ReadPackSection, ReadPackTruncation,
+    ReadPackSection, ReadPackSnippetRange, ReadPackTruncation,
 };
 use super::schemas::repo_onboarding_pack::RepoOnboardingPackRequest;
 use super::schemas::search::{SearchRequest, SearchResult};
+use super::schemas::symbol_lookup::{SymbolLookupMatch, SymbolLookupRequest, SymbolLookupResult};
 use super::schemas::text_search::{
     TextSearchCursorModeV1, TextSearchCursorV1, TextSearchMatch, TextSearchRequest,
     TextSearchResult,
 };
 use super::schemas::trace::{TraceRequest, TraceResult, TraceStep};
-use super::util::{path_has_extension_ignore_ascii_case, unix_ms};
+use super::util::{hex_encode_lower, path_has_extension_ignore_ascii_case, unix_ms};
 use crate::runtime_env;
 use anyhow::{Context as AnyhowContext, Result};
 use context_graph::{
@@ -65,6 +73,7 @@ use context_vector_store::{
     classify_path_kind, corpus_path_for_project_root, current_model_id, ChunkCorpus, DocumentKind,
     GraphNodeDoc, GraphNodeStore, GraphNodeStoreMeta, QueryKind, VectorIndex,
 };
+use getrandom::getrandom;
 use rmcp::handler::server::tool::ToolRouter;
 use rmcp::handler::server::wrapper::Parameters;
 use rmcp::model::{CallToolResult, Content, Implementation, ServerCapabilities, ServerInfo};
@@ -90,13 +99,26 @@ pub struct ContextFinderService {
 
 impl ContextFinderService {
     pub fn new() -> Self {
+        Self::with_extra_batch_handlers(Vec::new())
+    }
+
+    /// Like [`Self::new`], but additionally registers `extra` handlers with the `batch` tool's
+    /// dispatcher, ahead of the built-in [`BatchToolName`] handlers, so callers embedding this
+    /// crate can extend (or override) batch dispatch without forking `dispatch_tool`.
+    pub fn with_extra_batch_handlers(
+        extra: Vec<Box<dyn router::batch::BatchHandler>>,
+    ) -> Self {
         Self {
             profile: load_profile_from_env(),
             tool_router: Self::tool_router(),
-            state: Arc::new(ServiceState::new()),
+            state: Arc::new(ServiceState::with_extra_batch_handlers(extra)),
         }
     }
 
+    pub(super) fn batch_dispatcher(&self) -> &router::batch::BatchDispatcher {
+        &self.state.batch_dispatcher
+    }
+
     pub(super) async fn resolve_root(
         &self,
         raw_path: Option<&str>,
@@ -273,6 +295,8 @@ impl ContextFinderService {
             duration_ms: None,
             result: None,
             error: None,
+            incremental: false,
+            files_changed: None,
         };
 
         let templates = self.profile.embedding().clone();
@@ -524,21 +548,168 @@ type EngineHandle = Arc<Mutex<EngineSlot>>;
 struct ServiceState {
     engines: Mutex<EngineCache>,
     session: Mutex<SessionDefaults>,
+    cursor_store: Mutex<cursor_store::CursorStore>,
+    batch_dispatcher: router::batch::BatchDispatcher,
+    batch_tasks: Mutex<BatchTaskTable>,
+    index_tasks: Mutex<index_task_store::IndexTaskTable>,
 }
 
 impl ServiceState {
     fn new() -> Self {
+        Self::with_extra_batch_handlers(Vec::new())
+    }
+
+    fn with_extra_batch_handlers(extra: Vec<Box<dyn router::batch::BatchHandler>>) -> Self {
         Self {
             engines: Mutex::new(EngineCache::new(ENGINE_CACHE_CAPACITY)),
             session: Mutex::new(SessionDefaults::default()),
+            cursor_store: Mutex::new(cursor_store::CursorStore::new()),
+            batch_dispatcher: router::batch::BatchDispatcher::new().with_extra_handlers(extra),
+            batch_tasks: Mutex::new(BatchTaskTable::new()),
+            index_tasks: Mutex::new(index_task_store::IndexTaskTable::new()),
         }
     }
 
+    /// Loads `root`'s on-disk task log into memory the first time this process sees `root`,
+    /// returning any tasks that were `processing` when a prior run ended (server restart) so the
+    /// caller can make sure a drain loop is running for `root` to pick them back up.
+    pub(super) async fn index_task_ensure_loaded(
+        &self,
+        root: &Path,
+    ) -> Vec<index_task_store::RecoveredTask> {
+        self.index_tasks.lock().await.ensure_loaded(root)
+    }
+
+    /// Registers a new `index` task for `root` and returns `(uid, cancel_token, should_spawn)`.
+    pub(super) async fn index_task_insert(
+        &self,
+        root: &Path,
+        request: index_task_store::IndexTaskRequestSnapshot,
+    ) -> (u64, tokio_util::sync::CancellationToken, bool) {
+        self.index_tasks.lock().await.insert(root, request)
+    }
+
+    /// Claims the drain loop for `root`. Returns `true` if the caller must spawn one.
+    pub(super) async fn index_task_claim_drain(&self, root: &Path) -> bool {
+        self.index_tasks.lock().await.claim_drain(root)
+    }
+
+    /// Pops the next autobatched group of enqueued tasks for `root`, or releases the drain claim
+    /// and returns `None` if the queue for `root` is empty.
+    pub(super) async fn index_task_pop_batch(
+        &self,
+        root: &Path,
+    ) -> Option<index_task_store::IndexTaskBatch> {
+        self.index_tasks.lock().await.pop_batch_or_release(root)
+    }
+
+    pub(super) async fn index_task_set_processing_many(&self, uids: &[u64]) {
+        self.index_tasks.lock().await.set_processing_many(uids);
+    }
+
+    pub(super) async fn index_task_finish_many(&self, uids: &[u64], result: IndexResult) {
+        self.index_tasks.lock().await.finish_many(uids, result);
+    }
+
+    pub(super) async fn index_task_fail_many(&self, uids: &[u64], error: String) {
+        self.index_tasks.lock().await.fail_many(uids, error);
+    }
+
+    /// Clones `uids`' cancellation tokens so a caller can race them against an in-flight indexing
+    /// call without holding the task table lock for the duration of the call.
+    pub(super) async fn index_task_cancel_tokens(
+        &self,
+        uids: &[u64],
+    ) -> Vec<tokio_util::sync::CancellationToken> {
+        self.index_tasks.lock().await.cancel_tokens(uids)
+    }
+
+    pub(super) async fn index_task_cancel_many(&self, uids: &[u64]) {
+        self.index_tasks.lock().await.cancel_many(uids);
+    }
+
+    /// Signals cancellation for an `index` task. Returns `false` if `uid` is unknown.
+    pub(super) async fn index_task_cancel(&self, uid: u64) -> bool {
+        self.index_tasks.lock().await.cancel(uid)
+    }
+
+    pub(super) async fn index_task_get(&self, uid: u64) -> Option<IndexTaskStatus> {
+        self.index_tasks.lock().await.get(uid)
+    }
+
+    pub(super) async fn index_task_list(
+        &self,
+        root: &Path,
+        status: Option<IndexTaskState>,
+        limit: usize,
+    ) -> Vec<IndexTaskStatus> {
+        self.index_tasks.lock().await.list(root, status, limit)
+    }
+
+    /// Registers a new `submit_batch` task with `total` items and returns `(id, cancel_token)`.
+    pub(super) async fn batch_task_insert(
+        &self,
+        total: usize,
+    ) -> (String, tokio_util::sync::CancellationToken) {
+        self.batch_tasks.lock().await.insert(total)
+    }
+
+    /// Signals cancellation for a `submit_batch` task. Returns `false` if the id is unknown.
+    pub(super) async fn batch_task_cancel(&self, id: &str) -> bool {
+        self.batch_tasks.lock().await.cancel(id)
+    }
+
+    pub(super) async fn batch_task_set_processing(&self, id: &str) {
+        self.batch_tasks.lock().await.set_processing(id);
+    }
+
+    pub(super) async fn batch_task_update_progress(
+        &self,
+        id: &str,
+        processed: usize,
+        partial: BatchResult,
+    ) {
+        self.batch_tasks
+            .lock()
+            .await
+            .update_progress(id, processed, partial);
+    }
+
+    pub(super) async fn batch_task_finish(&self, id: &str, result: BatchResult) {
+        self.batch_tasks.lock().await.finish(id, result);
+    }
+
+    pub(super) async fn batch_task_fail(&self, id: &str) {
+        self.batch_tasks.lock().await.fail(id);
+    }
+
+    /// Looks up a `submit_batch` task's current progress. Returns `None` if the id is unknown or
+    /// was evicted by `BATCH_TASK_CAPACITY`.
+    pub(super) async fn batch_task_get(&self, id: &str) -> Option<BatchTaskStatus> {
+        self.batch_tasks.lock().await.get(id)
+    }
+
     async fn engine_handle(&self, root: &Path) -> EngineHandle {
         let mut cache = self.engines.lock().await;
         cache.get_or_insert(root)
     }
 
+    /// Stores a stored-cursor payload (e.g. a serialized recall session) and returns the
+    /// `store_id` a compact cursor can reference instead of inlining the full payload.
+    pub(super) async fn cursor_store_put(&self, payload: Vec<u8>) -> u64 {
+        self.cursor_store
+            .lock()
+            .await
+            .insert_persisted_best_effort(payload)
+    }
+
+    /// Resolves a `store_id` back to its payload. Returns `None` if the entry never existed,
+    /// expired, or was reclaimed (e.g. a fresh process that never persisted it to disk) --
+    /// callers are expected to treat that the same as a cache miss, not a hard error.
+    pub(super) async fn cursor_store_get(&self, store_id: u64) -> Option<Vec<u8>> {
+        self.cursor_store.lock().await.get(store_id)
+    }
+
     async fn resolve_root(&self, raw_path: Option<&str>) -> Result<(PathBuf, String), String> {
         if let Some(raw) = trimmed_non_empty(raw_path) {
             let root = canonicalize_root(raw).map_err(|err| format!("Invalid path: {err}"))?;
@@ -663,6 +834,120 @@ impl EngineCache {
     }
 }
 
+const BATCH_TASK_CAPACITY: usize = 64;
+
+struct BatchTaskEntry {
+    state: BatchTaskState,
+    processed: usize,
+    total: usize,
+    partial: Option<BatchResult>,
+    cancel: tokio_util::sync::CancellationToken,
+}
+
+/// Tracks `submit_batch` tasks so `batch_status` can poll their progress. Bounded by
+/// `BATCH_TASK_CAPACITY`, evicting the oldest entry (even if still running) once full --
+/// mirrors `cursor_store::CursorStore`'s best-effort LRU bound rather than failing inserts.
+struct BatchTaskTable {
+    next_id: u64,
+    entries: HashMap<String, BatchTaskEntry>,
+    order: VecDeque<String>,
+}
+
+impl BatchTaskTable {
+    fn new() -> Self {
+        Self {
+            next_id: 0,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    fn next_task_id(&mut self) -> String {
+        self.next_id = self.next_id.wrapping_add(1);
+        let mut random_bytes = [0u8; 8];
+        let _ = getrandom::getrandom(&mut random_bytes);
+        format!(
+            "{:016x}{:016x}",
+            self.next_id,
+            u64::from_be_bytes(random_bytes)
+        )
+    }
+
+    /// Registers a new task and returns `(id, cancel_token)`; the token is handed to the
+    /// `BatchRunner` that actually executes it, and also stored here so `cancel` can signal it.
+    fn insert(&mut self, total: usize) -> (String, tokio_util::sync::CancellationToken) {
+        while self.entries.len() >= BATCH_TASK_CAPACITY {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+
+        let id = self.next_task_id();
+        let cancel = tokio_util::sync::CancellationToken::new();
+        self.entries.insert(
+            id.clone(),
+            BatchTaskEntry {
+                state: BatchTaskState::Enqueued,
+                processed: 0,
+                total,
+                partial: None,
+                cancel: cancel.clone(),
+            },
+        );
+        self.order.push_back(id.clone());
+        (id, cancel)
+    }
+
+    /// Signals cancellation for `id`. Returns `false` if the id is unknown (already evicted or
+    /// never existed), matching the `Option`-returning style of the other lookups here.
+    fn cancel(&self, id: &str) -> bool {
+        let Some(entry) = self.entries.get(id) else {
+            return false;
+        };
+        entry.cancel.cancel();
+        true
+    }
+
+    fn set_processing(&mut self, id: &str) {
+        if let Some(entry) = self.entries.get_mut(id) {
+            entry.state = BatchTaskState::Processing;
+        }
+    }
+
+    fn update_progress(&mut self, id: &str, processed: usize, partial: BatchResult) {
+        if let Some(entry) = self.entries.get_mut(id) {
+            entry.processed = processed;
+            entry.partial = Some(partial);
+        }
+    }
+
+    fn finish(&mut self, id: &str, result: BatchResult) {
+        if let Some(entry) = self.entries.get_mut(id) {
+            entry.processed = result.items.len();
+            entry.state = BatchTaskState::Succeeded;
+            entry.partial = Some(result);
+        }
+    }
+
+    fn fail(&mut self, id: &str) {
+        if let Some(entry) = self.entries.get_mut(id) {
+            entry.state = BatchTaskState::Failed;
+        }
+    }
+
+    fn get(&self, id: &str) -> Option<BatchTaskStatus> {
+        let entry = self.entries.get(id)?;
+        Some(BatchTaskStatus {
+            id: id.to_string(),
+            state: entry.state,
+            processed: entry.processed,
+            total: entry.total,
+            partial: entry.partial.clone(),
+        })
+    }
+}
+
 struct EngineSlot {
     engine: Option<ProjectEngine>,
 }
@@ -1158,6 +1443,8 @@ where
 // Tool Implementations
 // ============================================================================
 
+mod cursor_store;
+mod index_task_store;
 mod router;
 
 #[tool_router]
@@ -1250,6 +1537,39 @@ impl ContextFinderService {
         router::batch::batch(self, request).await
     }
 
+    /// Enqueue a batch for background processing and return a task id (see `batch_status`).
+    #[tool(
+        description = "Enqueue a batch (same input as `batch`) for background processing. Returns { id } immediately; poll progress/result with batch_status."
+    )]
+    pub async fn submit_batch(
+        &self,
+        Parameters(request): Parameters<BatchRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        router::batch::submit_batch(self, request).await
+    }
+
+    /// Poll the progress/result of a `submit_batch` task.
+    #[tool(
+        description = "Poll a submit_batch task by id. Returns state (enqueued/processing/succeeded/failed), processed/total counts, and the partial BatchResult accumulated so far."
+    )]
+    pub async fn batch_status(
+        &self,
+        Parameters(request): Parameters<BatchStatusRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        router::batch::batch_status(self, request).await
+    }
+
+    /// Cancel a `submit_batch` task by id.
+    #[tool(
+        description = "Cancel a submit_batch task by id. Already-running items finish; remaining items are marked cancelled and batch_status still returns the (partial) result."
+    )]
+    pub async fn cancel_batch(
+        &self,
+        Parameters(request): Parameters<BatchStatusRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        router::batch::cancel_batch(self, request).await
+    }
+
     /// Diagnose model/GPU/index configuration
     #[tool(
         description = "Show diagnostics for model directory, CUDA/ORT runtime, and per-project index/corpus status. Use this when something fails (e.g., GPU provider missing)."
@@ -1305,6 +1625,61 @@ impl ContextFinderService {
         router::index::index(self, request).await
     }
 
+    /// Poll the progress/result of an `index` background task.
+    #[tool(
+        description = "Poll an index task by task_uid. Returns status (enqueued/processing/succeeded/failed/canceled), timestamps, and the IndexResult once succeeded."
+    )]
+    pub async fn get_task(
+        &self,
+        Parameters(request): Parameters<GetTaskRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        router::index::get_task(self, request).await
+    }
+
+    /// List `index` background tasks for a project.
+    #[tool(
+        description = "List index tasks for a project, newest first. Optionally filter by status and cap the count with limit (default 50)."
+    )]
+    pub async fn list_tasks(
+        &self,
+        Parameters(request): Parameters<ListTasksRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        router::index::list_tasks(self, request).await
+    }
+
+    /// Cancel an `index` background task by task_uid.
+    #[tool(
+        description = "Cancel an index task by task_uid. An enqueued task is skipped outright; a task already processing finishes its current indexing call first."
+    )]
+    pub async fn cancel_task(
+        &self,
+        Parameters(request): Parameters<CancelTaskRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        router::index::cancel_task(self, request).await
+    }
+
+    /// Export a project's semantic indexes to a portable archive file
+    #[tool(
+        description = "Dump all indexed model stores for a project plus a source-file manifest into one portable JSON archive, for sharing or warm-starting CI caches."
+    )]
+    pub async fn dump_index(
+        &self,
+        Parameters(request): Parameters<DumpIndexRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        router::dump_index::dump_index(self, request).await
+    }
+
+    /// Restore a project's semantic indexes from a dump_index archive
+    #[tool(
+        description = "Restore indexed model stores from a dump_index archive into a project. Reports any source file whose mtime/size/sha256 no longer matches the manifest so a follow-up index only re-embeds the drift."
+    )]
+    pub async fn restore_index(
+        &self,
+        Parameters(request): Parameters<RestoreIndexRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        router::dump_index::restore_index(self, request).await
+    }
+
     /// Find all usages of a symbol (impact analysis)
     #[tool(
         description = "Find all places where a symbol is used. Essential for refactoring - shows direct usages, transitive dependencies, and related tests."
@@ -1,4 +1,5 @@
 use super::{ReadPackRequest, ToolResult};
+use context_search::OffsetEncoding;
 use std::path::PathBuf;
 
 pub(super) struct ReadPackContext {
@@ -6,6 +7,10 @@ pub(super) struct ReadPackContext {
     pub(super) root_display: String,
     pub(super) max_chars: usize,
     pub(super) inner_max_chars: usize,
+    /// Offset encoding for each returned snippet's precise `range`, parsed from
+    /// `ReadPackRequest::offset_encoding`; `None` (including unrecognized values) skips computing
+    /// ranges, leaving `ReadPackSnippet::range` null.
+    pub(super) offset_encoding: Option<OffsetEncoding>,
 }
 
 pub(super) fn build_context(
@@ -27,11 +32,16 @@ pub(super) fn build_context(
         .saturating_sub(reserved_for_envelope)
         .max(64)
         .min(max_chars);
+    let offset_encoding = request
+        .offset_encoding
+        .as_deref()
+        .and_then(OffsetEncoding::parse);
 
     Ok(ReadPackContext {
         root,
         root_display,
         max_chars,
         inner_max_chars,
+        offset_encoding,
     })
 }
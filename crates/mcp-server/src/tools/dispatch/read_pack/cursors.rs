@@ -60,6 +60,8 @@ pub(super) struct ReadPackRecallCursorV1 {
     pub(super) include_docs: Option<bool>,
     #[serde(default)]
     pub(super) allow_secrets: bool,
+    #[serde(default)]
+    pub(super) fuzzy: bool,
     pub(super) next_question_index: usize,
 }
 
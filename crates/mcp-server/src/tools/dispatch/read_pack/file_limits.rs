@@ -23,7 +23,7 @@ pub(super) fn resolve_file_slice_max_chars(
     } else {
         match response_mode {
             ResponseMode::Full => ctx.inner_max_chars,
-            ResponseMode::Facts | ResponseMode::Minimal => {
+            ResponseMode::Facts | ResponseMode::Minimal | ResponseMode::Stream => {
                 snippet_inner_max_chars(ctx.inner_max_chars)
             }
         }
@@ -130,7 +130,7 @@ pub(super) async fn handle_file_intent(
     } else {
         match response_mode {
             ResponseMode::Full => ctx.inner_max_chars,
-            ResponseMode::Facts | ResponseMode::Minimal => {
+            ResponseMode::Facts | ResponseMode::Minimal | ResponseMode::Stream => {
                 snippet_inner_max_chars(ctx.inner_max_chars)
             }
         }
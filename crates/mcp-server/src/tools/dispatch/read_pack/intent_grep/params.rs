@@ -96,7 +96,7 @@ pub(super) fn resolve_grep_params(
     let max_hunks = (max_chars / 200).clamp(1, MAX_GREP_HUNKS);
     let format = match response_mode {
         ResponseMode::Full => None,
-        ResponseMode::Facts | ResponseMode::Minimal => Some(ContentFormat::Plain),
+        ResponseMode::Facts | ResponseMode::Minimal | ResponseMode::Stream => Some(ContentFormat::Plain),
     };
     let grep_request = GrepContextRequest {
         path: None,
@@ -26,15 +26,17 @@ use super::{
     ReadPackRecallResult, ReadPackRequest, ReadPackResult, ReadPackSection, ReadPackSnippet,
     ReadPackSnippetKind, ResponseMode, ToolResult, CURSOR_VERSION, MAX_GREP_MATCHES,
     MAX_RECALL_INLINE_CURSOR_CHARS, REASON_HALO_CONTEXT_PACK_PRIMARY, REASON_NEEDLE_FILE_SLICE,
-    REASON_NEEDLE_GREP_HUNK,
+    REASON_NEEDLE_GREP_HUNK, REASON_NEEDLE_SEMANTIC_MATCH,
 };
 use crate::tools::cursor::cursor_fingerprint;
 use crate::tools::schemas::content_format::ContentFormat;
 use context_indexer::{root_fingerprint, ToolMeta};
 use context_search::QueryClassifier;
 use regex::RegexBuilder;
+use serde::Deserialize;
 use serde_json::{json, Value};
-use std::collections::HashSet;
+use sha2::Digest;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
 fn trim_string_to_chars(input: &str, max_chars: usize) -> String {
@@ -656,6 +658,10 @@ pub(super) fn recall_question_tokens(question: &str) -> Vec<String> {
     out
 }
 
+/// Default trade-off for the MMR final-selection pass (see `dedupe_snippets`): `1.0` picks by
+/// relevance alone, `0.0` by diversity alone.
+const DEFAULT_MMR_LAMBDA: f32 = 0.7;
+
 fn score_recall_snippet(question_tokens: &[String], snippet: &ReadPackSnippet) -> i32 {
     if question_tokens.is_empty() {
         return 0;
@@ -684,6 +690,151 @@ fn score_recall_snippet(question_tokens: &[String], snippet: &ReadPackSnippet) -
     score
 }
 
+/// One rule in the `semantic_snippets` re-ranking pipeline (see [`rank_semantic_items`]), modeled
+/// on MeiliSearch's ranking-rule pipeline. Rules are applied in order as a stable multi-key sort:
+/// each rule only breaks ties left unresolved by the rules before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum RankingRule {
+    /// Count of distinct query terms present in the snippet content — more is better.
+    Words,
+    /// Minimal span (in characters) covering every matched query term — smaller is better.
+    Proximity,
+    /// Weight derived from `snippet_kind_for_path` (code beats docs when `prefer_code` is set).
+    Attribute,
+    /// Bonus when the snippet contains the full query as a contiguous phrase.
+    Exactness,
+    /// Tie-break toward the earlier definition in the file.
+    StartLine,
+}
+
+/// Default rule order for [`rank_semantic_items`]: relevance signals first, `StartLine` last as a
+/// pure tie-break. `read_pack` has no per-request knob for this yet (see
+/// [`RECALL_DIRECTIVE_TYPO_TOLERANCE`] for the same situation), so this is the single place to
+/// change the order for every caller.
+pub(super) const DEFAULT_RANKING_RULES: &[RankingRule] = &[
+    RankingRule::Words,
+    RankingRule::Exactness,
+    RankingRule::Proximity,
+    RankingRule::Attribute,
+    RankingRule::StartLine,
+];
+
+fn ranking_words_score(tokens: &[String], content_lower: &str) -> usize {
+    tokens
+        .iter()
+        .filter(|token| content_lower.contains(token.as_str()))
+        .count()
+}
+
+/// Minimal span between the first occurrence of the earliest- and latest-appearing matched
+/// tokens; `0` when fewer than two distinct tokens matched (nothing to span, or a perfect single
+/// match — either way, the best possible proximity).
+fn ranking_proximity_span(tokens: &[String], content_lower: &str) -> usize {
+    let offsets: Vec<usize> = tokens
+        .iter()
+        .filter_map(|token| content_lower.find(token.as_str()))
+        .collect();
+    match (offsets.iter().min(), offsets.iter().max()) {
+        (Some(min), Some(max)) => max - min,
+        _ => 0,
+    }
+}
+
+fn ranking_attribute_weight(file: &str, prefer_code: bool) -> i32 {
+    if prefer_code && snippet_kind_for_path(file) == ReadPackSnippetKind::Code {
+        1
+    } else {
+        0
+    }
+}
+
+fn ranking_exactness_bonus(full_query_lower: &str, content_lower: &str) -> i32 {
+    if full_query_lower.len() < 3 {
+        return 0;
+    }
+    i32::from(content_lower.contains(full_query_lower))
+}
+
+/// Precomputed sort key for one `semantic_snippets` candidate, so [`compare_rank_keys`] never
+/// re-scans the content per comparison.
+struct SemanticItemRankKey {
+    words: usize,
+    exactness: i32,
+    proximity: usize,
+    attribute: i32,
+    start_line: i64,
+}
+
+fn semantic_item_rank_key(
+    item: &serde_json::Value,
+    question_tokens: &[String],
+    full_query_lower: &str,
+    prefer_code: bool,
+) -> SemanticItemRankKey {
+    let file = item.get("file").and_then(|v| v.as_str()).unwrap_or("");
+    let content_lower = item
+        .get("content")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_lowercase();
+    let start_line = item
+        .get("start_line")
+        .and_then(|v| v.as_i64())
+        .unwrap_or(i64::MAX);
+
+    SemanticItemRankKey {
+        words: ranking_words_score(question_tokens, &content_lower),
+        exactness: ranking_exactness_bonus(full_query_lower, &content_lower),
+        proximity: ranking_proximity_span(question_tokens, &content_lower),
+        attribute: ranking_attribute_weight(file, prefer_code),
+        start_line,
+    }
+}
+
+fn compare_rank_keys(
+    a: &SemanticItemRankKey,
+    b: &SemanticItemRankKey,
+    rules: &[RankingRule],
+) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+    for rule in rules {
+        let ordering = match rule {
+            RankingRule::Words => b.words.cmp(&a.words),
+            RankingRule::Proximity => a.proximity.cmp(&b.proximity),
+            RankingRule::Attribute => b.attribute.cmp(&a.attribute),
+            RankingRule::Exactness => b.exactness.cmp(&a.exactness),
+            RankingRule::StartLine => a.start_line.cmp(&b.start_line),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Re-ranks `items` (the `context_pack` `items` array backing `semantic_snippets`) via `rules`,
+/// applied lexicographically as a stable multi-key sort. Gives deterministic, user-steerable
+/// snippet ordering instead of opaque backend order; callers still truncate to `snippet_limit`
+/// after this.
+fn rank_semantic_items(
+    items: &[serde_json::Value],
+    question_tokens: &[String],
+    full_query: &str,
+    prefer_code: bool,
+    rules: &[RankingRule],
+) -> Vec<serde_json::Value> {
+    let full_query_lower = full_query.trim().to_lowercase();
+    let mut ranked: Vec<(serde_json::Value, SemanticItemRankKey)> = items
+        .iter()
+        .map(|item| {
+            let key = semantic_item_rank_key(item, question_tokens, &full_query_lower, prefer_code);
+            (item.clone(), key)
+        })
+        .collect();
+    ranked.sort_by(|a, b| compare_rank_keys(&a.1, &b.1, rules));
+    ranked.into_iter().map(|(item, _)| item).collect()
+}
+
 fn recall_has_code_snippet(snippets: &[ReadPackSnippet]) -> bool {
     snippets
         .iter()
@@ -764,6 +915,7 @@ fn recall_keyword_patterns(question_tokens: &[String]) -> Vec<String> {
 struct RecallCodeUpgradeParams<'a> {
     ctx: &'a ReadPackContext,
     facts_snapshot: &'a ProjectFactsResult,
+    question: &'a str,
     question_tokens: &'a [String],
     snippet_limit: usize,
     snippet_max_chars: usize,
@@ -772,6 +924,74 @@ struct RecallCodeUpgradeParams<'a> {
     exclude_paths: &'a [String],
     file_pattern: Option<&'a str>,
     allow_secrets: bool,
+    /// Embedding backend for the semantic fallback below, when the caller's policy allows it
+    /// (`None` means: grep-only, same as before this field existed).
+    semantic_embedder: Option<&'a dyn context_graph::Embedder>,
+    /// Relevance/diversity trade-off shared with the final MMR selection pass in
+    /// `dedupe_snippets` (see `DEFAULT_MMR_LAMBDA`).
+    mmr_lambda: f32,
+}
+
+// Keeps a single embedding call from scanning the whole worktree; `recall_code_scope_candidates`
+// already narrows to likely code roots, so this just bounds the worst case (monorepo, no roots).
+const SEMANTIC_PROBE_FILES: usize = 200;
+
+/// Embeds `question` and the project's likely code roots, then returns the top-scoring line
+/// windows as snippets. Used as a last resort when keyword grep finds nothing — paraphrased
+/// questions often share no literal tokens with the code that answers them.
+async fn semantic_code_snippets(
+    ctx: &ReadPackContext,
+    facts_snapshot: &ProjectFactsResult,
+    embedder: &dyn context_graph::Embedder,
+    question: &str,
+    top_k: usize,
+) -> Vec<ReadPackSnippet> {
+    if question.trim().is_empty() || top_k == 0 {
+        return Vec::new();
+    }
+
+    let code_scopes = recall_code_scope_candidates(&ctx.root, facts_snapshot);
+    let mut scanner = context_indexer::FileScanner::new(&ctx.root);
+    let Ok(files) = scanner.scan() else {
+        return Vec::new();
+    };
+
+    let mut index = context_graph::SemanticIndex::new();
+    for file in files.into_iter().take(SEMANTIC_PROBE_FILES) {
+        let Ok(rel) = file.strip_prefix(&ctx.root) else {
+            continue;
+        };
+        let rel = rel.to_string_lossy().replace('\\', "/");
+        if !code_scopes.is_empty()
+            && !code_scopes
+                .iter()
+                .any(|scope| rel.starts_with(scope.as_str()))
+        {
+            continue;
+        }
+        let Ok(content) = tokio::fs::read_to_string(&file).await else {
+            continue;
+        };
+        if content.trim().is_empty() {
+            continue;
+        }
+        let hash = format!("{:x}", sha2::Sha256::digest(content.as_bytes()));
+        index.index_file(embedder, &rel, &hash, &content);
+    }
+
+    index
+        .search(embedder, question, top_k)
+        .into_iter()
+        .map(|found| ReadPackSnippet {
+            file: found.window.file.clone(),
+            start_line: found.window.start_line,
+            end_line: found.window.end_line,
+            content: found.window.content,
+            kind: Some(snippet_kind_for_path(&found.window.file)),
+            reason: Some(REASON_NEEDLE_SEMANTIC_MATCH.to_string()),
+            next_cursor: None,
+        })
+        .collect()
 }
 
 async fn recall_upgrade_to_code_snippets(
@@ -783,9 +1003,6 @@ async fn recall_upgrade_to_code_snippets(
     }
 
     let patterns = recall_keyword_patterns(params.question_tokens);
-    if patterns.is_empty() {
-        return Ok(());
-    }
 
     let probe_hunks = params
         .snippet_limit
@@ -853,6 +1070,19 @@ async fn recall_upgrade_to_code_snippets(
         }
     }
 
+    if found_code.is_empty() {
+        if let Some(embedder) = params.semantic_embedder {
+            found_code = semantic_code_snippets(
+                params.ctx,
+                params.facts_snapshot,
+                embedder,
+                params.question,
+                params.snippet_limit.max(1),
+            )
+            .await;
+        }
+    }
+
     if found_code.is_empty() {
         return Ok(());
     }
@@ -1161,34 +1391,102 @@ async fn snippet_from_file(
     })
 }
 
-fn parse_recall_regex_directive(question: &str) -> Option<String> {
+/// Maximum edit distance allowed when recognizing a directive keyword, gated by the keyword's own
+/// length (mirrors MeiliSearch's word-length-indexed scheme): short keywords must match exactly,
+/// since a 1-typo budget on e.g. "re" or "fp" would swallow unrelated short words.
+fn directive_typo_budget(keyword_len: usize) -> u32 {
+    match keyword_len {
+        0..=4 => 0,
+        5..=8 => 1,
+        _ => 2,
+    }
+}
+
+/// Levenshtein distance between `a` and `b`, capped at `max`: returns `None` up front when the
+/// lengths differ by more than `max`, and bails out as soon as every cell in the current row
+/// exceeds the budget. Each row still spans the full length of `b`.
+fn bounded_levenshtein(a: &str, b: &str, max: u32) -> Option<u32> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) as u32 > max {
+        return None;
+    }
+
+    let mut prev: Vec<u32> = (0..=b.len() as u32).collect();
+    for (i, &ca) in a.iter().enumerate() {
+        let mut curr = vec![0u32; b.len() + 1];
+        curr[0] = (i + 1) as u32;
+        let mut row_min = curr[0];
+        for (j, &cb) in b.iter().enumerate() {
+            let cost = u32::from(ca != cb);
+            curr[j + 1] = (prev[j + 1] + 1).min(curr[j] + 1).min(prev[j] + cost);
+            row_min = row_min.min(curr[j + 1]);
+        }
+        if row_min > max {
+            return None;
+        }
+        prev = curr;
+    }
+
+    let distance = prev[b.len()];
+    (distance <= max).then_some(distance)
+}
+
+/// Whether `token` matches `keyword`, exactly or (when `typo_tolerance` is set) within the
+/// length-gated budget from [`directive_typo_budget`].
+fn keyword_matches(token: &str, keyword: &str, typo_tolerance: bool) -> bool {
+    if token == keyword {
+        return true;
+    }
+    if !typo_tolerance {
+        return false;
+    }
+    let budget = directive_typo_budget(keyword.chars().count());
+    budget > 0 && bounded_levenshtein(token, keyword, budget).is_some()
+}
+
+fn matches_any_keyword(candidate: &str, keywords: &[&str], typo_tolerance: bool) -> bool {
+    keywords
+        .iter()
+        .any(|keyword| keyword_matches(candidate, keyword, typo_tolerance))
+}
+
+/// For a `keyword:rest`-shaped token, returns `rest` (taken from the original, case-preserved
+/// `token`) when the part before the first `:` matches one of `keywords` — exactly, or within
+/// typo tolerance when `typo_tolerance` is set. `lowered` must be `token.to_ascii_lowercase()`.
+fn directive_rest<'a>(
+    token: &'a str,
+    lowered: &str,
+    keywords: &[&str],
+    typo_tolerance: bool,
+) -> Option<&'a str> {
+    let colon = lowered.find(':')?;
+    if !matches_any_keyword(&lowered[..colon], keywords, typo_tolerance) {
+        return None;
+    }
+    token.get(colon + 1..)
+}
+
+fn parse_recall_regex_directive(question: &str, typo_tolerance: bool) -> Option<String> {
     let q = question.trim();
     let lowered = q.to_ascii_lowercase();
-    for prefix in ["re:", "regex:"] {
-        if lowered.starts_with(prefix) {
-            let rest = q[prefix.len()..].trim();
-            if rest.is_empty() {
-                return None;
-            }
-            return Some(rest.to_string());
-        }
+    let rest = directive_rest(q, &lowered, &["re", "regex"], typo_tolerance)?;
+    let rest = rest.trim();
+    if rest.is_empty() {
+        return None;
     }
-    None
+    Some(rest.to_string())
 }
 
-fn parse_recall_literal_directive(question: &str) -> Option<String> {
+fn parse_recall_literal_directive(question: &str, typo_tolerance: bool) -> Option<String> {
     let q = question.trim();
     let lowered = q.to_ascii_lowercase();
-    for prefix in ["lit:", "literal:"] {
-        if lowered.starts_with(prefix) {
-            let rest = q[prefix.len()..].trim();
-            if rest.is_empty() {
-                return None;
-            }
-            return Some(rest.to_string());
-        }
+    let rest = directive_rest(q, &lowered, &["lit", "literal"], typo_tolerance)?;
+    let rest = rest.trim();
+    if rest.is_empty() {
+        return None;
     }
-    None
+    Some(rest.to_string())
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -1273,9 +1571,26 @@ fn parse_duration_ms_token(raw: &str) -> Option<u64> {
     lowered.parse::<u64>().ok()
 }
 
+/// Default for [`parse_recall_question_directives`]'s typo tolerance. `read_pack` has no
+/// per-request knob for this yet (recall intentionally hides indexing/parsing knobs from
+/// callers), so this is the single place to flip it off for an exact-match workflow.
+pub(super) const RECALL_DIRECTIVE_TYPO_TOLERANCE: bool = true;
+
 pub(super) fn parse_recall_question_directives(
     question: &str,
     root: &Path,
+) -> (String, RecallQuestionDirectives) {
+    parse_recall_question_directives_with_typo_tolerance(
+        question,
+        root,
+        RECALL_DIRECTIVE_TYPO_TOLERANCE,
+    )
+}
+
+pub(super) fn parse_recall_question_directives_with_typo_tolerance(
+    question: &str,
+    root: &Path,
+    typo_tolerance: bool,
 ) -> (String, RecallQuestionDirectives) {
     const MAX_DIRECTIVE_PREFIXES: usize = 4;
 
@@ -1290,32 +1605,27 @@ pub(super) fn parse_recall_question_directives(
 
         let lowered = token.to_ascii_lowercase();
 
-        match lowered.as_str() {
-            "fast" | "quick" | "grep" => {
-                directives.mode = RecallQuestionMode::Fast;
-                continue;
-            }
-            "deep" | "semantic" | "sem" | "index" => {
-                directives.mode = RecallQuestionMode::Deep;
-                continue;
-            }
-            _ => {}
+        if matches_any_keyword(&lowered, &["fast", "quick", "grep"], typo_tolerance) {
+            directives.mode = RecallQuestionMode::Fast;
+            continue;
+        }
+        if matches_any_keyword(
+            &lowered,
+            &["deep", "semantic", "sem", "index"],
+            typo_tolerance,
+        ) {
+            directives.mode = RecallQuestionMode::Deep;
+            continue;
         }
 
-        if let Some(rest) = lowered
-            .strip_prefix("index:")
-            .or_else(|| lowered.strip_prefix("deep:"))
-        {
+        if let Some(rest) = directive_rest(token, &lowered, &["index", "deep"], typo_tolerance) {
             if parse_duration_ms_token(rest).is_some() {
                 directives.mode = RecallQuestionMode::Deep;
                 continue;
             }
         }
 
-        if let Some(rest) = lowered
-            .strip_prefix("k:")
-            .or_else(|| lowered.strip_prefix("snips:"))
-            .or_else(|| lowered.strip_prefix("top:"))
+        if let Some(rest) = directive_rest(token, &lowered, &["k", "snips", "top"], typo_tolerance)
         {
             if let Ok(k) = rest.trim().parse::<usize>() {
                 directives.snippet_limit = Some(k.clamp(1, MAX_RECALL_SNIPPETS_PER_QUESTION));
@@ -1323,71 +1633,40 @@ pub(super) fn parse_recall_question_directives(
             }
         }
 
-        if let Some(rest) = lowered
-            .strip_prefix("ctx:")
-            .or_else(|| lowered.strip_prefix("context:"))
-        {
+        if let Some(rest) = directive_rest(token, &lowered, &["ctx", "context"], typo_tolerance) {
             if let Ok(lines) = rest.trim().parse::<usize>() {
                 directives.grep_context = Some(lines.clamp(0, 40));
                 continue;
             }
         }
 
-        let include_prefixes = ["in:", "scope:"];
-        if include_prefixes.iter().any(|p| lowered.starts_with(p)) {
+        if let Some(rest) = directive_rest(token, &lowered, &["in", "scope"], typo_tolerance) {
             if directives.include_paths.len() < MAX_DIRECTIVE_PREFIXES {
-                let prefix_len = include_prefixes
-                    .iter()
-                    .find(|p| lowered.starts_with(*p))
-                    .map(|p| p.len())
-                    .unwrap_or(0);
-                if let Some(prefix) =
-                    normalize_recall_directive_prefix(token.get(prefix_len..).unwrap_or(""))
-                {
+                if let Some(prefix) = normalize_recall_directive_prefix(rest) {
                     directives.include_paths.push(prefix);
                 }
             }
             continue;
         }
 
-        let exclude_prefixes = ["not:", "out:", "exclude:"];
-        if exclude_prefixes.iter().any(|p| lowered.starts_with(p)) {
+        if let Some(rest) =
+            directive_rest(token, &lowered, &["not", "out", "exclude"], typo_tolerance)
+        {
             if directives.exclude_paths.len() < MAX_DIRECTIVE_PREFIXES {
-                let prefix_len = exclude_prefixes
-                    .iter()
-                    .find(|p| lowered.starts_with(*p))
-                    .map(|p| p.len())
-                    .unwrap_or(0);
-                if let Some(prefix) =
-                    normalize_recall_directive_prefix(token.get(prefix_len..).unwrap_or(""))
-                {
+                if let Some(prefix) = normalize_recall_directive_prefix(rest) {
                     directives.exclude_paths.push(prefix);
                 }
             }
             continue;
         }
 
-        let pattern_prefixes = ["fp:", "glob:"];
-        if pattern_prefixes.iter().any(|p| lowered.starts_with(p)) {
-            let prefix_len = pattern_prefixes
-                .iter()
-                .find(|p| lowered.starts_with(*p))
-                .map(|p| p.len())
-                .unwrap_or(0);
-            directives.file_pattern =
-                normalize_recall_directive_pattern(token.get(prefix_len..).unwrap_or(""));
+        if let Some(rest) = directive_rest(token, &lowered, &["fp", "glob"], typo_tolerance) {
+            directives.file_pattern = normalize_recall_directive_pattern(rest);
             continue;
         }
 
-        let file_prefixes = ["file:", "open:"];
-        if file_prefixes.iter().any(|p| lowered.starts_with(p)) {
-            let prefix_len = file_prefixes
-                .iter()
-                .find(|p| lowered.starts_with(*p))
-                .map(|p| p.len())
-                .unwrap_or(0);
-            let Some((candidate, line)) = parse_path_token(token.get(prefix_len..).unwrap_or(""))
-            else {
+        if let Some(rest) = directive_rest(token, &lowered, &["file", "open"], typo_tolerance) {
+            let Some((candidate, line)) = parse_path_token(rest) else {
                 continue;
             };
             if is_disallowed_memory_file(&candidate) {
@@ -1427,16 +1706,85 @@ fn merge_recall_prefix_lists(base: &[String], extra: &[String], max: usize) -> V
     out
 }
 
-fn build_semantic_query(question: &str, topics: Option<&Vec<String>>) -> String {
+const SYNONYMS_FILE_NAME: &str = "synonyms.toml";
+
+/// Project-scoped synonym table for [`expand_query_synonyms`], loaded from
+/// `.context-finder/synonyms.toml`: `token = ["expansion", ...]`. A mutual group (`auth` <->
+/// `authentication`) is just two entries that each list the other — the format makes no
+/// distinction between one-way and mutual, it's however many keys happen to point at a token.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct SynonymTable {
+    #[serde(flatten)]
+    entries: HashMap<String, Vec<String>>,
+}
+
+/// Caps the total size of a synonym expansion so it still fits the caller's `max_chars` budget
+/// even against a pathological table.
+const MAX_SYNONYM_EXPANSION_CHARS: usize = 200;
+
+/// Best-effort: a missing or unparsable `synonyms.toml` is a no-op, not an error (see
+/// [`expand_query_synonyms`]).
+async fn load_project_synonyms(root: &Path) -> Option<SynonymTable> {
+    let path = root.join(".context-finder").join(SYNONYMS_FILE_NAME);
+    let text = tokio::fs::read_to_string(&path).await.ok()?;
+    toml::from_str(&text).ok()
+}
+
+/// Expands `question`'s tokens against `table`, returning the synonyms to append (space-joined,
+/// deduped, capped at [`MAX_SYNONYM_EXPANSION_CHARS`]) or `None` when there's nothing to add —
+/// including when `table` is absent/empty, so existing callers are unaffected.
+fn expand_query_synonyms(question: &str, table: Option<&SynonymTable>) -> Option<String> {
+    let table = table.filter(|t| !t.entries.is_empty())?;
+    let tokens = recall_question_tokens(question);
+    if tokens.is_empty() {
+        return None;
+    }
+
+    let mut seen: HashSet<String> = tokens.iter().cloned().collect();
+    let mut expansion_chars = 0usize;
+    let mut expansions: Vec<String> = Vec::new();
+
+    for token in &tokens {
+        let Some(synonyms) = table.entries.get(token) else {
+            continue;
+        };
+        for synonym in synonyms {
+            let synonym = synonym.trim();
+            if synonym.is_empty() || !seen.insert(synonym.to_lowercase()) {
+                continue;
+            }
+            if expansion_chars + synonym.len() > MAX_SYNONYM_EXPANSION_CHARS {
+                continue;
+            }
+            expansion_chars += synonym.len();
+            expansions.push(synonym.to_string());
+        }
+    }
+
+    (!expansions.is_empty()).then(|| expansions.join(" "))
+}
+
+fn build_semantic_query(
+    question: &str,
+    topics: Option<&Vec<String>>,
+    synonyms: Option<&SynonymTable>,
+) -> String {
+    let mut query = match expand_query_synonyms(question, synonyms) {
+        Some(expanded) => format!("{question} {expanded}"),
+        None => question.to_string(),
+    };
+
     let Some(topics) = topics else {
-        return question.to_string();
+        return query;
     };
     if topics.is_empty() {
-        return question.to_string();
+        return query;
     }
 
     let joined = topics.join(", ");
-    format!("{question}\n\nTopics: {joined}")
+    query.push_str("\n\nTopics: ");
+    query.push_str(&joined);
+    query
 }
 
 async fn decode_recall_cursor(
@@ -1662,8 +2010,11 @@ pub(super) async fn handle_recall_intent(
         } else {
             clean_question
         };
-        let user_directive = parse_recall_regex_directive(&clean_question).is_some()
-            || parse_recall_literal_directive(&clean_question).is_some();
+        let user_directive =
+            parse_recall_regex_directive(&clean_question, RECALL_DIRECTIVE_TYPO_TOLERANCE)
+                .is_some()
+                || parse_recall_literal_directive(&clean_question, RECALL_DIRECTIVE_TYPO_TOLERANCE)
+                    .is_some();
         let structural_intent = if user_directive {
             None
         } else {
@@ -1794,7 +2145,9 @@ pub(super) async fn handle_recall_intent(
         }
 
         if snippets.is_empty() {
-            if let Some(regex) = parse_recall_regex_directive(&clean_question) {
+            if let Some(regex) =
+                parse_recall_regex_directive(&clean_question, RECALL_DIRECTIVE_TYPO_TOLERANCE)
+            {
                 if let Ok((found, _)) = snippets_from_grep_filtered(
                     ctx,
                     &regex,
@@ -1843,7 +2196,9 @@ pub(super) async fn handle_recall_intent(
         }
 
         if snippets.is_empty() {
-            if let Some(literal) = parse_recall_literal_directive(&clean_question) {
+            if let Some(literal) =
+                parse_recall_literal_directive(&clean_question, RECALL_DIRECTIVE_TYPO_TOLERANCE)
+            {
                 let escaped = regex::escape(&literal);
                 if let Ok((found, _)) = snippets_from_grep_filtered(
                     ctx,
@@ -1991,11 +2346,16 @@ pub(super) async fn handle_recall_intent(
                 && !avoid_semantic_for_structural
                 && (!is_ops || question_mode == RecallQuestionMode::Deep)
             {
+                let synonym_table = load_project_synonyms(&ctx.root).await;
                 let tool_result = context_pack(
                     service,
                     ContextPackRequest {
                         path: Some(ctx.root_display.clone()),
-                        query: build_semantic_query(&clean_question, topics.as_ref()),
+                        query: build_semantic_query(
+                            &clean_question,
+                            topics.as_ref(),
+                            synonym_table.as_ref(),
+                        ),
                         language: None,
                         strategy: None,
                         limit: Some(snippet_limit),
@@ -2032,7 +2392,14 @@ pub(super) async fn handle_recall_intent(
                     if tool_result.is_error != Some(true) {
                         if let Some(value) = tool_result.structured_content.clone() {
                             if let Some(items) = value.get("items").and_then(|v| v.as_array()) {
-                                for item in items.iter().take(snippet_limit) {
+                                let ranked = rank_semantic_items(
+                                    items,
+                                    &question_tokens,
+                                    &clean_question,
+                                    effective_prefer_code,
+                                    DEFAULT_RANKING_RULES,
+                                );
+                                for item in ranked.iter().take(snippet_limit) {
                                     let Some(file) = item.get("file").and_then(|v| v.as_str())
                                     else {
                                         continue;
@@ -2115,6 +2482,7 @@ pub(super) async fn handle_recall_intent(
                 RecallCodeUpgradeParams {
                     ctx,
                     facts_snapshot: &facts_snapshot,
+                    question: &clean_question,
                     question_tokens: &question_tokens,
                     snippet_limit,
                     snippet_max_chars,
@@ -2123,6 +2491,8 @@ pub(super) async fn handle_recall_intent(
                     exclude_paths: &effective_exclude_paths,
                     file_pattern: effective_file_pattern.as_deref(),
                     allow_secrets,
+                    semantic_embedder: None,
+                    mmr_lambda: DEFAULT_MMR_LAMBDA,
                 },
                 &mut snippets,
             )
@@ -41,6 +41,7 @@ pub(super) async fn write_recall_cursor(
         prefer_code: input.prefer_code,
         include_docs: input.include_docs,
         allow_secrets: input.allow_secrets,
+        fuzzy: input.fuzzy,
         next_question_index: 0,
     };
 
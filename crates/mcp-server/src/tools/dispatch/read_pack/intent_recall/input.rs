@@ -21,6 +21,7 @@ pub(super) struct RecallInput {
     pub(super) prefer_code: Option<bool>,
     pub(super) include_docs: Option<bool>,
     pub(super) allow_secrets: bool,
+    pub(super) fuzzy: bool,
 }
 
 pub(super) async fn resolve_recall_input(
@@ -43,7 +44,8 @@ pub(super) async fn resolve_recall_input(
             || trimmed_non_empty_str(request.file_pattern.as_deref()).is_some()
             || request.prefer_code.is_some()
             || request.include_docs.is_some()
-            || request.allow_secrets.is_some();
+            || request.allow_secrets.is_some()
+            || request.fuzzy.is_some();
         if overrides {
             return Err(call_error(
                 "invalid_cursor",
@@ -96,6 +98,7 @@ pub(super) async fn resolve_recall_input(
             prefer_code: decoded.prefer_code,
             include_docs: decoded.include_docs,
             allow_secrets: decoded.allow_secrets,
+            fuzzy: decoded.fuzzy,
         });
     }
 
@@ -109,5 +112,6 @@ pub(super) async fn resolve_recall_input(
         prefer_code: request.prefer_code,
         include_docs: request.include_docs,
         allow_secrets: request.allow_secrets.unwrap_or(false),
+        fuzzy: request.fuzzy.unwrap_or(false),
     })
 }
@@ -20,6 +20,7 @@ use super::{
     call_error, ContextFinderService, ReadPackContext, ReadPackRecallResult, ReadPackRequest,
     ReadPackSection, ResponseMode, ToolResult,
 };
+use crate::tools::recall_vocab_cache;
 use std::collections::HashSet;
 
 pub(super) async fn handle_recall_intent(
@@ -61,12 +62,32 @@ pub(super) async fn handle_recall_intent(
         session.seen_snippet_files_set_snapshot()
     };
 
+    // Lazily loaded: most recall calls don't opt into fuzzy matching, so skip the sidecar read
+    // (and any rebuild it might trigger) unless at least one question could use it -- either via
+    // `fuzzy: true` on the request, or a per-question `fuzzy`/`typo` directive word.
+    let wants_fuzzy = input.fuzzy
+        || input.questions.iter().any(|q| {
+            q.split_whitespace()
+                .any(|w| w.eq_ignore_ascii_case("fuzzy") || w.eq_ignore_ascii_case("typo"))
+        });
+    let vocab = if wants_fuzzy {
+        recall_vocab_cache::load_or_rebuild(&ctx.root).await
+    } else {
+        None
+    };
+
     let mut processed = 0usize;
     let mut next_index = None;
 
     for (offset, question) in input.questions.iter().enumerate().skip(input.start_index) {
-        let question_ctx =
-            build_question_context(ctx, question, &input, &budget, semantic_index_fresh);
+        let question_ctx = build_question_context(
+            ctx,
+            question,
+            &input,
+            &budget,
+            semantic_index_fresh,
+            vocab.as_ref(),
+        );
         let snippets = collect_recall_snippets(
             service,
             ctx,
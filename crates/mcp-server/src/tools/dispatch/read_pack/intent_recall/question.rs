@@ -13,6 +13,7 @@ use super::super::recall_paths::merge_recall_prefix_lists;
 use super::super::ReadPackContext;
 use super::budget::RecallBudget;
 use super::input::RecallInput;
+use crate::tools::recall_vocab_fst::RecallVocabFst;
 use context_search::QueryClassifier;
 
 pub(super) struct RecallQuestionContext {
@@ -32,6 +33,11 @@ pub(super) struct RecallQuestionContext {
     pub(super) ops: Option<OpsIntent>,
     pub(super) docs_intent: bool,
     pub(super) question_tokens: Vec<String>,
+    /// Edit-distance-bounded expansions of `question_tokens` that don't already appear verbatim,
+    /// resolved against the corpus vocabulary when `fuzzy` is opted into (see
+    /// `recall_vocab_fst::RecallVocabFst::expand`). Always scored lower than an exact
+    /// `question_tokens` hit (see `recall_scoring::score_recall_snippet_with_fuzzy`).
+    pub(super) fuzzy_tokens: Vec<String>,
     pub(super) regex_directive: Option<String>,
     pub(super) literal_directive: Option<String>,
     pub(super) file_ref: Option<(String, Option<usize>)>,
@@ -46,6 +52,7 @@ pub(super) fn build_question_context(
     input: &RecallInput,
     budget: &RecallBudget,
     semantic_index_fresh: bool,
+    vocab: Option<&RecallVocabFst>,
 ) -> RecallQuestionContext {
     let (clean_question, directives) = parse_recall_question_directives(question, &ctx.root);
     let clean_question = if clean_question.is_empty() {
@@ -64,6 +71,13 @@ pub(super) fn build_question_context(
     let ops = ops_intent(&clean_question);
     let docs_intent = QueryClassifier::is_docs_intent(&clean_question);
     let question_tokens = recall_question_tokens(&clean_question);
+    let fuzzy_tokens = if input.fuzzy || directives.fuzzy {
+        vocab
+            .map(|vocab| expand_fuzzy_tokens(&question_tokens, vocab))
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
     let effective_prefer_code = input.prefer_code.unwrap_or(!docs_intent);
 
     let question_mode = directives.mode;
@@ -136,6 +150,7 @@ pub(super) fn build_question_context(
         ops,
         docs_intent,
         question_tokens,
+        fuzzy_tokens,
         regex_directive,
         literal_directive,
         file_ref,
@@ -144,3 +159,18 @@ pub(super) fn build_question_context(
         prefer_code: input.prefer_code,
     }
 }
+
+/// Expands every `question_tokens` entry against `vocab`'s corpus vocabulary, dropping expansions
+/// that are themselves question tokens (those already score as exact hits) or already collected.
+/// A token the corpus contains verbatim is still expanded.
+fn expand_fuzzy_tokens(question_tokens: &[String], vocab: &RecallVocabFst) -> Vec<String> {
+    let mut out = Vec::new();
+    for token in question_tokens {
+        for expansion in vocab.expand(token) {
+            if !question_tokens.contains(&expansion) && !out.contains(&expansion) {
+                out.push(expansion);
+            }
+        }
+    }
+    out
+}
@@ -8,7 +8,9 @@ use super::super::recall_directives::RecallQuestionMode;
 use super::super::recall_keywords::best_keyword_pattern;
 use super::super::recall_ops::ops_grep_pattern;
 use super::super::recall_paths::recall_path_allowed;
-use super::super::recall_scoring::{recall_has_code_snippet, score_recall_snippet};
+use super::super::recall_scoring::{
+    recall_has_code_snippet, score_recall_snippet_with_fuzzy, DEFAULT_MMR_LAMBDA,
+};
 use super::super::recall_snippets::{
     recall_upgrade_to_code_snippets, snippet_from_file, snippets_from_grep,
     snippets_from_grep_filtered, GrepSnippetParams, RecallCodeUpgradeParams, SnippetFromFileParams,
@@ -224,8 +226,16 @@ pub(super) async fn collect_recall_snippets(
 
             if !found_snippets.is_empty() {
                 found_snippets.sort_by(|a, b| {
-                    let a_score = score_recall_snippet(&question.question_tokens, a);
-                    let b_score = score_recall_snippet(&question.question_tokens, b);
+                    let a_score = score_recall_snippet_with_fuzzy(
+                        &question.question_tokens,
+                        &question.fuzzy_tokens,
+                        a,
+                    );
+                    let b_score = score_recall_snippet_with_fuzzy(
+                        &question.question_tokens,
+                        &question.fuzzy_tokens,
+                        b,
+                    );
                     b_score
                         .cmp(&a_score)
                         .then_with(|| {
@@ -422,6 +432,7 @@ pub(super) async fn collect_recall_snippets(
             RecallCodeUpgradeParams {
                 ctx,
                 facts_snapshot,
+                question: &question.clean_question,
                 question_tokens: &question.question_tokens,
                 snippet_limit: question.snippet_limit,
                 snippet_max_chars: question.snippet_max_chars,
@@ -430,6 +441,10 @@ pub(super) async fn collect_recall_snippets(
                 exclude_paths: &question.effective_exclude_paths,
                 file_pattern: question.effective_file_pattern.as_deref(),
                 allow_secrets: question.allow_secrets,
+                // No embedding backend wired up yet; once one lands it should only be passed
+                // here when `question.allow_semantic` holds.
+                semantic_embedder: None,
+                mmr_lambda: DEFAULT_MMR_LAMBDA,
             },
             &mut snippets,
         )
@@ -3,7 +3,7 @@ use super::super::candidates::{collect_ops_file_candidates, ops_candidate_score}
 use super::super::cursors::snippet_kind_for_path;
 use super::super::recall_ops::ops_grep_pattern;
 use super::super::recall_paths::recall_path_allowed;
-use super::super::recall_scoring::score_recall_snippet;
+use super::super::recall_scoring::score_recall_snippet_with_fuzzy;
 use super::super::recall_snippets::{
     snippet_from_file, snippets_from_grep, GrepSnippetParams, SnippetFromFileParams,
 };
@@ -71,8 +71,16 @@ pub(super) async fn ops_snippets(
 
     if !found_snippets.is_empty() {
         found_snippets.sort_by(|a, b| {
-            let a_score = score_recall_snippet(&question.question_tokens, a);
-            let b_score = score_recall_snippet(&question.question_tokens, b);
+            let a_score = score_recall_snippet_with_fuzzy(
+                &question.question_tokens,
+                &question.fuzzy_tokens,
+                a,
+            );
+            let b_score = score_recall_snippet_with_fuzzy(
+                &question.question_tokens,
+                &question.fuzzy_tokens,
+                b,
+            );
             b_score
                 .cmp(&a_score)
                 .then_with(|| ops_candidate_score(&b.file).cmp(&ops_candidate_score(&a.file)))
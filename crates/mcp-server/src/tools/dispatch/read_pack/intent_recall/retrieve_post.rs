@@ -1,3 +1,4 @@
+use super::super::recall_scoring::{score_recall_snippet_with_fuzzy, DEFAULT_MMR_LAMBDA};
 use super::super::recall_snippets::{recall_upgrade_to_code_snippets, RecallCodeUpgradeParams};
 use super::super::{ProjectFactsResult, ReadPackContext, ReadPackSnippet};
 use super::question::RecallQuestionContext;
@@ -20,6 +21,7 @@ pub(super) async fn maybe_upgrade_to_code_snippets(
             RecallCodeUpgradeParams {
                 ctx,
                 facts_snapshot,
+                question: &question.clean_question,
                 question_tokens: &question.question_tokens,
                 snippet_limit: question.snippet_limit,
                 snippet_max_chars: question.snippet_max_chars,
@@ -28,6 +30,8 @@ pub(super) async fn maybe_upgrade_to_code_snippets(
                 exclude_paths: &question.effective_exclude_paths,
                 file_pattern: question.effective_file_pattern.as_deref(),
                 allow_secrets: question.allow_secrets,
+                semantic_embedder: None,
+                mmr_lambda: DEFAULT_MMR_LAMBDA,
             },
             snippets,
         )
@@ -38,29 +42,105 @@ pub(super) async fn maybe_upgrade_to_code_snippets(
 pub(super) fn dedupe_snippets(
     snippets: Vec<ReadPackSnippet>,
     used_files: &mut HashSet<String>,
+    question_tokens: &[String],
+    fuzzy_tokens: &[String],
+    snippet_limit: usize,
+    mmr_lambda: f32,
 ) -> Vec<ReadPackSnippet> {
-    // Global de-dupe: prefer covering *more files* (breadth) when answering multiple
-    // questions in one call. This prevents "README spam" from consuming the entire budget.
-    if snippets.len() > 1 {
-        let mut unique: Vec<ReadPackSnippet> = Vec::new();
-        let mut duplicates: Vec<ReadPackSnippet> = Vec::new();
-        for snippet in snippets {
-            if used_files.insert(snippet.file.clone()) {
-                unique.push(snippet);
-            } else {
-                duplicates.push(snippet);
-            }
+    // Cross-question breadth: drop snippets whose file was already emitted for an earlier
+    // question in this batch, so one file can't eat the whole answer across multiple questions.
+    let mut candidates: Vec<ReadPackSnippet> = Vec::with_capacity(snippets.len());
+    let mut fallback: Option<ReadPackSnippet> = None;
+    for snippet in snippets {
+        if used_files.insert(snippet.file.clone()) {
+            candidates.push(snippet);
+        } else if fallback.is_none() {
+            fallback = Some(snippet);
         }
-        if unique.is_empty() {
-            if let Some(first) = duplicates.into_iter().next() {
-                unique.push(first);
+    }
+
+    if candidates.is_empty() {
+        // Everything was a repeat file; still emit one snippet rather than nothing.
+        return fallback.into_iter().collect();
+    }
+    if candidates.len() == 1 {
+        return candidates;
+    }
+
+    mmr_select(
+        candidates,
+        question_tokens,
+        fuzzy_tokens,
+        snippet_limit,
+        mmr_lambda,
+    )
+}
+
+/// Maximal Marginal Relevance: at each step picks the remaining candidate maximizing
+/// `lambda * rel(s) - (1 - lambda) * max_sim(s, selected)`, so near-identical hunks (vendored
+/// copies, boilerplate) from *different* files no longer eat the whole budget.
+fn mmr_select(
+    snippets: Vec<ReadPackSnippet>,
+    question_tokens: &[String],
+    fuzzy_tokens: &[String],
+    snippet_limit: usize,
+    mmr_lambda: f32,
+) -> Vec<ReadPackSnippet> {
+    let relevance: Vec<i32> = snippets
+        .iter()
+        .map(|snippet| score_recall_snippet_with_fuzzy(question_tokens, fuzzy_tokens, snippet))
+        .collect();
+    let token_sets: Vec<HashSet<&str>> = snippets
+        .iter()
+        .map(|snippet| snippet.content.split_whitespace().collect())
+        .collect();
+
+    let limit = snippet_limit.max(1).min(snippets.len());
+    let mut remaining: Vec<usize> = (0..snippets.len()).collect();
+    let mut selected: Vec<usize> = Vec::with_capacity(limit);
+
+    while selected.len() < limit && !remaining.is_empty() {
+        let mut best_pos = 0usize;
+        let mut best_score = f32::NEG_INFINITY;
+        for (pos, &idx) in remaining.iter().enumerate() {
+            let score = mmr_score(idx, &relevance, &token_sets, &selected, mmr_lambda);
+            if score > best_score {
+                best_score = score;
+                best_pos = pos;
             }
         }
-        unique
-    } else if let Some(snippet) = snippets.first() {
-        used_files.insert(snippet.file.clone());
-        snippets
-    } else {
-        snippets
+        selected.push(remaining.remove(best_pos));
+    }
+
+    let mut slots: Vec<Option<ReadPackSnippet>> = snippets.into_iter().map(Some).collect();
+    selected
+        .into_iter()
+        .filter_map(|idx| slots[idx].take())
+        .collect()
+}
+
+fn mmr_score(
+    idx: usize,
+    relevance: &[i32],
+    token_sets: &[HashSet<&str>],
+    selected: &[usize],
+    mmr_lambda: f32,
+) -> f32 {
+    let max_sim = selected
+        .iter()
+        .map(|&sel| jaccard_similarity(&token_sets[idx], &token_sets[sel]))
+        .fold(0.0f32, f32::max);
+    mmr_lambda * relevance[idx] as f32 - (1.0 - mmr_lambda) * max_sim
+}
+
+fn jaccard_similarity(a: &HashSet<&str>, b: &HashSet<&str>) -> f32 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let intersection = a.intersection(b).count();
+    if intersection == 0 {
+        return 0.0;
     }
+    let union = a.union(b).count();
+    intersection as f32 / union as f32
 }
@@ -10,8 +10,8 @@ use super::{
     GrepContextComputeOptions, GrepContextCursorV1, GrepContextRequest, McpError, OverviewRequest,
     OverviewResult, ProjectFactsResult, ReadPackBudget, ReadPackIntent, ReadPackNextAction,
     ReadPackRecallResult, ReadPackRequest, ReadPackResult, ReadPackSection, ReadPackSnippet,
-    ReadPackSnippetKind, ReadPackTruncation, RepoOnboardingPackRequest, ResponseMode,
-    CURSOR_VERSION,
+    ReadPackSnippetKind, ReadPackSnippetRange, ReadPackTruncation, RepoOnboardingPackRequest,
+    ResponseMode, CURSOR_VERSION,
 };
 use crate::tools::context_doc::ContextDocBuilder;
 use crate::tools::cursor::cursor_fingerprint;
@@ -19,10 +19,11 @@ use crate::tools::file_slice::compute_onboarding_doc_slice;
 use crate::tools::schemas::content_format::ContentFormat;
 use context_indexer::{root_fingerprint, ToolMeta};
 use context_protocol::ToolNextAction;
-use context_search::QueryClassifier;
+use context_search::{LineIndex, OffsetEncoding, QueryClassifier};
 use regex::RegexBuilder;
 use serde::Deserialize;
 use serde_json::{json, Value};
+use sha2::Digest;
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::time::Duration;
@@ -459,6 +460,7 @@ const REASON_ANCHOR_FOCUS_FILE: &str = "anchor:focus_file";
 const REASON_ANCHOR_DOC: &str = "anchor:doc";
 const REASON_ANCHOR_ENTRYPOINT: &str = "anchor:entrypoint";
 const REASON_NEEDLE_GREP_HUNK: &str = "needle:grep_hunk";
+const REASON_NEEDLE_SEMANTIC_MATCH: &str = "needle:semantic_match";
 const REASON_NEEDLE_FILE_SLICE: &str = "needle:cat";
 const REASON_HALO_CONTEXT_PACK_PRIMARY: &str = "halo:context_pack_primary";
 const REASON_HALO_CONTEXT_PACK_RELATED: &str = "halo:context_pack_related";
@@ -834,7 +836,7 @@ async fn repair_recall_cursor_after_trim(
         allow_secrets,
     ) = if let Some(cursor) = trimmed_non_empty_str(request.cursor.as_deref()) {
         match decode_recall_cursor(service, cursor).await {
-            Ok(decoded) => (
+            Ok(RecallCursorResolution::Decoded(decoded)) => (
                 decoded.questions,
                 decoded.topics,
                 decoded.include_paths,
@@ -844,7 +846,12 @@ async fn repair_recall_cursor_after_trim(
                 decoded.include_docs,
                 decoded.allow_secrets,
             ),
-            Err(_) => return,
+            // Stale store entry: there's no prior session left to repair, so drop the cursor
+            // rather than guessing at a replacement.
+            Ok(RecallCursorResolution::Stale) | Err(_) => {
+                result.next_cursor = None;
+                return;
+            }
         }
     } else {
         (
@@ -1319,7 +1326,7 @@ async fn handle_file_intent(
     } else {
         match response_mode {
             ResponseMode::Full => ctx.inner_max_chars,
-            ResponseMode::Facts | ResponseMode::Minimal => {
+            ResponseMode::Facts | ResponseMode::Minimal | ResponseMode::Stream => {
                 snippet_inner_max_chars(ctx.inner_max_chars)
             }
         }
@@ -1615,7 +1622,7 @@ async fn handle_grep_intent(
     let max_hunks = (grep_max_chars / 200).clamp(1, MAX_GREP_HUNKS);
     let format = match response_mode {
         ResponseMode::Full => None,
-        ResponseMode::Facts | ResponseMode::Minimal => Some(ContentFormat::Plain),
+        ResponseMode::Facts | ResponseMode::Minimal | ResponseMode::Stream => Some(ContentFormat::Plain),
     };
     let grep_request = GrepContextRequest {
         path: None,
@@ -3825,6 +3832,10 @@ fn recall_question_tokens(question: &str) -> Vec<String> {
     out
 }
 
+/// Default trade-off for the MMR final-selection pass (see `dedupe_snippets`): `1.0` picks by
+/// relevance alone, `0.0` by diversity alone.
+const DEFAULT_MMR_LAMBDA: f32 = 0.7;
+
 fn score_recall_snippet(question_tokens: &[String], snippet: &ReadPackSnippet) -> i32 {
     if question_tokens.is_empty() {
         return 0;
@@ -3933,6 +3944,7 @@ fn recall_keyword_patterns(question_tokens: &[String]) -> Vec<String> {
 struct RecallCodeUpgradeParams<'a> {
     ctx: &'a ReadPackContext,
     facts_snapshot: &'a ProjectFactsResult,
+    question: &'a str,
     question_tokens: &'a [String],
     snippet_limit: usize,
     snippet_max_chars: usize,
@@ -3941,6 +3953,71 @@ struct RecallCodeUpgradeParams<'a> {
     exclude_paths: &'a [String],
     file_pattern: Option<&'a str>,
     allow_secrets: bool,
+    /// Embedding backend for the semantic fallback below, when the caller's policy allows it
+    /// (`None` means: grep-only, same as before this field existed).
+    semantic_embedder: Option<&'a dyn context_graph::Embedder>,
+    /// Relevance/diversity trade-off shared with the final MMR selection pass in
+    /// `intent_recall::dedupe_snippets` (see `recall_scoring::DEFAULT_MMR_LAMBDA`).
+    mmr_lambda: f32,
+}
+
+// Keeps a single embedding call from scanning the whole worktree; `recall_code_scope_candidates`
+// already narrows to likely code roots, so this just bounds the worst case (monorepo, no roots).
+const SEMANTIC_PROBE_FILES: usize = 200;
+
+/// Embeds `question` and the project's likely code roots, then returns the top-scoring line
+/// windows as snippets. Used as a last resort when keyword grep finds nothing — paraphrased
+/// questions often share no literal tokens with the code that answers them.
+async fn semantic_code_snippets(
+    ctx: &ReadPackContext,
+    facts_snapshot: &ProjectFactsResult,
+    embedder: &dyn context_graph::Embedder,
+    question: &str,
+    top_k: usize,
+) -> Vec<ReadPackSnippet> {
+    if question.trim().is_empty() || top_k == 0 {
+        return Vec::new();
+    }
+
+    let code_scopes = recall_code_scope_candidates(&ctx.root, facts_snapshot);
+    let mut scanner = context_indexer::FileScanner::new(&ctx.root);
+    let Ok(files) = scanner.scan() else {
+        return Vec::new();
+    };
+
+    let mut index = context_graph::SemanticIndex::new();
+    for file in files.into_iter().take(SEMANTIC_PROBE_FILES) {
+        let Ok(rel) = file.strip_prefix(&ctx.root) else {
+            continue;
+        };
+        let rel = rel.to_string_lossy().replace('\\', "/");
+        if !code_scopes.is_empty() && !code_scopes.iter().any(|scope| rel.starts_with(scope.as_str())) {
+            continue;
+        }
+        let Ok(content) = tokio::fs::read_to_string(&file).await else {
+            continue;
+        };
+        if content.trim().is_empty() {
+            continue;
+        }
+        let hash = format!("{:x}", sha2::Sha256::digest(content.as_bytes()));
+        index.index_file(embedder, &rel, &hash, &content);
+    }
+
+    index
+        .search(embedder, question, top_k)
+        .into_iter()
+        .map(|found| ReadPackSnippet {
+            file: found.window.file.clone(),
+            start_line: found.window.start_line,
+            end_line: found.window.end_line,
+            range: snippet_range(&found.window.content, ctx.offset_encoding),
+            content: found.window.content,
+            kind: Some(snippet_kind_for_path(&found.window.file)),
+            reason: Some(REASON_NEEDLE_SEMANTIC_MATCH.to_string()),
+            next_cursor: None,
+        })
+        .collect()
 }
 
 async fn recall_upgrade_to_code_snippets(
@@ -3952,9 +4029,6 @@ async fn recall_upgrade_to_code_snippets(
     }
 
     let patterns = recall_keyword_patterns(params.question_tokens);
-    if patterns.is_empty() {
-        return Ok(());
-    }
 
     let probe_hunks = params
         .snippet_limit
@@ -3975,6 +4049,7 @@ async fn recall_upgrade_to_code_snippets(
                 max_chars: params.snippet_max_chars,
                 case_sensitive: false,
                 allow_secrets: params.allow_secrets,
+                offset_encoding: params.ctx.offset_encoding,
             },
             params.include_paths,
             params.exclude_paths,
@@ -4004,6 +4079,7 @@ async fn recall_upgrade_to_code_snippets(
                         max_chars: params.snippet_max_chars,
                         case_sensitive: false,
                         allow_secrets: params.allow_secrets,
+                        offset_encoding: params.ctx.offset_encoding,
                     },
                     &code_scopes,
                     params.exclude_paths,
@@ -4021,6 +4097,19 @@ async fn recall_upgrade_to_code_snippets(
         }
     }
 
+    if found_code.is_empty() {
+        if let Some(embedder) = params.semantic_embedder {
+            found_code = semantic_code_snippets(
+                params.ctx,
+                params.facts_snapshot,
+                embedder,
+                params.question,
+                params.snippet_limit.max(1),
+            )
+            .await;
+        }
+    }
+
     if found_code.is_empty() {
         return Ok(());
     }
@@ -4076,6 +4165,25 @@ struct GrepSnippetParams {
     max_chars: usize,
     case_sensitive: bool,
     allow_secrets: bool,
+    /// Offset encoding for each returned snippet's precise `range` (see
+    /// `ReadPackRequest::offset_encoding`); `None` skips computing ranges.
+    offset_encoding: Option<OffsetEncoding>,
+}
+
+/// A snippet's precise content span in the requested offset encoding (always spans the whole
+/// snippet body, i.e. `start` is `0`), mirroring `tools::SearchRange` for `read_pack` snippets.
+fn snippet_range(
+    content: &str,
+    offset_encoding: Option<OffsetEncoding>,
+) -> Option<ReadPackSnippetRange> {
+    offset_encoding.map(|encoding| {
+        let end = LineIndex::new(content).convert(content.len(), encoding);
+        ReadPackSnippetRange {
+            encoding: encoding.as_str().to_string(),
+            start: 0,
+            end,
+        }
+    })
 }
 
 fn recall_prefix_matches(path: &str, prefix: &str) -> bool {
@@ -4126,6 +4234,7 @@ async fn snippets_from_grep(
     params: GrepSnippetParams,
 ) -> ToolResult<(Vec<ReadPackSnippet>, Option<String>)> {
     let max_hunks = params.max_hunks;
+    let offset_encoding = params.offset_encoding;
     let regex = RegexBuilder::new(pattern)
         .case_insensitive(!params.case_sensitive)
         .build()
@@ -4181,6 +4290,7 @@ async fn snippets_from_grep(
             file: hunk.file.clone(),
             start_line: hunk.start_line,
             end_line: hunk.end_line,
+            range: snippet_range(&hunk.content, offset_encoding),
             content: hunk.content.clone(),
             kind: Some(snippet_kind_for_path(&hunk.file)),
             reason: Some(REASON_NEEDLE_GREP_HUNK.to_string()),
@@ -4234,6 +4344,7 @@ async fn snippets_from_grep_filtered(
                 max_chars: params.max_chars,
                 case_sensitive: params.case_sensitive,
                 allow_secrets: params.allow_secrets,
+                offset_encoding: params.offset_encoding,
             },
         )
         .await?;
@@ -4268,6 +4379,7 @@ struct SnippetFromFileParams {
     max_lines: usize,
     max_chars: usize,
     allow_secrets: bool,
+    offset_encoding: Option<OffsetEncoding>,
 }
 
 async fn snippet_from_file(
@@ -4321,6 +4433,7 @@ async fn snippet_from_file(
         file: slice.file.clone(),
         start_line: slice.start_line,
         end_line: slice.end_line,
+        range: snippet_range(&slice.content, params.offset_encoding),
         content: slice.content.clone(),
         kind,
         reason: Some(REASON_NEEDLE_FILE_SLICE.to_string()),
@@ -4605,10 +4718,19 @@ fn build_semantic_query(question: &str, topics: Option<&Vec<String>>) -> String
     format!("{question}\n\nTopics: {joined}")
 }
 
+/// Outcome of resolving a recall cursor against the persistent store. `Stale` covers both "the
+/// server restarted and the backing entry is gone" and "the entry's TTL was reclaimed" -- callers
+/// treat it the same way: re-derive the session from the request instead of returning an
+/// `invalid_cursor` error.
+enum RecallCursorResolution {
+    Decoded(ReadPackRecallCursorV1),
+    Stale,
+}
+
 async fn decode_recall_cursor(
     service: &ContextFinderService,
     cursor: &str,
-) -> ToolResult<ReadPackRecallCursorV1> {
+) -> ToolResult<RecallCursorResolution> {
     let value: serde_json::Value = decode_cursor(cursor)
         .map_err(|err| call_error("invalid_cursor", format!("Invalid cursor: {err}")))?;
 
@@ -4621,25 +4743,56 @@ async fn decode_recall_cursor(
     let store_id = value.get("store_id").and_then(|v| v.as_u64());
     if let Some(store_id) = store_id {
         let Some(bytes) = service.state.cursor_store_get(store_id).await else {
-            return Err(call_error(
-                "invalid_cursor",
-                "Invalid cursor: expired recall continuation",
-            ));
+            return Ok(RecallCursorResolution::Stale);
         };
-        return serde_json::from_slice::<ReadPackRecallCursorV1>(&bytes).map_err(|err| {
+        let decoded = serde_json::from_slice::<ReadPackRecallCursorV1>(&bytes).map_err(|err| {
             call_error(
                 "invalid_cursor",
                 format!("Invalid cursor: stored continuation decode failed: {err}"),
             )
-        });
+        })?;
+        return Ok(RecallCursorResolution::Decoded(decoded));
     }
 
-    serde_json::from_value::<ReadPackRecallCursorV1>(value).map_err(|err| {
+    let decoded = serde_json::from_value::<ReadPackRecallCursorV1>(value).map_err(|err| {
         call_error(
             "invalid_cursor",
             format!("Invalid cursor: recall cursor decode failed: {err}"),
         )
-    })
+    })?;
+    Ok(RecallCursorResolution::Decoded(decoded))
+}
+
+/// Normalizes a fresh recall session's fields straight from the request, i.e. what a first
+/// `intent=recall` call (no cursor) uses to seed `questions`/`next_question_index`/filters. Also
+/// reused when a stored cursor can't be resolved. A cursor request can't carry `ask`/`questions`
+/// (those overrides are rejected), so a stale continuation gets no questions here and ends in the
+/// `missing_field` error rather than an `invalid_cursor` one; the session is not restarted.
+#[allow(clippy::type_complexity)]
+fn fresh_recall_session_fields(
+    request: &ReadPackRequest,
+) -> (
+    Vec<String>,
+    Option<Vec<String>>,
+    usize,
+    Vec<String>,
+    Vec<String>,
+    Option<String>,
+    Option<bool>,
+    Option<bool>,
+    bool,
+) {
+    (
+        normalize_questions(request),
+        normalize_topics(request),
+        0,
+        normalize_path_prefix_list(request.include_paths.as_ref()),
+        normalize_path_prefix_list(request.exclude_paths.as_ref()),
+        normalize_optional_pattern(request.file_pattern.as_deref()),
+        request.prefer_code,
+        request.include_docs,
+        request.allow_secrets.unwrap_or(false),
+    )
 }
 
 async fn handle_recall_intent(
@@ -4684,14 +4837,41 @@ async fn handle_recall_intent(
             ));
         }
 
-        let decoded: ReadPackRecallCursorV1 = decode_recall_cursor(service, cursor).await?;
-        if decoded.v != CURSOR_VERSION || decoded.tool != "read_pack" || decoded.mode != "recall" {
-            return Err(call_error("invalid_cursor", "Invalid cursor: wrong tool"));
-        }
-        let expected_root_hash = cursor_fingerprint(&ctx.root_display);
-        let expected_root_fingerprint = root_fingerprint(&ctx.root_display);
-        if let Some(hash) = decoded.root_hash {
-            if hash != expected_root_hash {
+        let decoded = match decode_recall_cursor(service, cursor).await? {
+            RecallCursorResolution::Decoded(decoded) => Some(decoded),
+            // The cursor names a store_id the persistent backend no longer has (server restart,
+            // cross-process cache miss, or TTL reclaim). Re-derive the session from the current
+            // request rather than surfacing a dead-cursor error. The override check above has
+            // already rejected `ask`/`questions` on a cursor request, so `questions.is_empty()`
+            // below raises the `missing_field` error instead of returning an empty recall result.
+            RecallCursorResolution::Stale => None,
+        };
+
+        if let Some(decoded) = decoded {
+            if decoded.v != CURSOR_VERSION
+                || decoded.tool != "read_pack"
+                || decoded.mode != "recall"
+            {
+                return Err(call_error("invalid_cursor", "Invalid cursor: wrong tool"));
+            }
+            let expected_root_hash = cursor_fingerprint(&ctx.root_display);
+            let expected_root_fingerprint = root_fingerprint(&ctx.root_display);
+            if let Some(hash) = decoded.root_hash {
+                if hash != expected_root_hash {
+                    return Err(invalid_cursor_with_meta_details(
+                        "Invalid cursor: different root",
+                        ToolMeta {
+                            root_fingerprint: Some(expected_root_fingerprint),
+                            ..ToolMeta::default()
+                        },
+                        json!({
+                            "expected_root_fingerprint": expected_root_fingerprint,
+                            "cursor_root_fingerprint": Some(hash),
+                        }),
+                    ));
+                }
+            } else if decoded.root.as_deref() != Some(ctx.root_display.as_str()) {
+                let cursor_root_fingerprint = decoded.root.as_deref().map(root_fingerprint);
                 return Err(invalid_cursor_with_meta_details(
                     "Invalid cursor: different root",
                     ToolMeta {
@@ -4700,48 +4880,27 @@ async fn handle_recall_intent(
                     },
                     json!({
                         "expected_root_fingerprint": expected_root_fingerprint,
-                        "cursor_root_fingerprint": Some(hash),
+                        "cursor_root_fingerprint": cursor_root_fingerprint,
                     }),
                 ));
             }
-        } else if decoded.root.as_deref() != Some(ctx.root_display.as_str()) {
-            let cursor_root_fingerprint = decoded.root.as_deref().map(root_fingerprint);
-            return Err(invalid_cursor_with_meta_details(
-                "Invalid cursor: different root",
-                ToolMeta {
-                    root_fingerprint: Some(expected_root_fingerprint),
-                    ..ToolMeta::default()
-                },
-                json!({
-                    "expected_root_fingerprint": expected_root_fingerprint,
-                    "cursor_root_fingerprint": cursor_root_fingerprint,
-                }),
-            ));
-        }
 
-        (
-            decoded.questions,
-            decoded.topics,
-            decoded.next_question_index,
-            decoded.include_paths,
-            decoded.exclude_paths,
-            decoded.file_pattern,
-            decoded.prefer_code,
-            decoded.include_docs,
-            decoded.allow_secrets,
-        )
+            (
+                decoded.questions,
+                decoded.topics,
+                decoded.next_question_index,
+                decoded.include_paths,
+                decoded.exclude_paths,
+                decoded.file_pattern,
+                decoded.prefer_code,
+                decoded.include_docs,
+                decoded.allow_secrets,
+            )
+        } else {
+            fresh_recall_session_fields(request)
+        }
     } else {
-        (
-            normalize_questions(request),
-            normalize_topics(request),
-            0,
-            normalize_path_prefix_list(request.include_paths.as_ref()),
-            normalize_path_prefix_list(request.exclude_paths.as_ref()),
-            normalize_optional_pattern(request.file_pattern.as_deref()),
-            request.prefer_code,
-            request.include_docs,
-            request.allow_secrets.unwrap_or(false),
-        )
+        fresh_recall_session_fields(request)
     };
 
     if questions.is_empty() {
@@ -4901,6 +5060,7 @@ async fn handle_recall_intent(
                     max_lines: snippet_max_lines,
                     max_chars: snippet_max_chars,
                     allow_secrets,
+                    offset_encoding: ctx.offset_encoding,
                 },
                 response_mode,
             )
@@ -4941,6 +5101,7 @@ async fn handle_recall_intent(
                             max_lines: snippet_max_lines,
                             max_chars: snippet_max_chars,
                             allow_secrets,
+                            offset_encoding: ctx.offset_encoding,
                         },
                         response_mode,
                     )
@@ -4970,6 +5131,7 @@ async fn handle_recall_intent(
                         max_chars: snippet_max_chars,
                         case_sensitive: true,
                         allow_secrets,
+                        offset_encoding: ctx.offset_encoding,
                     },
                     &effective_include_paths,
                     &effective_exclude_paths,
@@ -4992,6 +5154,7 @@ async fn handle_recall_intent(
                             max_chars: snippet_max_chars,
                             case_sensitive: false,
                             allow_secrets,
+                            offset_encoding: ctx.offset_encoding,
                         },
                         &effective_include_paths,
                         &effective_exclude_paths,
@@ -5020,6 +5183,7 @@ async fn handle_recall_intent(
                         max_chars: snippet_max_chars,
                         case_sensitive: false,
                         allow_secrets,
+                        offset_encoding: ctx.offset_encoding,
                     },
                     &effective_include_paths,
                     &effective_exclude_paths,
@@ -5068,6 +5232,7 @@ async fn handle_recall_intent(
                             max_chars: snippet_max_chars,
                             case_sensitive: false,
                             allow_secrets,
+                            offset_encoding: ctx.offset_encoding,
                         },
                     )
                     .await
@@ -5131,6 +5296,7 @@ async fn handle_recall_intent(
                                 max_lines: snippet_max_lines,
                                 max_chars: snippet_max_chars,
                                 allow_secrets,
+                                offset_encoding: ctx.offset_encoding,
                             },
                             response_mode,
                         )
@@ -5217,11 +5383,13 @@ async fn handle_recall_intent(
                                     if !allow_secrets && is_disallowed_memory_file(file) {
                                         continue;
                                     }
+                                    let trimmed_content = trim_chars(content, snippet_max_chars);
                                     snippets.push(ReadPackSnippet {
                                         file: file.to_string(),
                                         start_line,
                                         end_line,
-                                        content: trim_chars(content, snippet_max_chars),
+                                        range: snippet_range(&trimmed_content, ctx.offset_encoding),
+                                        content: trimmed_content,
                                         kind: if response_mode == ResponseMode::Minimal {
                                             None
                                         } else {
@@ -5252,6 +5420,7 @@ async fn handle_recall_intent(
                         max_chars: snippet_max_chars,
                         case_sensitive: false,
                         allow_secrets,
+                        offset_encoding: ctx.offset_encoding,
                     },
                     &effective_include_paths,
                     &effective_exclude_paths,
@@ -5276,6 +5445,7 @@ async fn handle_recall_intent(
                 RecallCodeUpgradeParams {
                     ctx,
                     facts_snapshot: &facts_snapshot,
+                    question: &clean_question,
                     question_tokens: &question_tokens,
                     snippet_limit,
                     snippet_max_chars,
@@ -5284,6 +5454,8 @@ async fn handle_recall_intent(
                     exclude_paths: &effective_exclude_paths,
                     file_pattern: effective_file_pattern.as_deref(),
                     allow_secrets,
+                    semantic_embedder: None,
+                    mmr_lambda: DEFAULT_MMR_LAMBDA,
                 },
                 &mut snippets,
             )
@@ -6210,6 +6382,8 @@ mod tests {
             prefer_code: None,
             include_docs: None,
             allow_secrets: None,
+            fuzzy: None,
+            offset_encoding: None,
         }
     }
 
@@ -6367,7 +6541,10 @@ mod tests {
                     ecosystems: Vec::new(),
                     build_tools: Vec::new(),
                     ci: Vec::new(),
+                    ci_jobs: Vec::new(),
+                    ci_triggers: Vec::new(),
                     contracts: Vec::new(),
+                    api_operations: Vec::new(),
                     key_dirs: Vec::new(),
                     modules: Vec::new(),
                     entry_points: Vec::new(),
@@ -6502,7 +6679,10 @@ mod tests {
                 ecosystems: Vec::new(),
                 build_tools: Vec::new(),
                 ci: Vec::new(),
+                ci_jobs: Vec::new(),
+                ci_triggers: Vec::new(),
                 contracts: Vec::new(),
+                api_operations: Vec::new(),
                 key_dirs: Vec::new(),
                 modules: Vec::new(),
                 entry_points: Vec::new(),
@@ -6553,7 +6733,10 @@ mod tests {
                 ecosystems: Vec::new(),
                 build_tools: Vec::new(),
                 ci: Vec::new(),
+                ci_jobs: Vec::new(),
+                ci_triggers: Vec::new(),
                 contracts: Vec::new(),
+                api_operations: Vec::new(),
                 key_dirs: Vec::new(),
                 modules: Vec::new(),
                 entry_points: Vec::new(),
@@ -6675,7 +6858,10 @@ mod tests {
             ecosystems: vec!["rust".to_string()],
             build_tools: vec!["cargo".to_string()],
             ci: Vec::new(),
+            ci_jobs: Vec::new(),
+            ci_triggers: Vec::new(),
             contracts: Vec::new(),
+            api_operations: Vec::new(),
             key_dirs: Vec::new(),
             modules: Vec::new(),
             entry_points: Vec::new(),
@@ -6718,7 +6904,10 @@ mod tests {
             ecosystems: vec!["rust".to_string()],
             build_tools: vec!["cargo".to_string()],
             ci: Vec::new(),
+            ci_jobs: Vec::new(),
+            ci_triggers: Vec::new(),
             contracts: Vec::new(),
+            api_operations: Vec::new(),
             key_dirs: Vec::new(),
             modules: Vec::new(),
             entry_points: Vec::new(),
@@ -6760,7 +6949,10 @@ mod tests {
             ecosystems: vec!["rust".to_string()],
             build_tools: vec!["cargo".to_string()],
             ci: Vec::new(),
+            ci_jobs: Vec::new(),
+            ci_triggers: Vec::new(),
             contracts: Vec::new(),
+            api_operations: Vec::new(),
             key_dirs: Vec::new(),
             modules: Vec::new(),
             entry_points: Vec::new(),
@@ -6815,6 +7007,7 @@ mod tests {
                 max_chars: 900,
                 case_sensitive: false,
                 allow_secrets: false,
+                offset_encoding: None,
             },
             &[],
             &[],
@@ -6857,6 +7050,56 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn recall_snippet_range_is_populated_when_offset_encoding_is_requested() {
+        let service = ContextFinderService::new();
+
+        let temp = tempdir().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src").join("main.rs"), b"fn velocity() {}\n").unwrap();
+
+        let root_display = root.to_string_lossy().to_string();
+        let mut request = base_request();
+        request.path = Some(root_display.clone());
+        request.questions = Some(vec!["where is velocity computed".to_string()]);
+        request.response_mode = Some(ResponseMode::Facts);
+        request.offset_encoding = Some("utf8".to_string());
+
+        let ctx = build_context(&request, root.to_path_buf(), root_display.clone()).unwrap();
+
+        let mut sections = Vec::new();
+        let mut next_cursor = None;
+        handle_recall_intent(
+            &service,
+            &ctx,
+            &request,
+            ResponseMode::Facts,
+            false,
+            &mut sections,
+            &mut next_cursor,
+        )
+        .await
+        .unwrap();
+
+        let recall = sections.iter().find_map(|section| match section {
+            ReadPackSection::Recall { result } => Some(result),
+            _ => None,
+        });
+        let recall = recall.expect("expected recall section");
+        assert!(
+            !recall.snippets.is_empty(),
+            "expected at least one recall snippet"
+        );
+        let range = recall.snippets[0]
+            .range
+            .as_ref()
+            .expect("expected range to be populated when offset_encoding is requested");
+        assert_eq!(range.encoding, "utf8");
+        assert_eq!(range.start, 0);
+        assert_eq!(range.end, recall.snippets[0].content.len());
+    }
+
     #[test]
     fn cursor_pagination_marks_budget_truncated_even_under_max_chars() {
         let mut request = base_request();
@@ -6874,7 +7117,10 @@ mod tests {
                     ecosystems: Vec::new(),
                     build_tools: Vec::new(),
                     ci: Vec::new(),
+                    ci_jobs: Vec::new(),
+                    ci_triggers: Vec::new(),
                     contracts: Vec::new(),
+                    api_operations: Vec::new(),
                     key_dirs: Vec::new(),
                     modules: Vec::new(),
                     entry_points: Vec::new(),
@@ -6934,7 +7180,10 @@ mod tests {
                         ecosystems: Vec::new(),
                         build_tools: Vec::new(),
                         ci: Vec::new(),
+                        ci_jobs: Vec::new(),
+                        ci_triggers: Vec::new(),
                         contracts: Vec::new(),
+                        api_operations: Vec::new(),
                         key_dirs: Vec::new(),
                         modules: Vec::new(),
                         entry_points: Vec::new(),
@@ -6993,7 +7242,10 @@ mod tests {
                         ecosystems: Vec::new(),
                         build_tools: Vec::new(),
                         ci: Vec::new(),
+                        ci_jobs: Vec::new(),
+                        ci_triggers: Vec::new(),
                         contracts: Vec::new(),
+                        api_operations: Vec::new(),
                         key_dirs: Vec::new(),
                         modules: Vec::new(),
                         entry_points: Vec::new(),
@@ -10,12 +10,475 @@ pub(super) const PROJECT_FACTS_VERSION: u32 = 1;
 const MAX_FACT_ECOSYSTEMS: usize = 8;
 const MAX_FACT_BUILD_TOOLS: usize = 10;
 const MAX_FACT_CI: usize = 6;
+const MAX_FACT_CI_JOBS: usize = 10;
+const MAX_FACT_CI_TRIGGERS: usize = 6;
 const MAX_FACT_CONTRACTS: usize = 8;
+const MAX_FACT_API_OPERATIONS: usize = 20;
 const MAX_FACT_KEY_DIRS: usize = 12;
 const MAX_FACT_MODULES: usize = 16;
 const MAX_FACT_ENTRY_POINTS: usize = 10;
 const MAX_FACT_KEY_CONFIGS: usize = 20;
 
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Parses a GitHub Actions workflow file far enough to recover job names (`jobs:` children) and
+/// trigger event names (`on:` scalar/flow-list/mapping), without a full YAML parser. This is
+/// deliberately a small hand-rolled walker rather than a YAML dependency (matching how this
+/// codebase already hand-parses Cargo.toml/package.json/go.mod and Kubernetes manifests).
+fn parse_github_workflow_facts(content: &str) -> (Vec<String>, Vec<String>) {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut triggers = Vec::new();
+    let mut jobs = Vec::new();
+
+    let mut idx = 0;
+    while idx < lines.len() {
+        let line = lines[idx];
+        let trimmed = line.trim();
+
+        if let Some(rest) = trimmed.strip_prefix("on:") {
+            let rest = rest.trim();
+            if let Some(list) = rest.strip_prefix('[').and_then(|r| r.strip_suffix(']')) {
+                for item in list.split(',') {
+                    let item = item.trim().trim_matches(['"', '\'']);
+                    if !item.is_empty() {
+                        triggers.push(item.to_string());
+                    }
+                }
+            } else if !rest.is_empty() {
+                triggers.push(rest.trim_matches(['"', '\'']).to_string());
+            } else {
+                let on_indent = indent_of(line);
+                let mut child_indent: Option<usize> = None;
+                let mut cursor = idx + 1;
+                while cursor < lines.len() {
+                    let next = lines[cursor];
+                    if next.trim().is_empty() {
+                        cursor += 1;
+                        continue;
+                    }
+                    let this_indent = indent_of(next);
+                    if this_indent <= on_indent {
+                        break;
+                    }
+                    // Only direct children of `on:` are events; deeper keys (`branches:`) are filters.
+                    if this_indent != *child_indent.get_or_insert(this_indent) {
+                        cursor += 1;
+                        continue;
+                    }
+                    let entry = next.trim_start().strip_prefix("- ").unwrap_or(next.trim());
+                    let name = entry.split(':').next().unwrap_or(entry).trim();
+                    if !name.is_empty() {
+                        triggers.push(name.to_string());
+                    }
+                    cursor += 1;
+                }
+            }
+        } else if trimmed == "jobs:" {
+            let jobs_indent = indent_of(line);
+            let mut child_indent: Option<usize> = None;
+            let mut cursor = idx + 1;
+            while cursor < lines.len() {
+                let next = lines[cursor];
+                if next.trim().is_empty() {
+                    cursor += 1;
+                    continue;
+                }
+                let this_indent = indent_of(next);
+                if this_indent <= jobs_indent {
+                    break;
+                }
+                let indent = *child_indent.get_or_insert(this_indent);
+                if this_indent == indent {
+                    if let Some(name) = next.trim().strip_suffix(':') {
+                        if !name.is_empty() {
+                            jobs.push(name.to_string());
+                        }
+                    }
+                }
+                cursor += 1;
+            }
+        }
+
+        idx += 1;
+    }
+
+    (triggers, jobs)
+}
+
+const HTTP_METHODS: &[&str] = &[
+    "get", "post", "put", "patch", "delete", "options", "head", "trace",
+];
+const ASYNCAPI_OPERATIONS: &[&str] = &["subscribe", "publish"];
+
+/// Walks a YAML mapping's `top_key:` children (API paths for OpenAPI, channels for AsyncAPI) and
+/// their nested operation children, extracting `OPERATION item (operationId)` entries. Same
+/// hand-rolled indentation-walker style as [`parse_github_workflow_facts`] -- deliberately not a
+/// full YAML parser, just enough structure to recover endpoint-level facts.
+fn parse_yaml_operations(content: &str, top_key: &str, allowed_operations: &[&str]) -> Vec<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut out = Vec::new();
+
+    let mut idx = 0;
+    while idx < lines.len() {
+        let line = lines[idx];
+        if line.trim() != format!("{top_key}:") {
+            idx += 1;
+            continue;
+        }
+
+        let top_indent = indent_of(line);
+        let mut item_indent: Option<usize> = None;
+        let mut cursor = idx + 1;
+        while cursor < lines.len() {
+            let item_line = lines[cursor];
+            if item_line.trim().is_empty() {
+                cursor += 1;
+                continue;
+            }
+            let this_indent = indent_of(item_line);
+            if this_indent <= top_indent {
+                break;
+            }
+            let indent = *item_indent.get_or_insert(this_indent);
+            if this_indent == indent {
+                if let Some(item_name) = item_line.trim().strip_suffix(':') {
+                    let item_name = item_name.trim_matches(['"', '\'']).to_string();
+                    let mut op_indent: Option<usize> = None;
+                    let mut op_cursor = cursor + 1;
+                    while op_cursor < lines.len() {
+                        let op_line = lines[op_cursor];
+                        if op_line.trim().is_empty() {
+                            op_cursor += 1;
+                            continue;
+                        }
+                        let op_this_indent = indent_of(op_line);
+                        if op_this_indent <= indent {
+                            break;
+                        }
+                        let oi = *op_indent.get_or_insert(op_this_indent);
+                        if op_this_indent == oi {
+                            if let Some(operation) = op_line.trim().strip_suffix(':') {
+                                let operation_lower = operation.to_lowercase();
+                                if allowed_operations.contains(&operation_lower.as_str()) {
+                                    let operation_id =
+                                        find_nested_scalar(&lines, op_cursor, op_this_indent, "operationId:");
+                                    out.push(match operation_id {
+                                        Some(id) => {
+                                            format!("{operation_lower} {item_name} ({id})")
+                                        }
+                                        None => format!("{operation_lower} {item_name}"),
+                                    });
+                                }
+                            }
+                        }
+                        op_cursor += 1;
+                    }
+                }
+            }
+            cursor += 1;
+        }
+
+        idx += 1;
+    }
+
+    out
+}
+
+/// Scans the block nested under `lines[start_idx]` (everything more indented than
+/// `parent_indent`) for a `prefix` scalar line (e.g. `operationId:`), returning its trimmed value.
+fn find_nested_scalar(
+    lines: &[&str],
+    start_idx: usize,
+    parent_indent: usize,
+    prefix: &str,
+) -> Option<String> {
+    let mut cursor = start_idx + 1;
+    while cursor < lines.len() {
+        let line = lines[cursor];
+        if line.trim().is_empty() {
+            cursor += 1;
+            continue;
+        }
+        if indent_of(line) <= parent_indent {
+            break;
+        }
+        if let Some(value) = line.trim().strip_prefix(prefix) {
+            return Some(value.trim().trim_matches(['"', '\'']).to_string());
+        }
+        cursor += 1;
+    }
+    None
+}
+
+/// Extracts `METHOD path (operationId)` entries from an OpenAPI/AsyncAPI JSON document's `paths`
+/// or `channels` map.
+fn parse_json_operations(
+    value: &serde_json::Value,
+    top_key: &str,
+    allowed_operations: &[&str],
+) -> Vec<String> {
+    let mut out = Vec::new();
+    let Some(items) = value.get(top_key).and_then(|v| v.as_object()) else {
+        return out;
+    };
+    for (item_name, operations) in items {
+        let Some(operations) = operations.as_object() else {
+            continue;
+        };
+        for (operation, op_value) in operations {
+            let operation_lower = operation.to_lowercase();
+            if !allowed_operations.contains(&operation_lower.as_str()) {
+                continue;
+            }
+            let operation_id = op_value.get("operationId").and_then(|v| v.as_str());
+            out.push(match operation_id {
+                Some(id) => format!("{operation_lower} {item_name} ({id})"),
+                None => format!("{operation_lower} {item_name}"),
+            });
+        }
+    }
+    out
+}
+
+/// Extracts `Service.Method` entries from a `.proto` file's `service { rpc ... }` blocks. A
+/// heuristic line scan rather than a real protobuf parser (matching the rest of this file's
+/// bounded, dependency-free fact extraction).
+fn parse_proto_operations(content: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut current_service: Option<String> = None;
+
+    for raw_line in content.lines() {
+        let line = raw_line.trim();
+        if let Some(rest) = line.strip_prefix("service ") {
+            let name = rest
+                .split(|c: char| c == '{' || c.is_whitespace())
+                .next()
+                .unwrap_or("");
+            if !name.is_empty() {
+                current_service = Some(name.to_string());
+            }
+        } else if current_service.is_some() && line.starts_with('}') {
+            current_service = None;
+        } else if let (Some(service), Some(rest)) = (&current_service, line.strip_prefix("rpc ")) {
+            let method = rest
+                .split(|c: char| c == '(' || c.is_whitespace())
+                .next()
+                .unwrap_or("");
+            if !method.is_empty() {
+                out.push(format!("{service}.{method}"));
+            }
+        }
+    }
+
+    out
+}
+
+/// Extracts `Query.field`/`Mutation.field` entries from a GraphQL SDL document's top-level
+/// `type Query { ... }` / `type Mutation { ... }` blocks.
+fn parse_graphql_operations(content: &str) -> Vec<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut out = Vec::new();
+
+    let mut idx = 0;
+    while idx < lines.len() {
+        let trimmed = lines[idx].trim();
+        let root_type = if trimmed.starts_with("type Query") {
+            Some("Query")
+        } else if trimmed.starts_with("type Mutation") {
+            Some("Mutation")
+        } else {
+            None
+        };
+
+        if let Some(root_type) = root_type {
+            idx += 1;
+            while idx < lines.len() {
+                let line = lines[idx].trim();
+                if line.starts_with('}') {
+                    break;
+                }
+                if line.is_empty() || line.starts_with('#') {
+                    idx += 1;
+                    continue;
+                }
+                let field_name = line
+                    .split(|c: char| c == '(' || c == ':')
+                    .next()
+                    .unwrap_or("")
+                    .trim();
+                if !field_name.is_empty() {
+                    out.push(format!("{root_type}.{field_name}"));
+                }
+                idx += 1;
+            }
+        }
+
+        idx += 1;
+    }
+
+    out
+}
+
+/// Parses a contract file's endpoint/operation-level surface, dispatching on extension/content.
+/// Returns an empty `Vec` (never an error) when the format isn't recognized or doesn't parse --
+/// callers fall back to the path-based `contracts` fact in that case.
+fn parse_api_operations(root: &Path, rel: &str) -> Vec<String> {
+    let Ok(content) = fs::read_to_string(root.join(rel)) else {
+        return Vec::new();
+    };
+
+    if rel.ends_with(".proto") {
+        return parse_proto_operations(&content);
+    }
+    if rel.ends_with(".graphql") || rel.ends_with(".gql") {
+        return parse_graphql_operations(&content);
+    }
+    if rel.ends_with(".json") {
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(&content) else {
+            return Vec::new();
+        };
+        if value.get("asyncapi").is_some() {
+            return parse_json_operations(&value, "channels", ASYNCAPI_OPERATIONS);
+        }
+        return parse_json_operations(&value, "paths", HTTP_METHODS);
+    }
+    if rel.ends_with(".yaml") || rel.ends_with(".yml") {
+        if content.lines().any(|line| line.trim_start().starts_with("asyncapi:")) {
+            return parse_yaml_operations(&content, "channels", ASYNCAPI_OPERATIONS);
+        }
+        return parse_yaml_operations(&content, "paths", HTTP_METHODS);
+    }
+
+    Vec::new()
+}
+
+/// Language-specific `main` signature used by the content-sniffing entrypoint fallback below.
+/// Deliberately a substring/prefix check rather than parsing -- this only needs to distinguish
+/// "looks like an entrypoint" from "doesn't", not produce an AST.
+fn has_entrypoint_signature(root: &Path, rel: &str) -> bool {
+    let Ok(content) = fs::read_to_string(root.join(rel)) else {
+        return false;
+    };
+
+    if content.starts_with("#!") {
+        return true;
+    }
+    if rel.ends_with(".rs") {
+        return content.contains("fn main(");
+    }
+    if rel.ends_with(".go") {
+        return content.contains("package main") && content.contains("func main(");
+    }
+    if rel.ends_with(".py") {
+        return content.contains("if __name__ == \"__main__\"")
+            || content.contains("if __name__ == '__main__'");
+    }
+
+    false
+}
+
+/// Collects entrypoint candidates from `package.json`'s `bin` (string or name->path map) and
+/// `scripts.start` (first `.js`/`.ts` token in the command line) fields.
+fn package_json_entrypoint_candidates(root: &Path) -> Vec<String> {
+    let Ok(content) = fs::read_to_string(root.join("package.json")) else {
+        return Vec::new();
+    };
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(&content) else {
+        return Vec::new();
+    };
+
+    let mut candidates = Vec::new();
+    match value.get("bin") {
+        Some(serde_json::Value::String(path)) => candidates.push(path.clone()),
+        Some(serde_json::Value::Object(map)) => {
+            for path in map.values() {
+                if let Some(path) = path.as_str() {
+                    candidates.push(path.to_string());
+                }
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(start) = value
+        .get("scripts")
+        .and_then(|scripts| scripts.get("start"))
+        .and_then(|start| start.as_str())
+    {
+        if let Some(token) = start
+            .split_whitespace()
+            .find(|token| token.ends_with(".js") || token.ends_with(".ts"))
+        {
+            candidates.push(token.to_string());
+        }
+    }
+
+    candidates
+        .into_iter()
+        .map(|path| path.trim_start_matches("./").to_string())
+        .collect()
+}
+
+/// Content-sniffing fallback for entrypoint detection: when the conventional-path whitelist
+/// doesn't find enough candidates (non-standard layouts), scans top-level and `src/`/`cmd/`
+/// source files for language-specific `main` signatures rather than giving up. Candidates are
+/// ranked deterministically (shorter path first, then lexicographic) and bounded to
+/// `max_candidates` before the caller feeds them through the usual `push_fact_path` dedup/bound
+/// logic.
+fn detect_entrypoints_by_content(root: &Path, max_candidates: usize) -> Vec<String> {
+    let mut candidates: Vec<String> = package_json_entrypoint_candidates(root);
+
+    for dir in ["", "src", "cmd"] {
+        let scan_root = if dir.is_empty() {
+            root.to_path_buf()
+        } else {
+            root.join(dir)
+        };
+        let Ok(entries) = fs::read_dir(&scan_root) else {
+            continue;
+        };
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let Ok(ty) = entry.file_type() else { continue };
+            if !ty.is_file() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            candidates.push(if dir.is_empty() {
+                name
+            } else {
+                format!("{dir}/{name}")
+            });
+        }
+    }
+
+    // Go entrypoints conventionally live one level below cmd/ (cmd/<binary>/main.go and friends),
+    // not directly inside it.
+    for subdir in list_immediate_subdirs(&root.join("cmd"), 24) {
+        let Ok(entries) = fs::read_dir(root.join("cmd").join(&subdir)) else {
+            continue;
+        };
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let Ok(ty) = entry.file_type() else { continue };
+            if !ty.is_file() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.ends_with(".go") {
+                candidates.push(format!("cmd/{subdir}/{name}"));
+            }
+        }
+    }
+
+    candidates.sort();
+    candidates.dedup();
+    candidates.retain(|rel| has_entrypoint_signature(root, rel));
+    candidates.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+    candidates.truncate(max_candidates);
+    candidates
+}
+
 fn push_fact(out: &mut Vec<String>, value: &str, max: usize) {
     if out.len() >= max {
         return;
@@ -231,7 +694,10 @@ pub(super) fn compute_project_facts(root: &Path) -> ProjectFactsResult {
     let mut ecosystems: Vec<String> = Vec::new();
     let mut build_tools: Vec<String> = Vec::new();
     let mut ci: Vec<String> = Vec::new();
+    let mut ci_jobs: Vec<String> = Vec::new();
+    let mut ci_triggers: Vec<String> = Vec::new();
     let mut contracts: Vec<String> = Vec::new();
+    let mut api_operations: Vec<String> = Vec::new();
     let mut key_dirs: Vec<String> = Vec::new();
     let mut modules: Vec<String> = Vec::new();
     let mut entry_points: Vec<String> = Vec::new();
@@ -244,7 +710,10 @@ pub(super) fn compute_project_facts(root: &Path) -> ProjectFactsResult {
             ecosystems,
             build_tools,
             ci,
+            ci_jobs,
+            ci_triggers,
             contracts,
+            api_operations,
             key_dirs,
             modules,
             entry_points,
@@ -348,6 +817,20 @@ pub(super) fn compute_project_facts(root: &Path) -> ProjectFactsResult {
     // CI/CD tooling.
     if root.join(".github").join("workflows").is_dir() {
         push_fact(&mut ci, "github_actions", MAX_FACT_CI);
+
+        let mut seen = HashSet::new();
+        for rel in collect_github_workflow_candidates(root, &mut seen) {
+            let Ok(content) = fs::read_to_string(root.join(&rel)) else {
+                continue;
+            };
+            let (triggers, jobs) = parse_github_workflow_facts(&content);
+            for trigger in triggers {
+                push_fact(&mut ci_triggers, &trigger, MAX_FACT_CI_TRIGGERS);
+            }
+            for job in jobs {
+                push_fact(&mut ci_jobs, &job, MAX_FACT_CI_JOBS);
+            }
+        }
     }
     if has_root_file(".gitlab-ci.yml") {
         push_fact(&mut ci, "gitlab_ci", MAX_FACT_CI);
@@ -387,6 +870,46 @@ pub(super) fn compute_project_facts(root: &Path) -> ProjectFactsResult {
         MAX_FACT_CONTRACTS,
     );
 
+    // Endpoint-level facts for the contract surfaces above: the path-based `contracts` list
+    // remains the fallback whenever a file doesn't parse (unrecognized format, malformed
+    // content), so a parse miss here never loses information, only detail.
+    if api_operations.len() < MAX_FACT_API_OPERATIONS {
+        for rel in &contracts {
+            if api_operations.len() >= MAX_FACT_API_OPERATIONS {
+                break;
+            }
+            let full = root.join(rel);
+            if full.is_file() {
+                for operation in parse_api_operations(root, rel) {
+                    push_fact(&mut api_operations, &operation, MAX_FACT_API_OPERATIONS);
+                }
+            } else if full.is_dir() {
+                let Ok(entries) = fs::read_dir(&full) else {
+                    continue;
+                };
+                let mut names: Vec<String> = entries
+                    .filter_map(|entry| {
+                        let entry = entry.ok()?;
+                        if !entry.file_type().ok()?.is_file() {
+                            return None;
+                        }
+                        Some(entry.file_name().to_string_lossy().to_string())
+                    })
+                    .collect();
+                names.sort();
+                for name in names {
+                    if api_operations.len() >= MAX_FACT_API_OPERATIONS {
+                        break;
+                    }
+                    let child_rel = format!("{rel}/{name}");
+                    for operation in parse_api_operations(root, &child_rel) {
+                        push_fact(&mut api_operations, &operation, MAX_FACT_API_OPERATIONS);
+                    }
+                }
+            }
+        }
+    }
+
     // Key top-level directories (agent navigation map, bounded).
     // Prefer a priority-ordered listing of *existing* directories over a fixed list: this keeps
     // project_facts useful across arbitrary repo topologies without hardcoding per-project rules.
@@ -612,6 +1135,15 @@ pub(super) fn compute_project_facts(root: &Path) -> ProjectFactsResult {
         }
     }
 
+    // Content-sniffing fallback: the whitelist above only knows conventional paths, so
+    // non-standard layouts (entrypoint under an unexpected name/directory) still come up empty.
+    if entry_points.len() < MAX_FACT_ENTRY_POINTS {
+        let remaining = MAX_FACT_ENTRY_POINTS - entry_points.len();
+        for rel in detect_entrypoints_by_content(root, remaining) {
+            push_fact_path(&mut entry_points, root, &rel, MAX_FACT_ENTRY_POINTS);
+        }
+    }
+
     // Key config files worth reading first (safe allowlist, bounded, agent-signal oriented).
     push_fact_path(
         &mut key_configs,
@@ -765,7 +1297,10 @@ pub(super) fn compute_project_facts(root: &Path) -> ProjectFactsResult {
         ecosystems,
         build_tools,
         ci,
+        ci_jobs,
+        ci_triggers,
         contracts,
+        api_operations,
         key_dirs,
         modules,
         entry_points,
@@ -50,6 +50,7 @@ pub(in crate::tools::dispatch::read_pack) struct RecallQuestionDirectives {
     pub(in crate::tools::dispatch::read_pack) exclude_paths: Vec<String>,
     pub(in crate::tools::dispatch::read_pack) file_pattern: Option<String>,
     pub(in crate::tools::dispatch::read_pack) file_ref: Option<(String, Option<usize>)>,
+    pub(in crate::tools::dispatch::read_pack) fuzzy: bool,
 }
 
 fn normalize_recall_directive_prefix(raw: &str) -> Option<String> {
@@ -123,6 +124,10 @@ pub(in crate::tools::dispatch::read_pack) fn parse_recall_question_directives(
                 directives.mode = RecallQuestionMode::Deep;
                 continue;
             }
+            "fuzzy" | "typo" => {
+                directives.fuzzy = true;
+                continue;
+            }
             _ => {}
         }
 
@@ -1,8 +1,24 @@
 use super::cursors::snippet_kind_for_path;
 use super::{ReadPackSnippet, ReadPackSnippetKind};
 
+/// Default trade-off for the MMR final-selection pass (see `intent_recall::dedupe_snippets`):
+/// `1.0` picks by relevance alone, `0.0` by diversity alone.
+pub(super) const DEFAULT_MMR_LAMBDA: f32 = 0.7;
+
 pub(super) fn score_recall_snippet(question_tokens: &[String], snippet: &ReadPackSnippet) -> i32 {
-    if question_tokens.is_empty() {
+    score_recall_snippet_with_fuzzy(question_tokens, &[], snippet)
+}
+
+/// Same as [`score_recall_snippet`], plus `fuzzy_tokens` -- edit-distance expansions of
+/// `question_tokens` that aren't exact hits themselves (see
+/// `intent_recall::question::expand_fuzzy_tokens`). Scored at roughly half weight so a typo'd
+/// question never outranks a snippet that actually matched verbatim.
+pub(super) fn score_recall_snippet_with_fuzzy(
+    question_tokens: &[String],
+    fuzzy_tokens: &[String],
+    snippet: &ReadPackSnippet,
+) -> i32 {
+    if question_tokens.is_empty() && fuzzy_tokens.is_empty() {
         return 0;
     }
     let file = snippet.file.to_ascii_lowercase();
@@ -17,6 +33,14 @@ pub(super) fn score_recall_snippet(question_tokens: &[String], snippet: &ReadPac
             score += 5;
         }
     }
+    for token in fuzzy_tokens {
+        if file.contains(token) {
+            score += 1;
+        }
+        if content.contains(token) {
+            score += 2;
+        }
+    }
 
     // Small heuristic boost: snippets with runnable commands are usually better for ops recall.
     if content.contains("cargo ") || content.contains("npm ") || content.contains("yarn ") {
@@ -17,10 +17,37 @@ use super::{
     REASON_NEEDLE_GREP_HUNK,
 };
 use crate::tools::schemas::content_format::ContentFormat;
+use context_search::{LineIndex, OffsetEncoding};
 use regex::RegexBuilder;
 use std::collections::HashSet;
 use std::path::Path;
 
+/// A snippet's precise content span in the requested offset encoding (always spans the whole
+/// snippet body, i.e. `start` is `0`), mirroring `tools::SearchRange` for recall/grep snippets so
+/// editor/LSP consumers can address `ReadPackSnippet::content` without re-deriving offsets from
+/// `start_line`/`end_line` themselves.
+#[derive(Debug, Clone, serde::Serialize, rmcp::schemars::JsonSchema)]
+pub(super) struct ReadPackSnippetRange {
+    /// "utf8", "utf16", or "char" -- matches the request's `offset_encoding`.
+    pub(super) encoding: String,
+    pub(super) start: usize,
+    pub(super) end: usize,
+}
+
+fn snippet_range(
+    content: &str,
+    offset_encoding: Option<OffsetEncoding>,
+) -> Option<ReadPackSnippetRange> {
+    offset_encoding.map(|encoding| {
+        let end = LineIndex::new(content).convert(content.len(), encoding);
+        ReadPackSnippetRange {
+            encoding: encoding.as_str().to_string(),
+            start: 0,
+            end,
+        }
+    })
+}
+
 fn recall_code_scope_candidates(root: &Path, facts: &ProjectFactsResult) -> Vec<String> {
     // A small, deterministic set of "likely code lives here" roots used as a second-pass scope
     // for precision grep (avoids README/docs-first matches when snippet_limit is tight).
@@ -80,6 +107,9 @@ pub(super) struct GrepSnippetParams {
     pub(super) max_chars: usize,
     pub(super) case_sensitive: bool,
     pub(super) allow_secrets: bool,
+    /// Offset encoding for each returned snippet's precise `range` ("utf8", "utf16", or "char");
+    /// `None` skips computing ranges, leaving `ReadPackSnippet::range` null.
+    pub(super) offset_encoding: Option<OffsetEncoding>,
 }
 
 pub(super) struct RecallCodeUpgradeParams<'a> {
@@ -93,6 +123,7 @@ pub(super) struct RecallCodeUpgradeParams<'a> {
     pub(super) exclude_paths: &'a [String],
     pub(super) file_pattern: Option<&'a str>,
     pub(super) allow_secrets: bool,
+    pub(super) offset_encoding: Option<OffsetEncoding>,
 }
 
 pub(super) async fn recall_upgrade_to_code_snippets(
@@ -127,6 +158,7 @@ pub(super) async fn recall_upgrade_to_code_snippets(
                 max_chars: params.snippet_max_chars,
                 case_sensitive: false,
                 allow_secrets: params.allow_secrets,
+                offset_encoding: params.offset_encoding,
             },
             params.include_paths,
             params.exclude_paths,
@@ -158,6 +190,7 @@ pub(super) async fn recall_upgrade_to_code_snippets(
                     max_chars: params.snippet_max_chars,
                     case_sensitive: false,
                     allow_secrets: params.allow_secrets,
+                    offset_encoding: params.offset_encoding,
                 },
                 &code_scopes,
                 params.exclude_paths,
@@ -228,6 +261,7 @@ pub(super) async fn snippets_from_grep(
     params: GrepSnippetParams,
 ) -> ToolResult<(Vec<ReadPackSnippet>, Option<String>)> {
     let max_hunks = params.max_hunks;
+    let offset_encoding = params.offset_encoding;
     let regex = RegexBuilder::new(pattern)
         .case_insensitive(!params.case_sensitive)
         .build()
@@ -280,6 +314,7 @@ pub(super) async fn snippets_from_grep(
             file: hunk.file.clone(),
             start_line: hunk.start_line,
             end_line: hunk.end_line,
+            range: snippet_range(&hunk.content, offset_encoding),
             content: hunk.content.clone(),
             kind: Some(snippet_kind_for_path(&hunk.file)),
             reason: Some(REASON_NEEDLE_GREP_HUNK.to_string()),
@@ -333,6 +368,7 @@ pub(super) async fn snippets_from_grep_filtered(
                 max_chars: params.max_chars,
                 case_sensitive: params.case_sensitive,
                 allow_secrets: params.allow_secrets,
+                offset_encoding: params.offset_encoding,
             },
         )
         .await?;
@@ -367,6 +403,7 @@ pub(super) struct SnippetFromFileParams {
     pub(super) max_lines: usize,
     pub(super) max_chars: usize,
     pub(super) allow_secrets: bool,
+    pub(super) offset_encoding: Option<OffsetEncoding>,
 }
 
 pub(super) async fn snippet_from_file(
@@ -421,6 +458,7 @@ pub(super) async fn snippet_from_file(
         file: slice.file.clone(),
         start_line: slice.start_line,
         end_line: slice.end_line,
+        range: snippet_range(&slice.content, params.offset_encoding),
         content: slice.content.clone(),
         kind,
         reason: Some(REASON_NEEDLE_FILE_SLICE.to_string()),
@@ -1,8 +1,10 @@
 mod file;
 mod grep;
 mod scope;
+mod semantic;
 mod upgrade;
 
 pub(super) use file::{snippet_from_file, SnippetFromFileParams};
 pub(super) use grep::{snippets_from_grep, snippets_from_grep_filtered, GrepSnippetParams};
+pub(super) use semantic::semantic_code_snippets;
 pub(super) use upgrade::{recall_upgrade_to_code_snippets, RecallCodeUpgradeParams};
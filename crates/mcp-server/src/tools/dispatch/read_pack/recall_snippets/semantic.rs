@@ -0,0 +1,65 @@
+use super::super::cursors::snippet_kind_for_path;
+use super::super::{ProjectFactsResult, ReadPackContext, ReadPackSnippet, REASON_NEEDLE_SEMANTIC_MATCH};
+use super::scope::recall_code_scope_candidates;
+use context_graph::{Embedder, SemanticIndex};
+use context_indexer::FileScanner;
+use sha2::{Digest, Sha256};
+
+// Keeps a single embedding call from scanning the whole worktree; `recall_code_scope_candidates`
+// already narrows to likely code roots, so this just bounds the worst case (monorepo, no roots).
+const SEMANTIC_PROBE_FILES: usize = 200;
+
+/// Embeds `question` and the project's likely code roots, then returns the top-scoring line
+/// windows as snippets. Used as a last resort when keyword grep (see
+/// `super::upgrade::recall_upgrade_to_code_snippets`) finds nothing — paraphrased questions
+/// often share no literal tokens with the code that answers them.
+pub(in crate::tools::dispatch::read_pack) async fn semantic_code_snippets(
+    ctx: &ReadPackContext,
+    facts_snapshot: &ProjectFactsResult,
+    embedder: &dyn Embedder,
+    question: &str,
+    top_k: usize,
+) -> Vec<ReadPackSnippet> {
+    if question.trim().is_empty() || top_k == 0 {
+        return Vec::new();
+    }
+
+    let code_scopes = recall_code_scope_candidates(&ctx.root, facts_snapshot);
+    let mut scanner = FileScanner::new(&ctx.root);
+    let Ok(files) = scanner.scan() else {
+        return Vec::new();
+    };
+
+    let mut index = SemanticIndex::new();
+    for file in files.into_iter().take(SEMANTIC_PROBE_FILES) {
+        let Ok(rel) = file.strip_prefix(&ctx.root) else {
+            continue;
+        };
+        let rel = rel.to_string_lossy().replace('\\', "/");
+        if !code_scopes.is_empty() && !code_scopes.iter().any(|scope| rel.starts_with(scope.as_str())) {
+            continue;
+        }
+        let Ok(content) = tokio::fs::read_to_string(&file).await else {
+            continue;
+        };
+        if content.trim().is_empty() {
+            continue;
+        }
+        let hash = format!("{:x}", Sha256::digest(content.as_bytes()));
+        index.index_file(embedder, &rel, &hash, &content);
+    }
+
+    index
+        .search(embedder, question, top_k)
+        .into_iter()
+        .map(|found| ReadPackSnippet {
+            file: found.window.file.clone(),
+            start_line: found.window.start_line,
+            end_line: found.window.end_line,
+            content: found.window.content,
+            kind: Some(snippet_kind_for_path(&found.window.file)),
+            reason: Some(REASON_NEEDLE_SEMANTIC_MATCH.to_string()),
+            next_cursor: None,
+        })
+        .collect()
+}
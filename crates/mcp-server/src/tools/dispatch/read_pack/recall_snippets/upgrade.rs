@@ -6,11 +6,13 @@ use super::super::ToolResult;
 use super::super::{ProjectFactsResult, ReadPackContext, ReadPackSnippet, ReadPackSnippetKind};
 use super::grep::{snippets_from_grep_filtered, GrepSnippetParams};
 use super::scope::recall_code_scope_candidates;
+use super::semantic::semantic_code_snippets;
 use std::collections::HashSet;
 
 pub(in crate::tools::dispatch::read_pack) struct RecallCodeUpgradeParams<'a> {
     pub(in crate::tools::dispatch::read_pack) ctx: &'a ReadPackContext,
     pub(in crate::tools::dispatch::read_pack) facts_snapshot: &'a ProjectFactsResult,
+    pub(in crate::tools::dispatch::read_pack) question: &'a str,
     pub(in crate::tools::dispatch::read_pack) question_tokens: &'a [String],
     pub(in crate::tools::dispatch::read_pack) snippet_limit: usize,
     pub(in crate::tools::dispatch::read_pack) snippet_max_chars: usize,
@@ -19,6 +21,14 @@ pub(in crate::tools::dispatch::read_pack) struct RecallCodeUpgradeParams<'a> {
     pub(in crate::tools::dispatch::read_pack) exclude_paths: &'a [String],
     pub(in crate::tools::dispatch::read_pack) file_pattern: Option<&'a str>,
     pub(in crate::tools::dispatch::read_pack) allow_secrets: bool,
+    /// Embedding backend for the semantic fallback below, when the caller's policy allows it
+    /// (`None` means: grep-only, same as before this field existed).
+    pub(in crate::tools::dispatch::read_pack) semantic_embedder:
+        Option<&'a dyn context_graph::Embedder>,
+    /// Relevance/diversity trade-off of the final MMR selection pass in
+    /// `intent_recall::dedupe_snippets` (see `recall_scoring::DEFAULT_MMR_LAMBDA`). Not read by
+    /// `recall_upgrade_to_code_snippets`, which orders merged snippets by kind, then keyword score.
+    pub(in crate::tools::dispatch::read_pack) mmr_lambda: f32,
 }
 
 pub(in crate::tools::dispatch::read_pack) async fn recall_upgrade_to_code_snippets(
@@ -30,9 +40,6 @@ pub(in crate::tools::dispatch::read_pack) async fn recall_upgrade_to_code_snippe
     }
 
     let patterns = recall_keyword_patterns(params.question_tokens);
-    if patterns.is_empty() {
-        return Ok(());
-    }
 
     let probe_hunks = params
         .snippet_limit
@@ -100,6 +107,19 @@ pub(in crate::tools::dispatch::read_pack) async fn recall_upgrade_to_code_snippe
         }
     }
 
+    if found_code.is_empty() {
+        if let Some(embedder) = params.semantic_embedder {
+            found_code = semantic_code_snippets(
+                params.ctx,
+                params.facts_snapshot,
+                embedder,
+                params.question,
+                params.snippet_limit.max(1),
+            )
+            .await;
+        }
+    }
+
     if found_code.is_empty() {
         return Ok(());
     }
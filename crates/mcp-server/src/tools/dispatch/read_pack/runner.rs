@@ -11,7 +11,9 @@ use super::overlap::{overlap_dedupe_snippet_sections, strip_snippet_reasons_for_
 use super::prepare::{prepare_read_pack, PreparedReadPack};
 use super::project_facts::compute_project_facts;
 use super::render::{apply_meta_to_sections, render_read_pack_context_doc, truncate_to_chars};
-use super::session::note_session_working_set_from_read_pack_result;
+use super::session::{
+    note_session_working_set_from_read_pack_result, note_session_working_set_from_sections,
+};
 use super::{
     finalize_read_pack_budget, CallToolResult, Content, ContextFinderService, McpError,
     ReadPackBudget, ReadPackIntent, ReadPackNextAction, ReadPackRequest, ReadPackResult,
@@ -128,6 +130,11 @@ pub(in crate::tools::dispatch) async fn read_pack(
         match tokio::time::timeout(Duration::from_millis(timeout_ms), handler_future).await {
             Ok(result) => result,
             Err(_) => {
+                if response_mode == ResponseMode::Stream {
+                    // Timed out: commit whatever sections the handler managed to build before
+                    // the timeout/budget trimming below has a chance to drop any of them.
+                    note_session_working_set_from_sections(service, &sections).await;
+                }
                 let mut result = ReadPackResult {
                     version: VERSION,
                     intent,
@@ -222,6 +229,12 @@ pub(in crate::tools::dispatch) async fn read_pack(
         return Ok(attach_meta(result, meta.clone()));
     }
 
+    if response_mode == ResponseMode::Stream {
+        // Commit sections to the session working set as soon as the handler is done producing
+        // them, rather than only after dedupe/budget trimming below may drop some of them.
+        note_session_working_set_from_sections(service, &sections).await;
+    }
+
     overlap_dedupe_snippet_sections(&mut sections);
     if response_mode != ResponseMode::Full {
         strip_snippet_reasons_for_output(&mut sections, true);
@@ -1,11 +1,8 @@
 use super::{ContextFinderService, ReadPackResult, ReadPackSection};
 
-pub(super) async fn note_session_working_set_from_read_pack_result(
-    service: &ContextFinderService,
-    result: &ReadPackResult,
-) {
+fn files_seen_in_sections(sections: &[ReadPackSection]) -> Vec<&str> {
     let mut files: Vec<&str> = Vec::new();
-    for section in &result.sections {
+    for section in sections {
         match section {
             ReadPackSection::Snippet { result } => files.push(&result.file),
             ReadPackSection::FileSlice { result } => files.push(&result.file),
@@ -17,7 +14,18 @@ pub(super) async fn note_session_working_set_from_read_pack_result(
             _ => {}
         }
     }
+    files
+}
 
+/// Marks every file referenced by `sections` as seen in the session's working set. Used both for
+/// the final assembled pack and, under [`ResponseMode::Stream`](super::ResponseMode::Stream), for
+/// the raw sections produced by an intent handler before budget/timeout trimming can drop any of
+/// them (see [`runner::read_pack`](super::runner::read_pack)).
+pub(super) async fn note_session_working_set_from_sections(
+    service: &ContextFinderService,
+    sections: &[ReadPackSection],
+) {
+    let files = files_seen_in_sections(sections);
     if files.is_empty() {
         return;
     }
@@ -27,3 +35,10 @@ pub(super) async fn note_session_working_set_from_read_pack_result(
         session.note_seen_snippet_file(file);
     }
 }
+
+pub(super) async fn note_session_working_set_from_read_pack_result(
+    service: &ContextFinderService,
+    result: &ReadPackResult,
+) {
+    note_session_working_set_from_sections(service, &result.sections).await;
+}
@@ -0,0 +1,67 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use ignore::WalkBuilder;
+
+/// Background workspace pre-warm: tracks which file extensions have already triggered a full
+/// `.gitignore`-aware walk of the session root, so a crawl kicked off by touching one `.rs` file
+/// doesn't re-walk the tree for every subsequent `.rs` file. Only the first file of each kind (or
+/// an explicit `all_files` pass) pays the walk cost.
+#[derive(Default)]
+pub(in crate::tools::dispatch) struct Crawl {
+    crawled_extensions: HashSet<String>,
+    all_files_crawled: bool,
+}
+
+impl Crawl {
+    pub(in crate::tools::dispatch) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a touch for `extension` (or, with `all_files`, the whole tree) and reports whether
+    /// this is the first time it's been seen. Callers should only spawn a walk when this returns
+    /// `true`; repeat touches of an already-crawled extension are no-ops.
+    pub(in crate::tools::dispatch) fn note_touch(
+        &mut self,
+        extension: Option<&str>,
+        all_files: bool,
+    ) -> bool {
+        if self.all_files_crawled {
+            return false;
+        }
+        if all_files {
+            self.all_files_crawled = true;
+            return true;
+        }
+        let key = extension.unwrap_or("").to_ascii_lowercase();
+        self.crawled_extensions.insert(key)
+    }
+
+    /// Forgets every extension seen so far. Called when the session root changes, since a crawl
+    /// of the old root says nothing about whether the new one is warm.
+    pub(in crate::tools::dispatch) fn reset(&mut self) {
+        self.crawled_extensions.clear();
+        self.all_files_crawled = false;
+    }
+}
+
+/// Walks `roots`, honoring `.gitignore`/`.ignore` and hidden-file rules, and returns the number of
+/// regular files visited. Best-effort: a root that doesn't exist, or individual entries that error
+/// out mid-walk (permission denied, broken symlinks), are skipped rather than failing the crawl.
+pub(in crate::tools::dispatch) fn walk_roots(roots: &[PathBuf]) -> usize {
+    roots.iter().map(|root| walk_root(root)).sum()
+}
+
+fn walk_root(root: &Path) -> usize {
+    if !root.is_dir() {
+        return 0;
+    }
+    WalkBuilder::new(root)
+        .hidden(true)
+        .git_ignore(true)
+        .ignore(true)
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().map(|ft| ft.is_file()).unwrap_or(false))
+        .count()
+}
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+/// A root's compiled ignore matcher, plus the mtimes of the files it was built from. Borrowing
+/// watchexec's and Zed's ignore-stack handling: `.gitignore`, `.ignore`, and `.git/info/exclude`
+/// are read at the root only (nested ignore files are not consulted) and layered with the same
+/// precedence the `ignore` crate gives them: `.ignore` over `.gitignore` over `.git/info/exclude`.
+struct CachedIgnore {
+    gitignore: Gitignore,
+    source_mtimes: Vec<Option<SystemTime>>,
+}
+
+fn cache() -> &'static Mutex<HashMap<PathBuf, CachedIgnore>> {
+    static CACHE: OnceLock<Mutex<HashMap<PathBuf, CachedIgnore>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn ignore_files_for(root: &Path) -> [PathBuf; 4] {
+    [
+        // Lowest precedence first: a later file's match overrides an earlier one's.
+        root.join(".git").join("info").join("exclude"),
+        root.join(".gitignore"),
+        root.join(".ignore"),
+        // Not gitignore-syntax itself, but its mtime must invalidate the cache like the others --
+        // its `exclude` globs are layered into the builder below via `add_line`.
+        root.join(super::resolve::PROJECT_DESCRIPTOR_FILE),
+    ]
+}
+
+fn source_mtimes_for(root: &Path) -> Vec<Option<SystemTime>> {
+    ignore_files_for(root)
+        .iter()
+        .map(|path| {
+            std::fs::metadata(path)
+                .ok()
+                .and_then(|meta| meta.modified().ok())
+        })
+        .collect()
+}
+
+fn build_gitignore(root: &Path) -> Gitignore {
+    let descriptor_file_name = std::ffi::OsStr::new(super::resolve::PROJECT_DESCRIPTOR_FILE);
+    let mut builder = GitignoreBuilder::new(root);
+    for path in ignore_files_for(root) {
+        if path.exists() && path.file_name() != Some(descriptor_file_name) {
+            let _ = builder.add(&path);
+        }
+    }
+    if let Some(descriptor) = super::resolve::load_project_descriptor(root) {
+        for pattern in descriptor.exclude {
+            let _ = builder.add_line(None, &pattern);
+        }
+    }
+    builder.build().unwrap_or_else(|_| {
+        GitignoreBuilder::new(root)
+            .build()
+            .expect("empty gitignore builds")
+    })
+}
+
+/// Whether `path` (expected to live under `root`) is excluded by `root`'s layered
+/// `.gitignore`/`.ignore`/`.git/info/exclude` plus the `exclude` globs of its `context-finder.json`.
+/// The compiled matcher is cached per-root, keyed by the mtimes of those four files, so repeated
+/// tool calls against the same root in a shared daemon don't re-parse them on every call.
+pub(in crate::tools::dispatch) fn is_ignored_under_root(root: &Path, path: &Path) -> bool {
+    let current_mtimes = source_mtimes_for(root);
+
+    let mut cache = cache()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let needs_rebuild = match cache.get(root) {
+        Some(cached) => cached.source_mtimes != current_mtimes,
+        None => true,
+    };
+    if needs_rebuild {
+        cache.insert(
+            root.to_path_buf(),
+            CachedIgnore {
+                gitignore: build_gitignore(root),
+                source_mtimes: current_mtimes,
+            },
+        );
+    }
+
+    let is_dir = std::fs::metadata(path)
+        .map(|meta| meta.is_dir())
+        .unwrap_or(false);
+    cache
+        .get(root)
+        .expect("entry inserted above if missing or stale")
+        .gitignore
+        .matched(path, is_dir)
+        .is_ignore()
+}
@@ -0,0 +1,63 @@
+use std::path::{Path, PathBuf};
+
+/// Why a `PathJail::contains` check failed.
+#[derive(Debug)]
+pub(in crate::tools::dispatch) enum JailError {
+    /// The candidate (or the jail root itself) could not be canonicalized, e.g. it doesn't exist
+    /// or a symlink component is broken.
+    ResolutionFailed(String),
+    /// The candidate resolved to a real location outside the jailed root.
+    Escaped,
+}
+
+impl std::fmt::Display for JailError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ResolutionFailed(err) => write!(f, "{err}"),
+            Self::Escaped => write!(f, "path escapes the jailed root"),
+        }
+    }
+}
+
+/// A symlink-safe containment check for a single root, borrowing the non-root chroot-jail idea
+/// from the Polkadot PVF workers: `contains` never trusts a lexical prefix match, it resolves
+/// every symlink component on both sides and compares the resulting canonical `Component`
+/// sequences. A lexical root like `/repo/vendor` that is itself a symlink to `/etc`, or a
+/// candidate that passes through one on the way down, cannot slip past this check the way
+/// `path.starts_with(root)` on un-canonicalized paths can.
+pub(in crate::tools::dispatch) struct PathJail {
+    root: PathBuf,
+}
+
+impl PathJail {
+    /// `root` must already exist; it is canonicalized once up front so every `contains` call
+    /// compares against the fully-resolved boundary.
+    pub(in crate::tools::dispatch) fn new(root: &Path) -> Result<Self, JailError> {
+        let root = root
+            .canonicalize()
+            .map_err(|err| JailError::ResolutionFailed(err.to_string()))?;
+        Ok(Self { root })
+    }
+
+    pub(in crate::tools::dispatch) fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Canonicalizes `path` and returns it only if it stays within the jail. Compares canonical
+    /// `Component` sequences (not string prefixes), so a symlink anywhere in `path` that resolves
+    /// outside `root` is rejected even if the lexical path looks contained.
+    pub(in crate::tools::dispatch) fn contains(&self, path: &Path) -> Result<PathBuf, JailError> {
+        let canonical = path
+            .canonicalize()
+            .map_err(|err| JailError::ResolutionFailed(err.to_string()))?;
+        let mut root_components = self.root.components();
+        let mut canonical_components = canonical.components();
+        for root_component in root_components.by_ref() {
+            match canonical_components.next() {
+                Some(candidate_component) if candidate_component == root_component => continue,
+                _ => return Err(JailError::Escaped),
+            }
+        }
+        Ok(canonical)
+    }
+}
@@ -1,11 +1,20 @@
+mod crawl;
+mod ignore_cache;
+mod jail;
 mod resolve;
 mod service;
 mod session_defaults;
+mod watch;
 
+pub(super) use crawl::{walk_roots, Crawl};
+pub(super) use jail::{JailError, PathJail};
 pub(super) use resolve::{
-    canonicalize_root, canonicalize_root_path, collect_relative_hints, env_root_override,
-    hint_score_for_root, rel_path_string, resolve_root_from_absolute_hints, root_path_from_mcp_uri,
-    scope_hint_from_relative_path,
+    canonicalize_root, canonicalize_root_path, canonicalize_root_path_bounded, classify_root,
+    collect_relative_hints, configured_root_markers, detect_root_by_markers,
+    discarded_ignored_hints, env_root_override, hint_score_for_root, load_project_descriptor,
+    rel_path_string, resolve_root_from_absolute_hints, root_path_from_mcp_uri,
+    scope_hint_from_relative_path, score_workspace_root_candidate, ResolutionStep, RootClass,
 };
 pub(super) use service::workspace_roots_preview;
 pub(super) use session_defaults::{trimmed_non_empty, SessionDefaults};
+pub(super) use watch::RootWatchHandle;
@@ -1,9 +1,11 @@
 use std::env;
 use std::path::{Path, PathBuf};
 
+use serde::Deserialize;
+
 pub(in crate::tools::dispatch) fn resolve_root_from_absolute_hints(
     hints: &[String],
-) -> Option<PathBuf> {
+) -> Option<(PathBuf, Option<&'static str>)> {
     for hint in hints {
         let trimmed = hint.trim();
         if trimmed.is_empty() {
@@ -13,8 +15,8 @@ pub(in crate::tools::dispatch) fn resolve_root_from_absolute_hints(
         if !path.is_absolute() {
             continue;
         }
-        if let Ok(root) = canonicalize_root_path(path) {
-            return Some(root);
+        if let Ok((root, detected_marker)) = canonicalize_root_path_bounded(path, None) {
+            return Some((root, detected_marker));
         }
     }
     None
@@ -45,16 +47,134 @@ pub(in crate::tools::dispatch) fn collect_relative_hints(hints: &[String]) -> Ve
     out
 }
 
+/// Scores `root` against `hints` by how many resolve to a file/dir that exists under it. Hints
+/// that resolve inside a path `root`'s layered `.gitignore`/`.ignore`/`.git/info/exclude` would
+/// exclude (e.g. a coincidental match under `target/` or `node_modules/`) don't count -- they're
+/// as likely to be vendored noise as a genuine signal for which root the caller meant.
 pub(in crate::tools::dispatch) fn hint_score_for_root(root: &Path, hints: &[String]) -> usize {
     let mut score = 0usize;
     for hint in hints {
-        if root.join(hint).exists() {
-            score = score.saturating_add(1);
+        let candidate = root.join(hint);
+        if !candidate.exists() {
+            continue;
+        }
+        if super::ignore_cache::is_ignored_under_root(root, &candidate) {
+            continue;
         }
+        score = score.saturating_add(1);
     }
     score
 }
 
+/// Scores `root` as a candidate for auto-disambiguating among several MCP workspace roots (see
+/// `chunk220-5`'s `rank_ambiguous_workspace_roots`). Three independent signals, summed so a root
+/// that wins on more than one counts more than a root that only coincidentally matches one:
+/// - the server process's cwd is inside `root` (a strong signal -- whatever spawned this
+///   connection most likely did so from the project directory)
+/// - recency: how close to the front of `recent_roots` (most-recently-used first) `root` is
+/// - `hint_score_for_root`: how many relative hints resolve to something real under `root`
+pub(in crate::tools::dispatch) fn score_workspace_root_candidate(
+    root: &Path,
+    cwd: Option<&Path>,
+    recent_roots: &[PathBuf],
+    hints: &[String],
+) -> usize {
+    let mut score = 0usize;
+    if cwd.is_some_and(|cwd| cwd.starts_with(root)) {
+        score = score.saturating_add(100);
+    }
+    if let Some(position) = recent_roots.iter().position(|recent| recent == root) {
+        score = score.saturating_add(10usize.saturating_sub(position.min(9)));
+    }
+    score.saturating_add(hint_score_for_root(root, hints))
+}
+
+/// The subset of `hints` that exist under `root` but were excluded from its `hint_score_for_root`
+/// because they fall inside an ignored directory -- surfaced by `RootDiagnostics` so an
+/// ambiguous-workspace error can explain why an otherwise-matching hint didn't count.
+pub(in crate::tools::dispatch) fn discarded_ignored_hints(
+    root: &Path,
+    hints: &[String],
+) -> Vec<String> {
+    hints
+        .iter()
+        .filter(|hint| {
+            let candidate = root.join(hint);
+            candidate.exists() && super::ignore_cache::is_ignored_under_root(root, &candidate)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Directory names whose presence anywhere in a root's path signal "this is a vendored/dependency
+/// checkout, not a workspace the user is actively editing" -- e.g. a Cargo crate unpacked under
+/// `~/.cargo/registry` that happens to match a relative hint by coincidence.
+const DEPENDENCY_DIR_MARKERS: &[&str] = &["target", "node_modules", "vendor", "registry", ".venv"];
+
+/// Whether `root` is a workspace the user is actively working in, or an external
+/// dependency/vendored checkout that merely happens to contain a matching hint. Used by
+/// `resolve_root_from_relative_hints` to avoid silently latching onto a dependency tree when two
+/// candidate roots tie on hint score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(in crate::tools::dispatch) enum RootClass {
+    WorkspaceMember,
+    ExternalDependency,
+}
+
+/// Classifies `root` as `ExternalDependency` if any path component names a known vendored/
+/// dependency directory (`target`, `node_modules`, `vendor`, `~/.cargo/registry`, `.venv`), or if
+/// `mcp_workspace_roots` is non-empty and `root` falls outside all of them. Otherwise
+/// `WorkspaceMember`.
+pub(in crate::tools::dispatch) fn classify_root(
+    root: &Path,
+    mcp_workspace_roots: &[PathBuf],
+) -> RootClass {
+    let under_dependency_dir = root.components().any(|component| {
+        component
+            .as_os_str()
+            .to_str()
+            .is_some_and(|name| DEPENDENCY_DIR_MARKERS.contains(&name))
+    });
+    if under_dependency_dir {
+        return RootClass::ExternalDependency;
+    }
+
+    let outside_workspace = !mcp_workspace_roots.is_empty()
+        && !mcp_workspace_roots
+            .iter()
+            .any(|workspace_root| root.starts_with(workspace_root));
+    if outside_workspace {
+        return RootClass::ExternalDependency;
+    }
+
+    RootClass::WorkspaceMember
+}
+
+/// One attempt `resolve_root_impl_with_hints` made while resolving a root: which strategy ran,
+/// which candidate path (if any) it tried, and why that attempt succeeded or was rejected.
+/// Accumulated into `SessionDefaults::last_resolution_trace` and rendered by `RootDiagnostics` so
+/// an opaque "relative `path` is ambiguous" failure comes with an ordered log of what was tried.
+#[derive(Debug, Clone)]
+pub(in crate::tools::dispatch) struct ResolutionStep {
+    pub strategy: &'static str,
+    pub candidate: Option<String>,
+    pub outcome: String,
+}
+
+impl ResolutionStep {
+    pub(in crate::tools::dispatch) fn new(
+        strategy: &'static str,
+        candidate: Option<String>,
+        outcome: impl Into<String>,
+    ) -> Self {
+        Self {
+            strategy,
+            candidate,
+            outcome: outcome.into(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(in crate::tools::dispatch) struct ScopeHint {
     pub include_paths: Vec<String>,
@@ -125,34 +245,94 @@ pub(in crate::tools::dispatch) fn env_root_override() -> Option<(String, String)
     None
 }
 
+/// The project-descriptor filename the resolver looks for alongside the usual markers (see
+/// `ProjectDescriptor`), for repos with no single canonical marker (generated code, polyglot
+/// monorepos) where the user wants to pin the root deterministically.
+pub(in crate::tools::dispatch) const PROJECT_DESCRIPTOR_FILE: &str = "context-finder.json";
+
+/// Synthetic marker name surfaced through the same `Option<&'static str>` slot as
+/// `PROJECT_ROOT_MARKERS` when a `context-finder.json` overrode the detected root, so
+/// `RootDiagnostics` can tell the two apart.
+const PROJECT_DESCRIPTOR_MARKER: &str = "context-finder.json";
+
+/// A `context-finder.json` manifest: lets a repo with no single canonical marker (generated code,
+/// polyglot monorepos) pin its logical root and indexing scope deterministically instead of
+/// relying on marker-walk heuristics.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub(in crate::tools::dispatch) struct ProjectDescriptor {
+    /// Path (relative to the directory containing this file) to the logical project root.
+    /// Defaults to the containing directory when absent.
+    #[serde(default)]
+    pub root: Option<String>,
+    /// Additional source roots (relative to the declared root) that the background pre-warm crawl
+    /// also walks. The indexer's own file scan does not read them.
+    #[serde(default)]
+    pub source_roots: Vec<String>,
+    /// Glob patterns (gitignore syntax) layered on top of `.gitignore`/`.ignore` in
+    /// `super::ignore_cache`, where they stop matching hints from counting toward a root. The
+    /// indexer's own file scan does not read them.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+/// Parses `dir`'s `context-finder.json`, if present. Malformed JSON is treated as absent rather
+/// than an error -- a manifest is an optional override, not load-bearing for basic resolution.
+pub(in crate::tools::dispatch) fn load_project_descriptor(dir: &Path) -> Option<ProjectDescriptor> {
+    let raw = std::fs::read_to_string(dir.join(PROJECT_DESCRIPTOR_FILE)).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+/// If `dir` (or an ancestor already chosen as the detected root) carries a `context-finder.json`
+/// declaring a `root`, resolves and returns that declared root instead. Otherwise returns `dir`
+/// unchanged, with `marker` passed through.
+fn apply_project_descriptor(
+    dir: PathBuf,
+    marker: Option<&'static str>,
+) -> (PathBuf, Option<&'static str>) {
+    let Some(descriptor) = load_project_descriptor(&dir) else {
+        return (dir, marker);
+    };
+    let Some(declared_root) = descriptor.root.as_deref() else {
+        return (dir, marker);
+    };
+    match dir.join(declared_root).canonicalize() {
+        Ok(canonical) => (canonical, Some(PROJECT_DESCRIPTOR_MARKER)),
+        Err(_) => (dir, marker),
+    }
+}
+
 pub(in crate::tools::dispatch) fn canonicalize_root(raw: &str) -> Result<PathBuf, String> {
     canonicalize_root_path(Path::new(raw))
 }
 
 pub(in crate::tools::dispatch) fn canonicalize_root_path(path: &Path) -> Result<PathBuf, String> {
+    canonicalize_root_path_bounded(path, None).map(|(root, _marker)| root)
+}
+
+/// Same as `canonicalize_root_path`, but never ascends above `ceiling` (an already-known
+/// workspace root) and reports the marker that matched, for diagnostics.
+pub(in crate::tools::dispatch) fn canonicalize_root_path_bounded(
+    path: &Path,
+    ceiling: Option<&Path>,
+) -> Result<(PathBuf, Option<&'static str>), String> {
     let canonical = path.canonicalize().map_err(|err| err.to_string())?;
 
-    // Agent-native UX: callers often pass a "current file" path as `path`.
-    // Treat that as a hint within the project and prefer the enclosing git root (when present),
-    // otherwise fall back to the file's parent directory.
-    let (base, is_file) = match std::fs::metadata(&canonical) {
-        Ok(meta) if meta.is_file() => (
-            canonical
-                .parent()
-                .map(PathBuf::from)
-                .ok_or_else(|| "Invalid path: file has no parent directory".to_string())?,
-            true,
-        ),
-        _ => (canonical, false),
+    // Agent-native UX: callers often pass a "current file" (or a directory nested somewhere
+    // inside a project) as `path`. Treat that as a hint within the project and ascend to the
+    // enclosing project root rather than using the leaf path verbatim.
+    let base = match std::fs::metadata(&canonical) {
+        Ok(meta) if meta.is_file() => canonical
+            .parent()
+            .map(PathBuf::from)
+            .ok_or_else(|| "Invalid path: file has no parent directory".to_string())?,
+        _ => canonical,
     };
 
-    if is_file {
-        if let Some(project_root) = find_project_root(&base) {
-            return Ok(project_root);
-        }
+    if let Some((project_root, marker)) = find_project_root_marker(&base, ceiling) {
+        return Ok(apply_project_descriptor(project_root, Some(marker)));
     }
 
-    Ok(base)
+    Ok(apply_project_descriptor(base, None))
 }
 
 pub(in crate::tools::dispatch) fn rel_path_string(path: &Path) -> Option<String> {
@@ -166,35 +346,128 @@ pub(in crate::tools::dispatch) fn rel_path_string(path: &Path) -> Option<String>
     }
 }
 
-fn find_git_root(start: &Path) -> Option<PathBuf> {
-    start
-        .ancestors()
-        .find(|candidate| candidate.join(".git").exists())
-        .map(PathBuf::from)
+/// Ordered by how strongly each marker signals "this is the project root" -- `.git` beats a
+/// vendored `Cargo.toml`/`package.json` at the same depth. Markers at different depths always
+/// prefer the nearest (lowest) one regardless of this order; the order only breaks ties when
+/// several markers sit in the same directory.
+const PROJECT_ROOT_MARKERS: &[&str] = &[
+    ".git",
+    "Cargo.toml",
+    "package.json",
+    "pyproject.toml",
+    "go.mod",
+    ".hg",
+    "pom.xml",
+    ".context-finder-root",
+];
+
+/// Overrides the default `PROJECT_ROOT_MARKERS` used by `detect_root_by_markers`. A comma-separated
+/// list, e.g. `CONTEXT_ROOT_MARKERS=Cargo.toml,.git`.
+const ROOT_MARKERS_ENV: &str = "CONTEXT_ROOT_MARKERS";
+
+/// The marker list `detect_root_by_markers` should use: a per-session override (see
+/// `SessionDefaults::root_markers`) takes precedence, then `CONTEXT_ROOT_MARKERS`, then the
+/// built-in `PROJECT_ROOT_MARKERS`.
+pub(in crate::tools::dispatch) fn configured_root_markers(
+    session_override: Option<&[String]>,
+) -> Vec<String> {
+    if let Some(markers) = session_override {
+        if !markers.is_empty() {
+            return markers.to_vec();
+        }
+    }
+    if let Ok(value) = env::var(ROOT_MARKERS_ENV) {
+        let custom: Vec<String> = value
+            .split(',')
+            .map(|marker| marker.trim().to_string())
+            .filter(|marker| !marker.is_empty())
+            .collect();
+        if !custom.is_empty() {
+            return custom;
+        }
+    }
+    PROJECT_ROOT_MARKERS
+        .iter()
+        .map(|marker| marker.to_string())
+        .collect()
 }
 
-pub(in crate::tools::dispatch) fn find_project_root(start: &Path) -> Option<PathBuf> {
-    if let Some(root) = find_git_root(start) {
-        return Some(root);
+/// Walks from `start` up to the nearest enclosing `.git` repository (or, absent one, to the user's
+/// home directory), looking for directories that contain any of `markers`.
+///
+/// Precedence (see `chunk220-1`): the top-most marker-bearing directory still inside the enclosing
+/// `.git` repository; else the `.git` repository root itself; else the top-most marker-bearing
+/// directory if there is no git repo; else `None` (caller falls back to the process cwd).
+pub(in crate::tools::dispatch) fn detect_root_by_markers(
+    start: &Path,
+    markers: &[String],
+) -> Option<PathBuf> {
+    if markers.is_empty() {
+        return None;
+    }
+
+    let home = dirs::home_dir();
+    let git_root = find_git_root(start, home.as_deref());
+
+    let mut marker_dirs: Vec<PathBuf> = Vec::new();
+    for candidate in start.ancestors() {
+        if markers.iter().any(|marker| candidate.join(marker).exists()) {
+            marker_dirs.push(candidate.to_path_buf());
+        }
+        let at_boundary = git_root.as_deref().is_some_and(|root| candidate == root)
+            || home.as_deref().is_some_and(|home| candidate == home);
+        if at_boundary {
+            break;
+        }
+    }
+
+    if let Some(git_root) = git_root {
+        return Some(marker_dirs.pop().unwrap_or(git_root));
+    }
+
+    marker_dirs.pop()
+}
+
+/// Ascends from `start` to the nearest ancestor containing a `.git` directory, never past `home`.
+fn find_git_root(start: &Path, home: Option<&Path>) -> Option<PathBuf> {
+    for candidate in start.ancestors() {
+        if candidate.join(".git").exists() {
+            return Some(candidate.to_path_buf());
+        }
+        if home.is_some_and(|home| candidate == home) {
+            break;
+        }
+    }
+    None
+}
+
+/// Ascends from `start` (inclusive) to the nearest ancestor containing a `PROJECT_ROOT_MARKERS`
+/// entry, borrowing the worktree/git-root concept from editors like Zed so a "current file" (or
+/// nested directory) hint resolves to the enclosing project rather than a leaf directory. Never
+/// ascends above `ceiling` (an already-known workspace root) or the user's home directory.
+pub(in crate::tools::dispatch) fn find_project_root_marker(
+    start: &Path,
+    ceiling: Option<&Path>,
+) -> Option<(PathBuf, &'static str)> {
+    let home = dirs::home_dir();
+    for candidate in start.ancestors() {
+        if let Some(marker) = PROJECT_ROOT_MARKERS
+            .iter()
+            .find(|marker| candidate.join(marker).exists())
+        {
+            return Some((candidate.to_path_buf(), marker));
+        }
+        if ceiling.is_some_and(|ceiling| candidate == ceiling)
+            || home.as_deref().is_some_and(|home| candidate == home)
+        {
+            break;
+        }
     }
+    None
+}
 
-    const MARKERS: &[&str] = &[
-        "AGENTS.md",
-        "Cargo.toml",
-        "package.json",
-        "pyproject.toml",
-        "go.mod",
-        "pom.xml",
-        "build.gradle",
-        "build.gradle.kts",
-        "CMakeLists.txt",
-        "Makefile",
-    ];
-
-    start
-        .ancestors()
-        .find(|candidate| MARKERS.iter().any(|marker| candidate.join(marker).exists()))
-        .map(PathBuf::from)
+pub(in crate::tools::dispatch) fn find_project_root(start: &Path) -> Option<PathBuf> {
+    find_project_root_marker(start, None).map(|(root, _marker)| root)
 }
 
 pub(in crate::tools::dispatch) fn root_path_from_mcp_uri(uri: &str) -> Option<PathBuf> {
@@ -3,9 +3,11 @@ use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use super::{
-    canonicalize_root, canonicalize_root_path, collect_relative_hints, env_root_override,
-    hint_score_for_root, rel_path_string, resolve_root_from_absolute_hints, trimmed_non_empty,
-    RootUpdateSnapshot, RootUpdateSource,
+    canonicalize_root, canonicalize_root_path, canonicalize_root_path_bounded, classify_root,
+    collect_relative_hints, configured_root_markers, detect_root_by_markers,
+    discarded_ignored_hints, env_root_override, hint_score_for_root, rel_path_string,
+    resolve_root_from_absolute_hints, score_workspace_root_candidate, trimmed_non_empty, JailError,
+    PathJail, ResolutionStep, RootClass, RootUpdateSnapshot, RootUpdateSource,
 };
 
 use super::super::ContextFinderService;
@@ -41,16 +43,26 @@ struct RootDiagnostics {
     last_root_set: Option<RootUpdateSnapshot>,
     last_root_update: Option<RootUpdateSnapshot>,
     cwd: Option<String>,
+    discarded_ignored_hints: Vec<String>,
+    resolution_trace: Vec<ResolutionStep>,
 }
 
 impl RootDiagnostics {
     async fn capture(service: &ContextFinderService) -> Self {
-        let (session_root, last_root_set, last_root_update) = {
+        let (
+            session_root,
+            last_root_set,
+            last_root_update,
+            discarded_ignored_hints,
+            resolution_trace,
+        ) = {
             let session = service.session.lock().await;
             (
                 session.root_display(),
                 session.last_root_set_snapshot(),
                 session.last_root_update_snapshot(),
+                session.last_discarded_hints(),
+                session.last_resolution_trace(),
             )
         };
         let cwd = env::current_dir()
@@ -61,7 +73,44 @@ impl RootDiagnostics {
             last_root_set,
             last_root_update,
             cwd,
+            discarded_ignored_hints,
+            resolution_trace,
+        }
+    }
+
+    fn resolution_trace_json(&self) -> serde_json::Value {
+        serde_json::Value::Array(
+            self.resolution_trace
+                .iter()
+                .map(|step| {
+                    let mut out = serde_json::Map::new();
+                    out.insert("strategy".to_string(), serde_json::json!(step.strategy));
+                    if let Some(candidate) = step.candidate.as_deref() {
+                        out.insert("candidate".to_string(), serde_json::json!(candidate));
+                    }
+                    out.insert("outcome".to_string(), serde_json::json!(step.outcome));
+                    serde_json::Value::Object(out)
+                })
+                .collect(),
+        )
+    }
+
+    /// A compact one-line rendering of the trace (`strategy[candidate]->outcome`, `; `-joined),
+    /// suitable for appending to an error message without blowing up its size.
+    fn resolution_trace_summary(&self) -> Option<String> {
+        if self.resolution_trace.is_empty() {
+            return None;
         }
+        let rendered = self
+            .resolution_trace
+            .iter()
+            .map(|step| match step.candidate.as_deref() {
+                Some(candidate) => format!("{}[{candidate}]->{}", step.strategy, step.outcome),
+                None => format!("{}->{}", step.strategy, step.outcome),
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+        Some(truncate_to_chars(&rendered, 400))
     }
 
     fn update_json(update: &RootUpdateSnapshot) -> serde_json::Value {
@@ -74,6 +123,12 @@ impl RootDiagnostics {
         if let Some(tool) = update.source_tool.as_deref() {
             out.insert("source_tool".to_string(), serde_json::json!(tool));
         }
+        if let Some(marker) = update.detected_marker {
+            out.insert("detected_marker".to_string(), serde_json::json!(marker));
+        }
+        if let Some(event) = update.watch_event {
+            out.insert("watch_event".to_string(), serde_json::json!(event));
+        }
         serde_json::Value::Object(out)
     }
 
@@ -97,6 +152,15 @@ impl RootDiagnostics {
                 out.insert("last_root_update".to_string(), Self::update_json(update));
             }
         }
+        if !self.discarded_ignored_hints.is_empty() {
+            out.insert(
+                "discarded_ignored_hints".to_string(),
+                serde_json::json!(self.discarded_ignored_hints),
+            );
+        }
+        if !self.resolution_trace.is_empty() {
+            out.insert("resolution_trace".to_string(), self.resolution_trace_json());
+        }
         if out.is_empty() {
             serde_json::Value::Null
         } else {
@@ -113,6 +177,12 @@ impl RootDiagnostics {
         if let Some(tool) = update.source_tool.as_deref() {
             out.push_str(&format!(" tool={tool}"));
         }
+        if let Some(marker) = update.detected_marker {
+            out.push_str(&format!(" marker={marker}"));
+        }
+        if let Some(event) = update.watch_event {
+            out.push_str(&format!(" watch_event={event}"));
+        }
         out
     }
 
@@ -145,6 +215,15 @@ impl RootDiagnostics {
                 notes.push(format!("hint=root_set path={cwd}"));
             }
         }
+        if !self.discarded_ignored_hints.is_empty() {
+            notes.push(format!(
+                "discarded_ignored_hints={}",
+                self.discarded_ignored_hints.join(",")
+            ));
+        }
+        if let Some(summary) = self.resolution_trace_summary() {
+            notes.push(format!("resolution_trace={summary}"));
+        }
         if notes.is_empty() {
             message
         } else {
@@ -190,6 +269,42 @@ fn select_workspace_root_by_hints(roots: &[PathBuf], hints: &[String]) -> Option
     }
 }
 
+/// Auto-disambiguates among `candidates` (several MCP workspace roots) by summing
+/// `score_workspace_root_candidate` for each and picking the strict winner -- see `chunk220-5`.
+/// Returns `None` (caller falls back to the `root_set`/`path` disambiguation error) when nothing
+/// scores above zero or the top candidates are tied, same tie-break discipline as
+/// `select_workspace_root_by_hints`.
+fn rank_ambiguous_workspace_roots(
+    candidates: &[PathBuf],
+    cwd: Option<&Path>,
+    recent_roots: &[PathBuf],
+    hints: &[String],
+) -> Option<PathBuf> {
+    let mut best_score = 0usize;
+    let mut best: Option<PathBuf> = None;
+    let mut tied = false;
+
+    for root in candidates {
+        let score = score_workspace_root_candidate(root, cwd, recent_roots, hints);
+        if score == 0 {
+            continue;
+        }
+        if score > best_score {
+            best_score = score;
+            best = Some(root.clone());
+            tied = false;
+        } else if score == best_score {
+            tied = true;
+        }
+    }
+
+    if best_score == 0 || tied {
+        None
+    } else {
+        best
+    }
+}
+
 impl ContextFinderService {
     pub(in crate::tools::dispatch) async fn resolve_root_for_tool(
         &self,
@@ -210,6 +325,7 @@ impl ContextFinderService {
             .resolve_root_impl_with_hints(raw_path, hints, Some(tool))
             .await?;
         self.touch_daemon_best_effort(&root);
+        super::watch::ensure_root_watch(self, &root).await;
         Ok((root, root_display))
     }
 
@@ -249,14 +365,39 @@ impl ContextFinderService {
             .await
     }
 
+    /// Appends `step` to `trace` and persists it into the session immediately, so
+    /// `RootDiagnostics` sees the steps tried so far even if this call returns on the very next
+    /// line (most resolution failures return right after the step that caused them).
+    async fn record_resolution_step(&self, trace: &mut Vec<ResolutionStep>, step: ResolutionStep) {
+        trace.push(step);
+        self.session
+            .lock()
+            .await
+            .set_last_resolution_trace(trace.clone());
+    }
+
     async fn resolve_root_impl_with_hints(
         &self,
         raw_path: Option<&str>,
         hints: &[String],
         source_tool: Option<&'static str>,
     ) -> Result<(PathBuf, String), String> {
+        // Ordered log of every strategy this call tried, for `RootDiagnostics::resolution_trace`.
+        // Reset per call (not accumulated across calls) so it reflects only the most recent
+        // attempt; persisted into the session after each step so it survives an early return.
+        let mut trace: Vec<ResolutionStep> = Vec::new();
+
         if trimmed_non_empty(raw_path).is_none() {
             if let Some(message) = self.session.lock().await.root_mismatch_error() {
+                self.record_resolution_step(
+                    &mut trace,
+                    ResolutionStep::new(
+                        "root_mismatch_error",
+                        None,
+                        "session root mismatch recorded earlier; failing fast",
+                    ),
+                )
+                .await;
                 return Err(message.to_string());
             }
         }
@@ -283,13 +424,31 @@ impl ContextFinderService {
             if !self.allow_cwd_root_fallback && raw_path.is_absolute() {
                 if let Some((root, root_display)) = session_root.as_ref() {
                     let session_root_allowed_by_workspace = mcp_workspace_roots.is_empty()
-                        || mcp_workspace_roots
-                            .iter()
-                            .any(|candidate| root.starts_with(candidate));
+                        || mcp_workspace_roots.iter().any(|candidate| {
+                            PathJail::new(candidate).is_ok_and(|jail| jail.contains(root).is_ok())
+                        });
                     if session_root_allowed_by_workspace {
-                        let canonical = match PathBuf::from(raw).canonicalize() {
-                            Ok(value) => value,
+                        self.record_resolution_step(
+                            &mut trace,
+                            ResolutionStep::new(
+                                "sticky_root_absolute_hint",
+                                Some(raw.to_string()),
+                                format!("absolute path with existing sticky root {root_display}; treating as in-project hint"),
+                            ),
+                        )
+                        .await;
+                        let jail = match PathJail::new(root) {
+                            Ok(jail) => jail,
                             Err(err) => {
+                                self.record_resolution_step(
+                                    &mut trace,
+                                    ResolutionStep::new(
+                                        "sticky_root_absolute_hint",
+                                        Some(root_display.clone()),
+                                        format!("sticky root failed to jail: {err}"),
+                                    ),
+                                )
+                                .await;
                                 return Err(decorate_invalid_path_error(
                                     self,
                                     format!("Invalid path: {err}"),
@@ -297,21 +456,49 @@ impl ContextFinderService {
                                 .await);
                             }
                         };
-                        if !canonical.starts_with(root) {
-                            return Err(
-                                decorate_invalid_path_error(
+                        let canonical = match jail.contains(Path::new(raw)) {
+                            Ok(value) => value,
+                            Err(JailError::ResolutionFailed(err)) => {
+                                self.record_resolution_step(
+                                    &mut trace,
+                                    ResolutionStep::new(
+                                        "sticky_root_absolute_hint",
+                                        Some(raw.to_string()),
+                                        format!("failed to resolve under sticky root: {err}"),
+                                    ),
+                                )
+                                .await;
+                                return Err(decorate_invalid_path_error(
                                     self,
-                                    "Invalid path: absolute `path` is outside the current project; call root_set to switch projects."
-                                        .to_string(),
+                                    format!("Invalid path: {err}"),
                                 )
-                                .await,
-                            );
-                        }
+                                .await);
+                            }
+                            Err(JailError::Escaped) => {
+                                self.record_resolution_step(
+                                    &mut trace,
+                                    ResolutionStep::new(
+                                        "sticky_root_absolute_hint",
+                                        Some(raw.to_string()),
+                                        "path escapes the sticky root jail",
+                                    ),
+                                )
+                                .await;
+                                return Err(
+                                    decorate_invalid_path_error(
+                                        self,
+                                        "Invalid path: absolute `path` is outside the current project; call root_set to switch projects."
+                                            .to_string(),
+                                    )
+                                    .await,
+                                );
+                            }
+                        };
 
                         let focus_file = std::fs::metadata(&canonical)
                             .ok()
                             .filter(|meta| meta.is_file())
-                            .and_then(|_| canonical.strip_prefix(root).ok())
+                            .and_then(|_| canonical.strip_prefix(jail.root()).ok())
                             .and_then(rel_path_string);
 
                         let mut session = self.session.lock().await;
@@ -323,6 +510,7 @@ impl ContextFinderService {
                                 RootUpdateSource::ResolvePath,
                                 requested_path.clone(),
                                 source_tool.clone(),
+                                None,
                             );
                         }
                         return Ok((root.clone(), root_display.clone()));
@@ -347,6 +535,23 @@ impl ContextFinderService {
                 {
                     candidates.push(workspace_root.join(raw_norm));
                 } else {
+                    let discarded = mcp_workspace_roots
+                        .iter()
+                        .flat_map(|root| discarded_ignored_hints(root, &raw_hint))
+                        .collect();
+                    self.session
+                        .lock()
+                        .await
+                        .set_last_discarded_hints(discarded);
+                    self.record_resolution_step(
+                        &mut trace,
+                        ResolutionStep::new(
+                            "multi_root_disambiguate_path",
+                            Some(raw.to_string()),
+                            "relative path hint didn't uniquely match any workspace root",
+                        ),
+                    )
+                    .await;
                     return Err(
                         decorate_invalid_path_error(
                             self,
@@ -361,6 +566,15 @@ impl ContextFinderService {
                 // Shared daemon mode must not guess across projects.
                 candidates.push(PathBuf::from(raw));
             } else {
+                self.record_resolution_step(
+                    &mut trace,
+                    ResolutionStep::new(
+                        "relative_path_no_root",
+                        Some(raw.to_string()),
+                        "no session/workspace root to resolve relative path against",
+                    ),
+                )
+                .await;
                 return Err(
                     decorate_invalid_path_error(
                         self,
@@ -373,8 +587,14 @@ impl ContextFinderService {
 
             let mut last_err: Option<String> = None;
             for candidate in candidates {
-                match canonicalize_root_path(&candidate) {
-                    Ok(root) => {
+                // Never let the walk-up marker search escape a workspace root we already know
+                // about; the nearest enclosing marker must still stay inside the declared project.
+                let ceiling = mcp_workspace_roots
+                    .iter()
+                    .find(|workspace_root| candidate.starts_with(workspace_root))
+                    .cloned();
+                match canonicalize_root_path_bounded(&candidate, ceiling.as_deref()) {
+                    Ok((root, detected_marker)) => {
                         let root_display = root.to_string_lossy().to_string();
 
                         // Agent-native UX: callers often pass a "current file" path as `path`.
@@ -382,11 +602,13 @@ impl ContextFinderService {
                         // intent=memory` can surface the current working file without requiring
                         // extra parameters.
                         let mut focus_file: Option<String> = None;
-                        if let Ok(canonical) = candidate.canonicalize() {
-                            if let Ok(meta) = std::fs::metadata(&canonical) {
-                                if meta.is_file() {
-                                    if let Ok(rel) = canonical.strip_prefix(&root) {
-                                        focus_file = rel_path_string(rel);
+                        if let Ok(jail) = PathJail::new(&root) {
+                            if let Ok(canonical) = jail.contains(&candidate) {
+                                if let Ok(meta) = std::fs::metadata(&canonical) {
+                                    if meta.is_file() {
+                                        if let Ok(rel) = canonical.strip_prefix(jail.root()) {
+                                            focus_file = rel_path_string(rel);
+                                        }
                                     }
                                 }
                             }
@@ -399,6 +621,16 @@ impl ContextFinderService {
                                 &root_display,
                                 session.mcp_workspace_roots(),
                             ));
+                            drop(session);
+                            self.record_resolution_step(
+                                &mut trace,
+                                ResolutionStep::new(
+                                    "candidate_canonicalize",
+                                    Some(candidate.to_string_lossy().to_string()),
+                                    format!("resolved to {root_display}, but outside MCP workspace roots"),
+                                ),
+                            )
+                            .await;
                             continue;
                         }
                         if self.allow_cwd_root_fallback || session.initialized() {
@@ -409,13 +641,33 @@ impl ContextFinderService {
                                 RootUpdateSource::ResolvePath,
                                 requested_path.clone(),
                                 source_tool.clone(),
+                                detected_marker,
                             );
                         }
+                        drop(session);
+                        self.record_resolution_step(
+                            &mut trace,
+                            ResolutionStep::new(
+                                "candidate_canonicalize",
+                                Some(candidate.to_string_lossy().to_string()),
+                                format!("resolved to {root_display}"),
+                            ),
+                        )
+                        .await;
                         return Ok((root, root_display));
                     }
 
                     Err(err) => {
                         last_err = Some(format!("Invalid path: {err}"));
+                        self.record_resolution_step(
+                            &mut trace,
+                            ResolutionStep::new(
+                                "candidate_canonicalize",
+                                Some(candidate.to_string_lossy().to_string()),
+                                format!("failed: {err}"),
+                            ),
+                        )
+                        .await;
                     }
                 }
             }
@@ -443,12 +695,22 @@ impl ContextFinderService {
                                 .to_string(),
                         );
                     }
+                    drop(session);
+                    self.record_resolution_step(
+                        &mut trace,
+                        ResolutionStep::new(
+                            "sticky_root",
+                            Some(root_display.clone()),
+                            "reused established session root",
+                        ),
+                    )
+                    .await;
                     return Ok((root, root_display));
                 }
             }
         }
 
-        if let Some(root) = resolve_root_from_absolute_hints(hints) {
+        if let Some((root, detected_marker)) = resolve_root_from_absolute_hints(hints) {
             let root_display = root.to_string_lossy().to_string();
             let mut session = self.session.lock().await;
             if !session.root_allowed_by_workspace(&root) {
@@ -470,8 +732,19 @@ impl ContextFinderService {
                     RootUpdateSource::ResolvePath,
                     None,
                     source_tool.clone(),
+                    detected_marker,
                 );
             }
+            drop(session);
+            self.record_resolution_step(
+                &mut trace,
+                ResolutionStep::new(
+                    "absolute_hints",
+                    Some(root_display.clone()),
+                    "resolved from an absolute hint path",
+                ),
+            )
+            .await;
             return Ok((root, root_display));
         }
 
@@ -503,10 +776,39 @@ impl ContextFinderService {
                             RootUpdateSource::ResolvePath,
                             None,
                             source_tool.clone(),
+                            None,
                         );
                     }
+                    drop(session);
+                    self.record_resolution_step(
+                        &mut trace,
+                        ResolutionStep::new(
+                            "multi_root_disambiguate_hints",
+                            Some(root_display.clone()),
+                            "relative hints uniquely matched one workspace root",
+                        ),
+                    )
+                    .await;
                     return Ok((root, root_display));
                 }
+
+                let discarded = workspace_roots
+                    .iter()
+                    .flat_map(|root| discarded_ignored_hints(root, &relative_hints))
+                    .collect();
+                self.session
+                    .lock()
+                    .await
+                    .set_last_discarded_hints(discarded);
+                self.record_resolution_step(
+                    &mut trace,
+                    ResolutionStep::new(
+                        "multi_root_disambiguate_hints",
+                        None,
+                        "relative hints didn't uniquely match a workspace root",
+                    ),
+                )
+                .await;
             }
 
             if self.allow_cwd_root_fallback {
@@ -514,6 +816,15 @@ impl ContextFinderService {
                     .resolve_root_from_relative_hints(&relative_hints, source_tool.as_deref())
                     .await
                 {
+                    self.record_resolution_step(
+                        &mut trace,
+                        ResolutionStep::new(
+                            "recent_roots_hints",
+                            Some(root_display.clone()),
+                            "matched a recently-used root via relative hints",
+                        ),
+                    )
+                    .await;
                     return Ok((root, root_display));
                 }
             }
@@ -536,14 +847,45 @@ impl ContextFinderService {
             let notify = self.roots_notify.clone();
             let _ = tokio::time::timeout(Duration::from_millis(wait_ms), notify.notified()).await;
             if let Some((root, root_display)) = self.session.lock().await.clone_root() {
+                self.record_resolution_step(
+                    &mut trace,
+                    ResolutionStep::new(
+                        "roots_pending_wait",
+                        Some(root_display.clone()),
+                        format!(
+                            "waited up to {wait_ms}ms for MCP roots/list, session root established"
+                        ),
+                    ),
+                )
+                .await;
                 return Ok((root, root_display));
             }
+            self.record_resolution_step(
+                &mut trace,
+                ResolutionStep::new(
+                    "roots_pending_wait",
+                    None,
+                    format!(
+                        "waited up to {wait_ms}ms for MCP roots/list, no session root established"
+                    ),
+                ),
+            )
+            .await;
         }
 
         if let Some((var, value)) = env_root_override() {
             let root = match canonicalize_root(&value) {
                 Ok(value) => value,
                 Err(err) => {
+                    self.record_resolution_step(
+                        &mut trace,
+                        ResolutionStep::new(
+                            "env_override",
+                            Some(value.clone()),
+                            format!("{var} failed to canonicalize: {err}"),
+                        ),
+                    )
+                    .await;
                     return Err(decorate_invalid_path_error(
                         self,
                         format!("Invalid path from {var}: {err}"),
@@ -573,18 +915,89 @@ impl ContextFinderService {
                     RootUpdateSource::EnvOverride,
                     Some(value),
                     source_tool.clone(),
+                    None,
                 );
             }
+            drop(session);
+            self.record_resolution_step(
+                &mut trace,
+                ResolutionStep::new(
+                    "env_override",
+                    Some(root_display.clone()),
+                    format!("resolved from {var}"),
+                ),
+            )
+            .await;
             return Ok((root, root_display));
         }
 
         if !self.allow_cwd_root_fallback {
             if self.session.lock().await.mcp_roots_ambiguous() {
+                let workspace_roots = { self.session.lock().await.mcp_workspace_roots().to_vec() };
+                let cwd = env::current_dir().ok();
+                let recent_roots = self.state.recent_roots().await;
+                let relative_hints = collect_relative_hints(hints);
+                if let Some(root) = rank_ambiguous_workspace_roots(
+                    &workspace_roots,
+                    cwd.as_deref(),
+                    &recent_roots,
+                    &relative_hints,
+                ) {
+                    let root_display = root.to_string_lossy().to_string();
+                    let focus_file = relative_hints.iter().find_map(|hint| {
+                        let candidate = root.join(hint);
+                        std::fs::metadata(&candidate)
+                            .ok()
+                            .filter(|meta| meta.is_file())
+                            .and_then(|_| rel_path_string(Path::new(hint)))
+                    });
+                    let mut session = self.session.lock().await;
+                    if session.initialized() {
+                        session.set_root(
+                            root.clone(),
+                            root_display.clone(),
+                            focus_file,
+                            RootUpdateSource::WorkspaceRootRanked,
+                            None,
+                            source_tool.clone(),
+                            None,
+                        );
+                    }
+                    drop(session);
+                    self.record_resolution_step(
+                        &mut trace,
+                        ResolutionStep::new(
+                            "workspace_root_ranked",
+                            Some(root_display.clone()),
+                            "multiple MCP workspace roots detected; auto-selected the top-ranked candidate",
+                        ),
+                    )
+                    .await;
+                    return Ok((root, root_display));
+                }
+                self.record_resolution_step(
+                    &mut trace,
+                    ResolutionStep::new(
+                        "no_root_available",
+                        None,
+                        "multiple MCP workspace roots detected, ranking tied; refusing to guess",
+                    ),
+                )
+                .await;
                 return Err(
                     "Missing project root: multiple MCP workspace roots detected; call `root_set` (recommended) or pass `path` to disambiguate."
                         .to_string(),
                 );
             }
+            self.record_resolution_step(
+                &mut trace,
+                ResolutionStep::new(
+                    "no_root_available",
+                    None,
+                    "no session/workspace root and daemon mode disallows cwd fallback",
+                ),
+            )
+            .await;
             return Err(
                 "Missing project root: call `root_set` (recommended), or pass `path`, or enable MCP roots, or set CONTEXT_ROOT/CONTEXT_PROJECT_ROOT."
                     .to_string(),
@@ -593,10 +1006,49 @@ impl ContextFinderService {
 
         let cwd = env::current_dir()
             .map_err(|err| format!("Failed to determine current directory: {err}"))?;
-        let candidate = cwd;
+
+        // Marker-walk fallback: runs before the plain cwd fallback so launching an agent from a
+        // subdirectory of a real project (no `root_set`/`path`, no MCP roots) still lands on the
+        // project root rather than the launch directory itself.
+        let root_markers = {
+            let session = self.session.lock().await;
+            configured_root_markers(Some(session.root_markers()))
+        };
+        let detected_by_markers = detect_root_by_markers(&cwd, &root_markers);
+        if let Some(detected) = detected_by_markers.as_ref() {
+            self.record_resolution_step(
+                &mut trace,
+                ResolutionStep::new(
+                    "marker_walk_fallback",
+                    Some(detected.to_string_lossy().to_string()),
+                    "found a root marker walking up from cwd",
+                ),
+            )
+            .await;
+        } else {
+            self.record_resolution_step(
+                &mut trace,
+                ResolutionStep::new(
+                    "marker_walk_fallback",
+                    None,
+                    "no root marker found walking up from cwd",
+                ),
+            )
+            .await;
+        }
+        let candidate = detected_by_markers.unwrap_or(cwd);
         let root = match canonicalize_root_path(&candidate) {
             Ok(root) => root,
             Err(err) => {
+                self.record_resolution_step(
+                    &mut trace,
+                    ResolutionStep::new(
+                        "cwd_fallback",
+                        Some(candidate.to_string_lossy().to_string()),
+                        format!("failed to canonicalize cwd: {err}"),
+                    ),
+                )
+                .await;
                 return Err(
                     decorate_invalid_path_error(self, format!("Invalid path: {err}")).await,
                 );
@@ -605,11 +1057,22 @@ impl ContextFinderService {
         let root_display = root.to_string_lossy().to_string();
         let mut session = self.session.lock().await;
         if !session.root_allowed_by_workspace(&root) {
-            return Err(root_outside_workspace_error(
+            let err = root_outside_workspace_error(
                 "Missing project root: computed cwd root",
                 &root_display,
                 session.mcp_workspace_roots(),
-            ) + " Call `root_set` or pass `path`.");
+            ) + " Call `root_set` or pass `path`.";
+            drop(session);
+            self.record_resolution_step(
+                &mut trace,
+                ResolutionStep::new(
+                    "cwd_fallback",
+                    Some(root_display.clone()),
+                    "computed cwd root is outside MCP workspace roots",
+                ),
+            )
+            .await;
+            return Err(err);
         }
         if self.allow_cwd_root_fallback || session.initialized() {
             session.set_root(
@@ -619,8 +1082,19 @@ impl ContextFinderService {
                 RootUpdateSource::CwdFallback,
                 None,
                 source_tool.clone(),
+                None,
             );
         }
+        drop(session);
+        self.record_resolution_step(
+            &mut trace,
+            ResolutionStep::new(
+                "cwd_fallback",
+                Some(root_display.clone()),
+                "resolved from the server process cwd",
+            ),
+        )
+        .await;
         Ok((root, root_display))
     }
 
@@ -664,6 +1138,23 @@ impl ContextFinderService {
             return None;
         }
 
+        // Among tied candidates, prefer a workspace member over an external dependency/vendored
+        // checkout (e.g. a `~/.cargo/registry` crate that happens to contain a matching hint) so
+        // the resolver doesn't silently latch onto a dependency tree.
+        if best_roots.len() > 1 {
+            let mcp_workspace_roots = { self.session.lock().await.mcp_workspace_roots().to_vec() };
+            let workspace_members: Vec<PathBuf> = best_roots
+                .iter()
+                .filter(|root| {
+                    classify_root(root, &mcp_workspace_roots) == RootClass::WorkspaceMember
+                })
+                .cloned()
+                .collect();
+            if !workspace_members.is_empty() {
+                best_roots = workspace_members;
+            }
+        }
+
         let chosen = if best_roots.len() == 1 {
             best_roots.remove(0)
         } else if let Some((root, _)) = session_root {
@@ -688,6 +1179,7 @@ impl ContextFinderService {
             RootUpdateSource::ResolvePath,
             None,
             source_tool,
+            None,
         );
         Some((chosen, root_display))
     }
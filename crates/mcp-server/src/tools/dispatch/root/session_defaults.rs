@@ -2,6 +2,10 @@ use std::collections::{HashSet, VecDeque};
 use std::path::PathBuf;
 use std::time::SystemTime;
 
+use super::crawl::Crawl;
+use super::jail::PathJail;
+use super::resolve::ResolutionStep;
+use super::watch::RootWatchHandle;
 use crate::tools::util::unix_ms;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -11,6 +15,15 @@ pub(in crate::tools::dispatch) enum RootUpdateSource {
     McpRoots,
     CwdFallback,
     EnvOverride,
+    /// The session's sticky root was cleared because a background filesystem watch observed the
+    /// root directory disappear (removed or renamed out from under a long-lived daemon connection)
+    /// or a root marker file change along its ancestor chain (see `super::watch`).
+    Watcher,
+    /// Auto-selected among several MCP workspace roots by `rank_ambiguous_workspace_roots` (cwd
+    /// containment, recent-root recency, relative hints) rather than via an explicit `root_set` or
+    /// a uniquely-matching hint (see `chunk220-5`). Distinct from `McpRoots` (the single-root
+    /// case) so callers can tell an inferred pick apart from the unambiguous one.
+    WorkspaceRootRanked,
 }
 
 impl RootUpdateSource {
@@ -21,6 +34,8 @@ impl RootUpdateSource {
             RootUpdateSource::McpRoots => "mcp_roots",
             RootUpdateSource::CwdFallback => "cwd_fallback",
             RootUpdateSource::EnvOverride => "env_override",
+            RootUpdateSource::Watcher => "watcher",
+            RootUpdateSource::WorkspaceRootRanked => "workspace_root_ranked",
         }
     }
 }
@@ -31,6 +46,13 @@ struct RootUpdate {
     source: RootUpdateSource,
     requested_path: Option<String>,
     source_tool: Option<String>,
+    /// The `PROJECT_ROOT_MARKERS` entry (e.g. `.git`, `Cargo.toml`) that the walk-up resolver
+    /// matched to land on this root, if any (see `super::resolve::find_project_root_marker`), or
+    /// `context-finder.json` when a project descriptor's declared `root` overrode it.
+    detected_marker: Option<&'static str>,
+    /// Set only for `RootUpdateSource::Watcher`: why the watch invalidated the root (e.g.
+    /// `"missing"` -- the watched root no longer exists, whether removed or renamed away).
+    watch_event: Option<&'static str>,
 }
 
 impl RootUpdate {
@@ -38,12 +60,16 @@ impl RootUpdate {
         source: RootUpdateSource,
         requested_path: Option<String>,
         source_tool: Option<String>,
+        detected_marker: Option<&'static str>,
+        watch_event: Option<&'static str>,
     ) -> Self {
         Self {
             at_ms: unix_ms(SystemTime::now()),
             source,
             requested_path,
             source_tool,
+            detected_marker,
+            watch_event,
         }
     }
 
@@ -53,6 +79,8 @@ impl RootUpdate {
             source: self.source.as_str(),
             requested_path: self.requested_path.clone(),
             source_tool: self.source_tool.clone(),
+            detected_marker: self.detected_marker,
+            watch_event: self.watch_event,
         }
     }
 }
@@ -63,6 +91,8 @@ pub(in crate::tools::dispatch) struct RootUpdateSnapshot {
     pub source: &'static str,
     pub requested_path: Option<String>,
     pub source_tool: Option<String>,
+    pub detected_marker: Option<&'static str>,
+    pub watch_event: Option<&'static str>,
 }
 
 #[derive(Default)]
@@ -87,15 +117,41 @@ pub(in crate::tools::dispatch) struct SessionDefaults {
     /// When non-empty, resolved roots must be within one of these directories.
     mcp_workspace_roots: Vec<PathBuf>,
 
+    /// Per-session override of the marker list `super::resolve::detect_root_by_markers` walks
+    /// for, taking precedence over `CONTEXT_ROOT_MARKERS`/the built-in default list. Empty means
+    /// no override.
+    root_markers: Vec<String>,
+
     /// Fail-closed: when we detect that the session root is outside the MCP workspace roots,
     /// we record an error and refuse to serve requests without an explicit `path`.
     root_mismatch_error: Option<String>,
     last_root_set: Option<RootUpdate>,
     last_root_update: Option<RootUpdate>,
+
+    /// Hints that existed under a candidate workspace root but were discarded as `.gitignore`-ed
+    /// during multi-root disambiguation (see `super::resolve::discarded_ignored_hints`). Surfaced
+    /// by `RootDiagnostics` so an ambiguous-workspace error can explain why a hint didn't count.
+    last_discarded_hints: Vec<String>,
+
+    /// The ordered log of strategies `resolve_root_impl_with_hints` tried on its most recent run
+    /// (sticky-root rejection, per-candidate canonicalization, the roots-pending wait, env
+    /// override, cwd fallback, ...). Surfaced by `RootDiagnostics` as `resolution_trace` so a
+    /// failed resolution isn't just "Invalid path" with no record of what was attempted.
+    last_resolution_trace: Vec<ResolutionStep>,
     // Working-set: ephemeral, per-connection state (no disk). Used to avoid repeating the same
     // anchors/snippets across multiple calls in one agent session.
     seen_snippet_files: VecDeque<String>,
     seen_snippet_files_set: HashSet<String>,
+
+    /// Extension/`all_files` dedup for the background pre-warm crawl (see `super::crawl`).
+    crawl: Crawl,
+    /// Whether a pre-warm crawl of the session root is currently in flight.
+    crawl_pending: bool,
+
+    /// The live background filesystem watch on the current sticky root, if one is running (see
+    /// `super::watch`). Dropped (stopping the watch) whenever the root changes or is cleared, so
+    /// it never outlives the root it was watching.
+    watch: Option<RootWatchHandle>,
 }
 
 impl SessionDefaults {
@@ -127,6 +183,14 @@ impl SessionDefaults {
         &self.mcp_workspace_roots
     }
 
+    pub(in crate::tools::dispatch) fn set_root_markers(&mut self, markers: Vec<String>) {
+        self.root_markers = markers;
+    }
+
+    pub(in crate::tools::dispatch) fn root_markers(&self) -> &[String] {
+        &self.root_markers
+    }
+
     pub(in crate::tools::dispatch) fn root_allowed_by_workspace(
         &self,
         root: &std::path::Path,
@@ -134,9 +198,12 @@ impl SessionDefaults {
         if self.mcp_workspace_roots.is_empty() {
             return true;
         }
+        // Symlink-safe containment: a lexical `starts_with` can be fooled by a symlink inside the
+        // resolved root that points outside the workspace, so compare fully-canonicalized paths
+        // via `PathJail` instead (see `super::jail`).
         self.mcp_workspace_roots
             .iter()
-            .any(|candidate| root.starts_with(candidate))
+            .any(|candidate| PathJail::new(candidate).is_ok_and(|jail| jail.contains(root).is_ok()))
     }
 
     pub(in crate::tools::dispatch) fn root_mismatch_error(&self) -> Option<&str> {
@@ -167,6 +234,25 @@ impl SessionDefaults {
         self.last_root_update.as_ref().map(RootUpdate::snapshot)
     }
 
+    pub(in crate::tools::dispatch) fn set_last_discarded_hints(&mut self, hints: Vec<String>) {
+        self.last_discarded_hints = hints;
+    }
+
+    pub(in crate::tools::dispatch) fn last_discarded_hints(&self) -> Vec<String> {
+        self.last_discarded_hints.clone()
+    }
+
+    pub(in crate::tools::dispatch) fn set_last_resolution_trace(
+        &mut self,
+        trace: Vec<ResolutionStep>,
+    ) {
+        self.last_resolution_trace = trace;
+    }
+
+    pub(in crate::tools::dispatch) fn last_resolution_trace(&self) -> Vec<ResolutionStep> {
+        self.last_resolution_trace.clone()
+    }
+
     pub(in crate::tools::dispatch) fn focus_file(&self) -> Option<String> {
         self.focus_file.clone()
     }
@@ -186,9 +272,13 @@ impl SessionDefaults {
         self.root_mismatch_error = None;
         self.last_root_set = None;
         self.last_root_update = None;
+        self.crawl.reset();
+        self.crawl_pending = false;
+        self.watch = None;
         self.clear_working_set();
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(in crate::tools::dispatch) fn set_root(
         &mut self,
         root: PathBuf,
@@ -197,6 +287,7 @@ impl SessionDefaults {
         source: RootUpdateSource,
         requested_path: Option<String>,
         source_tool: Option<String>,
+        detected_marker: Option<&'static str>,
     ) {
         let root_changed = match self.root.as_ref() {
             Some(prev) => prev != &root,
@@ -207,19 +298,62 @@ impl SessionDefaults {
         self.focus_file = focus_file;
         self.mcp_roots_ambiguous = false;
         self.root_mismatch_error = None;
-        self.note_root_update(source, requested_path, source_tool);
+        self.note_root_update(source, requested_path, source_tool, detected_marker, None);
         if root_changed {
+            self.crawl.reset();
+            self.crawl_pending = false;
             self.clear_working_set();
+            // The running watch (if any) was started for the previous root; drop it here so
+            // `super::watch::ensure_root_watch` starts a fresh one for the new root.
+            self.watch = None;
         }
     }
 
+    /// Called by `super::watch` when a background filesystem watch observes the sticky root
+    /// disappear (removed or renamed away) or one of its root marker files change. Clears the root so the next `resolve_root_impl_with_hints`
+    /// transparently re-resolves from hints/workspace roots instead of failing deep inside
+    /// canonicalization with a generic "Invalid path".
+    pub(in crate::tools::dispatch) fn invalidate_root_from_watch(
+        &mut self,
+        watched_root: String,
+        watch_event: &'static str,
+    ) {
+        self.root = None;
+        self.root_display = None;
+        self.focus_file = None;
+        self.watch = None;
+        self.note_root_update(
+            RootUpdateSource::Watcher,
+            Some(watched_root),
+            None,
+            None,
+            Some(watch_event),
+        );
+    }
+
+    pub(in crate::tools::dispatch) fn watch_root(&self) -> Option<&std::path::Path> {
+        self.watch.as_ref().map(RootWatchHandle::root)
+    }
+
+    pub(in crate::tools::dispatch) fn set_watch(&mut self, handle: RootWatchHandle) {
+        self.watch = Some(handle);
+    }
+
     fn note_root_update(
         &mut self,
         source: RootUpdateSource,
         requested_path: Option<String>,
         source_tool: Option<String>,
+        detected_marker: Option<&'static str>,
+        watch_event: Option<&'static str>,
     ) {
-        let update = RootUpdate::new(source, requested_path, source_tool);
+        let update = RootUpdate::new(
+            source,
+            requested_path,
+            source_tool,
+            detected_marker,
+            watch_event,
+        );
         if source == RootUpdateSource::RootSet {
             self.last_root_set = Some(update.clone());
         }
@@ -231,6 +365,24 @@ impl SessionDefaults {
         self.seen_snippet_files_set.clear();
     }
 
+    pub(in crate::tools::dispatch) fn crawl_pending(&self) -> bool {
+        self.crawl_pending
+    }
+
+    pub(in crate::tools::dispatch) fn set_crawl_pending(&mut self, pending: bool) {
+        self.crawl_pending = pending;
+    }
+
+    /// Records a touch for `extension` (or `all_files`) against the session's crawl state,
+    /// returning whether the caller should spawn a fresh pre-warm walk.
+    pub(in crate::tools::dispatch) fn note_crawl_touch(
+        &mut self,
+        extension: Option<&str>,
+        all_files: bool,
+    ) -> bool {
+        self.crawl.note_touch(extension, all_files)
+    }
+
     pub(in crate::tools::dispatch) fn note_seen_snippet_file(&mut self, file: &str) {
         const MAX_SEEN: usize = 160;
 
@@ -0,0 +1,193 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use log::warn;
+use notify::{Config as NotifyConfig, Event, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+use super::super::ContextFinderService;
+use super::resolve::{configured_root_markers, PROJECT_DESCRIPTOR_FILE};
+
+/// Debounces the burst of events a single remove/rename typically produces (e.g. a directory
+/// rename on some platforms fires once per descendant) so one disappearance only invalidates the
+/// root once.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Disables the marker-chain watch added in `chunk220-4` (the root still gets the disappearance
+/// watch from `chunk219-4`), for headless/CI setups that want the older static-root behavior and
+/// would rather not pay for extra inotify/fsevent watches. Unset/`0`/`false` leaves it enabled.
+const DISABLE_MARKER_WATCH_ENV: &str = "CONTEXT_FINDER_DISABLE_ROOT_MARKER_WATCH";
+
+fn marker_watch_disabled() -> bool {
+    std::env::var(DISABLE_MARKER_WATCH_ENV)
+        .ok()
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+/// Owns the live fsevent/inotify watch backing a session's sticky root. Holding this alive is what
+/// keeps the OS-level watch registered; dropping it (e.g. when the root changes, see
+/// `SessionDefaults::set_root`) tears the watch down.
+pub(in crate::tools::dispatch) struct RootWatchHandle {
+    root: PathBuf,
+    _watcher: RecommendedWatcher,
+}
+
+impl RootWatchHandle {
+    pub(in crate::tools::dispatch) fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+/// Starts a best-effort background watch on `root` the first time this session sees it, so a
+/// long-lived daemon connection notices when its sticky root is deleted or renamed out from under
+/// it instead of failing every subsequent call deep inside canonicalization with a generic
+/// "Invalid path" (see `RootUpdateSource::Watcher`). Borrows the "watch the parent, not the leaf"
+/// trick from watchexec's `fs` module: a watch placed directly on a path that then gets removed
+/// stops delivering events on some platforms (the inotify watch descriptor dies with the inode),
+/// so this watches `root`'s parent and checks `root` itself on every event instead.
+///
+/// Also watches (unless `CONTEXT_FINDER_DISABLE_ROOT_MARKER_WATCH` is set, see
+/// `marker_watch_disabled`) `root` itself and its ancestor chain up to the enclosing `.git`
+/// repository or the user's home directory -- the same span `detect_root_by_markers` walks -- for
+/// the marker files/`context-finder.json` that anchored this root changing. If one is removed,
+/// moved, or a new one appears alongside it, the cached root is marked dirty the same way a
+/// disappeared root is, so the next tool call re-resolves from scratch (re-canonicalizing,
+/// re-checking `root_allowed_by_workspace`, possibly landing on a newly-added nearer marker)
+/// instead of silently continuing to serve the stale root for the rest of the session. This is
+/// necessarily best-effort: a marker added deeper inside `root` (below the original hint that
+/// anchored it) isn't covered without a recursive watch of the whole subtree, which isn't worth
+/// the cost here.
+///
+/// Daemon-mode only (`allow_cwd_root_fallback == false`): a CLI invocation's process exits along
+/// with its cwd anyway, so there's nothing for a watch to protect there. A no-op if a watch is
+/// already running for `root`.
+pub(in crate::tools::dispatch) async fn ensure_root_watch(
+    service: &ContextFinderService,
+    root: &Path,
+) {
+    if service.allow_cwd_root_fallback {
+        return;
+    }
+
+    let session_markers = {
+        let session = service.session.lock().await;
+        if session.watch_root() == Some(root) {
+            return;
+        }
+        session.root_markers().to_vec()
+    };
+
+    let watch_target = root.parent().unwrap_or(root).to_path_buf();
+
+    let (tx, mut rx) = mpsc::channel::<notify::Result<Event>>(16);
+    let watcher = RecommendedWatcher::new(
+        move |res| {
+            let _ = tx.blocking_send(res);
+        },
+        NotifyConfig::default(),
+    );
+    let mut watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            warn!("root watch init failed for {}: {err}", root.display());
+            return;
+        }
+    };
+    if let Err(err) = watcher.watch(&watch_target, RecursiveMode::NonRecursive) {
+        warn!("root watch failed for {}: {err}", watch_target.display());
+        return;
+    }
+
+    let marker_names = if marker_watch_disabled() {
+        Vec::new()
+    } else {
+        for ancestor in marker_watch_chain(root) {
+            if ancestor == watch_target {
+                continue;
+            }
+            if let Err(err) = watcher.watch(&ancestor, RecursiveMode::NonRecursive) {
+                warn!("root marker watch failed for {}: {err}", ancestor.display());
+            }
+        }
+        let mut names = configured_root_markers(Some(&session_markers));
+        names.push(PROJECT_DESCRIPTOR_FILE.to_string());
+        names
+    };
+
+    let root = root.to_path_buf();
+    {
+        let mut session = service.session.lock().await;
+        session.set_watch(RootWatchHandle {
+            root: root.clone(),
+            _watcher: watcher,
+        });
+    }
+
+    let service = service.clone();
+    tokio::spawn(async move {
+        let mut last_fire: Option<Instant> = None;
+
+        while let Some(event) = rx.recv().await {
+            let Ok(event) = event else {
+                continue;
+            };
+
+            let watch_event = if !root.exists() {
+                Some("missing")
+            } else if event_touches_marker(&event, &marker_names) {
+                Some("markers_changed")
+            } else {
+                None
+            };
+            let Some(watch_event) = watch_event else {
+                continue;
+            };
+
+            let now = Instant::now();
+            if last_fire.is_some_and(|prev| now.duration_since(prev) < DEBOUNCE) {
+                continue;
+            }
+            last_fire = Some(now);
+
+            let mut session = service.session.lock().await;
+            // Another watch may have already replaced this one (root re-resolved to the same
+            // path after a prior invalidation); only invalidate if we're still the active watch.
+            if session.watch_root() != Some(root.as_path()) {
+                break;
+            }
+            session.invalidate_root_from_watch(root.to_string_lossy().to_string(), watch_event);
+            break;
+        }
+    });
+}
+
+/// `root` plus its ancestors up to (and including) the enclosing `.git` repository, or the user's
+/// home directory if there is none -- the same span `detect_root_by_markers` is willing to walk
+/// when looking for a nearer marker.
+fn marker_watch_chain(root: &Path) -> Vec<PathBuf> {
+    let home = dirs::home_dir();
+    let mut chain = Vec::new();
+    for ancestor in root.ancestors() {
+        chain.push(ancestor.to_path_buf());
+        let at_boundary = ancestor.join(".git").exists() || home.as_deref() == Some(ancestor);
+        if at_boundary {
+            break;
+        }
+    }
+    chain
+}
+
+/// Whether `event` touched a path named like one of `marker_names` (a marker file/directory
+/// appearing, disappearing, or being renamed in a watched directory).
+fn event_touches_marker(event: &Event, marker_names: &[String]) -> bool {
+    if marker_names.is_empty() {
+        return false;
+    }
+    event.paths.iter().any(|path| {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| marker_names.iter().any(|marker| marker == name))
+    })
+}
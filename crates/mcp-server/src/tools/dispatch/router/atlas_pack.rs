@@ -222,10 +222,23 @@ pub(in crate::tools::dispatch) async fn atlas_pack(
         }
     }
 
-    let (text, bounded_truncated) = doc.finish_bounded(result.budget.max_chars);
-    result.budget.used_chars = text.chars().count();
+    let (mut text, bounded_truncated) = doc.finish_bounded(result.budget.max_chars);
     result.budget.truncated = result.budget.truncated || bounded_truncated;
 
+    if let Some(max_tokens) = result.budget.max_tokens {
+        let counter = context_protocol::HeuristicTokenCounter;
+        use context_protocol::TokenCounter;
+        let before = counter.count(&text);
+        text = context_protocol::truncate_to_tokens(&text, max_tokens, &counter);
+        let tokens = counter.count(&text);
+        if tokens < before {
+            result.budget.truncated = true;
+            result.budget.truncation = Some(context_protocol::BudgetTruncation::MaxTokens);
+        }
+        result.budget.used_tokens = Some(tokens);
+    }
+    result.budget.used_chars = text.chars().count();
+
     let call_result = CallToolResult::success(vec![Content::text(text)]);
     Ok(attach_structured_content(
         call_result,
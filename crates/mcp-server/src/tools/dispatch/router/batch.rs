@@ -1,14 +1,21 @@
 use super::super::{
     compute_used_chars, extract_path_from_input, parse_tool_result_as_json, prepare_item_input,
     push_item_or_truncate, resolve_batch_refs, BatchBudget, BatchItemResult, BatchItemStatus,
-    BatchRequest, BatchResult, BatchToolName, CallToolResult, Content, ContextFinderService,
+    BatchRequest, BatchResult, BatchRetryPolicy, BatchStatusRequest, BatchTaskState,
+    BatchTaskStatus, BatchToolName, CallToolResult, Content, ContextFinderService,
     ContextPackRequest, ContextRequest, DoctorRequest, ExplainRequest, FileSliceRequest,
     GrepContextRequest, ImpactRequest, IndexRequest, ListFilesRequest, MapRequest, McpError,
-    OverviewRequest, Parameters, SearchRequest, TextSearchRequest, TraceRequest,
+    OverviewRequest, Parameters, SearchRequest, SymbolLookupRequest, TextSearchRequest,
+    TraceRequest,
 };
-use crate::tools::schemas::batch::BatchItem;
-use std::collections::HashSet;
+use super::error::is_retriable_tool_error;
+use crate::tools::schemas::batch::{BatchItem, BatchItemGuard};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
 
 const DEFAULT_MAX_CHARS: usize = 20_000;
 const MAX_MAX_CHARS: usize = 500_000;
@@ -30,48 +37,862 @@ fn validate_batch_version(version: u32) -> Option<String> {
     }
 }
 
+/// Extracts the `<id>` from a `$ref` JSON Pointer of the form `#/items/<id>/...` (the shape
+/// documented on [`BatchItem::input`]).
+fn ref_pointer_item_id(pointer: &str) -> Option<String> {
+    let rest = pointer.strip_prefix("#/items/")?;
+    let id = rest.split('/').next()?;
+    (!id.is_empty()).then(|| id.to_string())
+}
+
+/// Recursively scans a raw (pre-resolution) batch item `input` for `$ref` wrappers and collects
+/// the item ids they reference.
+fn collect_ref_ids(value: &serde_json::Value, ids: &mut HashSet<String>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            if let Some(serde_json::Value::String(pointer)) = map.get("$ref") {
+                if let Some(id) = ref_pointer_item_id(pointer) {
+                    ids.insert(id);
+                }
+            }
+            for nested in map.values() {
+                collect_ref_ids(nested, ids);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for nested in items {
+                collect_ref_ids(nested, ids);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// True if `value` is "truthy" for `only_if`/`skip_if` purposes: anything but `null`, `false`, `0`,
+/// `""`, `[]`, and `{}`.
+fn is_truthy(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Null => false,
+        serde_json::Value::Bool(b) => *b,
+        serde_json::Value::Number(n) => n.as_f64().map(|n| n != 0.0).unwrap_or(true),
+        serde_json::Value::String(s) => !s.is_empty(),
+        serde_json::Value::Array(items) => !items.is_empty(),
+        serde_json::Value::Object(map) => !map.is_empty(),
+    }
+}
+
+/// Resolves a single `$ref` pointer (the same syntax used inside [`BatchItem::input`]) against
+/// `ctx`, independent of whatever `$ref`s live inside an item's `input`.
+fn resolve_guard_ref(guard: &BatchItemGuard, ctx: &serde_json::Value) -> Result<serde_json::Value, String> {
+    resolve_batch_refs(serde_json::json!({ "$ref": guard.r#ref }), ctx)
+}
+
+/// Evaluates `item.only_if`/`item.skip_if` against `ctx`. Returns `Ok(Some(reason))` when the item
+/// should be skipped (recorded as [`BatchItemStatus::Skipped`] without dispatching), `Ok(None)` when
+/// it should run, and `Err` when a guard's `$ref` itself fails to resolve. `only_if` is checked
+/// first; if both are set, `skip_if` is only evaluated once `only_if` already says "run".
+fn evaluate_batch_guards(
+    item: &BatchItem,
+    ctx: Option<&serde_json::Value>,
+) -> Result<Option<String>, String> {
+    let Some(ctx) = ctx else {
+        // No ref context (batch v1): guards have nothing to resolve against, so they never fire.
+        return Ok(None);
+    };
+
+    if let Some(guard) = item.only_if.as_ref() {
+        let resolved = resolve_guard_ref(guard, ctx)?;
+        if !is_truthy(&resolved) {
+            return Ok(Some(format!(
+                "skipped: only_if ref '{}' resolved to a falsy/empty value",
+                guard.r#ref
+            )));
+        }
+    }
+
+    if let Some(guard) = item.skip_if.as_ref() {
+        let resolved = resolve_guard_ref(guard, ctx)?;
+        if is_truthy(&resolved) {
+            return Ok(Some(format!(
+                "skipped: skip_if ref '{}' resolved to a truthy value",
+                guard.r#ref
+            )));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Finds the first `$ref` pointer (pre-order) anywhere inside `value`, alongside the exact wrapper
+/// object it was read from.
+fn find_first_ref_pointer(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Object(map) => {
+            if let Some(serde_json::Value::String(pointer)) = map.get("$ref") {
+                return Some(pointer.clone());
+            }
+            for nested in map.values() {
+                if let Some(found) = find_first_ref_pointer(nested) {
+                    return Some(found);
+                }
+            }
+            None
+        }
+        serde_json::Value::Array(items) => items.iter().find_map(find_first_ref_pointer),
+        _ => None,
+    }
+}
+
+/// Rewrites every `$ref` wrapper pointing at exactly `from_pointer` to point at
+/// `{from_pointer}/{index}` instead, leaving other `$ref`s (and everything else) untouched. Used to
+/// turn one `for_each` item into `N` per-element items.
+fn rewrite_ref_pointer(value: &serde_json::Value, from_pointer: &str, index: usize) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => {
+            if let Some(serde_json::Value::String(pointer)) = map.get("$ref") {
+                if pointer == from_pointer {
+                    let mut rewritten = map.clone();
+                    rewritten.insert(
+                        "$ref".to_string(),
+                        serde_json::Value::String(format!("{from_pointer}/{index}")),
+                    );
+                    return serde_json::Value::Object(rewritten);
+                }
+            }
+            serde_json::Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), rewrite_ref_pointer(v, from_pointer, index)))
+                    .collect(),
+            )
+        }
+        serde_json::Value::Array(items) => serde_json::Value::Array(
+            items
+                .iter()
+                .map(|v| rewrite_ref_pointer(v, from_pointer, index))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// If `item.for_each` is set and `item.input` contains a `$ref` that resolves (against `ctx`) to a
+/// JSON array, returns one rewritten `input` per array element (each element's `$ref` narrowed to
+/// `.../<index>`). Returns `None` when `for_each` is unset, there's no `$ref` in `input`, or the
+/// referenced value isn't an array -- callers should fall back to running the item once, unchanged.
+fn fan_out_inputs(item: &BatchItem, ctx: Option<&serde_json::Value>) -> Option<Vec<serde_json::Value>> {
+    if !item.for_each {
+        return None;
+    }
+    let ctx = ctx?;
+    let pointer = find_first_ref_pointer(&item.input)?;
+    let resolved = resolve_batch_refs(serde_json::json!({ "$ref": pointer }), ctx).ok()?;
+    let serde_json::Value::Array(elements) = resolved else {
+        return None;
+    };
+
+    Some(
+        (0..elements.len())
+            .map(|index| rewrite_ref_pointer(&item.input, &pointer, index))
+            .collect(),
+    )
+}
+
+/// Builds the ref-dependency DAG over batch item indices (an edge means "depends on") by scanning
+/// each item's raw `input` for `$ref` pointers into other items, then validates it's acyclic.
+///
+/// Returns `(in_degree, dependents)` on success, where `dependents[i]` lists the indices that
+/// unblock once item `i` completes. Errs with the ids involved in a cycle, if any.
+fn build_batch_dependency_graph(items: &[BatchItem]) -> Result<(Vec<usize>, Vec<Vec<usize>>), String> {
+    let mut id_to_index = std::collections::HashMap::new();
+    for (index, item) in items.iter().enumerate() {
+        let trimmed = item.id.trim();
+        if !trimmed.is_empty() {
+            id_to_index.entry(trimmed.to_string()).or_insert(index);
+        }
+    }
+
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); items.len()];
+    let mut in_degree: Vec<usize> = vec![0; items.len()];
+    for (index, item) in items.iter().enumerate() {
+        let mut referenced_ids = HashSet::new();
+        collect_ref_ids(&item.input, &mut referenced_ids);
+        // `only_if`/`skip_if` resolve against the same ref context as `input` (see
+        // `evaluate_batch_guards`), so a guarded item depends on whatever it guards against just as
+        // much as it depends on the ids inside `input` -- otherwise `run_items_concurrently` can
+        // dispatch the item before its guard's dependency has produced output to resolve against.
+        if let Some(guard) = item.only_if.as_ref() {
+            if let Some(id) = ref_pointer_item_id(&guard.r#ref) {
+                referenced_ids.insert(id);
+            }
+        }
+        if let Some(guard) = item.skip_if.as_ref() {
+            if let Some(id) = ref_pointer_item_id(&guard.r#ref) {
+                referenced_ids.insert(id);
+            }
+        }
+        // `needs` declares edges a `$ref` can't express (e.g. "run after this side effect"), so
+        // it's folded into the same id set the inferred-from-`$ref` edges come from.
+        for needed_id in &item.needs {
+            referenced_ids.insert(needed_id.trim().to_string());
+        }
+
+        let mut dependencies = HashSet::new();
+        for referenced_id in referenced_ids {
+            if let Some(&dep_index) = id_to_index.get(&referenced_id) {
+                if dep_index != index {
+                    dependencies.insert(dep_index);
+                }
+            }
+        }
+        for dep_index in dependencies {
+            dependents[dep_index].push(index);
+            in_degree[index] += 1;
+        }
+    }
+
+    // Kahn's algorithm, used here purely to validate acyclicity (the resulting order is discarded
+    // -- actual scheduling happens in `run_items_concurrently` as dependencies resolve).
+    let mut check_in_degree = in_degree.clone();
+    let mut queue: Vec<usize> = (0..items.len()).filter(|&i| check_in_degree[i] == 0).collect();
+    let mut cursor = 0;
+    while cursor < queue.len() {
+        let current = queue[cursor];
+        cursor += 1;
+        for &dependent in &dependents[current] {
+            check_in_degree[dependent] -= 1;
+            if check_in_degree[dependent] == 0 {
+                queue.push(dependent);
+            }
+        }
+    }
+
+    if queue.len() != items.len() {
+        let cyclic: Vec<String> = (0..items.len())
+            .filter(|&i| check_in_degree[i] != 0)
+            .map(|i| items[i].id.trim().to_string())
+            .collect();
+        return Err(format!(
+            "Batch item refs form a cycle and cannot be scheduled: {}",
+            cyclic.join(", ")
+        ));
+    }
+
+    Ok((in_degree, dependents))
+}
+
+fn rejected_result(item: &BatchItem, message: String) -> BatchItemResult {
+    BatchItemResult {
+        id: item.id.trim().to_string(),
+        tool: item.tool,
+        status: BatchItemStatus::Error,
+        message: Some(message),
+        data: serde_json::Value::Null,
+    }
+}
+
+fn cancelled_result(item: &BatchItem) -> BatchItemResult {
+    BatchItemResult {
+        id: item.id.trim().to_string(),
+        tool: item.tool,
+        status: BatchItemStatus::Cancelled,
+        message: Some("Batch was cancelled before this item ran".to_string()),
+        data: serde_json::Value::Null,
+    }
+}
+
+fn skipped_result(item: &BatchItem, reason: String) -> BatchItemResult {
+    BatchItemResult {
+        id: item.id.trim().to_string(),
+        tool: item.tool,
+        status: BatchItemStatus::Skipped,
+        message: Some(reason),
+        data: serde_json::Value::Null,
+    }
+}
+
+fn release_dependents(
+    index: usize,
+    dependents: &[Vec<usize>],
+    in_degree: &mut [usize],
+    ready: &mut VecDeque<usize>,
+) {
+    for &dependent in &dependents[index] {
+        in_degree[dependent] -= 1;
+        if in_degree[dependent] == 0 {
+            ready.push_back(dependent);
+        }
+    }
+}
+
+fn store_ref_context_entry(ref_context: &mut Option<serde_json::Value>, result: &BatchItemResult) {
+    let Some(ctx) = ref_context.as_mut() else {
+        return;
+    };
+    let Some(items) = ctx
+        .get_mut("items")
+        .and_then(serde_json::Value::as_object_mut)
+    else {
+        return;
+    };
+    items.insert(
+        result.id.clone(),
+        serde_json::json!({
+            "tool": result.tool,
+            "status": result.status,
+            "message": result.message,
+            "data": result.data,
+        }),
+    );
+}
+
+/// Runs one already-ref-resolved batch item. Shared by the concurrent scheduler; budget
+/// reservation is atomic (`reserved_chars`) because several of these can run at once, so
+/// `remaining_chars` can't be read off a plain `&mut` field the way the sequential path does.
+async fn run_single_item(
+    service: &ContextFinderService,
+    item: &BatchItem,
+    inferred_path: Option<&str>,
+    ref_context: Option<&serde_json::Value>,
+    max_chars: usize,
+    reserved_chars: &AtomicUsize,
+) -> BatchItemResult {
+    let trimmed_id = item.id.trim().to_string();
+
+    match evaluate_batch_guards(item, ref_context) {
+        Ok(Some(reason)) => return skipped_result(item, reason),
+        Ok(None) => {}
+        Err(err) => {
+            return BatchItemResult {
+                id: trimmed_id,
+                tool: item.tool,
+                status: BatchItemStatus::Error,
+                message: Some(format!("Guard ref resolution error: {err}")),
+                data: serde_json::Value::Null,
+            };
+        }
+    }
+
+    if let Some(raw_inputs) = fan_out_inputs(item, ref_context) {
+        return run_fanned_out_item(
+            service,
+            item,
+            &trimmed_id,
+            raw_inputs,
+            inferred_path,
+            ref_context,
+            max_chars,
+            reserved_chars,
+        )
+        .await;
+    }
+
+    let resolved_input = if let Some(ctx) = ref_context {
+        match resolve_batch_refs(item.input.clone(), ctx) {
+            Ok(value) => value,
+            Err(err) => {
+                return BatchItemResult {
+                    id: trimmed_id,
+                    tool: item.tool,
+                    status: BatchItemStatus::Error,
+                    message: Some(format!("Ref resolution error: {err}")),
+                    data: serde_json::Value::Null,
+                };
+            }
+        }
+    } else {
+        item.input.clone()
+    };
+
+    if let Some(item_path) = extract_path_from_input(&resolved_input) {
+        if let Some(batch_path) = inferred_path {
+            if batch_path != item_path {
+                return BatchItemResult {
+                    id: trimmed_id,
+                    tool: item.tool,
+                    status: BatchItemStatus::Error,
+                    message: Some(format!(
+                        "Batch path mismatch: batch uses '{batch_path}', item uses '{item_path}'"
+                    )),
+                    data: serde_json::Value::Null,
+                };
+            }
+        }
+    }
+
+    // Reserve a conservative share of the remaining budget before dispatch so sibling in-flight
+    // items see it too, then release the reservation once this item's real contribution is about
+    // to be folded into `used_chars` by the sequential finishing pass.
+    let reserved_before = reserved_chars.load(Ordering::SeqCst);
+    let remaining = max_chars.saturating_sub(reserved_before);
+    let estimate = remaining.clamp(1, DEFAULT_MAX_CHARS);
+    reserved_chars.fetch_add(estimate, Ordering::SeqCst);
+
+    let input = prepare_item_input(resolved_input, inferred_path, item.tool, remaining);
+    let tool_result = dispatch_tool(service, item.tool, input).await;
+    let outcome = materialize_item_result(trimmed_id, item.tool, tool_result);
+
+    reserved_chars.fetch_sub(estimate, Ordering::SeqCst);
+    outcome
+}
+
+/// Runs one `for_each` item's per-element raw inputs (each still carrying its own narrowed `$ref`)
+/// sequentially, resolving and dispatching each in turn, and folds the per-element outcomes into a
+/// single [`BatchItemResult`] whose `data` is the array of element results in order.
+#[allow(clippy::too_many_arguments)]
+async fn run_fanned_out_item(
+    service: &ContextFinderService,
+    item: &BatchItem,
+    trimmed_id: &str,
+    raw_inputs: Vec<serde_json::Value>,
+    inferred_path: Option<&str>,
+    ref_context: Option<&serde_json::Value>,
+    max_chars: usize,
+    reserved_chars: &AtomicUsize,
+) -> BatchItemResult {
+    let mut data = Vec::with_capacity(raw_inputs.len());
+    let mut errors = 0usize;
+
+    for raw_input in raw_inputs {
+        let resolved_input = match ref_context {
+            Some(ctx) => match resolve_batch_refs(raw_input, ctx) {
+                Ok(value) => value,
+                Err(err) => {
+                    errors += 1;
+                    data.push(serde_json::json!({
+                        "status": "error",
+                        "message": format!("Ref resolution error: {err}"),
+                    }));
+                    continue;
+                }
+            },
+            None => raw_input,
+        };
+
+        let reserved_before = reserved_chars.load(Ordering::SeqCst);
+        let remaining = max_chars.saturating_sub(reserved_before);
+        let estimate = remaining.clamp(1, DEFAULT_MAX_CHARS);
+        reserved_chars.fetch_add(estimate, Ordering::SeqCst);
+
+        let input = prepare_item_input(resolved_input, inferred_path, item.tool, remaining);
+        let tool_result = dispatch_tool(service, item.tool, input).await;
+        let element = materialize_item_result(trimmed_id.to_string(), item.tool, tool_result);
+
+        reserved_chars.fetch_sub(estimate, Ordering::SeqCst);
+
+        if element.status == BatchItemStatus::Error {
+            errors += 1;
+        }
+        data.push(serde_json::json!({
+            "status": element.status,
+            "message": element.message,
+            "data": element.data,
+        }));
+    }
+
+    BatchItemResult {
+        id: trimmed_id.to_string(),
+        tool: item.tool,
+        status: if errors == 0 {
+            BatchItemStatus::Ok
+        } else {
+            BatchItemStatus::Error
+        },
+        message: (errors > 0)
+            .then(|| format!("for_each: {errors}/{} elements failed", data.len())),
+        data: serde_json::Value::Array(data),
+    }
+}
+
+/// Runs `items` respecting their ref-dependency DAG, dispatching independent items concurrently
+/// (bounded by `concurrency`) via a `tokio::JoinSet`. Returns per-index results (`None` means the
+/// item was never started because `stop_on_error` tripped first or an upstream dependency never
+/// ran; a cancelled item instead gets an explicit `BatchItemStatus::Cancelled` entry) plus the
+/// final `ref_context`.
+#[allow(clippy::too_many_arguments)]
+async fn run_items_concurrently(
+    service: &ContextFinderService,
+    items: Vec<BatchItem>,
+    inferred_path: Option<String>,
+    mut ref_context: Option<serde_json::Value>,
+    max_chars: usize,
+    stop_on_error: bool,
+    concurrency: usize,
+    cancel_token: CancellationToken,
+) -> (Vec<Option<BatchItemResult>>, Option<serde_json::Value>) {
+    let total = items.len();
+    let items = Arc::new(items);
+
+    let mut seen_ids = HashSet::new();
+    let mut results: Vec<Option<BatchItemResult>> = vec![None; total];
+    for (index, item) in items.iter().enumerate() {
+        let trimmed_id = item.id.trim().to_string();
+        if trimmed_id.is_empty() {
+            results[index] = Some(rejected_result(item, "Batch item id must not be empty".to_string()));
+        } else if !seen_ids.insert(trimmed_id.clone()) {
+            results[index] = Some(rejected_result(
+                item,
+                format!("Duplicate batch item id is not supported: '{trimmed_id}'"),
+            ));
+        }
+    }
+
+    let (mut in_degree, dependents) = match build_batch_dependency_graph(&items) {
+        Ok(graph) => graph,
+        Err(message) => {
+            for (index, item) in items.iter().enumerate() {
+                if results[index].is_none() {
+                    results[index] = Some(rejected_result(item, message.clone()));
+                }
+            }
+            return (results, ref_context);
+        }
+    };
+
+    let mut ready: VecDeque<usize> = VecDeque::new();
+    let mut stopped = false;
+    for index in 0..total {
+        if let Some(result) = results[index].clone() {
+            if stop_on_error && result.status == BatchItemStatus::Error {
+                stopped = true;
+            }
+            store_ref_context_entry(&mut ref_context, &result);
+            release_dependents(index, &dependents, &mut in_degree, &mut ready);
+        } else if in_degree[index] == 0 {
+            ready.push_back(index);
+        }
+    }
+
+    let reserved_chars = Arc::new(AtomicUsize::new(0));
+    let mut join_set: JoinSet<(usize, BatchItemResult)> = JoinSet::new();
+    let mut task_index_by_id: std::collections::HashMap<tokio::task::Id, usize> =
+        std::collections::HashMap::new();
+    let mut in_flight = 0usize;
+
+    loop {
+        while in_flight < concurrency && !stopped && !cancel_token.is_cancelled() {
+            let Some(index) = ready.pop_front() else {
+                break;
+            };
+            in_flight += 1;
+
+            let service = service.clone();
+            let items = Arc::clone(&items);
+            let inferred_path = inferred_path.clone();
+            let ref_snapshot = ref_context.clone();
+            let reserved_chars = Arc::clone(&reserved_chars);
+
+            let handle = join_set.spawn(async move {
+                let result = run_single_item(
+                    &service,
+                    &items[index],
+                    inferred_path.as_deref(),
+                    ref_snapshot.as_ref(),
+                    max_chars,
+                    &reserved_chars,
+                )
+                .await;
+                (index, result)
+            });
+            task_index_by_id.insert(handle.id(), index);
+        }
+
+        if in_flight == 0 {
+            break;
+        }
+
+        let completed = match join_set.join_next_with_id().await {
+            Some(Ok((id, (index, result)))) => {
+                task_index_by_id.remove(&id);
+                Some((index, result))
+            }
+            Some(Err(join_err)) => task_index_by_id
+                .remove(&join_err.id())
+                .map(|index| {
+                    let item = &items[index];
+                    (
+                        index,
+                        rejected_result(item, format!("Batch item task panicked: {join_err}")),
+                    )
+                }),
+            None => None,
+        };
+        in_flight -= 1;
+
+        let Some((index, result)) = completed else {
+            continue;
+        };
+
+        if stop_on_error && result.status == BatchItemStatus::Error {
+            stopped = true;
+        }
+        store_ref_context_entry(&mut ref_context, &result);
+        release_dependents(index, &dependents, &mut in_degree, &mut ready);
+        results[index] = Some(result);
+    }
+
+    if cancel_token.is_cancelled() {
+        for (index, item) in items.iter().enumerate() {
+            if results[index].is_none() {
+                results[index] = Some(cancelled_result(item));
+            }
+        }
+    }
+
+    (results, ref_context)
+}
+
+/// A pluggable handler for one or more [`BatchToolName`] variants, used by [`BatchDispatcher`]
+/// in place of a hardcoded `match`.
+///
+/// `handle` returns a boxed future rather than being an `async fn` so that `BatchHandler` stays
+/// object-safe (trait objects can't have `async fn` methods, since each impl's future is a
+/// distinct, unnameable type).
+pub(in crate::tools::dispatch) trait BatchHandler: Send + Sync {
+    /// Whether this handler wants to process `tool`.
+    fn accepts(&self, tool: BatchToolName) -> bool;
+
+    fn handle<'a>(
+        &'a self,
+        service: &'a ContextFinderService,
+        input: serde_json::Value,
+    ) -> std::pin::Pin<
+        Box<dyn std::future::Future<Output = std::result::Result<CallToolResult, McpError>> + Send + 'a>,
+    >;
+}
+
+/// Defines a [`BatchHandler`] that deserializes `input` as `$req` and forwards it to
+/// `service.$method`, matching the existing `typed_call!` shape used before the registry.
+macro_rules! typed_batch_handler {
+    ($name:ident, $tool:pat, $req:ty, $method:ident, $tool_name:literal) => {
+        struct $name;
+
+        impl BatchHandler for $name {
+            fn accepts(&self, tool: BatchToolName) -> bool {
+                matches!(tool, $tool)
+            }
+
+            fn handle<'a>(
+                &'a self,
+                service: &'a ContextFinderService,
+                input: serde_json::Value,
+            ) -> std::pin::Pin<
+                Box<
+                    dyn std::future::Future<Output = std::result::Result<CallToolResult, McpError>>
+                        + Send
+                        + 'a,
+                >,
+            > {
+                Box::pin(async move {
+                    match serde_json::from_value::<$req>(input) {
+                        Ok(req) => service.$method(Parameters(req)).await,
+                        Err(err) => Ok(CallToolResult::error(vec![Content::text(format!(
+                            "Invalid input for {}: {err}",
+                            $tool_name
+                        ))])),
+                    }
+                })
+            }
+        }
+    };
+}
+
+typed_batch_handler!(MapBatchHandler, BatchToolName::Map, MapRequest, map, "map");
+typed_batch_handler!(
+    FileSliceBatchHandler,
+    BatchToolName::FileSlice,
+    FileSliceRequest,
+    file_slice,
+    "file_slice"
+);
+typed_batch_handler!(
+    ListFilesBatchHandler,
+    BatchToolName::ListFiles,
+    ListFilesRequest,
+    list_files,
+    "list_files"
+);
+typed_batch_handler!(
+    TextSearchBatchHandler,
+    BatchToolName::TextSearch,
+    TextSearchRequest,
+    text_search,
+    "text_search"
+);
+typed_batch_handler!(
+    GrepContextBatchHandler,
+    BatchToolName::GrepContext,
+    GrepContextRequest,
+    grep_context,
+    "grep_context"
+);
+typed_batch_handler!(
+    DoctorBatchHandler,
+    BatchToolName::Doctor,
+    DoctorRequest,
+    doctor,
+    "doctor"
+);
+typed_batch_handler!(
+    SearchBatchHandler,
+    BatchToolName::Search,
+    SearchRequest,
+    search,
+    "search"
+);
+typed_batch_handler!(
+    ContextBatchHandler,
+    BatchToolName::Context,
+    ContextRequest,
+    context,
+    "context"
+);
+typed_batch_handler!(
+    ContextPackBatchHandler,
+    BatchToolName::ContextPack,
+    ContextPackRequest,
+    context_pack,
+    "context_pack"
+);
+typed_batch_handler!(
+    IndexBatchHandler,
+    BatchToolName::Index,
+    IndexRequest,
+    index,
+    "index"
+);
+typed_batch_handler!(
+    ImpactBatchHandler,
+    BatchToolName::Impact,
+    ImpactRequest,
+    impact,
+    "impact"
+);
+typed_batch_handler!(
+    SymbolLookupBatchHandler,
+    BatchToolName::SymbolLookup,
+    SymbolLookupRequest,
+    symbol_lookup,
+    "symbol_lookup"
+);
+typed_batch_handler!(
+    TraceBatchHandler,
+    BatchToolName::Trace,
+    TraceRequest,
+    trace,
+    "trace"
+);
+typed_batch_handler!(
+    ExplainBatchHandler,
+    BatchToolName::Explain,
+    ExplainRequest,
+    explain,
+    "explain"
+);
+typed_batch_handler!(
+    OverviewBatchHandler,
+    BatchToolName::Overview,
+    OverviewRequest,
+    overview,
+    "overview"
+);
+
+fn default_batch_handlers() -> Vec<Box<dyn BatchHandler>> {
+    vec![
+        Box::new(MapBatchHandler),
+        Box::new(FileSliceBatchHandler),
+        Box::new(ListFilesBatchHandler),
+        Box::new(TextSearchBatchHandler),
+        Box::new(GrepContextBatchHandler),
+        Box::new(DoctorBatchHandler),
+        Box::new(SearchBatchHandler),
+        Box::new(ContextBatchHandler),
+        Box::new(ContextPackBatchHandler),
+        Box::new(IndexBatchHandler),
+        Box::new(ImpactBatchHandler),
+        Box::new(SymbolLookupBatchHandler),
+        Box::new(TraceBatchHandler),
+        Box::new(ExplainBatchHandler),
+        Box::new(OverviewBatchHandler),
+    ]
+}
+
+/// Ordered registry of [`BatchHandler`]s consulted by `batch`'s per-item dispatch. Replaces a
+/// hardcoded `match tool { ... }` so handlers for tools layered on top of the built-in
+/// [`BatchToolName`] set can be registered via [`ContextFinderService::with_extra_batch_handlers`]
+/// without touching this match.
+pub(in crate::tools::dispatch) struct BatchDispatcher {
+    handlers: Vec<Box<dyn BatchHandler>>,
+}
+
+impl BatchDispatcher {
+    pub(in crate::tools::dispatch) fn new() -> Self {
+        Self {
+            handlers: default_batch_handlers(),
+        }
+    }
+
+    /// Registers `extra` ahead of the built-in handlers, so they may also override a built-in
+    /// tool's handling if desired.
+    pub(in crate::tools::dispatch) fn with_extra_handlers(
+        mut self,
+        mut extra: Vec<Box<dyn BatchHandler>>,
+    ) -> Self {
+        extra.append(&mut self.handlers);
+        self.handlers = extra;
+        self
+    }
+
+    async fn dispatch(
+        &self,
+        service: &ContextFinderService,
+        tool: BatchToolName,
+        input: serde_json::Value,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        for handler in &self.handlers {
+            if handler.accepts(tool) {
+                return handler.handle(service, input).await;
+            }
+        }
+        Ok(call_error(format!(
+            "No batch handler registered for {tool:?}"
+        )))
+    }
+}
+
 async fn dispatch_tool(
     service: &ContextFinderService,
     tool: BatchToolName,
     input: serde_json::Value,
 ) -> std::result::Result<CallToolResult, McpError> {
-    macro_rules! typed_call {
-        ($req:ty, $method:ident, $tool_name:literal) => {{
-            match serde_json::from_value::<$req>(input) {
-                Ok(req) => service.$method(Parameters(req)).await,
-                Err(err) => Ok(CallToolResult::error(vec![Content::text(format!(
-                    "Invalid input for {}: {err}",
-                    $tool_name
-                ))])),
-            }
-        }};
-    }
+    service
+        .batch_dispatcher()
+        .dispatch(service, tool, input)
+        .await
+}
 
-    match tool {
-        BatchToolName::Map => typed_call!(MapRequest, map, "map"),
-        BatchToolName::FileSlice => typed_call!(FileSliceRequest, file_slice, "file_slice"),
-        BatchToolName::ListFiles => typed_call!(ListFilesRequest, list_files, "list_files"),
-        BatchToolName::TextSearch => typed_call!(TextSearchRequest, text_search, "text_search"),
-        BatchToolName::GrepContext => typed_call!(GrepContextRequest, grep_context, "grep_context"),
-        BatchToolName::Doctor => typed_call!(DoctorRequest, doctor, "doctor"),
-        BatchToolName::Search => typed_call!(SearchRequest, search, "search"),
-        BatchToolName::Context => typed_call!(ContextRequest, context, "context"),
-        BatchToolName::ContextPack => typed_call!(ContextPackRequest, context_pack, "context_pack"),
-        BatchToolName::Index => typed_call!(IndexRequest, index, "index"),
-        BatchToolName::Impact => typed_call!(ImpactRequest, impact, "impact"),
-        BatchToolName::Trace => typed_call!(TraceRequest, trace, "trace"),
-        BatchToolName::Explain => typed_call!(ExplainRequest, explain, "explain"),
-        BatchToolName::Overview => typed_call!(OverviewRequest, overview, "overview"),
-    }
+/// Computes a stable cache key for `(tool, input)` so [`BatchRunner`] can memoize identical
+/// tool calls within one batch. `input` must already be fully resolved (post `resolve_batch_refs`
+/// / `prepare_item_input`) so two items that only differ by an unresolved `$ref` still collide
+/// once they resolve to the same concrete call.
+fn batch_item_cache_key(tool: BatchToolName, input: &serde_json::Value) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    format!("{tool:?}").hash(&mut hasher);
+    input.to_string().hash(&mut hasher);
+    hasher.finish()
 }
 
 struct BatchRunner<'a> {
     service: &'a ContextFinderService,
     stop_on_error: bool,
+    dedup: bool,
+    cancel_token: CancellationToken,
     inferred_path: Option<String>,
     seen_ids: HashSet<String>,
     ref_context: Option<serde_json::Value>,
     output: BatchResult,
+    dedup_cache: HashMap<u64, BatchItemResult>,
+    retry: Option<BatchRetryPolicy>,
 }
 
 impl<'a> BatchRunner<'a> {
@@ -79,6 +900,7 @@ impl<'a> BatchRunner<'a> {
         service: &'a ContextFinderService,
         version: u32,
         max_chars: usize,
+        max_tokens: Option<usize>,
         inferred_path: Option<String>,
     ) -> Self {
         let output = BatchResult {
@@ -87,6 +909,8 @@ impl<'a> BatchRunner<'a> {
             budget: BatchBudget {
                 max_chars,
                 used_chars: 0,
+                max_tokens,
+                used_tokens: None,
                 truncated: false,
             },
             meta: None,
@@ -102,10 +926,14 @@ impl<'a> BatchRunner<'a> {
         Self {
             service,
             stop_on_error: false,
+            dedup: true,
+            cancel_token: CancellationToken::new(),
             inferred_path,
             seen_ids: HashSet::new(),
             ref_context,
             output,
+            dedup_cache: HashMap::new(),
+            retry: None,
         }
     }
 
@@ -114,6 +942,21 @@ impl<'a> BatchRunner<'a> {
         self
     }
 
+    const fn with_dedup(mut self, dedup: bool) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    const fn with_retry(mut self, retry: Option<BatchRetryPolicy>) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    fn with_cancel_token(mut self, cancel_token: CancellationToken) -> Self {
+        self.cancel_token = cancel_token;
+        self
+    }
+
     const fn remaining_chars(&self) -> usize {
         self.output
             .budget
@@ -191,8 +1034,31 @@ impl<'a> BatchRunner<'a> {
                 .is_some_and(|v| v.status == BatchItemStatus::Error))
     }
 
+    /// Records `id` as cancelled and keeps the run going so every remaining item in the caller's
+    /// loop also gets an explicit `Cancelled` entry (instead of stopping, like `push_rejected`
+    /// does for `stop_on_error`).
+    fn push_cancelled(&mut self, id: String, tool: BatchToolName) -> bool {
+        let cancelled = BatchItemResult {
+            id,
+            tool,
+            status: BatchItemStatus::Cancelled,
+            message: Some("Batch was cancelled before this item ran".to_string()),
+            data: serde_json::Value::Null,
+        };
+
+        if !push_item_or_truncate(&mut self.output, cancelled) {
+            return false;
+        }
+        self.store_last_item_in_ref_context();
+        true
+    }
+
     async fn run_item(&mut self, item: BatchItem) -> bool {
         let trimmed_id = item.id.trim().to_string();
+        if self.cancel_token.is_cancelled() {
+            return self.push_cancelled(trimmed_id, item.tool);
+        }
+
         if trimmed_id.is_empty() {
             return self.push_rejected(
                 item.id,
@@ -206,6 +1072,28 @@ impl<'a> BatchRunner<'a> {
             return self.push_rejected(trimmed_id, item.tool, message);
         }
 
+        match evaluate_batch_guards(&item, self.ref_context.as_ref()) {
+            Ok(Some(reason)) => {
+                let outcome = skipped_result(&item, reason);
+                return self.push_processed(outcome);
+            }
+            Ok(None) => {}
+            Err(err) => {
+                return self.push_rejected(
+                    trimmed_id,
+                    item.tool,
+                    format!("Guard ref resolution error: {err}"),
+                );
+            }
+        }
+
+        if let Some(raw_inputs) = fan_out_inputs(&item, self.ref_context.as_ref()) {
+            let outcome = self
+                .run_fanned_out_item(&item, &trimmed_id, raw_inputs)
+                .await;
+            return self.push_processed(outcome);
+        }
+
         let resolved_input = if let Some(ctx) = self.ref_context.as_ref() {
             match resolve_batch_refs(item.input, ctx) {
                 Ok(value) => value,
@@ -244,12 +1132,147 @@ impl<'a> BatchRunner<'a> {
             item.tool,
             self.remaining_chars(),
         );
-        let tool_result = dispatch_tool(self.service, item.tool, input).await;
-        let outcome = materialize_item_result(trimmed_id, item.tool, tool_result);
+
+        let cache_key = self.dedup.then(|| batch_item_cache_key(item.tool, &input));
+        if let Some(cached) = cache_key.and_then(|key| self.dedup_cache.get(&key)) {
+            let outcome = BatchItemResult {
+                id: trimmed_id,
+                tool: cached.tool,
+                status: cached.status,
+                message: Some("deduplicated: reused result from an identical earlier item".to_string()),
+                data: cached.data.clone(),
+            };
+            return self.push_processed(outcome);
+        }
+
+        if self.cancel_token.is_cancelled() {
+            return self.push_cancelled(trimmed_id, item.tool);
+        }
+
+        let (tool_result, attempts) = self.dispatch_with_retry(item.tool, input).await;
+        let mut outcome = materialize_item_result(trimmed_id, item.tool, tool_result);
+        if attempts > 1 {
+            outcome.message = Some(match outcome.message {
+                Some(existing) => format!("{existing} (after {attempts} attempts)"),
+                None => format!("succeeded after {attempts} attempts"),
+            });
+        }
+
+        if let Some(key) = cache_key {
+            self.dedup_cache.insert(key, outcome.clone());
+        }
 
         self.push_processed(outcome)
     }
 
+    /// Runs one `for_each` item's per-element raw inputs (each still carrying its own narrowed
+    /// `$ref`) through the same resolve/dedup/retry path as [`Self::run_item`], folding the
+    /// per-element outcomes into a single [`BatchItemResult`] whose `data` is the array of element
+    /// results in order.
+    async fn run_fanned_out_item(
+        &mut self,
+        item: &BatchItem,
+        trimmed_id: &str,
+        raw_inputs: Vec<serde_json::Value>,
+    ) -> BatchItemResult {
+        let mut data = Vec::with_capacity(raw_inputs.len());
+        let mut errors = 0usize;
+
+        for raw_input in raw_inputs {
+            let resolved_input = match self.ref_context.as_ref() {
+                Some(ctx) => match resolve_batch_refs(raw_input, ctx) {
+                    Ok(value) => value,
+                    Err(err) => {
+                        errors += 1;
+                        data.push(serde_json::json!({
+                            "status": "error",
+                            "message": format!("Ref resolution error: {err}"),
+                        }));
+                        continue;
+                    }
+                },
+                None => raw_input,
+            };
+
+            let input = prepare_item_input(
+                resolved_input,
+                self.inferred_path.as_deref(),
+                item.tool,
+                self.remaining_chars(),
+            );
+
+            let cache_key = self.dedup.then(|| batch_item_cache_key(item.tool, &input));
+            let element = if let Some(cached) = cache_key.and_then(|key| self.dedup_cache.get(&key)) {
+                BatchItemResult {
+                    id: trimmed_id.to_string(),
+                    tool: cached.tool,
+                    status: cached.status,
+                    message: Some(
+                        "deduplicated: reused result from an identical earlier item".to_string(),
+                    ),
+                    data: cached.data.clone(),
+                }
+            } else {
+                let (tool_result, _attempts) = self.dispatch_with_retry(item.tool, input).await;
+                let element = materialize_item_result(trimmed_id.to_string(), item.tool, tool_result);
+                if let Some(key) = cache_key {
+                    self.dedup_cache.insert(key, element.clone());
+                }
+                element
+            };
+
+            if element.status == BatchItemStatus::Error {
+                errors += 1;
+            }
+            data.push(serde_json::json!({
+                "status": element.status,
+                "message": element.message,
+                "data": element.data,
+            }));
+        }
+
+        BatchItemResult {
+            id: trimmed_id.to_string(),
+            tool: item.tool,
+            status: if errors == 0 {
+                BatchItemStatus::Ok
+            } else {
+                BatchItemStatus::Error
+            },
+            message: (errors > 0)
+                .then(|| format!("for_each: {errors}/{} elements failed", data.len())),
+            data: serde_json::Value::Array(data),
+        }
+    }
+
+    /// Dispatches `tool`/`input`, retrying a retriable dispatch error (see
+    /// [`super::error::retriable_tool_error`]) with exponential backoff per `self.retry`. Returns
+    /// the final outcome plus how many attempts it took (1 if no retry was needed or configured).
+    /// Non-retriable errors and cancellation are not retried.
+    async fn dispatch_with_retry(
+        &self,
+        tool: BatchToolName,
+        input: serde_json::Value,
+    ) -> (std::result::Result<CallToolResult, McpError>, u32) {
+        let Some(policy) = self.retry else {
+            return (dispatch_tool(self.service, tool, input).await, 1);
+        };
+        let max_attempts = policy.max_attempts.max(1);
+
+        let mut attempt = 1;
+        loop {
+            let result = dispatch_tool(self.service, tool, input.clone()).await;
+            let is_retriable = matches!(&result, Ok(r) if is_retriable_tool_error(r));
+            if !is_retriable || attempt >= max_attempts || self.cancel_token.is_cancelled() {
+                return (result, attempt);
+            }
+
+            let delay_ms = policy.base_delay_ms.saturating_mul(1u64 << (attempt - 1));
+            tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+            attempt += 1;
+        }
+    }
+
     fn finish(self) -> CallToolResult {
         CallToolResult::success(vec![Content::text(
             serde_json::to_string_pretty(&self.output).unwrap_or_default(),
@@ -270,6 +1293,14 @@ impl<'a> BatchRunner<'a> {
                 self.output.budget.truncated = true;
             }
         }
+        if let Some(max_tokens) = self.output.budget.max_tokens {
+            let raw = serde_json::to_string(&self.output).unwrap_or_default();
+            let tokens = context_protocol::estimate_tokens_heuristic(&raw);
+            self.output.budget.used_tokens = Some(tokens);
+            if tokens > max_tokens {
+                self.output.budget.truncated = true;
+            }
+        }
     }
 }
 
@@ -328,13 +1359,44 @@ pub(in crate::tools::dispatch) async fn batch(
         Ok((_, root_display)) => Some(root_display),
         Err(message) => return Ok(call_error(message)),
     };
-    let mut runner = BatchRunner::new(service, version, max_chars, inferred_path)
-        .with_stop_on_error(request.stop_on_error);
+    let cancel_token = CancellationToken::new();
+    let mut runner = BatchRunner::new(service, version, max_chars, request.max_tokens, inferred_path.clone())
+        .with_stop_on_error(request.stop_on_error)
+        .with_dedup(request.dedup)
+        .with_retry(request.retry)
+        .with_cancel_token(cancel_token.clone());
     runner.update_ref_context_path();
 
-    for item in request.items {
-        if !runner.run_item(item).await {
-            break;
+    match request.concurrency.filter(|&concurrency| concurrency > 1) {
+        Some(concurrency) => {
+            let (results, ref_context) = run_items_concurrently(
+                service,
+                request.items,
+                inferred_path,
+                runner.ref_context.clone(),
+                max_chars,
+                request.stop_on_error,
+                concurrency,
+                cancel_token,
+            )
+            .await;
+            runner.ref_context = ref_context;
+
+            for result in results {
+                let Some(result) = result else {
+                    break;
+                };
+                if !runner.push_processed(result) {
+                    break;
+                }
+            }
+        }
+        None => {
+            for item in request.items {
+                if !runner.run_item(item).await {
+                    break;
+                }
+            }
         }
     }
 
@@ -342,6 +1404,151 @@ pub(in crate::tools::dispatch) async fn batch(
     Ok(runner.finish())
 }
 
+/// Enqueues `request` for background processing and returns its task id immediately. The batch
+/// itself runs on a spawned task that reports progress via `ContextFinderService`'s task table,
+/// polled through [`batch_status`]; it can be aborted early via [`cancel_batch`].
+pub(in crate::tools::dispatch) async fn submit_batch(
+    service: &ContextFinderService,
+    request: BatchRequest,
+) -> Result<CallToolResult, McpError> {
+    if request.items.is_empty() {
+        return Ok(call_error("Batch items must not be empty"));
+    }
+    if let Some(message) = validate_batch_version(request.version.unwrap_or(DEFAULT_VERSION)) {
+        return Ok(call_error(message));
+    }
+
+    let total = request.items.len();
+    let (task_id, cancel_token) = service.state.batch_task_insert(total).await;
+
+    let tracked_service = service.clone();
+    let tracked_id = task_id.clone();
+    tokio::spawn(async move {
+        run_tracked_batch(tracked_service, request, tracked_id, cancel_token).await;
+    });
+
+    Ok(CallToolResult::success(vec![Content::text(
+        serde_json::to_string_pretty(&serde_json::json!({ "id": task_id })).unwrap_or_default(),
+    )]))
+}
+
+/// Returns the current [`BatchTaskStatus`] for a `submit_batch` task id.
+pub(in crate::tools::dispatch) async fn batch_status(
+    service: &ContextFinderService,
+    request: BatchStatusRequest,
+) -> Result<CallToolResult, McpError> {
+    match service.state.batch_task_get(&request.id).await {
+        Some(status) => Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&status).unwrap_or_default(),
+        )])),
+        None => Ok(call_error(format!(
+            "Unknown or expired batch task id: '{}'",
+            request.id
+        ))),
+    }
+}
+
+/// Signals cancellation for a `submit_batch` task. The task keeps running until its current
+/// in-flight item(s) finish; remaining items are then marked `Cancelled` and the run still
+/// completes normally (reported via `batch_status`, including the now-cancelled items).
+pub(in crate::tools::dispatch) async fn cancel_batch(
+    service: &ContextFinderService,
+    request: BatchStatusRequest,
+) -> Result<CallToolResult, McpError> {
+    if service.state.batch_task_cancel(&request.id).await {
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&serde_json::json!({ "id": request.id, "cancelled": true }))
+                .unwrap_or_default(),
+        )]))
+    } else {
+        Ok(call_error(format!(
+            "Unknown or expired batch task id: '{}'",
+            request.id
+        )))
+    }
+}
+
+/// Runs `request` the same way [`batch`] does, except it reports progress into the task table
+/// keyed by `task_id` after every item (sequential mode) or once the whole concurrent run
+/// completes (concurrent mode doesn't have a natural per-item checkpoint to hook without
+/// threading the task id through `run_items_concurrently`).
+async fn run_tracked_batch(
+    service: ContextFinderService,
+    request: BatchRequest,
+    task_id: String,
+    cancel_token: CancellationToken,
+) {
+    service.state.batch_task_set_processing(&task_id).await;
+
+    let max_chars = request
+        .max_chars
+        .unwrap_or(DEFAULT_MAX_CHARS)
+        .clamp(1, MAX_MAX_CHARS);
+    let version = request.version.unwrap_or(DEFAULT_VERSION);
+
+    let inferred_path = match service.resolve_root(request.path.as_deref()).await {
+        Ok((_, root_display)) => Some(root_display),
+        Err(_) => {
+            service.state.batch_task_fail(&task_id).await;
+            return;
+        }
+    };
+
+    let mut runner = BatchRunner::new(&service, version, max_chars, request.max_tokens, inferred_path.clone())
+        .with_stop_on_error(request.stop_on_error)
+        .with_dedup(request.dedup)
+        .with_retry(request.retry)
+        .with_cancel_token(cancel_token.clone());
+    runner.update_ref_context_path();
+
+    match request.concurrency.filter(|&concurrency| concurrency > 1) {
+        Some(concurrency) => {
+            let (results, ref_context) = run_items_concurrently(
+                &service,
+                request.items,
+                inferred_path,
+                runner.ref_context.clone(),
+                max_chars,
+                request.stop_on_error,
+                concurrency,
+                cancel_token,
+            )
+            .await;
+            runner.ref_context = ref_context;
+
+            for result in results {
+                let Some(result) = result else {
+                    break;
+                };
+                if !runner.push_processed(result) {
+                    break;
+                }
+            }
+        }
+        None => {
+            for item in request.items {
+                if !runner.run_item(item).await {
+                    break;
+                }
+                service
+                    .state
+                    .batch_task_update_progress(
+                        &task_id,
+                        runner.output.items.len(),
+                        runner.output.clone(),
+                    )
+                    .await;
+            }
+        }
+    }
+
+    runner.apply_meta().await;
+    service
+        .state
+        .batch_task_finish(&task_id, runner.output.clone())
+        .await;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
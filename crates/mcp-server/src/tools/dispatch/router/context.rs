@@ -2,6 +2,7 @@ use super::super::{
     AutoIndexPolicy, CallToolResult, Content, ContextFinderService, ContextHit, ContextRequest,
     ContextResult, McpError, RelatedCode,
 };
+use super::error::retriable_tool_error;
 
 /// Search with graph context
 pub(in crate::tools::dispatch) async fn context(
@@ -12,6 +13,7 @@ pub(in crate::tools::dispatch) async fn context(
     let strategy = match request.strategy.as_deref() {
         Some("direct") => context_graph::AssemblyStrategy::Direct,
         Some("deep") => context_graph::AssemblyStrategy::Deep,
+        Some("semantic") => context_graph::AssemblyStrategy::Semantic,
         _ => context_graph::AssemblyStrategy::Extended,
     };
 
@@ -49,9 +51,12 @@ pub(in crate::tools::dispatch) async fn context(
         );
 
         if let Err(e) = engine.engine_mut().ensure_graph(language).await {
-            return Ok(CallToolResult::error(vec![Content::text(format!(
-                "Graph build error: {e}"
-            ))]));
+            let message = format!("Graph build error: {e}");
+            return Ok(if e.is_retriable() {
+                retriable_tool_error(message)
+            } else {
+                CallToolResult::error(vec![Content::text(message)])
+            });
         }
 
         match engine
@@ -62,9 +67,12 @@ pub(in crate::tools::dispatch) async fn context(
         {
             Ok(r) => r,
             Err(e) => {
-                return Ok(CallToolResult::error(vec![Content::text(format!(
-                    "Search error: {e}"
-                ))]));
+                let message = format!("Search error: {e}");
+                return Ok(if e.is_retriable() {
+                    retriable_tool_error(message)
+                } else {
+                    CallToolResult::error(vec![Content::text(message)])
+                });
             }
         }
     };
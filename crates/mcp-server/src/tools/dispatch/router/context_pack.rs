@@ -5,6 +5,7 @@ use super::super::{
     ContextPackRequest, GraphDocConfig, GraphNodeDoc, GraphNodeStore, GraphNodeStoreMeta, McpError,
     QueryClassifier, QueryKind, QueryType, RelatedMode, CONTEXT_PACK_VERSION, GRAPH_DOC_VERSION,
 };
+use super::error::retriable_tool_error;
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
@@ -62,6 +63,7 @@ fn parse_strategy(
     match raw {
         Some("direct") => context_graph::AssemblyStrategy::Direct,
         Some("deep") => context_graph::AssemblyStrategy::Deep,
+        Some("semantic") => context_graph::AssemblyStrategy::Semantic,
         Some(_) => context_graph::AssemblyStrategy::Extended,
         None => {
             if !docs_intent && matches!(query_type, QueryType::Identifier | QueryType::Path) {
@@ -483,7 +485,12 @@ pub(in crate::tools::dispatch) async fn context_pack(
 
     let language = select_language(request.language.as_deref(), &mut engine);
     if let Err(err) = engine.engine_mut().ensure_graph(language).await {
-        return Ok(tool_error(format!("Graph build error: {err}")));
+        let message = format!("Graph build error: {err}");
+        return Ok(if err.is_retriable() {
+            retriable_tool_error(message)
+        } else {
+            tool_error(message)
+        });
     }
 
     let available_models = engine.engine_mut().available_models.clone();
@@ -497,7 +504,12 @@ pub(in crate::tools::dispatch) async fn context_pack(
     {
         Ok(r) => r,
         Err(e) => {
-            return Ok(tool_error(format!("Search error: {e}")));
+            let message = format!("Search error: {e}");
+            return Ok(if e.is_retriable() {
+                retriable_tool_error(message)
+            } else {
+                tool_error(message)
+            });
         }
     };
 
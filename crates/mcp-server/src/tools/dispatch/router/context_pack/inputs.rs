@@ -52,6 +52,7 @@ fn parse_strategy(
     match raw {
         Some("direct") => context_graph::AssemblyStrategy::Direct,
         Some("deep") => context_graph::AssemblyStrategy::Deep,
+        Some("semantic") => context_graph::AssemblyStrategy::Semantic,
         Some(_) => context_graph::AssemblyStrategy::Extended,
         None => {
             if !docs_intent && matches!(query_type, QueryType::Identifier | QueryType::Path) {
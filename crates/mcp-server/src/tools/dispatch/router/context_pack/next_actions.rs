@@ -116,7 +116,7 @@ pub(super) fn maybe_add_low_noise_next_actions(
 
     let want_next_actions = match inputs.response_mode {
         ResponseMode::Full => true,
-        ResponseMode::Facts => inputs.format_version == 2 || anomaly,
+        ResponseMode::Facts | ResponseMode::Stream => inputs.format_version == 2 || anomaly,
         ResponseMode::Minimal => inputs.format_version == 2,
     };
     if !want_next_actions {
@@ -74,7 +74,7 @@ pub(in crate::tools::dispatch::router::context_pack) fn maybe_push_trust_micro_m
     let retrieval_mode = retrieval_mode_label(output, semantic_disabled_reason);
 
     let show = match response_mode {
-        ResponseMode::Full | ResponseMode::Facts => true,
+        ResponseMode::Full | ResponseMode::Facts | ResponseMode::Stream => true,
         ResponseMode::Minimal => {
             output.items.is_empty()
                 || output.budget.truncated
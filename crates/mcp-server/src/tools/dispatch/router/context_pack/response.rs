@@ -33,7 +33,7 @@ pub(super) async fn finalize_context_pack(
         ResponseMode::Minimal => {
             output.meta.index_state = None;
         }
-        ResponseMode::Facts => {}
+        ResponseMode::Facts | ResponseMode::Stream => {}
         ResponseMode::Full => {
             if output.items.is_empty() && semantic_disabled_reason.is_some() {
                 let budgets = super::super::super::mcp_default_budgets();
@@ -0,0 +1,321 @@
+use super::super::schemas::dump_index::{
+    DumpIndexRequest, DumpIndexResult, IndexDumpArchive, IndexDumpFileEntry, IndexDumpManifest,
+    RestoreIndexRequest, RestoreIndexResult, INDEX_DUMP_FORMAT_VERSION,
+};
+use super::super::{
+    hex_encode_lower, unix_ms, CallToolResult, Content, ContextFinderService, McpError,
+};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+
+/// A `restore_index` archive may come from another machine or a CI cache, so every model_id in it
+/// is untrusted input before it's joined onto `indexes_dir` -- reject anything that isn't a bare
+/// directory-name component (no separators, no `..`) rather than letting `Path::join` walk it
+/// somewhere outside the indexes directory.
+fn is_safe_model_id(model_id: &str) -> bool {
+    !model_id.is_empty()
+        && !model_id.contains('/')
+        && !model_id.contains('\\')
+        && model_id != "."
+        && model_id != ".."
+}
+
+/// Model id dir names under `.context-finder/indexes` that have a built `index.json`, matching
+/// the enumeration `doctor` already does for drift diagnostics.
+fn indexed_model_dirs(root: &std::path::Path) -> Vec<String> {
+    let indexes_dir = root.join(".context-finder").join("indexes");
+    let mut models = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(&indexes_dir) {
+        for entry in entries.flatten() {
+            if entry.path().join("index.json").exists() {
+                models.push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+    }
+    models.sort();
+    models
+}
+
+/// Export a project's indexed stores plus a file-inventory manifest into one self-describing
+/// archive file, so it can be copied to another machine or restored from a CI cache.
+pub(in crate::tools::dispatch) async fn dump_index(
+    service: &ContextFinderService,
+    request: DumpIndexRequest,
+) -> Result<CallToolResult, McpError> {
+    let start = std::time::Instant::now();
+    let (root, root_display) = match service.resolve_root(request.path.as_deref()).await {
+        Ok(pair) => pair,
+        Err(message) => return Ok(CallToolResult::error(vec![Content::text(message)])),
+    };
+
+    let model_ids = indexed_model_dirs(&root);
+    if model_ids.is_empty() {
+        return Ok(CallToolResult::error(vec![Content::text(format!(
+            "No semantic indexes found under '{root_display}'. Run `index` first."
+        ))]));
+    }
+
+    let mut stores = std::collections::BTreeMap::new();
+    for model_id in &model_ids {
+        let index_path = root
+            .join(".context-finder")
+            .join("indexes")
+            .join(model_id)
+            .join("index.json");
+        let bytes = match tokio::fs::read(&index_path).await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Failed to read index for model '{model_id}': {e}"
+                ))]));
+            }
+        };
+        stores.insert(model_id.clone(), STANDARD.encode(&bytes));
+    }
+
+    let corpus = match ContextFinderService::load_chunk_corpus(&root).await {
+        Ok(corpus) => corpus.unwrap_or_default(),
+        Err(e) => {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to load chunk corpus: {e:#}"
+            ))]));
+        }
+    };
+
+    let mut files = Vec::new();
+    for file_path in corpus.files().keys() {
+        let absolute = root.join(file_path);
+        let Ok(meta) = std::fs::metadata(&absolute) else {
+            continue; // removed since the corpus was last built; leave it out of the manifest
+        };
+        let Ok(bytes) = std::fs::read(&absolute) else {
+            continue;
+        };
+        let mut hasher = Sha256::new();
+        hasher.update(&bytes);
+        files.push(IndexDumpFileEntry {
+            path: file_path.clone(),
+            file_size_bytes: meta.len(),
+            file_mtime_ms: meta.modified().map(unix_ms).unwrap_or(0),
+            content_sha256: hex_encode_lower(&hasher.finalize()),
+        });
+    }
+    let file_count = files.len();
+
+    let archive = IndexDumpArchive {
+        manifest: IndexDumpManifest {
+            format_version: INDEX_DUMP_FORMAT_VERSION,
+            created_at_ms: unix_ms(std::time::SystemTime::now()),
+            model_ids: model_ids.clone(),
+            files,
+        },
+        stores,
+    };
+
+    let dump_dir = root.join(".context-finder").join("dumps");
+    if let Err(e) = tokio::fs::create_dir_all(&dump_dir).await {
+        return Ok(CallToolResult::error(vec![Content::text(format!(
+            "Failed to create dump directory {}: {e}",
+            dump_dir.display()
+        ))]));
+    }
+    let dump_path = dump_dir.join(format!("dump-{}.json", archive.manifest.created_at_ms));
+    let body = match serde_json::to_vec_pretty(&archive) {
+        Ok(body) => body,
+        Err(e) => {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to serialize dump archive: {e}"
+            ))]));
+        }
+    };
+    if let Err(e) = tokio::fs::write(&dump_path, &body).await {
+        return Ok(CallToolResult::error(vec![Content::text(format!(
+            "Failed to write dump archive {}: {e}",
+            dump_path.display()
+        ))]));
+    }
+
+    let result = DumpIndexResult {
+        dump_path: dump_path.to_string_lossy().into_owned(),
+        format_version: INDEX_DUMP_FORMAT_VERSION,
+        model_ids,
+        file_count,
+        time_ms: u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX),
+        meta: Some(service.tool_meta(&root).await),
+    };
+
+    Ok(CallToolResult::success(vec![Content::text(
+        serde_json::to_string_pretty(&result).unwrap_or_default(),
+    )]))
+}
+
+/// Rehydrate a dump produced by `dump_index` into the target project's index location, flagging
+/// any source file whose current mtime/size/sha256 no longer matches the manifest as `stale` so a
+/// follow-up incremental `index` only has to re-embed the drift.
+pub(in crate::tools::dispatch) async fn restore_index(
+    service: &ContextFinderService,
+    request: RestoreIndexRequest,
+) -> Result<CallToolResult, McpError> {
+    let start = std::time::Instant::now();
+    let (root, _root_display) = match service.resolve_root(request.path.as_deref()).await {
+        Ok(pair) => pair,
+        Err(message) => return Ok(CallToolResult::error(vec![Content::text(message)])),
+    };
+
+    let dump_path = PathBuf::from(&request.dump_file);
+    let body = match tokio::fs::read(&dump_path).await {
+        Ok(body) => body,
+        Err(e) => {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to read dump archive '{}': {e}",
+                dump_path.display()
+            ))]));
+        }
+    };
+    let archive: IndexDumpArchive = match serde_json::from_slice(&body) {
+        Ok(archive) => archive,
+        Err(e) => {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Invalid dump archive '{}': {e}",
+                dump_path.display()
+            ))]));
+        }
+    };
+
+    if archive.manifest.format_version != INDEX_DUMP_FORMAT_VERSION {
+        return Ok(CallToolResult::error(vec![Content::text(format!(
+            "Unsupported dump format_version {} (expected {INDEX_DUMP_FORMAT_VERSION})",
+            archive.manifest.format_version
+        ))]));
+    }
+
+    let registry = match context_vector_store::ModelRegistry::from_env() {
+        Ok(registry) => registry,
+        Err(e) => {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Model registry error: {e}"
+            ))]));
+        }
+    };
+    for model_id in &archive.manifest.model_ids {
+        if !is_safe_model_id(model_id) {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Unsafe model_id '{model_id}' in dump manifest"
+            ))]));
+        }
+        if let Err(e) = registry.dimension(model_id) {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Unknown or unsupported model_id '{model_id}' in dump: {e}"
+            ))]));
+        }
+    }
+
+    // `stores` is a separate, independently attacker-controlled map from `manifest.model_ids` --
+    // validate every key here too rather than trusting the manifest check above to cover it, and
+    // require it be a subset of the manifest's allowlisted ids.
+    for model_id in archive.stores.keys() {
+        if !is_safe_model_id(model_id) {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Unsafe model_id '{model_id}' in dump stores"
+            ))]));
+        }
+        if !archive.manifest.model_ids.contains(model_id) {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Store entry '{model_id}' is not listed in the dump manifest's model_ids"
+            ))]));
+        }
+        if let Err(e) = registry.dimension(model_id) {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Unknown or unsupported model_id '{model_id}' in dump stores: {e}"
+            ))]));
+        }
+    }
+
+    let indexes_dir = root.join(".context-finder").join("indexes");
+    if let Err(e) = tokio::fs::create_dir_all(&indexes_dir).await {
+        return Ok(CallToolResult::error(vec![Content::text(format!(
+            "Failed to create indexes directory {}: {e}",
+            indexes_dir.display()
+        ))]));
+    }
+
+    let mut models_restored = 0usize;
+    for (model_id, encoded) in &archive.stores {
+        let bytes = match STANDARD.decode(encoded.as_bytes()) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Corrupt store payload for model '{model_id}': {e}"
+                ))]));
+            }
+        };
+        let model_dir = indexes_dir.join(model_id);
+        if let Err(e) = tokio::fs::create_dir_all(&model_dir).await {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to create {}: {e}",
+                model_dir.display()
+            ))]));
+        }
+        if let Err(e) = tokio::fs::write(model_dir.join("index.json"), &bytes).await {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to write restored index for model '{model_id}': {e}"
+            ))]));
+        }
+        models_restored += 1;
+    }
+
+    let mut stale_files = Vec::new();
+    for entry in &archive.manifest.files {
+        let absolute = root.join(&entry.path);
+        let stale = match std::fs::metadata(&absolute) {
+            Ok(meta) => {
+                let current_mtime_ms = meta.modified().map(unix_ms).unwrap_or(0);
+                if meta.len() != entry.file_size_bytes || current_mtime_ms != entry.file_mtime_ms {
+                    true
+                } else {
+                    match std::fs::read(&absolute) {
+                        Ok(bytes) => {
+                            let mut hasher = Sha256::new();
+                            hasher.update(&bytes);
+                            hex_encode_lower(&hasher.finalize()) != entry.content_sha256
+                        }
+                        Err(_) => true,
+                    }
+                }
+            }
+            Err(_) => true, // file no longer exists on disk: treat as stale
+        };
+        if stale {
+            stale_files.push(entry.path.clone());
+        }
+    }
+
+    let index_path = archive
+        .manifest
+        .model_ids
+        .first()
+        .map(|model_id| {
+            indexes_dir
+                .join(model_id)
+                .join("index.json")
+                .to_string_lossy()
+                .into_owned()
+        })
+        .unwrap_or_default();
+
+    let result = RestoreIndexResult {
+        files: archive.manifest.files.len(),
+        models_restored,
+        time_ms: u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX),
+        index_path,
+        format_version: archive.manifest.format_version,
+        model_ids: archive.manifest.model_ids,
+        stale_files,
+        meta: Some(service.tool_meta(&root).await),
+    };
+
+    Ok(CallToolResult::success(vec![Content::text(
+        serde_json::to_string_pretty(&result).unwrap_or_default(),
+    )]))
+}
@@ -111,6 +111,30 @@ pub(in crate::tools::dispatch) fn internal_error(message: impl Into<String>) ->
     tool_error("internal", message)
 }
 
+/// Marker prepended to an error message's text when the underlying failure is plausibly
+/// transient (e.g. vector-store/graph contention) and therefore worth retrying. `batch`'s retry
+/// policy looks for this prefix via [`is_retriable_tool_error`]; to a plain reader it's just a
+/// normal note at the front of the error text.
+const RETRIABLE_PREFIX: &str = "[retriable] ";
+
+/// Builds an error `CallToolResult` tagged as retriable (see [`RETRIABLE_PREFIX`]).
+pub(in crate::tools::dispatch) fn retriable_tool_error(message: impl Into<String>) -> CallToolResult {
+    CallToolResult::error(vec![Content::text(format!(
+        "{RETRIABLE_PREFIX}{}",
+        message.into()
+    ))])
+}
+
+/// True if `result` is an error tagged by [`retriable_tool_error`].
+pub(in crate::tools::dispatch) fn is_retriable_tool_error(result: &CallToolResult) -> bool {
+    result.is_error == Some(true)
+        && result
+            .content
+            .first()
+            .and_then(|c| c.as_text())
+            .is_some_and(|t| t.text.starts_with(RETRIABLE_PREFIX))
+}
+
 pub(in crate::tools::dispatch) fn invalid_cursor_with_meta(
     message: impl Into<String>,
     meta: ToolMeta,
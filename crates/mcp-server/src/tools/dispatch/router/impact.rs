@@ -1,15 +1,21 @@
 use super::super::{
     AutoIndexPolicy, CallToolResult, Content, ContextFinderService, ImpactRequest, ImpactResult,
-    McpError, SymbolLocation, UsageInfo,
+    McpError, SymbolLocation, SymbolSuggestion, UsageInfo,
 };
 use crate::tools::util::path_has_extension_ignore_ascii_case;
 use context_code_chunker::CodeChunk;
-use context_graph::CodeGraph;
+use context_graph::{CodeGraph, FuzzySymbolMatch};
 use petgraph::graph::NodeIndex;
 use std::collections::HashSet;
+use std::path::Path;
 
 const MAX_DIRECT: usize = 200;
 const MAX_TRANSITIVE: usize = 200;
+/// How many near-miss candidates to report when `symbol` has no exact match.
+const MAX_FUZZY_SUGGESTIONS: usize = 5;
+/// A top fuzzy candidate is auto-selected (treated as if the caller had typed it) only when its
+/// score beats the runner-up by at least this factor, or there is no runner-up at all.
+const FUZZY_AUTO_SELECT_RATIO: f32 = 1.5;
 
 fn success_payload(result: &ImpactResult) -> CallToolResult {
     CallToolResult::success(vec![Content::text(
@@ -32,10 +38,53 @@ fn best_effort_text_only(symbol: String, chunks: &[CodeChunk]) -> ImpactResult {
         tests: Vec::new(),
         public_api: false,
         mermaid,
+        resolved_symbol: None,
+        suggestions: Vec::new(),
         meta: None,
     }
 }
 
+/// Picks the top fuzzy candidate to auto-select as if the caller had typed it exactly, when it
+/// unambiguously dominates the runner-up (or there is no runner-up).
+fn fuzzy_auto_select(suggestions: &[FuzzySymbolMatch<'_>]) -> Option<NodeIndex> {
+    let top = suggestions.first()?;
+    let dominates = suggestions.get(1).map_or(true, |second| {
+        top.score >= second.score * FUZZY_AUTO_SELECT_RATIO
+    });
+    dominates.then_some(top.node)
+}
+
+fn to_symbol_suggestions(
+    graph: &CodeGraph,
+    matches: &[FuzzySymbolMatch<'_>],
+) -> Vec<SymbolSuggestion> {
+    matches
+        .iter()
+        .filter_map(|m| {
+            graph.get_node(m.node).map(|nd| SymbolSuggestion {
+                symbol: m.symbol.to_string(),
+                file: nd.symbol.file_path.clone(),
+                line: nd.symbol.start_line,
+                score: m.score,
+            })
+        })
+        .collect()
+}
+
+/// Treats `symbol` as a prefix against the project's FST symbol index, for O(prefix) resolution
+/// ahead of [`CodeGraph::fuzzy_find_symbols`]'s subsequence scan. Only auto-selects when the
+/// prefix resolves to usages of exactly one distinct symbol name (an ambiguous prefix falls
+/// through to the fuzzy matcher instead of guessing).
+async fn resolve_via_symbol_fst(root: &Path, graph: &CodeGraph, symbol: &str) -> Option<NodeIndex> {
+    let index = crate::tools::symbol_fst_cache::load_or_rebuild(root, graph).await;
+    let hits = index.prefix(symbol, 2);
+    let (first, rest) = hits.split_first()?;
+    if rest.iter().any(|hit| hit.symbol != first.symbol) {
+        return None;
+    }
+    graph.find_node(&first.symbol)
+}
+
 fn should_skip_graph_symbol(symbol_name: &str, file_path: &str) -> bool {
     symbol_name == "unknown" || path_has_extension_ignore_ascii_case(file_path, "md")
 }
@@ -154,6 +203,56 @@ fn count_files_affected(direct: &[UsageInfo], transitive: &[UsageInfo]) -> usize
         .len()
 }
 
+fn analyze_found_symbol(
+    graph: &CodeGraph,
+    node: NodeIndex,
+    chunks: &[CodeChunk],
+    depth: usize,
+    symbol: String,
+) -> ImpactResult {
+    let definition = graph.get_node(node).map(|nd| SymbolLocation {
+        file: nd.symbol.file_path.clone(),
+        line: nd.symbol.start_line,
+    });
+
+    let (mut direct, mut seen_direct) = collect_direct_usages(graph, node);
+
+    let transitive = if depth > 1 {
+        collect_transitive_usages(graph, node, depth)
+    } else {
+        Vec::new()
+    };
+
+    let exclude_chunk_id = graph.get_node(node).map(|nd| nd.chunk_id.as_str());
+    add_text_hits_to_direct(
+        &mut direct,
+        &mut seen_direct,
+        chunks,
+        &symbol,
+        exclude_chunk_id,
+    );
+
+    let tests = collect_related_tests(graph, node);
+    let public_api = graph.is_public_api(node);
+    let mermaid = ContextFinderService::generate_impact_mermaid(&symbol, &direct, &transitive);
+    let total_usages = direct.len() + transitive.len();
+
+    ImpactResult {
+        symbol,
+        definition,
+        total_usages,
+        files_affected: count_files_affected(&direct, &transitive),
+        direct,
+        transitive,
+        tests,
+        public_api,
+        mermaid,
+        resolved_symbol: None,
+        suggestions: Vec::new(),
+        meta: None,
+    }
+}
+
 /// Find all usages of a symbol (impact analysis)
 pub(in crate::tools::dispatch) async fn impact(
     service: &ContextFinderService,
@@ -200,50 +299,27 @@ pub(in crate::tools::dispatch) async fn impact(
             Some(assembler) => {
                 let graph = assembler.graph();
                 match graph.find_node(&symbol) {
-                    None => best_effort_text_only(symbol, chunks),
-                    Some(node) => {
-                        let definition = graph.get_node(node).map(|nd| SymbolLocation {
-                            file: nd.symbol.file_path.clone(),
-                            line: nd.symbol.start_line,
-                        });
-
-                        let (mut direct, mut seen_direct) = collect_direct_usages(graph, node);
-
-                        let transitive = if depth > 1 {
-                            collect_transitive_usages(graph, node, depth)
-                        } else {
-                            Vec::new()
-                        };
-
-                        let exclude_chunk_id = graph.get_node(node).map(|nd| nd.chunk_id.as_str());
-                        add_text_hits_to_direct(
-                            &mut direct,
-                            &mut seen_direct,
-                            chunks,
-                            &symbol,
-                            exclude_chunk_id,
-                        );
-
-                        let tests = collect_related_tests(graph, node);
-                        let public_api = graph.is_public_api(node);
-                        let mermaid = ContextFinderService::generate_impact_mermaid(
-                            &symbol,
-                            &direct,
-                            &transitive,
-                        );
-                        let total_usages = direct.len() + transitive.len();
-
-                        ImpactResult {
-                            symbol,
-                            definition,
-                            total_usages,
-                            files_affected: count_files_affected(&direct, &transitive),
-                            direct,
-                            transitive,
-                            tests,
-                            public_api,
-                            mermaid,
-                            meta: None,
+                    Some(node) => analyze_found_symbol(graph, node, chunks, depth, symbol),
+                    None => {
+                        let fst_node = resolve_via_symbol_fst(&root, graph, &symbol).await;
+                        let fuzzy = graph.fuzzy_find_symbols(&symbol, MAX_FUZZY_SUGGESTIONS);
+                        let suggestions = to_symbol_suggestions(graph, &fuzzy);
+                        match fst_node.or_else(|| fuzzy_auto_select(&fuzzy)) {
+                            Some(node) => {
+                                let resolved_symbol = graph.get_node(node).and_then(|nd| {
+                                    (nd.symbol.name != symbol).then(|| nd.symbol.name.clone())
+                                });
+                                let mut result =
+                                    analyze_found_symbol(graph, node, chunks, depth, symbol);
+                                result.resolved_symbol = resolved_symbol;
+                                result.suggestions = suggestions;
+                                result
+                            }
+                            None => {
+                                let mut result = best_effort_text_only(symbol, chunks);
+                                result.suggestions = suggestions;
+                                result
+                            }
                         }
                     }
                 }
@@ -1,26 +1,24 @@
+use super::super::index_task_store::IndexTaskRequestSnapshot;
 use super::super::{
-    current_model_id, index_path_for_model, CallToolResult, Content, ContextFinderService,
-    IndexRequest, IndexResult, McpError, QueryKind,
+    current_model_id, index_path_for_model, CallToolResult, CancelTaskRequest, Content,
+    ContextFinderService, GetTaskRequest, IndexRequest, IndexResult, IndexTaskState,
+    IndexTaskStatus, ListTasksRequest, McpError, QueryKind,
 };
 use std::collections::HashSet;
+use std::path::Path;
+use std::time::Duration;
 
-/// Index a project
-pub(in crate::tools::dispatch) async fn index(
-    service: &ContextFinderService,
-    request: IndexRequest,
-) -> Result<CallToolResult, McpError> {
-    let force = request.force.unwrap_or(false);
-    let full = request.full.unwrap_or(false) || force;
-    let experts = request.experts.unwrap_or(false);
-    let extra_models = request.models.unwrap_or_default();
-
-    let canonical = match service.resolve_root(request.path.as_deref()).await {
-        Ok((root, _)) => root,
-        Err(message) => {
-            return Ok(CallToolResult::error(vec![Content::text(message)]));
-        }
-    };
+const DEFAULT_LIST_TASKS_LIMIT: usize = 50;
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
 
+/// Runs the actual indexing work for `root`, the same way the old synchronous `index` tool did.
+/// Shared by the immediate inline path (`wait: Some(true)`) and the background worker spawned for
+/// enqueued tasks.
+async fn run_index(
+    service: &ContextFinderService,
+    root: &Path,
+    request: &IndexTaskRequestSnapshot,
+) -> Result<IndexResult, String> {
     let start = std::time::Instant::now();
 
     let primary_model_id = current_model_id().unwrap_or_else(|_| "bge-small".to_string());
@@ -31,7 +29,7 @@ pub(in crate::tools::dispatch) async fn index(
     seen.insert(primary_model_id.clone());
     models.push(primary_model_id.clone());
 
-    if experts {
+    if request.experts {
         let expert_cfg = service.profile.experts();
         for kind in [
             QueryKind::Identifier,
@@ -46,26 +44,18 @@ pub(in crate::tools::dispatch) async fn index(
         }
     }
 
-    for model_id in extra_models {
+    for model_id in &request.models {
         if seen.insert(model_id.clone()) {
-            models.push(model_id);
+            models.push(model_id.clone());
         }
     }
 
-    let registry = match context_vector_store::ModelRegistry::from_env() {
-        Ok(r) => r,
-        Err(e) => {
-            return Ok(CallToolResult::error(vec![Content::text(format!(
-                "Model registry error: {e}"
-            ))]));
-        }
-    };
+    let registry = context_vector_store::ModelRegistry::from_env()
+        .map_err(|e| format!("Model registry error: {e}"))?;
     for model_id in &models {
-        if let Err(e) = registry.dimension(model_id) {
-            return Ok(CallToolResult::error(vec![Content::text(format!(
-                "Unknown or unsupported model_id '{model_id}': {e}"
-            ))]));
-        }
+        registry
+            .dimension(model_id)
+            .map_err(|e| format!("Unknown or unsupported model_id '{model_id}': {e}"))?;
     }
 
     let specs: Vec<context_indexer::ModelIndexSpec> = models
@@ -73,26 +63,17 @@ pub(in crate::tools::dispatch) async fn index(
         .map(|model_id| context_indexer::ModelIndexSpec::new(model_id.clone(), templates.clone()))
         .collect();
 
-    let indexer = match context_indexer::MultiModelProjectIndexer::new(&canonical).await {
-        Ok(i) => i,
-        Err(e) => {
-            return Ok(CallToolResult::error(vec![Content::text(format!(
-                "Indexer init error: {e}"
-            ))]));
-        }
-    };
+    let indexer = context_indexer::MultiModelProjectIndexer::new(root)
+        .await
+        .map_err(|e| format!("Indexer init error: {e}"))?;
 
-    let stats = match indexer.index_models(&specs, full).await {
-        Ok(s) => s,
-        Err(e) => {
-            return Ok(CallToolResult::error(vec![Content::text(format!(
-                "Indexing error: {e}"
-            ))]));
-        }
-    };
+    let stats = indexer
+        .index_models(&specs, request.full)
+        .await
+        .map_err(|e| format!("Indexing error: {e}"))?;
 
     let time_ms = u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX);
-    let index_path = index_path_for_model(&canonical, &primary_model_id);
+    let index_path = index_path_for_model(root, &primary_model_id);
 
     let mut result = IndexResult {
         files: stats.files,
@@ -101,9 +82,252 @@ pub(in crate::tools::dispatch) async fn index(
         index_path: index_path.to_string_lossy().to_string(),
         meta: None,
     };
-    result.meta = Some(service.tool_meta(&canonical).await);
+    result.meta = Some(service.tool_meta(root).await);
+    Ok(result)
+}
+
+/// Drains `root`'s task queue one autobatch at a time: pop the next compatible group of enqueued
+/// tasks (see `IndexTaskTable::pop_batch_or_release`), run a single indexing pass for the merged
+/// request, then fan the one result out to every uid in the batch so they all transition to
+/// `succeeded`/`failed` together. Exits once a pop finds the queue empty, which also releases the
+/// drain claim for `root` -- `claim_drain` guarantees a fresh loop gets spawned if a task shows up
+/// after that but before this loop actually returns.
+///
+/// Races the indexing call against the batch's cancellation tokens (polled every
+/// `WAIT_POLL_INTERVAL`, same cadence `index`'s `wait: true` path already uses) so `cancel_task`
+/// called mid-batch actually takes effect -- the batch transitions to `canceled` instead of riding
+/// the in-flight call to `succeeded`. The indexing call itself still isn't interruptible
+/// mid-file -- it keeps running to completion in the background and its result is simply
+/// discarded -- but the task table reflects cancellation as soon as it's observed rather than
+/// only before a batch starts.
+async fn run_drain_loop(service: ContextFinderService, root: std::path::PathBuf) {
+    loop {
+        let Some(batch) = service.state.index_task_pop_batch(&root).await else {
+            return;
+        };
+        service
+            .state
+            .index_task_set_processing_many(&batch.uids)
+            .await;
+
+        let tokens = service.state.index_task_cancel_tokens(&batch.uids).await;
+        match run_cancelable(&tokens, run_index(&service, &root, &batch.request)).await {
+            Some(Ok(result)) => {
+                service
+                    .state
+                    .index_task_finish_many(&batch.uids, result)
+                    .await
+            }
+            Some(Err(error)) => service.state.index_task_fail_many(&batch.uids, error).await,
+            None => service.state.index_task_cancel_many(&batch.uids).await,
+        }
+    }
+}
+
+/// Polls `tokens` for cancellation while `work` runs, yielding `None` as soon as any token cancels
+/// (abandoning interest in `work`'s result, though `work` itself keeps running to completion) or
+/// `Some(...)` once `work` finishes first. Polls rather than awaiting `CancellationToken::cancelled()`
+/// directly so a batch's several tokens (one per autobatched uid) can be raced without pulling in
+/// an extra futures-combinator dependency.
+async fn run_cancelable<T>(
+    tokens: &[tokio_util::sync::CancellationToken],
+    work: impl std::future::Future<Output = T>,
+) -> Option<T> {
+    tokio::pin!(work);
+    loop {
+        if tokens.iter().any(|t| t.is_cancelled()) {
+            return None;
+        }
+        tokio::select! {
+            result = &mut work => return Some(result),
+            () = tokio::time::sleep(WAIT_POLL_INTERVAL) => {}
+        }
+    }
+}
+
+/// Makes sure a drain loop is running for `root`, spawning one if none is currently claimed.
+async fn ensure_drain_loop(service: &ContextFinderService, root: &Path) {
+    if service.state.index_task_claim_drain(root).await {
+        let tracked_service = service.clone();
+        let tracked_root = root.to_path_buf();
+        tokio::spawn(run_drain_loop(tracked_service, tracked_root));
+    }
+}
+
+/// Recovers any tasks left `processing` by a prior run for `root` (reset to `enqueued`) and makes
+/// sure a drain loop is running to pick them back up.
+async fn recover_interrupted_tasks(service: &ContextFinderService, root: &Path) {
+    let recovered = service.state.index_task_ensure_loaded(root).await;
+    if !recovered.is_empty() {
+        ensure_drain_loop(service, root).await;
+    }
+}
+
+/// Index a project. Enqueues a background task and returns `{ task_uid, status: "enqueued" }`
+/// immediately; pass `wait: true` to poll internally and return the final `IndexResult` instead
+/// (the old synchronous behavior). A burst of enqueued tasks for the same project is autobatched
+/// by the drain loop rather than re-walking the tree once per task -- see `run_drain_loop`.
+pub(in crate::tools::dispatch) async fn index(
+    service: &ContextFinderService,
+    request: IndexRequest,
+) -> Result<CallToolResult, McpError> {
+    let force = request.force.unwrap_or(false);
+    let full = request.full.unwrap_or(false) || force;
+    let experts = request.experts.unwrap_or(false);
+    let extra_models = request.models.unwrap_or_default();
+    let wait = request.wait.unwrap_or(false);
+
+    let canonical = match service.resolve_root(request.path.as_deref()).await {
+        Ok((root, _)) => root,
+        Err(message) => {
+            return Ok(CallToolResult::error(vec![Content::text(message)]));
+        }
+    };
+
+    recover_interrupted_tasks(service, &canonical).await;
+
+    let snapshot = IndexTaskRequestSnapshot {
+        full,
+        experts,
+        models: extra_models,
+    };
+    let (uid, _cancel, should_spawn) = service.state.index_task_insert(&canonical, snapshot).await;
+
+    if should_spawn {
+        let tracked_service = service.clone();
+        let tracked_root = canonical.clone();
+        tokio::spawn(run_drain_loop(tracked_service, tracked_root));
+    }
+
+    if !wait {
+        let status = service.state.index_task_get(uid).await;
+        return Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&status).unwrap_or_default(),
+        )]));
+    }
+
+    loop {
+        let Some(status) = service.state.index_task_get(uid).await else {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Index task disappeared from the task table before completing".to_string(),
+            )]));
+        };
+        if is_terminal(status.status) {
+            return Ok(render_wait_result(status));
+        }
+        tokio::time::sleep(WAIT_POLL_INTERVAL).await;
+    }
+}
+
+fn is_terminal(state: IndexTaskState) -> bool {
+    matches!(
+        state,
+        IndexTaskState::Succeeded | IndexTaskState::Failed | IndexTaskState::Canceled
+    )
+}
+
+fn render_wait_result(status: IndexTaskStatus) -> CallToolResult {
+    use IndexTaskState as S;
+    match status.status {
+        S::Succeeded => {
+            let Some(result) = status.result else {
+                return CallToolResult::error(vec![Content::text(
+                    "Index task succeeded without a result".to_string(),
+                )]);
+            };
+            CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(&result).unwrap_or_default(),
+            )])
+        }
+        S::Failed => CallToolResult::error(vec![Content::text(
+            status
+                .error
+                .unwrap_or_else(|| "Index task failed".to_string()),
+        )]),
+        S::Canceled => CallToolResult::error(vec![Content::text(
+            "Index task was canceled before it ran".to_string(),
+        )]),
+        S::Enqueued | S::Processing => CallToolResult::error(vec![Content::text(
+            "Index task did not reach a terminal state".to_string(),
+        )]),
+    }
+}
+
+/// Poll the progress/result of an `index` background task.
+pub(in crate::tools::dispatch) async fn get_task(
+    service: &ContextFinderService,
+    request: GetTaskRequest,
+) -> Result<CallToolResult, McpError> {
+    let canonical = match service.resolve_root(request.path.as_deref()).await {
+        Ok((root, _)) => root,
+        Err(message) => {
+            return Ok(CallToolResult::error(vec![Content::text(message)]));
+        }
+    };
+    recover_interrupted_tasks(service, &canonical).await;
+
+    match service.state.index_task_get(request.task_uid).await {
+        Some(status) => Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&status).unwrap_or_default(),
+        )])),
+        None => Ok(CallToolResult::error(vec![Content::text(format!(
+            "Unknown or expired index task uid: {}",
+            request.task_uid
+        ))])),
+    }
+}
+
+/// List `index` background tasks for a project, newest first.
+pub(in crate::tools::dispatch) async fn list_tasks(
+    service: &ContextFinderService,
+    request: ListTasksRequest,
+) -> Result<CallToolResult, McpError> {
+    let canonical = match service.resolve_root(request.path.as_deref()).await {
+        Ok((root, _)) => root,
+        Err(message) => {
+            return Ok(CallToolResult::error(vec![Content::text(message)]));
+        }
+    };
+    recover_interrupted_tasks(service, &canonical).await;
+
+    let limit = request.limit.unwrap_or(DEFAULT_LIST_TASKS_LIMIT);
+    let tasks = service
+        .state
+        .index_task_list(&canonical, request.status, limit)
+        .await;
 
     Ok(CallToolResult::success(vec![Content::text(
-        serde_json::to_string_pretty(&result).unwrap_or_default(),
+        serde_json::to_string_pretty(&serde_json::json!({ "tasks": tasks })).unwrap_or_default(),
     )]))
 }
+
+/// Cancel an `index` background task. A task still `enqueued` is skipped outright (dropped from
+/// its batch the next time `pop_batch_or_release` runs); a task already `processing` is caught by
+/// `run_drain_loop`'s poll of the cancellation token between indexing calls -- see the cancellation
+/// caveat on `IndexTaskTable::cancel`.
+pub(in crate::tools::dispatch) async fn cancel_task(
+    service: &ContextFinderService,
+    request: CancelTaskRequest,
+) -> Result<CallToolResult, McpError> {
+    let canonical = match service.resolve_root(request.path.as_deref()).await {
+        Ok((root, _)) => root,
+        Err(message) => {
+            return Ok(CallToolResult::error(vec![Content::text(message)]));
+        }
+    };
+    recover_interrupted_tasks(service, &canonical).await;
+
+    if service.state.index_task_cancel(request.task_uid).await {
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(
+                &serde_json::json!({ "task_uid": request.task_uid, "canceled": true }),
+            )
+            .unwrap_or_default(),
+        )]))
+    } else {
+        Ok(CallToolResult::error(vec![Content::text(format!(
+            "Unknown or expired index task uid: {}",
+            request.task_uid
+        ))]))
+    }
+}
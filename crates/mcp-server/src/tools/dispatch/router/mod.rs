@@ -7,6 +7,7 @@ pub(super) mod context;
 pub(super) mod context_pack;
 pub(super) mod cursor_alias;
 pub(super) mod doctor;
+pub(super) mod dump_index;
 pub(super) mod error;
 pub(super) mod evidence_fetch;
 pub(super) mod explain;
@@ -14,6 +15,7 @@ pub(super) mod file_slice;
 pub(super) mod grep_context;
 pub(super) mod help;
 pub(super) mod impact;
+pub(super) mod index;
 pub(super) mod list_files;
 pub(super) mod ls;
 pub(super) mod map;
@@ -30,6 +32,7 @@ pub(super) mod root;
 pub(super) mod runbook_pack;
 pub(super) mod search;
 pub(super) mod semantic_fallback;
+pub(super) mod symbol_lookup;
 pub(super) mod text_search;
 pub(super) mod trace;
 pub(super) mod worktree_pack;
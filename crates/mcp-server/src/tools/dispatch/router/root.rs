@@ -11,7 +11,15 @@ pub(in crate::tools::dispatch) async fn root_get(
     service: &ContextFinderService,
     _request: RootGetRequest,
 ) -> Result<CallToolResult, McpError> {
-    let (session_root, focus_file, workspace_roots, roots_pending, ambiguous, mismatch) = {
+    let (
+        session_root,
+        focus_file,
+        workspace_roots,
+        roots_pending,
+        ambiguous,
+        mismatch,
+        crawl_pending,
+    ) = {
         let session = service.session.lock().await;
         let session_root = session.root_display();
         let focus_file = session.focus_file();
@@ -27,6 +35,7 @@ pub(in crate::tools::dispatch) async fn root_get(
             session.roots_pending(),
             session.mcp_roots_ambiguous(),
             session.root_mismatch_error().map(|s| s.to_string()),
+            session.crawl_pending(),
         )
     };
 
@@ -63,6 +72,9 @@ pub(in crate::tools::dispatch) async fn root_get(
     if let Some(message) = mismatch.as_deref() {
         doc.push_note(&format!("root_mismatch_error={message}"));
     }
+    if crawl_pending {
+        doc.push_note("crawl_pending=true");
+    }
     doc.push_root_fingerprint(meta.root_fingerprint);
 
     let result = RootGetResult {
@@ -72,6 +84,7 @@ pub(in crate::tools::dispatch) async fn root_get(
         roots_pending,
         workspace_roots_ambiguous: ambiguous,
         root_mismatch_error: mismatch,
+        crawl_pending,
         meta,
     };
 
@@ -162,6 +175,8 @@ pub(in crate::tools::dispatch) async fn root_set(
         session.set_root(root.clone(), root_display.clone(), focus_file.clone());
     }
 
+    let crawl_pending = maybe_spawn_crawl(service, &root).await;
+
     let (workspace_roots, roots_pending, ambiguous, mismatch) = {
         let session = service.session.lock().await;
         (
@@ -197,6 +212,9 @@ pub(in crate::tools::dispatch) async fn root_set(
                 .join(", ")
         ));
     }
+    if crawl_pending {
+        doc.push_note("crawl_pending=true");
+    }
     doc.push_root_fingerprint(meta.root_fingerprint);
 
     let result = RootSetResult {
@@ -206,9 +224,64 @@ pub(in crate::tools::dispatch) async fn root_set(
         roots_pending,
         workspace_roots_ambiguous: ambiguous,
         root_mismatch_error: mismatch,
+        crawl_pending,
         meta,
     };
     let mut out = CallToolResult::success(vec![Content::text(doc.finish())]);
     out.structured_content = Some(serde_json::json!(result));
     Ok(out)
 }
+
+/// Kicks off a best-effort background pre-warm crawl of `root` the first time this session sees
+/// it (see `crate::tools::dispatch::root::Crawl`), so the first `read_pack`/recall call isn't
+/// cold. A no-op when the root was already crawled in this session or is outside the workspace
+/// (`root_mismatch_error` is set): there's nothing safe to warm in that case.
+async fn maybe_spawn_crawl(service: &ContextFinderService, root: &std::path::Path) -> bool {
+    let (should_crawl, mut roots) = {
+        let mut session = service.session.lock().await;
+        if session.root_mismatch_error().is_some() {
+            (false, Vec::new())
+        } else {
+            (
+                session.note_crawl_touch(None, true),
+                session.mcp_workspace_roots().to_vec(),
+            )
+        }
+    };
+
+    if !should_crawl {
+        return false;
+    }
+
+    roots.retain(|candidate| candidate != root);
+    roots.insert(0, root.to_path_buf());
+
+    // A `context-finder.json` can declare extra source roots (e.g. a second language stack in a
+    // polyglot monorepo) that live outside the declared root itself; fold those into the same
+    // pre-warm crawl.
+    if let Some(descriptor) = crate::tools::dispatch::root::load_project_descriptor(root) {
+        for source_root in descriptor.source_roots {
+            let candidate = root.join(&source_root);
+            if candidate.is_dir() && !roots.iter().any(|known| known == &candidate) {
+                roots.push(candidate);
+            }
+        }
+    }
+
+    {
+        let mut session = service.session.lock().await;
+        session.set_crawl_pending(true);
+    }
+
+    let service = service.clone();
+    tokio::spawn(async move {
+        let _files_seen =
+            tokio::task::spawn_blocking(move || crate::tools::dispatch::root::walk_roots(&roots))
+                .await;
+
+        let mut session = service.session.lock().await;
+        session.set_crawl_pending(false);
+    });
+
+    true
+}
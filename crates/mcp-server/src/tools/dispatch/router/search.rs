@@ -2,6 +2,7 @@ use super::super::{
     AutoIndexPolicy, CallToolResult, Content, ContextFinderService, McpError, SearchRequest,
     SearchResult,
 };
+use super::error::retriable_tool_error;
 
 /// Semantic code search
 pub(in crate::tools::dispatch) async fn search(
@@ -42,9 +43,12 @@ pub(in crate::tools::dispatch) async fn search(
         {
             Ok(r) => r,
             Err(e) => {
-                return Ok(CallToolResult::error(vec![Content::text(format!(
-                    "Search error: {e}"
-                ))]));
+                let message = format!("Search error: {e}");
+                return Ok(if e.is_retriable() {
+                    retriable_tool_error(message)
+                } else {
+                    CallToolResult::error(vec![Content::text(message)])
+                });
             }
         }
     };
@@ -0,0 +1,80 @@
+use super::super::{
+    AutoIndexPolicy, CallToolResult, Content, ContextFinderService, McpError, SymbolLookupMatch,
+    SymbolLookupRequest, SymbolLookupResult,
+};
+use context_graph::SymbolFstMatch;
+
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 200;
+
+fn to_match(m: SymbolFstMatch) -> SymbolLookupMatch {
+    SymbolLookupMatch {
+        symbol: m.symbol,
+        file: m.file,
+        line: m.line,
+    }
+}
+
+/// Ordered-prefix or bounded edit-distance fuzzy lookup over every symbol name in the project,
+/// backed by [`context_graph::SymbolFstIndex`].
+pub(in crate::tools::dispatch) async fn symbol_lookup(
+    service: &ContextFinderService,
+    request: SymbolLookupRequest,
+) -> Result<CallToolResult, McpError> {
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let prefix = request.prefix;
+
+    let root = match service.resolve_root(request.path.as_deref()).await {
+        Ok((root, _)) => root,
+        Err(message) => {
+            return Ok(CallToolResult::error(vec![Content::text(message)]));
+        }
+    };
+
+    let policy = AutoIndexPolicy::from_request(request.auto_index, request.auto_index_budget_ms);
+    let (mut engine, meta) = match service.prepare_semantic_engine(&root, policy).await {
+        Ok(engine) => engine,
+        Err(e) => {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Error: {e}"
+            ))]));
+        }
+    };
+
+    let detected_language = {
+        let chunks = engine.engine_mut().context_search.hybrid().chunks();
+        ContextFinderService::detect_language(chunks)
+    };
+    let graph_ready = engine
+        .engine_mut()
+        .ensure_graph(detected_language)
+        .await
+        .is_ok();
+
+    let matches = if graph_ready {
+        match engine.engine_mut().context_search.assembler() {
+            Some(assembler) => {
+                let graph = assembler.graph();
+                let index = crate::tools::symbol_fst_cache::load_or_rebuild(&root, graph).await;
+                match request.fuzzy_edits {
+                    Some(edits) => index.fuzzy(&prefix, edits, limit),
+                    None => index.prefix(&prefix, limit),
+                }
+            }
+            None => Vec::new(),
+        }
+    } else {
+        Vec::new()
+    };
+
+    drop(engine);
+
+    let result = SymbolLookupResult {
+        prefix,
+        matches: matches.into_iter().map(to_match).collect(),
+        meta: Some(meta),
+    };
+    Ok(CallToolResult::success(vec![Content::text(
+        serde_json::to_string_pretty(&result).unwrap_or_default(),
+    )]))
+}
@@ -248,9 +248,11 @@ fn search_in_filesystem(
 ) -> std::result::Result<TextSearchOutcome, CallToolResult> {
     let mut outcome = TextSearchOutcome::new();
 
-    let scanner = FileScanner::new(root);
-    let mut candidates: Vec<(String, PathBuf)> = scanner
+    let mut scanner = FileScanner::new(root);
+    let scanned = scanner
         .scan()
+        .map_err(|e| call_error(format!("Error: {e:#}")))?;
+    let mut candidates: Vec<(String, PathBuf)> = scanned
         .into_iter()
         .filter_map(|file| normalize_relative_path(root, &file).map(|rel| (rel, file)))
         .filter(|(rel, _)| ContextFinderService::matches_file_pattern(rel, settings.file_pattern))
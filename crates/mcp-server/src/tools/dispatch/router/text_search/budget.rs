@@ -9,7 +9,7 @@ pub(super) fn text_search_content_budget(max_chars: usize, response_mode: Respon
             // `.context` envelopes are intentionally tiny; reserve just enough headroom for:
             // [CONTENT], A:/R: lines, and an optional cursor block.
             ResponseMode::Minimal => 80,
-            ResponseMode::Facts => 100,
+            ResponseMode::Facts | ResponseMode::Stream => 100,
             ResponseMode::Full => 320,
         },
         20,
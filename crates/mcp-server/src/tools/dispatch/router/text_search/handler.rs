@@ -403,7 +403,7 @@ pub(in crate::tools::dispatch) async fn text_search(
         ResponseMode::Full => {
             result.meta = Some(meta_for_output.clone());
         }
-        ResponseMode::Facts => {
+        ResponseMode::Facts | ResponseMode::Stream => {
             result.meta = Some(provenance_meta.clone());
         }
         ResponseMode::Minimal => {
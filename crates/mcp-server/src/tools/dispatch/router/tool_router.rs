@@ -392,6 +392,19 @@ impl ContextFinderService {
         ))
     }
 
+    /// Prefix/fuzzy symbol name lookup backed by an FST index
+    #[tool(
+        description = "Look up symbol names by prefix (autocomplete) or bounded edit-distance fuzzy match, backed by an FST index shared with impact's fallback resolution."
+    )]
+    pub async fn symbol_lookup(
+        &self,
+        Parameters(request): Parameters<SymbolLookupRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        Ok(strip_structured_content(
+            super::symbol_lookup::symbol_lookup(self, request).await?,
+        ))
+    }
+
     /// Trace call path between two symbols
     #[tool(
         description = "Show call chain from one symbol to another. Essential for understanding code flow and debugging."
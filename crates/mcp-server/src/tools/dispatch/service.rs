@@ -164,6 +164,7 @@ impl ServerHandler for ContextFinderService {
                                     RootUpdateSource::McpRoots,
                                     None,
                                     None,
+                                    None,
                                 );
                             }
                             n if n > 1 => {
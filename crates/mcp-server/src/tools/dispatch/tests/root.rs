@@ -29,6 +29,7 @@ async fn resolve_root_waits_for_initialize_roots_list() {
             RootUpdateSource::RootSet,
             None,
             None,
+            None,
         );
         session.set_roots_pending(false);
         drop(session);
@@ -127,6 +128,7 @@ async fn daemon_refuses_absolute_path_outside_session_root() {
             RootUpdateSource::RootSet,
             None,
             None,
+            None,
         );
     }
 
@@ -172,6 +174,7 @@ async fn daemon_accepts_absolute_file_hint_within_session_root() {
             RootUpdateSource::RootSet,
             None,
             None,
+            None,
         );
     }
 
@@ -229,6 +232,7 @@ async fn session_refuses_root_outside_workspace_roots_until_explicit_path() {
             RootUpdateSource::RootSet,
             None,
             None,
+            None,
         );
     }
 
@@ -301,6 +305,7 @@ async fn relative_path_is_resolved_against_session_root_before_process_cwd() {
             RootUpdateSource::RootSet,
             None,
             None,
+            None,
         );
     }
 
@@ -385,6 +390,7 @@ async fn root_set_can_switch_projects_even_when_session_root_is_already_set() {
             RootUpdateSource::RootSet,
             None,
             None,
+            None,
         );
     }
 
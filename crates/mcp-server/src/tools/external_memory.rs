@@ -52,7 +52,7 @@ fn kind_priority(kind: &str) -> u32 {
 fn budget_for_query(response_mode: ResponseMode) -> OverlayBudget {
     match response_mode {
         ResponseMode::Minimal => OverlayBudget { max_total_hits: 0 },
-        ResponseMode::Facts => OverlayBudget { max_total_hits: 5 },
+        ResponseMode::Facts | ResponseMode::Stream => OverlayBudget { max_total_hits: 5 },
         ResponseMode::Full => OverlayBudget { max_total_hits: 8 },
     }
 }
@@ -60,7 +60,7 @@ fn budget_for_query(response_mode: ResponseMode) -> OverlayBudget {
 fn budget_for_recent(response_mode: ResponseMode) -> OverlayBudget {
     match response_mode {
         ResponseMode::Minimal => OverlayBudget { max_total_hits: 0 },
-        ResponseMode::Facts => OverlayBudget { max_total_hits: 4 },
+        ResponseMode::Facts | ResponseMode::Stream => OverlayBudget { max_total_hits: 4 },
         ResponseMode::Full => OverlayBudget { max_total_hits: 6 },
     }
 }
@@ -182,7 +182,7 @@ fn build_embed_text(kind: &str, title: Option<&str>, text: &str, max_chars: usiz
 fn excerpt_chars(response_mode: ResponseMode) -> usize {
     match response_mode {
         ResponseMode::Minimal => 240,
-        ResponseMode::Facts => 420,
+        ResponseMode::Facts | ResponseMode::Stream => 420,
         ResponseMode::Full => 800,
     }
 }
@@ -333,7 +333,7 @@ fn diversity_caps(response_mode: ResponseMode) -> DiversityCaps {
             max_prompts: 0,
             max_replies: 0,
         },
-        ResponseMode::Facts => DiversityCaps {
+        ResponseMode::Facts | ResponseMode::Stream => DiversityCaps {
             max_prompts: 1,
             max_replies: 0,
         },
@@ -511,7 +511,7 @@ fn progress_matches_session_file(progress: &CodexSessionProgress, meta: &CodexSe
 fn seed_session_cursor(file_len_bytes: u64, response_mode: ResponseMode) -> u64 {
     let seed_bytes: u64 = match response_mode {
         ResponseMode::Minimal => 96 * 1024,
-        ResponseMode::Facts => 192 * 1024,
+        ResponseMode::Facts | ResponseMode::Stream => 192 * 1024,
         ResponseMode::Full => 384 * 1024,
     };
     file_len_bytes.saturating_sub(seed_bytes)
@@ -533,7 +533,7 @@ fn jsonl_read_limits(response_mode: ResponseMode) -> JsonlReadLimits {
             max_lines: 2_000,
             max_line_bytes: 512 * 1024,
         },
-        ResponseMode::Facts => JsonlReadLimits {
+        ResponseMode::Facts | ResponseMode::Stream => JsonlReadLimits {
             max_read_bytes: 768 * 1024,
             max_lines: 4_000,
             max_line_bytes: 1024 * 1024,
@@ -1,6 +1,7 @@
 use anyhow::{Context as AnyhowContext, Result};
 use context_indexer::ToolMeta;
 use sha2::{Digest, Sha256};
+use std::collections::HashSet;
 use std::io::{BufRead, BufReader, Seek};
 use std::path::{Path, PathBuf};
 
@@ -8,7 +9,7 @@ use super::cursor::{cursor_fingerprint, decode_cursor, encode_cursor, CURSOR_VER
 use super::paths::normalize_relative_path;
 use super::schemas::content_format::ContentFormat;
 use super::schemas::file_slice::{
-    FileSliceCursorV1, FileSliceRequest, FileSliceResult, FileSliceTruncation,
+    FileSliceCursorV1, FileSliceRequest, FileSliceResult, FileSliceTruncation, FileSliceWindow,
 };
 use super::schemas::response_mode::ResponseMode;
 use super::secrets::is_potential_secret_path;
@@ -18,6 +19,8 @@ const DEFAULT_MAX_LINES: usize = 200;
 const MAX_MAX_LINES: usize = 5_000;
 const DEFAULT_MAX_CHARS: usize = 2_000;
 const MAX_MAX_CHARS: usize = 500_000;
+const DEFAULT_CONTEXT_LINES: usize = 2;
+const MAX_CONTEXT_LINES: usize = 50;
 
 fn file_slice_envelope_reserve(response_mode: ResponseMode, display_file: &str) -> usize {
     // Keep enough headroom for the response envelope so tight `max_chars` budgets still return a
@@ -35,7 +38,7 @@ fn file_slice_envelope_reserve(response_mode: ResponseMode, display_file: &str)
 
     let base_reserve: usize = match response_mode {
         ResponseMode::Minimal => 120,
-        ResponseMode::Facts => 200,
+        ResponseMode::Facts | ResponseMode::Stream => 200,
         ResponseMode::Full => 380,
     };
 
@@ -52,6 +55,8 @@ struct CursorValidation<'a> {
     allow_secrets: bool,
     file_size_bytes: u64,
     file_mtime_ms: u64,
+    query: Option<Vec<String>>,
+    context_lines: Option<usize>,
 }
 
 fn resolve_candidate_path(root: &Path, file_str: &str) -> PathBuf {
@@ -131,6 +136,8 @@ fn encode_next_cursor(
         next_byte_offset,
         file_size_bytes: validation.file_size_bytes,
         file_mtime_ms: validation.file_mtime_ms,
+        query: validation.query.clone(),
+        context_lines: validation.context_lines,
     };
 
     encode_cursor(&token).map_err(|err| format!("Error: {err:#}"))
@@ -256,6 +263,351 @@ fn read_file_slice(cfg: &ReadSliceConfig<'_>) -> std::result::Result<ReadSliceOu
     })
 }
 
+struct ScannedLine {
+    line_no: usize,
+    byte_offset: u64,
+    text: String,
+}
+
+fn scan_lines(
+    canonical_file: &Path,
+    display_file: &str,
+) -> std::result::Result<Vec<ScannedLine>, String> {
+    let file = std::fs::File::open(canonical_file)
+        .map_err(|e| format!("Failed to open '{display_file}': {e}"))?;
+    let mut reader = BufReader::new(file);
+    let mut lines = Vec::new();
+    let mut offset = 0u64;
+    let mut line_no = 0usize;
+    let mut buf = String::new();
+
+    loop {
+        let line_start_offset = offset;
+        buf.clear();
+        let bytes_read = reader
+            .read_line(&mut buf)
+            .map_err(|e| format!("Failed to read '{display_file}': {e}"))?;
+        if bytes_read == 0 {
+            break;
+        }
+        offset += bytes_read as u64;
+        line_no += 1;
+        let text = buf
+            .trim_end_matches('\n')
+            .trim_end_matches('\r')
+            .to_string();
+        lines.push(ScannedLine {
+            line_no,
+            byte_offset: line_start_offset,
+            text,
+        });
+    }
+
+    Ok(lines)
+}
+
+/// Lowercases, trims, dedups and drops empty terms so `"Foo"` and `"foo"` aren't searched twice.
+fn normalize_query_terms(raw: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut terms = Vec::new();
+    for term in raw {
+        let trimmed = term.trim().to_lowercase();
+        if !trimmed.is_empty() && seen.insert(trimmed.clone()) {
+            terms.push(trimmed);
+        }
+    }
+    terms
+}
+
+/// `(line index into `lines`, index into the *present* terms list)` for every case-insensitive
+/// substring match, plus how many distinct terms actually occur anywhere in the file (terms that
+/// never occur are simply excluded from the "cover every term" requirement below).
+fn find_term_occurrences(lines: &[ScannedLine], terms: &[String]) -> (Vec<(usize, usize)>, usize) {
+    let mut present_terms = Vec::new();
+    let mut present_index = vec![usize::MAX; terms.len()];
+    let mut occurrences = Vec::new();
+
+    for (line_idx, line) in lines.iter().enumerate() {
+        let lower = line.text.to_lowercase();
+        for (term_idx, term) in terms.iter().enumerate() {
+            if lower.contains(term.as_str()) {
+                if present_index[term_idx] == usize::MAX {
+                    present_index[term_idx] = present_terms.len();
+                    present_terms.push(term_idx);
+                }
+                occurrences.push((line_idx, present_index[term_idx]));
+            }
+        }
+    }
+
+    (occurrences, present_terms.len())
+}
+
+/// Classic "smallest range covering every distinct value" sliding window over the term
+/// occurrences (sorted by line already, since `find_term_occurrences` scans top to bottom):
+/// every maximal-left span ending at `right` that still covers all present terms is recorded,
+/// giving one candidate span per locality. `expand_and_merge_spans` turns these into the final
+/// windows.
+fn minimal_covering_spans(
+    occurrences: &[(usize, usize)],
+    present_term_count: usize,
+) -> Vec<(usize, usize)> {
+    if present_term_count == 0 || occurrences.is_empty() {
+        return Vec::new();
+    }
+
+    let mut counts = vec![0usize; present_term_count];
+    let mut distinct = 0usize;
+    let mut left = 0usize;
+    let mut spans = Vec::new();
+
+    for right in 0..occurrences.len() {
+        let (_, term) = occurrences[right];
+        if counts[term] == 0 {
+            distinct += 1;
+        }
+        counts[term] += 1;
+
+        while distinct == present_term_count {
+            spans.push((occurrences[left].0, occurrences[right].0));
+            let (_, left_term) = occurrences[left];
+            counts[left_term] -= 1;
+            if counts[left_term] == 0 {
+                distinct -= 1;
+            }
+            left += 1;
+        }
+    }
+
+    spans
+}
+
+/// Expands each span by `context_lines` on both sides (clamped to the file) then merges any spans
+/// that now touch or overlap, so a cluster of nearby matches collapses into one window.
+fn expand_and_merge_spans(
+    spans: &[(usize, usize)],
+    context_lines: usize,
+    max_idx: usize,
+) -> Vec<(usize, usize)> {
+    if spans.is_empty() {
+        return Vec::new();
+    }
+
+    let mut expanded: Vec<(usize, usize)> = spans
+        .iter()
+        .map(|&(lo, hi)| {
+            (
+                lo.saturating_sub(context_lines),
+                (hi + context_lines).min(max_idx),
+            )
+        })
+        .collect();
+    expanded.sort_unstable();
+
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (lo, hi) in expanded {
+        match merged.last_mut() {
+            Some(last) if lo <= last.1.saturating_add(1) => last.1 = last.1.max(hi),
+            _ => merged.push((lo, hi)),
+        }
+    }
+    merged
+}
+
+/// Conditional-fetch fast path: if `if_unmodified_since_ms`/`if_none_match` confirm the file is
+/// unchanged, return a lightweight `not_modified` result instead of formatting the requested
+/// slice, so long-running sessions can cheaply revalidate a file window they already hold.
+fn check_not_modified(
+    request: &FileSliceRequest,
+    canonical_file: &Path,
+    display_file: &str,
+    file_size_bytes: u64,
+    file_mtime_ms: u64,
+) -> std::result::Result<Option<FileSliceResult>, String> {
+    if request.if_none_match.is_none() && request.if_unmodified_since_ms.is_none() {
+        return Ok(None);
+    }
+    if let Some(since_ms) = request.if_unmodified_since_ms {
+        if file_mtime_ms > since_ms {
+            return Ok(None);
+        }
+    }
+
+    let bytes = std::fs::read(canonical_file)
+        .map_err(|e| format!("Failed to read '{display_file}': {e}"))?;
+    let mut hasher = Sha256::new();
+    hasher.update(&bytes);
+    let content_sha256 = hex_encode_lower(&hasher.finalize());
+
+    if let Some(expected) = request.if_none_match.as_deref() {
+        if expected != content_sha256 {
+            return Ok(None);
+        }
+    }
+
+    Ok(Some(FileSliceResult {
+        file: display_file.to_string(),
+        start_line: 0,
+        end_line: 0,
+        returned_lines: Some(0),
+        used_chars: Some(0),
+        max_lines: None,
+        max_chars: None,
+        truncated: false,
+        truncation: None,
+        next_cursor: None,
+        next_actions: None,
+        meta: Some(ToolMeta::default()),
+        file_size_bytes: Some(file_size_bytes),
+        file_mtime_ms: Some(file_mtime_ms),
+        content_sha256: Some(content_sha256),
+        content: String::new(),
+        windows: None,
+        not_modified: Some(true),
+    }))
+}
+
+struct QueryWindowsOutcome {
+    start_line: usize,
+    end_line: usize,
+    content: String,
+    content_sha256: String,
+    used_chars: usize,
+    returned_lines: usize,
+    truncated: bool,
+    truncation: Option<FileSliceTruncation>,
+    next_cursor: Option<String>,
+    windows: Vec<FileSliceWindow>,
+}
+
+/// Grep/window mode for `file_slice`: instead of a flat `start_line..start_line+max_lines` range,
+/// find the minimal spans of the file that each cover at least one occurrence of every present
+/// query term, expand them by `context_lines`, merge overlaps, and emit one `FileSliceWindow` per
+/// resulting span -- all under the same `max_lines`/`max_chars` budgets and cursor pagination as
+/// the regular range mode (`read_file_slice`).
+#[allow(clippy::too_many_arguments)]
+fn compute_query_windows(
+    canonical_file: &Path,
+    display_file: &str,
+    terms: &[String],
+    context_lines: usize,
+    resume_from_line: usize,
+    max_lines: usize,
+    content_max_chars: usize,
+    format: ContentFormat,
+    cursor_validation: &CursorValidation<'_>,
+) -> std::result::Result<QueryWindowsOutcome, String> {
+    let lines = scan_lines(canonical_file, display_file)?;
+    let max_idx = lines.len().saturating_sub(1);
+
+    let (occurrences, present_term_count) = find_term_occurrences(&lines, terms);
+    let matched_idxs: HashSet<usize> = occurrences.iter().map(|&(line_idx, _)| line_idx).collect();
+    let spans = minimal_covering_spans(&occurrences, present_term_count);
+    let merged_spans = expand_and_merge_spans(&spans, context_lines, max_idx);
+
+    let mut windows = Vec::new();
+    let mut content = String::new();
+    let mut used_chars = 0usize;
+    let mut returned_lines = 0usize;
+    let mut truncated = false;
+    let mut truncation = None;
+    let mut next_cursor = None;
+
+    for &(lo, hi) in &merged_spans {
+        let window_start_line = lines[lo].line_no;
+        if window_start_line < resume_from_line {
+            continue;
+        }
+
+        let window_line_count = hi - lo + 1;
+        if returned_lines.saturating_add(window_line_count) > max_lines {
+            truncated = true;
+            truncation = Some(FileSliceTruncation::MaxLines);
+            next_cursor = Some(encode_next_cursor(
+                cursor_validation,
+                window_start_line,
+                lines[lo].byte_offset,
+            )?);
+            break;
+        }
+
+        let mut window_content = String::new();
+        let mut window_chars = 0usize;
+        for (i, line_idx) in (lo..=hi).enumerate() {
+            let line = &lines[line_idx];
+            let prefix = if format == ContentFormat::Numbered {
+                format!("{}: ", line.line_no)
+            } else {
+                String::new()
+            };
+            let piece_chars = prefix
+                .chars()
+                .count()
+                .saturating_add(line.text.chars().count());
+            window_chars += if i == 0 { piece_chars } else { 1 + piece_chars };
+            if i > 0 {
+                window_content.push('\n');
+            }
+            window_content.push_str(&prefix);
+            window_content.push_str(&line.text);
+        }
+
+        if used_chars.saturating_add(window_chars) > content_max_chars {
+            truncated = true;
+            truncation = Some(FileSliceTruncation::MaxChars);
+            next_cursor = Some(encode_next_cursor(
+                cursor_validation,
+                window_start_line,
+                lines[lo].byte_offset,
+            )?);
+            break;
+        }
+
+        if returned_lines > 0 {
+            content.push('\n');
+            used_chars += 1;
+        }
+        content.push_str(&window_content);
+        used_chars += window_chars;
+        returned_lines += window_line_count;
+
+        let matched_lines: Vec<usize> = (lo..=hi)
+            .filter(|idx| matched_idxs.contains(idx))
+            .map(|idx| lines[idx].line_no)
+            .collect();
+
+        windows.push(FileSliceWindow {
+            start_line: lines[lo].line_no,
+            end_line: lines[hi].line_no,
+            content: window_content,
+            matched_lines,
+        });
+    }
+
+    let start_line = windows
+        .first()
+        .map(|w| w.start_line)
+        .unwrap_or(resume_from_line);
+    let end_line = windows.last().map(|w| w.end_line).unwrap_or(0);
+
+    let mut hasher = Sha256::new();
+    hasher.update(content.as_bytes());
+    let content_sha256 = hex_encode_lower(&hasher.finalize());
+
+    Ok(QueryWindowsOutcome {
+        start_line,
+        end_line,
+        content,
+        content_sha256,
+        used_chars,
+        returned_lines,
+        truncated,
+        truncation,
+        next_cursor,
+        windows,
+    })
+}
+
 pub(super) fn compute_file_slice_result(
     root: &Path,
     root_display: &str,
@@ -324,6 +676,16 @@ pub(super) fn compute_file_slice_result(
     let file_size_bytes = meta.len();
     let file_mtime_ms = meta.modified().map(unix_ms).unwrap_or(0);
 
+    if let Some(result) = check_not_modified(
+        request,
+        &canonical_file,
+        &display_file,
+        file_size_bytes,
+        file_mtime_ms,
+    )? {
+        return Ok(result);
+    }
+
     let max_lines = request
         .max_lines
         .or_else(|| cursor_payload.as_ref().map(|c| c.max_lines))
@@ -342,6 +704,19 @@ pub(super) fn compute_file_slice_result(
         .unwrap_or(ContentFormat::Plain);
     let response_mode = request.response_mode.unwrap_or(ResponseMode::Minimal);
 
+    let query_terms = normalize_query_terms(
+        request
+            .query
+            .as_deref()
+            .or_else(|| cursor_payload.as_ref().and_then(|c| c.query.as_deref()))
+            .unwrap_or(&[]),
+    );
+    let context_lines = request
+        .context_lines
+        .or_else(|| cursor_payload.as_ref().and_then(|c| c.context_lines))
+        .unwrap_or(DEFAULT_CONTEXT_LINES)
+        .min(MAX_CONTEXT_LINES);
+
     // `max_chars` is a hard budget for the whole tool output. Reserve envelope headroom and spend
     // the rest on actual file content.
     //
@@ -351,7 +726,7 @@ pub(super) fn compute_file_slice_result(
     let reserve = {
         let min_content = match response_mode {
             ResponseMode::Minimal => 120,
-            ResponseMode::Facts => 200,
+            ResponseMode::Facts | ResponseMode::Stream => 200,
             ResponseMode::Full => 260,
         };
         let raw = file_slice_envelope_reserve(response_mode, &display_file);
@@ -368,6 +743,12 @@ pub(super) fn compute_file_slice_result(
         allow_secrets,
         file_size_bytes,
         file_mtime_ms,
+        query: if query_terms.is_empty() {
+            None
+        } else {
+            Some(query_terms.clone())
+        },
+        context_lines: Some(context_lines),
     };
     let request_with_cursor_filled = FileSliceRequest {
         path: request.path.clone(),
@@ -379,10 +760,50 @@ pub(super) fn compute_file_slice_result(
         response_mode: request.response_mode,
         allow_secrets: request.allow_secrets,
         cursor: request.cursor.clone(),
+        query: request.query.clone(),
+        context_lines: request.context_lines,
+        if_none_match: request.if_none_match.clone(),
+        if_unmodified_since_ms: request.if_unmodified_since_ms,
     };
     let (using_cursor, start_line, start_byte_offset) =
         decode_resume_cursor(&request_with_cursor_filled, &validation, start_line)?;
 
+    if !query_terms.is_empty() {
+        let resume_from_line = if using_cursor { start_line } else { 1 };
+        let windows_outcome = compute_query_windows(
+            &canonical_file,
+            &display_file,
+            &query_terms,
+            context_lines,
+            resume_from_line,
+            max_lines,
+            content_max_chars,
+            format,
+            &validation,
+        )?;
+
+        return Ok(FileSliceResult {
+            file: display_file,
+            start_line: windows_outcome.start_line,
+            end_line: windows_outcome.end_line,
+            returned_lines: Some(windows_outcome.returned_lines),
+            used_chars: Some(windows_outcome.used_chars),
+            max_lines: Some(max_lines),
+            max_chars: Some(output_max_chars),
+            truncated: windows_outcome.truncated,
+            truncation: windows_outcome.truncation,
+            next_cursor: windows_outcome.next_cursor,
+            next_actions: None,
+            meta: Some(ToolMeta::default()),
+            file_size_bytes: Some(file_size_bytes),
+            file_mtime_ms: Some(file_mtime_ms),
+            content_sha256: Some(windows_outcome.content_sha256),
+            content: windows_outcome.content,
+            windows: Some(windows_outcome.windows),
+            not_modified: None,
+        });
+    }
+
     let read_cfg = ReadSliceConfig {
         canonical_file: &canonical_file,
         display_file: &display_file,
@@ -417,6 +838,8 @@ pub(super) fn compute_file_slice_result(
         file_mtime_ms: Some(file_mtime_ms),
         content_sha256: Some(content_sha256),
         content: read.content,
+        windows: None,
+        not_modified: None,
     })
 }
 
@@ -519,5 +942,7 @@ pub(super) fn compute_onboarding_doc_slice(
         file_mtime_ms: Some(file_mtime_ms),
         content_sha256: Some(content_sha256),
         content,
+        windows: None,
+        not_modified: None,
     })
 }
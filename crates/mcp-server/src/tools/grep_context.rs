@@ -157,8 +157,8 @@ async fn collect_candidates(
         return Ok(("filesystem".to_string(), candidates));
     }
 
-    let scanner = FileScanner::new(root);
-    let files = scanner.scan();
+    let mut scanner = FileScanner::new(root);
+    let files = scanner.scan()?;
     let mut rels: Vec<String> = files
         .into_iter()
         .filter_map(|p| normalize_relative_path(root, &p))
@@ -54,8 +54,8 @@ pub(super) async fn compute_list_files_result(
     // not silently ignore files just because they're not in the corpus.
     let source = "filesystem".to_string();
 
-    let scanner = FileScanner::new(root);
-    let scanned_paths = scanner.scan();
+    let mut scanner = FileScanner::new(root);
+    let scanned_paths = scanner.scan()?;
     let scanned_files = scanned_paths.len();
 
     let mut candidates: Vec<String> = scanned_paths
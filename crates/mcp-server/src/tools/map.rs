@@ -148,8 +148,8 @@ async fn populate_map_from_filesystem(
     total_lines: &mut usize,
     total_chunks: &mut usize,
 ) -> Result<()> {
-    let scanner = FileScanner::new(root);
-    let files = scanner.scan();
+    let mut scanner = FileScanner::new(root);
+    let files = scanner.scan()?;
     let chunker = Chunker::new(chunker_config_for_map());
 
     for file in files {
@@ -1,6 +1,6 @@
 use anyhow::{anyhow, Context as AnyhowContext, Result};
 use context_indexer::{FileScanner, ToolMeta};
-use context_protocol::{enforce_max_chars, BudgetTruncation, ToolNextAction};
+use context_protocol::{enforce_max_chars, enforce_max_tokens, BudgetTruncation, ToolNextAction};
 use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::Path;
 
@@ -100,9 +100,9 @@ pub(super) async fn compute_meaning_focus_result(
         .map(|q| q.to_string())
         .unwrap_or_else(|| format!("focus:{focus_rel}"));
 
-    let scanner = FileScanner::new(root);
+    let mut scanner = FileScanner::new(root);
     let mut all_files: Vec<String> = Vec::new();
-    for abs in scanner.scan() {
+    for abs in scanner.scan()? {
         let Some(rel) = normalize_relative_path(root, &abs) else {
             continue;
         };
@@ -517,6 +517,8 @@ pub(super) async fn compute_meaning_focus_result(
         budget: MeaningFocusBudget {
             max_chars,
             used_chars: 0,
+            max_tokens: request.max_tokens,
+            used_tokens: None,
             truncated: false,
             truncation: None,
         },
@@ -541,6 +543,20 @@ fn trim_to_budget(result: &mut MeaningFocusResult) -> anyhow::Result<()> {
         |inner| shrink_pack(&mut inner.pack),
     )?;
     result.budget.used_chars = used;
+
+    if let Some(max_tokens) = result.budget.max_tokens {
+        enforce_max_tokens(
+            result,
+            max_tokens,
+            |inner, used| inner.budget.used_tokens = Some(used),
+            |inner| {
+                inner.budget.truncated = true;
+                inner.budget.truncation = Some(BudgetTruncation::MaxTokens);
+            },
+            |inner| shrink_pack(&mut inner.pack),
+        )?;
+        result.budget.used_chars = result.pack.chars().count();
+    }
     Ok(())
 }
 
@@ -1,6 +1,6 @@
 use anyhow::Result;
 use context_indexer::{FileScanner, ToolMeta};
-use context_protocol::{enforce_max_chars, BudgetTruncation, ToolNextAction};
+use context_protocol::{enforce_max_chars, enforce_max_tokens, BudgetTruncation, ToolNextAction};
 use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::Path;
 
@@ -53,9 +53,9 @@ pub(super) async fn compute_meaning_pack_result(
     let map_limit = request.map_limit.unwrap_or(DEFAULT_MAP_LIMIT).clamp(1, 200);
 
     // v0: facts-only map derived from filesystem paths (gitignore-aware), no full-file parsing.
-    let scanner = FileScanner::new(root);
+    let mut scanner = FileScanner::new(root);
     let mut files: Vec<String> = Vec::new();
-    for abs in scanner.scan() {
+    for abs in scanner.scan()? {
         let Some(rel) = normalize_relative_path(root, &abs) else {
             continue;
         };
@@ -510,6 +510,8 @@ pub(super) async fn compute_meaning_pack_result(
         budget: MeaningPackBudget {
             max_chars,
             used_chars: 0,
+            max_tokens: request.max_tokens,
+            used_tokens: None,
             truncated: false,
             truncation: None,
         },
@@ -534,6 +536,20 @@ fn trim_to_budget(result: &mut MeaningPackResult) -> anyhow::Result<()> {
         |inner| shrink_pack(&mut inner.pack),
     )?;
     result.budget.used_chars = used;
+
+    if let Some(max_tokens) = result.budget.max_tokens {
+        enforce_max_tokens(
+            result,
+            max_tokens,
+            |inner, used| inner.budget.used_tokens = Some(used),
+            |inner| {
+                inner.budget.truncated = true;
+                inner.budget.truncation = Some(BudgetTruncation::MaxTokens);
+            },
+            |inner| shrink_pack(&mut inner.pack),
+        )?;
+        result.budget.used_chars = result.pack.chars().count();
+    }
     Ok(())
 }
 
@@ -27,10 +27,13 @@ mod notebook_store;
 mod notebook_suggest;
 mod notebook_types;
 mod paths;
+mod recall_vocab_cache;
+mod recall_vocab_fst;
 mod repo_onboarding_pack;
 mod runbook_pack;
 mod schemas;
 mod secrets;
+mod symbol_fst_cache;
 mod util;
 mod worktree_pack;
 
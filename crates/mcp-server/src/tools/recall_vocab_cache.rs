@@ -0,0 +1,98 @@
+//! On-disk cache for [`RecallVocabFst`], the corpus-wide word list `read_pack`'s recall intent
+//! fuzzy-expands typo'd question tokens against (see
+//! `dispatch::read_pack::recall_fuzzy::expand_fuzzy_tokens`).
+//!
+//! Rebuilt only when the project watermark changes, the same staleness check
+//! [`crate::index_warmup`] and [`crate::tools::symbol_fst_cache`] use; a rebuild just retokenizes
+//! the already-persisted [`ChunkCorpus`], so this is a plain load-or-rebuild rather than anything
+//! incremental per-chunk.
+
+use std::path::{Path, PathBuf};
+
+use context_indexer::{assess_staleness, compute_project_watermark, Watermark};
+use context_vector_store::{
+    context_dir_for_project_root, corpus_path_for_project_root, ChunkCorpus,
+};
+use serde::{Deserialize, Serialize};
+
+use super::recall_vocab_fst::RecallVocabFst;
+
+const CACHE_FINGERPRINT: &str = "recall-vocab-fst-v1";
+
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    fingerprint: String,
+    watermark: Watermark,
+    fst_bytes: Vec<u8>,
+}
+
+fn cache_path(root: &Path) -> PathBuf {
+    context_dir_for_project_root(root).join("recall_vocab_fst.json")
+}
+
+/// Loads the cached vocabulary index if its stored watermark still matches the project's current
+/// watermark; otherwise retokenizes the persisted corpus and writes the refreshed sidecar back.
+/// Best-effort: a missing/corrupt sidecar, an unindexed project (no `corpus.json` yet), or a
+/// failed write never fails the caller -- the recall path just falls back to exact-only matching
+/// for that call.
+pub(crate) async fn load_or_rebuild(root: &Path) -> Option<RecallVocabFst> {
+    let path = cache_path(root);
+    let current_watermark = compute_project_watermark(root).await.ok();
+
+    if let Some(watermark) = &current_watermark {
+        if let Some(index) = try_load_fresh(&path, watermark) {
+            return Some(index);
+        }
+    }
+
+    let corpus = ChunkCorpus::load(corpus_path_for_project_root(root))
+        .await
+        .ok()?;
+    let index = RecallVocabFst::build(corpus_tokens(&corpus));
+    if let Some(watermark) = current_watermark {
+        save(&path, &index, watermark);
+    }
+    Some(index)
+}
+
+fn try_load_fresh(path: &Path, watermark: &Watermark) -> Option<RecallVocabFst> {
+    let raw = std::fs::read_to_string(path).ok()?;
+    let cached: CacheFile = serde_json::from_str(&raw).ok()?;
+    if cached.fingerprint != CACHE_FINGERPRINT {
+        return None;
+    }
+    if assess_staleness(watermark, true, false, Some(&cached.watermark)).stale {
+        return None;
+    }
+    RecallVocabFst::from_parts(cached.fst_bytes).ok()
+}
+
+fn save(path: &Path, index: &RecallVocabFst, watermark: Watermark) {
+    let file = CacheFile {
+        fingerprint: CACHE_FINGERPRINT.to_string(),
+        watermark,
+        fst_bytes: index.as_fst_bytes().to_vec(),
+    };
+    let Ok(serialized) = serde_json::to_string(&file) else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let _ = std::fs::write(path, serialized);
+}
+
+/// Same splitting/lowercasing/length rules as `recall_question_tokens`, minus the stopword list --
+/// a fuzzy-match vocabulary benefits from keeping common words in, since a typo'd stopword should
+/// still resolve back to itself.
+fn corpus_tokens(corpus: &ChunkCorpus) -> impl Iterator<Item = String> + '_ {
+    corpus.files().values().flatten().flat_map(|chunk| {
+        chunk
+            .content
+            .split(|c: char| !c.is_alphanumeric() && c != '_' && c != '-')
+            .filter(|token| token.len() >= 3 && token.len() <= 40)
+            .filter(|token| !token.chars().all(|c| c.is_ascii_digit()))
+            .map(|token| token.to_lowercase())
+            .collect::<Vec<_>>()
+    })
+}
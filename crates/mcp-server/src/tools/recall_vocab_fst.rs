@@ -0,0 +1,117 @@
+//! FST-backed fuzzy expansion over the corpus vocabulary, for typo-tolerant `read_pack` recall
+//! keyword matching (see `dispatch::read_pack::recall_scoring::score_recall_snippet`). Mirrors
+//! `context_graph::SymbolFstIndex`'s Levenshtein-automaton approach, but over arbitrary corpus
+//! words rather than symbol names, and only ever returns *additional* candidate tokens -- callers
+//! still prefer an exact `question_tokens` hit when one exists. This module does no I/O itself --
+//! callers own persistence (see `recall_vocab_cache`, which persists this next to the semantic
+//! index).
+
+use fst::automaton::Levenshtein;
+use fst::{IntoStreamer, Set, Streamer};
+
+/// Per-token cap on how many fuzzy expansions [`RecallVocabFst::expand`] returns, so one
+/// badly-misspelled token in a huge corpus can't blow up a question's match set.
+const MAX_EXPANSIONS: usize = 20;
+
+/// Bounded edit distance for a token of length `len`: short tokens tolerate zero edits (every
+/// letter is load-bearing and false positives are cheap), medium tokens tolerate one, long ones
+/// two.
+fn edits_for_len(len: usize) -> u32 {
+    match len {
+        0..=4 => 0,
+        5..=8 => 1,
+        _ => 2,
+    }
+}
+
+/// In-memory FST set over every distinct word seen in the indexed corpus. Build once per project
+/// snapshot via [`RecallVocabFst::build`]; reconstruct a persisted one via
+/// [`RecallVocabFst::from_parts`].
+pub(crate) struct RecallVocabFst {
+    set: Set<Vec<u8>>,
+}
+
+impl RecallVocabFst {
+    /// Builds the index from `tokens` (typically every `recall_question_tokens`-shaped word
+    /// extracted from the corpus), deduplicating and sorting as `fst::Set` requires.
+    pub(crate) fn build<I: IntoIterator<Item = String>>(tokens: I) -> Self {
+        let mut unique: Vec<String> = tokens.into_iter().collect();
+        unique.sort();
+        unique.dedup();
+        let set = Set::from_iter(unique.iter())
+            .unwrap_or_else(|_| Set::from_iter(std::iter::empty::<&str>()).expect("empty set"));
+        Self { set }
+    }
+
+    /// Reconstructs an index previously taken apart via [`Self::as_fst_bytes`], e.g. when loading
+    /// a persisted cache.
+    pub(crate) fn from_parts(fst_bytes: Vec<u8>) -> fst::Result<Self> {
+        Ok(Self {
+            set: Set::new(fst_bytes)?,
+        })
+    }
+
+    /// Raw FST bytes, for persisting.
+    pub(crate) fn as_fst_bytes(&self) -> &[u8] {
+        self.set.as_fst().as_bytes()
+    }
+
+    /// Edit-distance-bounded expansions of `token` actually present in the corpus vocabulary,
+    /// shortest first (a closer length is a closer typo), capped at [`MAX_EXPANSIONS`]. Edit
+    /// distance scales with `token`'s length via [`edits_for_len`]; a token of 4 characters or
+    /// fewer never expands.
+    pub(crate) fn expand(&self, token: &str) -> Vec<String> {
+        let edits = edits_for_len(token.chars().count());
+        if edits == 0 {
+            return Vec::new();
+        }
+        let Ok(automaton) = Levenshtein::new(token, edits) else {
+            return Vec::new();
+        };
+        let mut stream = self.set.search(automaton).into_stream();
+        let mut out = Vec::new();
+        while let Some(key) = stream.next() {
+            let candidate = String::from_utf8_lossy(key).into_owned();
+            if candidate != token {
+                out.push(candidate);
+            }
+        }
+        out.sort_by_key(|candidate| candidate.len());
+        out.truncate(MAX_EXPANSIONS);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expand_finds_a_one_edit_typo() {
+        let vocab = RecallVocabFst::build(
+            ["authentication", "middleware"]
+                .into_iter()
+                .map(str::to_string),
+        );
+        let matches = vocab.expand("authenticaiton");
+        assert!(matches.contains(&"authentication".to_string()));
+    }
+
+    #[test]
+    fn expand_never_fuzzes_short_tokens() {
+        let vocab = RecallVocabFst::build(["sync"].into_iter().map(str::to_string));
+        assert!(vocab.expand("sync").is_empty());
+        assert!(vocab.expand("sinc").is_empty());
+    }
+
+    #[test]
+    fn round_trips_through_persisted_bytes() {
+        let vocab = RecallVocabFst::build(["authentication"].into_iter().map(str::to_string));
+        let rebuilt =
+            RecallVocabFst::from_parts(vocab.as_fst_bytes().to_vec()).expect("valid fst bytes");
+        assert_eq!(
+            rebuilt.expand("authenticaiton"),
+            vocab.expand("authenticaiton")
+        );
+    }
+}
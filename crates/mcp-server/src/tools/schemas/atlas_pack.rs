@@ -28,6 +28,12 @@ pub struct AtlasPackRequest {
     )]
     pub max_chars: Option<usize>,
 
+    /// Maximum estimated LLM tokens for the `.context` response (no default: unenforced unless set).
+    #[schemars(
+        description = "Maximum estimated LLM tokens for the .context response (unenforced unless set)."
+    )]
+    pub max_tokens: Option<usize>,
+
     /// Response mode:
     /// - "facts" (default): CP pack + lightweight summary, strips next_actions.
     /// - "full": includes next_actions (drill-down + evidence_fetch) and richer worktree summaries.
@@ -40,6 +46,10 @@ pub struct AtlasPackRequest {
 pub struct AtlasPackBudget {
     pub max_chars: usize,
     pub used_chars: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub used_tokens: Option<usize>,
     pub truncated: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub truncation: Option<BudgetTruncation>,
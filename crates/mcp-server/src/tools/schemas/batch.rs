@@ -16,6 +16,7 @@ pub enum BatchToolName {
     ContextPack,
     Index,
     Impact,
+    SymbolLookup,
     Trace,
     Explain,
     Overview,
@@ -47,16 +48,77 @@ pub struct BatchRequest {
     )]
     pub max_chars: Option<usize>,
 
+    /// Maximum estimated LLM tokens for the serialized batch result (best effort).
+    #[schemars(
+        description = "Maximum estimated LLM tokens for the serialized batch result (best effort, unenforced unless set)."
+    )]
+    pub max_tokens: Option<usize>,
+
     /// If true, stop processing after the first item error.
     #[schemars(description = "If true, stop processing after the first item error.")]
     #[serde(default)]
     pub stop_on_error: bool,
 
+    /// Reuse the result of an earlier item that had an identical `(tool, resolved input)` instead
+    /// of calling the tool again (default: true). Reused results are marked via `message`. Set to
+    /// `false` for batches with intentionally side-effecting or non-idempotent items (e.g. a
+    /// `doctor` call meant to re-check live state).
+    #[schemars(
+        description = "Reuse the result of an earlier item with identical (tool, resolved input) instead of recomputing it (default: true). Set false if items are intentionally side-effecting/non-idempotent."
+    )]
+    #[serde(default = "default_true")]
+    pub dedup: bool,
+
+    /// Opt-in: run independent items concurrently instead of strictly sequentially.
+    ///
+    /// Items whose `input` contains a `$ref` pointing at another item's id still run only after
+    /// that item completes (dependency order is always respected); this only parallelizes items
+    /// that have no such dependency on each other. Values <= 1 (or omitted) mean the existing
+    /// sequential behavior.
+    #[schemars(
+        description = "Opt-in: run independent items concurrently, up to this many at once (values <= 1 = sequential, the default). Items with a $ref dependency on another item still wait for it."
+    )]
+    pub concurrency: Option<usize>,
+
+    /// Retry policy for transient (retriable) dispatch failures, e.g. a locked/contended vector
+    /// index. Omit for no retries (the default: each item gets exactly one attempt).
+    #[schemars(
+        description = "Retry policy for transient dispatch failures (e.g. vector-store/graph contention). Omitted = no retries."
+    )]
+    pub retry: Option<BatchRetryPolicy>,
+
     /// Batch items to execute.
     #[schemars(description = "Batch items to execute.")]
     pub items: Vec<BatchItem>,
 }
 
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema, Clone, Copy)]
+pub struct BatchRetryPolicy {
+    /// Maximum attempts per item, including the first (default: 3).
+    #[schemars(description = "Maximum attempts per item, including the first (default: 3).")]
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Base delay in milliseconds; attempt N waits `base_delay_ms * 2^(N-1)` before retrying.
+    #[schemars(
+        description = "Base delay in milliseconds for exponential backoff between retry attempts (default: 100)."
+    )]
+    #[serde(default = "default_base_delay_ms")]
+    pub base_delay_ms: u64,
+}
+
+fn default_max_attempts() -> u32 {
+    3
+}
+
+fn default_base_delay_ms() -> u64 {
+    100
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct BatchItem {
     /// Caller-provided identifier used to correlate results (trimmed).
@@ -76,6 +138,55 @@ pub struct BatchItem {
     /// The wrapper is recognized only when the object contains exactly `$ref` (+ optional `$default`).
     #[serde(default, alias = "payload")]
     pub input: serde_json::Value,
+
+    /// Only run this item if the ref resolves to a truthy value (non-null, non-false, non-zero,
+    /// non-empty string/array/object); otherwise the item is recorded with status `skipped` and
+    /// never dispatched. Evaluated against the same ref context as `$ref` wrappers in `input`.
+    #[schemars(
+        description = "Only run this item if the referenced value is truthy/non-empty (e.g. skip grep_context when an upstream list_files result is empty) instead of failing on an empty ref."
+    )]
+    #[serde(default)]
+    pub only_if: Option<BatchItemGuard>,
+
+    /// Skip this item (status `skipped`) if the ref resolves to a truthy value; otherwise run it.
+    /// The inverse of `only_if`. Setting both is redundant but not an error; `only_if` is checked
+    /// first.
+    #[schemars(
+        description = "Skip this item (status skipped) if the referenced value is truthy/non-empty. Inverse of only_if."
+    )]
+    #[serde(default)]
+    pub skip_if: Option<BatchItemGuard>,
+
+    /// Fan out: `input` must contain a `$ref` pointing at a whole array (no trailing index), e.g.
+    /// `#/items/files/data/files`. Instead of failing with a type mismatch, the item runs once per
+    /// array element (that `$ref` rewritten to `.../<index>` for each run) and `data` becomes a
+    /// JSON array of each run's result, in element order. Lets a batch express "list files -> grep
+    /// each file" without the caller pre-enumerating indices.
+    #[schemars(
+        description = "Fan out: if input's $ref points at a whole array, run this item once per element and return data as a nested array, instead of requiring the caller to pre-enumerate indices."
+    )]
+    #[serde(default)]
+    pub for_each: bool,
+
+    /// Ids of other items this one depends on, beyond whatever `$ref` pointers already imply.
+    /// The dependency graph is the union of this and every `$ref` found in `input`/guards, so
+    /// `needs` only matters for ordering an item can't otherwise express via `$ref` (e.g. it must
+    /// run after another item's side effect, not its data). Unknown ids are ignored.
+    #[schemars(
+        description = "Ids of other items this one must run after, in addition to any dependency already implied by a $ref in input."
+    )]
+    #[serde(default)]
+    pub needs: Vec<String>,
+}
+
+/// A guard condition for [`BatchItem::only_if`] / [`BatchItem::skip_if`]: a `$ref` pointer
+/// resolved against the batch's ref context, independent of (and evaluated before) any `$ref`
+/// resolution inside `input`.
+#[derive(Debug, Deserialize, schemars::JsonSchema, Clone)]
+pub struct BatchItemGuard {
+    /// JSON Pointer ref into a prior item's result, e.g. `#/items/files/data/files`.
+    #[serde(rename = "$ref")]
+    pub r#ref: String,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema, Clone, Copy, PartialEq, Eq)]
@@ -83,12 +194,19 @@ pub struct BatchItem {
 pub enum BatchItemStatus {
     Ok,
     Error,
+    Cancelled,
+    /// Skipped because `only_if`/`skip_if` evaluated to "don't run" (see [`BatchItem::only_if`]).
+    Skipped,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema, Clone)]
 pub struct BatchBudget {
     pub max_chars: usize,
     pub used_chars: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub used_tokens: Option<usize>,
     pub truncated: bool,
 }
 
@@ -110,3 +228,31 @@ pub struct BatchResult {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub meta: Option<ToolMeta>,
 }
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct BatchStatusRequest {
+    /// Task id returned by `submit_batch`.
+    #[schemars(description = "Task id returned by submit_batch.")]
+    pub id: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchTaskState {
+    Enqueued,
+    Processing,
+    Succeeded,
+    Failed,
+}
+
+/// Progress snapshot for a `submit_batch` task, returned by `batch_status`.
+#[derive(Debug, Serialize, schemars::JsonSchema, Clone)]
+pub struct BatchTaskStatus {
+    pub id: String,
+    pub state: BatchTaskState,
+    pub processed: usize,
+    pub total: usize,
+    /// Items processed so far (partial `BatchResult`), present once at least one item has run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partial: Option<BatchResult>,
+}
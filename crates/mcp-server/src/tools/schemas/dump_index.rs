@@ -0,0 +1,85 @@
+use context_indexer::ToolMeta;
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+
+pub(in crate::tools) const INDEX_DUMP_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct DumpIndexRequest {
+    /// Project directory path
+    #[schemars(
+        description = "Project directory to dump (defaults to session root; fallback: CONTEXT_FINDER_ROOT/CONTEXT_FINDER_PROJECT_ROOT, git root, then cwd)."
+    )]
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct DumpIndexResult {
+    /// Path of the written dump archive
+    pub dump_path: String,
+    pub format_version: u32,
+    /// Model IDs bundled in this dump
+    pub model_ids: Vec<String>,
+    /// Number of source files recorded in the manifest
+    pub file_count: usize,
+    pub time_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<ToolMeta>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct RestoreIndexRequest {
+    /// Project directory path to restore into
+    #[schemars(
+        description = "Target project directory (defaults to session root; fallback: CONTEXT_FINDER_ROOT/CONTEXT_FINDER_PROJECT_ROOT, git root, then cwd)."
+    )]
+    pub path: Option<String>,
+
+    /// Path to a dump archive produced by `dump_index`
+    #[schemars(description = "Path to a dump archive file produced by dump_index")]
+    pub dump_file: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct RestoreIndexResult {
+    /// Number of files recorded in the restored manifest
+    pub files: usize,
+    /// Number of model indexes restored
+    pub models_restored: usize,
+    pub time_ms: u64,
+    pub index_path: String,
+    pub format_version: u32,
+    pub model_ids: Vec<String>,
+    /// Source files whose current mtime/sha256 no longer match the manifest; re-run `index` to
+    /// re-embed just this drift instead of a full reindex.
+    pub stale_files: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<ToolMeta>,
+}
+
+/// One entry in an `IndexDumpManifest`, recording the fields needed to detect drift on restore --
+/// the same trio already tracked per-file by `FileSliceCursorV1`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(in crate::tools) struct IndexDumpFileEntry {
+    pub(in crate::tools) path: String,
+    pub(in crate::tools) file_size_bytes: u64,
+    pub(in crate::tools) file_mtime_ms: u64,
+    pub(in crate::tools) content_sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(in crate::tools) struct IndexDumpManifest {
+    pub(in crate::tools) format_version: u32,
+    pub(in crate::tools) created_at_ms: u64,
+    pub(in crate::tools) model_ids: Vec<String>,
+    pub(in crate::tools) files: Vec<IndexDumpFileEntry>,
+}
+
+/// A single self-describing dump archive: the manifest plus the raw (base64-encoded) bytes of
+/// each bundled model's `index.json`, keyed by model id dir name. Serialized as one JSON document
+/// so it can be copied around as a single file (shared, attached to a CI cache, etc).
+#[derive(Debug, Serialize, Deserialize)]
+pub(in crate::tools) struct IndexDumpArchive {
+    pub(in crate::tools) manifest: IndexDumpManifest,
+    pub(in crate::tools) stores: std::collections::BTreeMap<String, String>,
+}
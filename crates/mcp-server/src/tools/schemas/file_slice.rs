@@ -29,6 +29,31 @@ pub struct FileSliceRequest {
     /// Opaque cursor token to continue a previous response. When provided, `start_line` is ignored.
     #[schemars(description = "Opaque cursor token to continue a previous file_slice response")]
     pub cursor: Option<String>,
+
+    /// Grep/window mode: return only the windows of the file mentioning these terms
+    #[schemars(
+        description = "If set, skip the flat start_line range and instead return minimal windows around the lines where these terms occur (smallest span covering at least one occurrence of every present term, expanded by context_lines and merged when overlapping)"
+    )]
+    pub query: Option<Vec<String>>,
+
+    /// Lines of context to keep around each matched window (default: 2)
+    #[schemars(
+        description = "Lines of context to expand each matched window by on each side (only used with `query`, default 2)"
+    )]
+    pub context_lines: Option<usize>,
+
+    /// A prior `content_sha256` for this file; if it still matches, skip re-reading/formatting
+    /// content and return a lightweight `not_modified` result instead
+    #[schemars(
+        description = "A previously-returned content_sha256 for this file. If the file's content still hashes to this value, the response omits `content` and sets not_modified=true."
+    )]
+    pub if_none_match: Option<String>,
+
+    /// Skip re-fetching if the file's mtime is still at or before this value (ms since epoch)
+    #[schemars(
+        description = "Only re-fetch content if the file's mtime is after this value (ms since epoch); otherwise return a lightweight not_modified result"
+    )]
+    pub if_unmodified_since_ms: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -43,6 +68,8 @@ pub(in crate::tools) struct FileSliceCursorV1 {
     pub(in crate::tools) next_byte_offset: u64,
     pub(in crate::tools) file_size_bytes: u64,
     pub(in crate::tools) file_mtime_ms: u64,
+    pub(in crate::tools) query: Option<Vec<String>>,
+    pub(in crate::tools) context_lines: Option<usize>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema, Clone, Copy, PartialEq, Eq)]
@@ -52,6 +79,17 @@ pub enum FileSliceTruncation {
     MaxChars,
 }
 
+/// One minimal window around query term matches: a contiguous `[start_line, end_line]` span
+/// (already expanded by `context_lines` and merged with any overlapping neighbors) plus the exact
+/// line numbers within it where a query term was found.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct FileSliceWindow {
+    pub start_line: usize,
+    pub end_line: usize,
+    pub content: String,
+    pub matched_lines: Vec<usize>,
+}
+
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct FileSliceResult {
     pub file: String,
@@ -72,4 +110,13 @@ pub struct FileSliceResult {
     pub file_mtime_ms: u64,
     pub content_sha256: String,
     pub content: String,
+    /// Present only in grep/window mode (`query` was set); `start_line`/`end_line`/`content` above
+    /// still cover the full span from the first to the last window for backward-compatible callers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub windows: Option<Vec<FileSliceWindow>>,
+    /// `true` when `if_none_match`/`if_unmodified_since_ms` matched the file's current state;
+    /// `content` is omitted and only `file`/`file_size_bytes`/`file_mtime_ms`/`content_sha256` are
+    /// populated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub not_modified: Option<bool>,
 }
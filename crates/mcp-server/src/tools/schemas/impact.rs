@@ -53,6 +53,12 @@ pub struct ImpactResult {
     pub public_api: bool,
     /// Mermaid diagram
     pub mermaid: String,
+    /// Symbol actually analyzed, if `symbol` had no exact match and was resolved via fuzzy matching
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_symbol: Option<String>,
+    /// Near-miss symbol suggestions, populated when `symbol` had no exact match
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<SymbolSuggestion>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub meta: Option<ToolMeta>,
 }
@@ -63,6 +69,16 @@ pub struct SymbolLocation {
     pub line: usize,
 }
 
+/// A ranked near-miss symbol candidate, returned when `ImpactRequest::symbol` misses exactly.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct SymbolSuggestion {
+    pub symbol: String,
+    pub file: String,
+    pub line: usize,
+    /// Fuzzy match score (higher is a better match; not bounded to a fixed range)
+    pub score: f32,
+}
+
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct UsageInfo {
     pub file: String,
@@ -27,9 +27,15 @@ pub struct IndexRequest {
     /// Full reindex (skip incremental checks)
     #[schemars(description = "Run a full reindex (skip incremental checks)")]
     pub full: Option<bool>,
+
+    /// Wait for completion instead of returning a task immediately
+    #[schemars(
+        description = "If true, block and poll internally until the task finishes, returning the final IndexResult (old synchronous behavior). Defaults to false: enqueue a background task and return { task_uid, status: \"enqueued\" } immediately; poll with get_task."
+    )]
+    pub wait: Option<bool>,
 }
 
-#[derive(Debug, Serialize, schemars::JsonSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct IndexResult {
     /// Number of files indexed
     pub files: usize,
@@ -42,3 +48,73 @@ pub struct IndexResult {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub meta: Option<ToolMeta>,
 }
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexTaskKind {
+    Index,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexTaskState {
+    Enqueued,
+    Processing,
+    Succeeded,
+    Failed,
+    Canceled,
+}
+
+/// Status snapshot for an `index` background task, returned by `get_task`/`list_tasks`.
+#[derive(Debug, Serialize, schemars::JsonSchema, Clone)]
+pub struct IndexTaskStatus {
+    pub uid: u64,
+    pub kind: IndexTaskKind,
+    pub status: IndexTaskState,
+    pub enqueued_at_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<IndexResult>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetTaskRequest {
+    /// Project directory path (defaults to session root, same resolution as other tools)
+    #[schemars(description = "Project directory the task belongs to (defaults to session root)")]
+    pub path: Option<String>,
+
+    /// Task uid returned by `index`
+    #[schemars(description = "Task uid returned by index")]
+    pub task_uid: u64,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListTasksRequest {
+    /// Project directory path (defaults to session root, same resolution as other tools)
+    #[schemars(description = "Project directory to list tasks for (defaults to session root)")]
+    pub path: Option<String>,
+
+    /// Only return tasks in this state
+    #[schemars(description = "Only return tasks in this state")]
+    pub status: Option<IndexTaskState>,
+
+    /// Maximum number of tasks to return (newest first)
+    #[schemars(description = "Maximum number of tasks to return (newest first, default 50)")]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CancelTaskRequest {
+    /// Project directory path (defaults to session root, same resolution as other tools)
+    #[schemars(description = "Project directory the task belongs to (defaults to session root)")]
+    pub path: Option<String>,
+
+    /// Task uid returned by `index`
+    #[schemars(description = "Task uid returned by index")]
+    pub task_uid: u64,
+}
@@ -33,6 +33,12 @@ pub struct MeaningFocusRequest {
     #[schemars(description = "Maximum number of UTF-8 characters for the meaning pack")]
     pub max_chars: Option<usize>,
 
+    /// Maximum estimated LLM tokens for the entire meaning pack (no default: unenforced unless set).
+    #[schemars(
+        description = "Maximum estimated LLM tokens for the meaning pack (unenforced unless set)"
+    )]
+    pub max_tokens: Option<usize>,
+
     /// Response mode:
     /// - "facts" (default): keeps meta/index_state for freshness, strips next_actions to reduce noise.
     /// - "full": includes meta/index_state and next_actions (when applicable).
@@ -58,6 +64,10 @@ pub type MeaningFocusNextAction = ToolNextAction;
 pub struct MeaningFocusBudget {
     pub max_chars: usize,
     pub used_chars: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub used_tokens: Option<usize>,
     pub truncated: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub truncation: Option<MeaningFocusTruncation>,
@@ -95,6 +95,29 @@ pub struct ReadPackRequest {
         description = "Auto-index time budget in milliseconds for intent=query (default: 3000)."
     )]
     pub auto_index_budget_ms: Option<u64>,
+
+    /// Typo-tolerant recall keyword matching: fuzzy-expand question tokens against the corpus
+    /// vocabulary (default: false)
+    #[schemars(
+        description = "Typo-tolerant recall keyword matching for intent=recall: fuzzy-expand question tokens against the corpus vocabulary via a bounded edit-distance match (default: false, also settable per-question with a `fuzzy`/`typo` directive word)."
+    )]
+    pub fuzzy: Option<bool>,
+
+    /// Offset encoding for each snippet's precise `range` ("utf8", "utf16", or "char"). Omit to
+    /// skip computing ranges, leaving `range` null -- unrecognized values do the same.
+    #[schemars(description = "Offset encoding for precise snippet ranges: utf8 | utf16 | char")]
+    pub offset_encoding: Option<String>,
+}
+
+/// A snippet's precise content span in the requested offset encoding, for editor/LSP clients that
+/// can't work off `start_line`/`end_line` alone. Always spans the whole of the snippet's
+/// `content`, i.e. `start` is `0`. Mirrors `tools::SearchRange` for `read_pack`'s snippets.
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+pub struct ReadPackSnippetRange {
+    /// "utf8", "utf16", or "char" -- matches the request's `offset_encoding`.
+    pub encoding: String,
+    pub start: usize,
+    pub end: usize,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
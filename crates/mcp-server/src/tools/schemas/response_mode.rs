@@ -10,6 +10,12 @@ use serde::{Deserialize, Serialize};
 /// - `full`: opt-in diagnostics (freshness meta/index_state, counters, next actions when applicable).
 /// - `minimal`: smallest possible output (strips helper fields and diagnostics).
 /// - `compact`: alias for `minimal` (common user expectation).
+/// - `stream`: same payload shape/budget as `facts`, but `read_pack` commits each produced section
+///   to the session's working set as soon as it is built, rather than only after the whole pack is
+///   assembled and trimmed. MCP tool calls are still a single request/response (there is no
+///   server-push transport wired up here), so this does not deliver partial results early; it just
+///   means a later probe in the same pack (or a slow timeout) can't cause an earlier section's
+///   files to be forgotten.
 #[derive(Debug, Deserialize, Serialize, schemars::JsonSchema, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum ResponseMode {
@@ -17,6 +23,7 @@ pub enum ResponseMode {
     Facts,
     #[serde(alias = "compact")]
     Minimal,
+    Stream,
 }
 
 #[cfg(test)]
@@ -28,4 +35,11 @@ mod tests {
         let parsed: ResponseMode = serde_json::from_str("\"compact\"").expect("deserialize");
         assert_eq!(parsed, ResponseMode::Minimal);
     }
+
+    #[test]
+    fn stream_round_trips_through_snake_case() {
+        let parsed: ResponseMode = serde_json::from_str("\"stream\"").expect("deserialize");
+        assert_eq!(parsed, ResponseMode::Stream);
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), "\"stream\"");
+    }
 }
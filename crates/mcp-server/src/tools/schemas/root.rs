@@ -26,6 +26,11 @@ pub struct RootGetResult {
     /// If set, the session root is outside workspace roots and calls must pass an explicit `path`.
     pub root_mismatch_error: Option<String>,
 
+    /// Whether a background pre-warm crawl of the session root is currently in flight. Clients
+    /// can poll `root_get` and wait for this to flip to `false` before assuming the index is warm.
+    #[serde(default)]
+    pub crawl_pending: bool,
+
     #[serde(default)]
     pub meta: ToolMeta,
 }
@@ -49,5 +54,7 @@ pub struct RootSetResult {
     pub workspace_roots_ambiguous: bool,
     pub root_mismatch_error: Option<String>,
     #[serde(default)]
+    pub crawl_pending: bool,
+    #[serde(default)]
     pub meta: ToolMeta,
 }
@@ -0,0 +1,57 @@
+use context_indexer::ToolMeta;
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SymbolLookupRequest {
+    /// Project directory path
+    #[schemars(
+        description = "Project directory path (defaults to session root; fallback: CONTEXT_FINDER_ROOT/CONTEXT_FINDER_PROJECT_ROOT, git root, then cwd)."
+    )]
+    pub path: Option<String>,
+
+    /// Symbol name prefix to look up (ordered prefix enumeration, e.g. "Vector" matches
+    /// "VectorStore" and "VectorSearch").
+    #[schemars(
+        description = "Symbol name prefix, e.g. \"Vector\" matches VectorStore/VectorSearch."
+    )]
+    pub prefix: String,
+
+    /// Maximum number of matches to return (default: 20).
+    #[schemars(description = "Maximum number of matches to return (default: 20).")]
+    pub limit: Option<usize>,
+
+    /// If set, also run a bounded edit-distance fuzzy search (1-2) instead of a strict prefix
+    /// match, for typo-tolerant lookup (e.g. "VecStor" with fuzzy_edits=2 still finds
+    /// "VectorStore"). Omit for exact prefix matching only.
+    #[schemars(
+        description = "Bounded edit-distance (1-2) fuzzy search instead of strict prefix matching. Omit for exact prefix matching."
+    )]
+    pub fuzzy_edits: Option<u32>,
+
+    /// Automatically build or refresh the semantic index before executing (default: true)
+    #[schemars(
+        description = "Automatically build or refresh the semantic index before executing (default: true)."
+    )]
+    pub auto_index: Option<bool>,
+
+    /// Auto-index time budget in milliseconds (default: 3000)
+    #[schemars(description = "Auto-index time budget in milliseconds (default: 3000).")]
+    pub auto_index_budget_ms: Option<u64>,
+}
+
+/// One ranked symbol location returned by `symbol_lookup`.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct SymbolLookupMatch {
+    pub symbol: String,
+    pub file: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct SymbolLookupResult {
+    pub prefix: String,
+    pub matches: Vec<SymbolLookupMatch>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<ToolMeta>,
+}
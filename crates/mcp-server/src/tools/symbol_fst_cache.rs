@@ -0,0 +1,79 @@
+//! On-disk cache for [`context_graph::SymbolFstIndex`], shared by the `symbol_lookup` tool and
+//! `impact`'s prefix-resolution fallback.
+//!
+//! Rebuilt only when the project watermark changes, the same staleness check
+//! [`crate::index_warmup`] uses for the semantic index; a rebuild is cheap relative to a full
+//! re-index (it just walks symbols already present in the in-memory [`CodeGraph`]), so this is a
+//! plain load-or-rebuild rather than anything incremental per-symbol.
+
+use std::path::{Path, PathBuf};
+
+use context_graph::{CodeGraph, SymbolFstIndex};
+use context_indexer::{assess_staleness, compute_project_watermark, Watermark};
+use context_vector_store::context_dir_for_project_root;
+use serde::{Deserialize, Serialize};
+
+const CACHE_FINGERPRINT: &str = "symbol-fst-v1";
+
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    fingerprint: String,
+    watermark: Watermark,
+    fst_bytes: Vec<u8>,
+    files: Vec<String>,
+    duplicates: Vec<Vec<(u32, usize)>>,
+}
+
+fn cache_path(root: &Path) -> PathBuf {
+    context_dir_for_project_root(root).join("symbol_fst.json")
+}
+
+/// Loads the cached index if its stored watermark still matches the project's current watermark;
+/// otherwise rebuilds it from `graph` and writes the refreshed sidecar back. Best-effort: a
+/// missing/corrupt sidecar or a failed write never fails the caller, it just means a rebuild (or
+/// an in-memory-only result) this time.
+pub(crate) async fn load_or_rebuild(root: &Path, graph: &CodeGraph) -> SymbolFstIndex {
+    let path = cache_path(root);
+    let current_watermark = compute_project_watermark(root).await.ok();
+
+    if let Some(watermark) = &current_watermark {
+        if let Some(index) = try_load_fresh(&path, watermark) {
+            return index;
+        }
+    }
+
+    let index = SymbolFstIndex::build(graph);
+    if let Some(watermark) = current_watermark {
+        save(&path, &index, watermark);
+    }
+    index
+}
+
+fn try_load_fresh(path: &Path, watermark: &Watermark) -> Option<SymbolFstIndex> {
+    let raw = std::fs::read_to_string(path).ok()?;
+    let cached: CacheFile = serde_json::from_str(&raw).ok()?;
+    if cached.fingerprint != CACHE_FINGERPRINT {
+        return None;
+    }
+    if assess_staleness(watermark, true, false, Some(&cached.watermark)).stale {
+        return None;
+    }
+    SymbolFstIndex::from_parts(cached.fst_bytes, cached.files, cached.duplicates).ok()
+}
+
+fn save(path: &Path, index: &SymbolFstIndex, watermark: Watermark) {
+    let file = CacheFile {
+        fingerprint: CACHE_FINGERPRINT.to_string(),
+        watermark,
+        fst_bytes: index.as_fst_bytes().to_vec(),
+        files: index.files().to_vec(),
+        duplicates: index.duplicates().to_vec(),
+    };
+    let Ok(serialized) = serde_json::to_string(&file) else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let _ = std::fs::write(path, serialized);
+}
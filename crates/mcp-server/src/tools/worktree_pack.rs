@@ -446,6 +446,11 @@ async fn compute_worktree_purpose_summary(
         map_depth: Some(2),
         map_limit: Some(10),
         max_chars: Some(PURPOSE_MEANING_MAX_CHARS),
+        max_tokens: None,
+        cache_path: None,
+        rules: Vec::new(),
+        semantic: None,
+        semantic_weight: None,
     };
 
     let engine = meaning::meaning_pack(worktree_root, worktree_display, &request)
@@ -259,3 +259,230 @@ async fn batch_v2_ref_to_failed_item_data_returns_error() -> Result<()> {
     service.cancel().await.context("shutdown mcp service")?;
     Ok(())
 }
+
+#[tokio::test]
+async fn batch_v2_for_each_fans_out_over_ref_array() -> Result<()> {
+    let bin = locate_context_finder_mcp_bin()?;
+
+    let mut cmd = Command::new(bin);
+    cmd.env_remove("CONTEXT_FINDER_MODEL_DIR");
+    cmd.env("CONTEXT_FINDER_PROFILE", "quality");
+    cmd.env("RUST_LOG", "warn");
+    cmd.env("CONTEXT_FINDER_DISABLE_DAEMON", "1");
+
+    let transport = TokioChildProcess::new(cmd).context("spawn mcp server")?;
+    let service = tokio::time::timeout(Duration::from_secs(10), ().serve(transport))
+        .await
+        .context("timeout starting MCP server")??;
+
+    let tmp = tempfile::tempdir().context("tempdir")?;
+    let root = tmp.path();
+    std::fs::create_dir_all(root.join("src")).context("mkdir src")?;
+    std::fs::write(root.join("src").join("a.txt"), "TARGET\n").context("write a.txt")?;
+    std::fs::write(root.join("src").join("b.txt"), "TARGET\n").context("write b.txt")?;
+
+    let args = serde_json::json!({
+        "version": 2,
+        "path": root.to_string_lossy(),
+        "max_chars": 20000,
+        "items": [
+            { "id": "files", "tool": "list_files", "input": { "file_pattern": "src/*", "limit": 10 } },
+            {
+                "id": "ctx",
+                "tool": "grep_context",
+                "for_each": true,
+                "input": { "pattern": "TARGET", "file": { "$ref": "#/items/files/data/files" }, "before": 0, "after": 0 }
+            }
+        ]
+    });
+
+    let result = tokio::time::timeout(
+        Duration::from_secs(10),
+        service.call_tool(CallToolRequestParam {
+            name: "batch".into(),
+            arguments: args.as_object().cloned(),
+        }),
+    )
+    .await
+    .context("timeout calling batch")??;
+
+    assert_ne!(result.is_error, Some(true), "batch returned error");
+    let text = result
+        .content
+        .first()
+        .and_then(|c| c.as_text())
+        .map(|t| t.text.as_str())
+        .context("batch did not return text content")?;
+    let json: Value = serde_json::from_str(text).context("batch output is not valid JSON")?;
+
+    let items = json
+        .get("items")
+        .and_then(Value::as_array)
+        .context("batch items missing")?;
+    let ctx_item = items
+        .iter()
+        .find(|v| v.get("id").and_then(Value::as_str) == Some("ctx"))
+        .context("missing ctx item")?;
+    assert_eq!(ctx_item.get("status").and_then(Value::as_str), Some("ok"));
+
+    let elements = ctx_item
+        .get("data")
+        .and_then(Value::as_array)
+        .context("ctx item data should be a fanned-out array")?;
+    assert_eq!(elements.len(), 2, "expected one result per src/*.txt file");
+    for element in elements {
+        assert_eq!(element.get("status").and_then(Value::as_str), Some("ok"));
+    }
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn batch_v2_only_if_skips_item_when_ref_is_empty() -> Result<()> {
+    let bin = locate_context_finder_mcp_bin()?;
+
+    let mut cmd = Command::new(bin);
+    cmd.env_remove("CONTEXT_FINDER_MODEL_DIR");
+    cmd.env("CONTEXT_FINDER_PROFILE", "quality");
+    cmd.env("RUST_LOG", "warn");
+    cmd.env("CONTEXT_FINDER_DISABLE_DAEMON", "1");
+
+    let transport = TokioChildProcess::new(cmd).context("spawn mcp server")?;
+    let service = tokio::time::timeout(Duration::from_secs(10), ().serve(transport))
+        .await
+        .context("timeout starting MCP server")??;
+
+    let tmp = tempfile::tempdir().context("tempdir")?;
+    let root = tmp.path();
+    std::fs::create_dir_all(root.join("src")).context("mkdir src")?;
+    std::fs::write(root.join("src").join("a.txt"), "hello\n").context("write a.txt")?;
+
+    let args = serde_json::json!({
+        "version": 2,
+        "path": root.to_string_lossy(),
+        "max_chars": 20000,
+        "items": [
+            { "id": "files", "tool": "list_files", "input": { "file_pattern": "src/*.md", "limit": 10 } },
+            {
+                "id": "ctx",
+                "only_if": { "$ref": "#/items/files/data/files" },
+                "tool": "grep_context",
+                "input": { "pattern": "hello", "file": { "$ref": "#/items/files/data/files/0" }, "before": 0, "after": 0 }
+            }
+        ]
+    });
+
+    let result = tokio::time::timeout(
+        Duration::from_secs(10),
+        service.call_tool(CallToolRequestParam {
+            name: "batch".into(),
+            arguments: args.as_object().cloned(),
+        }),
+    )
+    .await
+    .context("timeout calling batch")??;
+
+    assert_ne!(result.is_error, Some(true), "batch returned error");
+    let text = result
+        .content
+        .first()
+        .and_then(|c| c.as_text())
+        .map(|t| t.text.as_str())
+        .context("batch did not return text content")?;
+    let json: Value = serde_json::from_str(text).context("batch output is not valid JSON")?;
+
+    let items = json
+        .get("items")
+        .and_then(Value::as_array)
+        .context("batch items missing")?;
+    let ctx_item = items
+        .iter()
+        .find(|v| v.get("id").and_then(Value::as_str) == Some("ctx"))
+        .context("missing ctx item")?;
+    assert_eq!(
+        ctx_item.get("status").and_then(Value::as_str),
+        Some("skipped"),
+        "expected ctx item to be skipped because files/data/files was empty"
+    );
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn batch_v2_concurrency_respects_guard_ref_dependency() -> Result<()> {
+    let bin = locate_context_finder_mcp_bin()?;
+
+    let mut cmd = Command::new(bin);
+    cmd.env_remove("CONTEXT_FINDER_MODEL_DIR");
+    cmd.env("CONTEXT_FINDER_PROFILE", "quality");
+    cmd.env("RUST_LOG", "warn");
+    cmd.env("CONTEXT_FINDER_DISABLE_DAEMON", "1");
+
+    let transport = TokioChildProcess::new(cmd).context("spawn mcp server")?;
+    let service = tokio::time::timeout(Duration::from_secs(10), ().serve(transport))
+        .await
+        .context("timeout starting MCP server")??;
+
+    let tmp = tempfile::tempdir().context("tempdir")?;
+    let root = tmp.path();
+    std::fs::create_dir_all(root.join("src")).context("mkdir src")?;
+    std::fs::write(root.join("src").join("a.txt"), "hello\n").context("write a.txt")?;
+
+    // "ctx"'s `input` doesn't reference "files" at all -- only its `only_if` guard does. Without a
+    // guard-aware dependency graph, "ctx" gets in_degree 0 just like "files" and the concurrent
+    // scheduler (concurrency: 2) dispatches both in the same tick, so "ctx"'s guard resolves
+    // against a ref context that doesn't have "files" in it yet.
+    let args = serde_json::json!({
+        "version": 2,
+        "path": root.to_string_lossy(),
+        "max_chars": 20000,
+        "concurrency": 2,
+        "items": [
+            { "id": "files", "tool": "list_files", "input": { "file_pattern": "src/*", "limit": 10 } },
+            {
+                "id": "ctx",
+                "only_if": { "$ref": "#/items/files/data/files" },
+                "tool": "grep_context",
+                "input": { "pattern": "hello", "file": "src/a.txt", "before": 0, "after": 0 }
+            }
+        ]
+    });
+
+    let result = tokio::time::timeout(
+        Duration::from_secs(10),
+        service.call_tool(CallToolRequestParam {
+            name: "batch".into(),
+            arguments: args.as_object().cloned(),
+        }),
+    )
+    .await
+    .context("timeout calling batch")??;
+
+    assert_ne!(result.is_error, Some(true), "batch returned error");
+    let text = result
+        .content
+        .first()
+        .and_then(|c| c.as_text())
+        .map(|t| t.text.as_str())
+        .context("batch did not return text content")?;
+    let json: Value = serde_json::from_str(text).context("batch output is not valid JSON")?;
+
+    let items = json
+        .get("items")
+        .and_then(Value::as_array)
+        .context("batch items missing")?;
+    let ctx_item = items
+        .iter()
+        .find(|v| v.get("id").and_then(Value::as_str) == Some("ctx"))
+        .context("missing ctx item")?;
+    assert_eq!(
+        ctx_item.get("status").and_then(Value::as_str),
+        Some("ok"),
+        "ctx's only_if guard should wait for files to complete before resolving, got: {ctx_item:?}"
+    );
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
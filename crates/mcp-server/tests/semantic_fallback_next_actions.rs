@@ -92,7 +92,7 @@ async fn search_full_mode_suggests_grep_context_when_semantic_disabled_and_no_hi
     let watermark = compute_project_watermark(root)
         .await
         .context("compute project watermark")?;
-    write_index_watermark(&index_dir.join("index.json"), watermark)
+    write_index_watermark(root, &index_dir.join("index.json"), watermark)
         .await
         .context("write watermark.json")?;
 
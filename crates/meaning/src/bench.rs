@@ -0,0 +1,182 @@
+//! Reproducible benchmark harness for the classification/evidence pipeline.
+//!
+//! Takes a JSON workload spec (repo path -> expected anchor/boundary counts), runs
+//! `classify_files`, `classify_boundaries`, and `extract_asyncapi_flows` directly (the hot
+//! phases that scan `all_files` repeatedly), plus a full `meaning_pack` call to exercise anchor
+//! selection end-to-end, and reports per-phase wall-clock, files scanned, and bytes read as
+//! machine-readable JSON so results can be diffed across commits.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::common::{classify_boundaries, classify_files, extract_asyncapi_flows};
+use crate::model::MeaningPackRequest;
+use crate::pack::meaning_pack;
+use crate::paths::normalize_relative_path;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BenchWorkloadEntry {
+    pub repo_path: PathBuf,
+    pub expected_anchor_count: Option<usize>,
+    pub expected_boundary_count: Option<usize>,
+    /// Fractional tolerance applied to both expected counts and timing drift checks.
+    #[serde(default = "default_tolerance")]
+    pub tolerance: f64,
+}
+
+fn default_tolerance() -> f64 {
+    0.2
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BenchWorkload {
+    pub entries: Vec<BenchWorkloadEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseTiming {
+    pub phase: &'static str,
+    pub wall_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchEntryReport {
+    pub repo_path: String,
+    pub files_scanned: usize,
+    pub bytes_read: u64,
+    pub anchor_count: usize,
+    pub boundary_count: usize,
+    pub phases: Vec<PhaseTiming>,
+    pub pass: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    pub entries: Vec<BenchEntryReport>,
+    pub all_passed: bool,
+}
+
+pub async fn run_benchmark(workload_path: &Path) -> Result<BenchReport> {
+    let raw = std::fs::read_to_string(workload_path)
+        .with_context(|| format!("read workload {}", workload_path.display()))?;
+    let workload: BenchWorkload =
+        serde_json::from_str(&raw).with_context(|| "parse workload JSON")?;
+
+    let mut entries = Vec::new();
+    let mut all_passed = true;
+    for entry in &workload.entries {
+        let report = run_one(entry).await?;
+        all_passed &= report.pass;
+        entries.push(report);
+    }
+    Ok(BenchReport {
+        entries,
+        all_passed,
+    })
+}
+
+async fn run_one(entry: &BenchWorkloadEntry) -> Result<BenchEntryReport> {
+    let root = entry.repo_path.as_path();
+    let mut phases: Vec<PhaseTiming> = Vec::new();
+    let mut failures: Vec<String> = Vec::new();
+
+    let mut scanner = context_indexer::FileScanner::new(root);
+    let scan_start = Instant::now();
+    let mut files: Vec<String> = Vec::new();
+    let mut bytes_read: u64 = 0;
+    for abs in scanner.scan()? {
+        let Some(rel) = normalize_relative_path(root, &abs) else {
+            continue;
+        };
+        bytes_read += std::fs::metadata(&abs).map(|m| m.len()).unwrap_or(0);
+        files.push(rel);
+    }
+    phases.push(PhaseTiming {
+        phase: "scan",
+        wall_ms: elapsed_ms(scan_start),
+    });
+
+    let classify_start = Instant::now();
+    let (entrypoints, contracts) = classify_files(&files);
+    phases.push(PhaseTiming {
+        phase: "classify_files",
+        wall_ms: elapsed_ms(classify_start),
+    });
+
+    let boundaries_start = Instant::now();
+    let boundaries = classify_boundaries(&files, &entrypoints, &contracts);
+    phases.push(PhaseTiming {
+        phase: "classify_boundaries",
+        wall_ms: elapsed_ms(boundaries_start),
+    });
+
+    let flows_start = Instant::now();
+    let _flows = extract_asyncapi_flows(root, &contracts, None).await;
+    phases.push(PhaseTiming {
+        phase: "extract_asyncapi_flows",
+        wall_ms: elapsed_ms(flows_start),
+    });
+
+    let pack_start = Instant::now();
+    let root_display = root.to_string_lossy().to_string();
+    let pack_request = MeaningPackRequest {
+        query: "overview".to_string(),
+        map_depth: None,
+        map_limit: None,
+        max_chars: None,
+        max_tokens: None,
+        cache_path: None,
+        rules: Vec::new(),
+        semantic: None,
+        semantic_weight: None,
+    };
+    let pack_result = meaning_pack(root, &root_display, &pack_request).await?;
+    phases.push(PhaseTiming {
+        phase: "meaning_pack",
+        wall_ms: elapsed_ms(pack_start),
+    });
+    let anchor_count = pack_result.pack.matches("ANCHOR ").count();
+
+    let boundary_count = boundaries.len();
+    if let Some(expected) = entry.expected_anchor_count {
+        if !within_tolerance(anchor_count, expected, entry.tolerance) {
+            failures.push(format!(
+                "anchor_count {anchor_count} outside tolerance of expected {expected}"
+            ));
+        }
+    }
+    if let Some(expected) = entry.expected_boundary_count {
+        if !within_tolerance(boundary_count, expected, entry.tolerance) {
+            failures.push(format!(
+                "boundary_count {boundary_count} outside tolerance of expected {expected}"
+            ));
+        }
+    }
+
+    Ok(BenchEntryReport {
+        repo_path: root_display,
+        files_scanned: files.len(),
+        bytes_read,
+        anchor_count,
+        boundary_count,
+        phases,
+        pass: failures.is_empty(),
+        failures,
+    })
+}
+
+fn elapsed_ms(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.0
+}
+
+fn within_tolerance(actual: usize, expected: usize, tolerance: f64) -> bool {
+    if expected == 0 {
+        return actual == 0;
+    }
+    let diff = (actual as f64 - expected as f64).abs() / (expected as f64);
+    diff <= tolerance
+}
@@ -0,0 +1,25 @@
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let mut workload: Option<PathBuf> = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--workload" => {
+                let val = args.next().context("--workload requires a path")?;
+                workload = Some(PathBuf::from(val));
+            }
+            other => anyhow::bail!("unknown argument: {other}"),
+        }
+    }
+    let workload = workload.context("--workload <path> is required")?;
+
+    let report = context_meaning::run_benchmark(&workload).await?;
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    if !report.all_passed {
+        std::process::exit(1);
+    }
+    Ok(())
+}
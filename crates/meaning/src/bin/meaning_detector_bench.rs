@@ -0,0 +1,27 @@
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let mut workload_dir: Option<PathBuf> = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--workload-dir" => {
+                let val = args.next().context("--workload-dir requires a path")?;
+                workload_dir = Some(PathBuf::from(val));
+            }
+            other => anyhow::bail!("unknown argument: {other}"),
+        }
+    }
+    let workload_dir = workload_dir.unwrap_or_else(|| {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures/detector_workloads")
+    });
+
+    let report = context_meaning::run_detector_benchmark(&workload_dir).await?;
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    if !report.all_passed {
+        std::process::exit(1);
+    }
+    Ok(())
+}
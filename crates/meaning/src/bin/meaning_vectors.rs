@@ -0,0 +1,29 @@
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+
+fn main() -> Result<()> {
+    let mut cases_dir: Option<PathBuf> = None;
+    let mut out: Option<PathBuf> = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--cases-dir" => {
+                let val = args.next().context("--cases-dir requires a path")?;
+                cases_dir = Some(PathBuf::from(val));
+            }
+            "--out" => {
+                let val = args.next().context("--out requires a path")?;
+                out = Some(PathBuf::from(val));
+            }
+            other => anyhow::bail!("unknown argument: {other}"),
+        }
+    }
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let cases_dir = cases_dir.unwrap_or_else(|| manifest_dir.join("fixtures/vectors/cases"));
+    let out = out.unwrap_or_else(|| manifest_dir.join("fixtures/vectors/corpus.json"));
+
+    let corpus = context_meaning::convert_vectors(&cases_dir)?;
+    context_meaning::write_flattened(&corpus, &out)?;
+    println!("wrote flattened vector corpus to {}", out.display());
+    Ok(())
+}
@@ -0,0 +1,224 @@
+//! Persistent, content-addressed cache for the per-file scan work that
+//! `hash_and_count_lines` and the broker/flow detectors repeat on every call. Keyed by
+//! repo-relative path (see `normalize_relative_path`), it lets a warm re-scan of an unchanged
+//! file skip hashing entirely (size+mtime match) or skip re-analysis after a mtime-only touch
+//! (hash still matches). Cold files and genuinely changed files always fall through to the real
+//! analysis.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::common::{hash_and_count_lines, FlowDirection};
+
+/// Bumped whenever the scan/classification logic changes shape so stale entries from an old
+/// analyzer version are never mistaken for fresh ones.
+pub const CACHE_FINGERPRINT: &str = "meaning-scan-v1";
+
+/// How a [`ScanCache`] is allowed to interact with its sidecar file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CacheMode {
+    /// Caching disabled: every lookup falls through to a fresh hash, nothing is persisted.
+    #[default]
+    Off,
+    /// Read stale entries, reuse what still matches, and persist updates via `save`.
+    ReadWrite,
+    /// Read stale entries and reuse what still matches, but never write the sidecar back.
+    ReadOnly,
+}
+
+/// Cached broker classification for a single file, keyed alongside its hash so it can be
+/// reused without re-running `parse_yaml_containers` when the file is unchanged. A file can
+/// yield more than one candidate (e.g. a k8s manifest with several `containers:` entries), so
+/// these are cached per-file as a list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedBroker {
+    pub proto: String,
+    pub role: String,
+    pub confidence: f32,
+    pub image: Option<String>,
+    pub via: Option<String>,
+    pub line_range: Option<(usize, usize)>,
+}
+
+/// Cached flow edge parsed out of a single contract file (AsyncAPI/proto/Syndicate), keyed
+/// alongside its hash so `extract_asyncapi_flows`/`extract_proto_flows`/`extract_syndicate_flows`
+/// can skip re-parsing when the file is unchanged. `direction` is `FlowDirection::as_str()`
+/// ("pub"/"sub") so this stays plain-data serializable without deriving on `FlowDirection` itself.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedFlowEdge {
+    pub channel: String,
+    pub direction: String,
+    pub protocol: Option<String>,
+}
+
+impl CachedFlowEdge {
+    pub(crate) fn direction(&self) -> Option<FlowDirection> {
+        match self.direction.as_str() {
+            "pub" => Some(FlowDirection::Publish),
+            "sub" => Some(FlowDirection::Subscribe),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    sha256: String,
+    line_count: usize,
+    size: u64,
+    mtime_secs: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    brokers: Option<Vec<CachedBroker>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    flows: Option<Vec<CachedFlowEdge>>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    /// Bumped whenever the analyzer logic or query-independent config changes shape; a
+    /// mismatch invalidates the whole cache rather than risking stale derived facts.
+    fingerprint: String,
+    entries: HashMap<String, CacheEntry>,
+}
+
+/// Incremental, content-addressed scan cache backed by a single sidecar JSON file.
+pub struct ScanCache {
+    mode: CacheMode,
+    path: PathBuf,
+    fingerprint: String,
+    entries: HashMap<String, CacheEntry>,
+    dirty: bool,
+}
+
+impl ScanCache {
+    /// Loads the sidecar at `path` if `mode` permits reading. A missing file, an unparseable
+    /// file, or a `fingerprint` mismatch all start an empty (not an error) cache, since any of
+    /// those just mean "nothing usable is cached yet".
+    pub fn load(path: &Path, mode: CacheMode, fingerprint: &str) -> ScanCache {
+        let mut cache = ScanCache {
+            mode,
+            path: path.to_path_buf(),
+            fingerprint: fingerprint.to_string(),
+            entries: HashMap::new(),
+            dirty: false,
+        };
+        if mode == CacheMode::Off {
+            return cache;
+        }
+        let Ok(raw) = std::fs::read_to_string(path) else {
+            return cache;
+        };
+        let Ok(file) = serde_json::from_str::<CacheFile>(&raw) else {
+            return cache;
+        };
+        if file.fingerprint == fingerprint {
+            cache.entries = file.entries;
+        }
+        cache
+    }
+
+    /// Persists the cache to its sidecar path, atomically via a same-directory temp file.
+    /// A no-op under `Off`/`ReadOnly` or when nothing changed since `load`.
+    pub fn save(&self) -> Result<()> {
+        if self.mode != CacheMode::ReadWrite || !self.dirty {
+            return Ok(());
+        }
+        let file = CacheFile {
+            fingerprint: self.fingerprint.clone(),
+            entries: self.entries.clone(),
+        };
+        let serialized =
+            serde_json::to_string(&file).context("serialize scan cache sidecar")?;
+        let tmp_path = self.path.with_extension("tmp");
+        std::fs::write(&tmp_path, serialized)
+            .with_context(|| format!("write scan cache sidecar {}", tmp_path.display()))?;
+        std::fs::rename(&tmp_path, &self.path)
+            .with_context(|| format!("install scan cache sidecar {}", self.path.display()))?;
+        Ok(())
+    }
+
+    /// Content-addressed `hash_and_count_lines`: stats `abs` first, and if size+mtime match the
+    /// entry cached for `rel`, returns the cached hash and line count without reading the file.
+    /// Otherwise re-hashes and refreshes the entry (keeping any cached broker fact only if the
+    /// hash is unchanged, i.e. a mtime-only touch).
+    pub async fn hash_and_count_lines(&mut self, rel: &str, abs: &Path) -> Result<(String, usize)> {
+        if self.mode == CacheMode::Off {
+            return hash_and_count_lines(abs).await;
+        }
+
+        let meta = tokio::fs::metadata(abs).await?;
+        let size = meta.len();
+        let mtime_secs = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        if let Some(entry) = self.entries.get(rel) {
+            if entry.size == size && entry.mtime_secs == mtime_secs {
+                return Ok((entry.sha256.clone(), entry.line_count));
+            }
+        }
+
+        let (sha256, line_count) = hash_and_count_lines(abs).await?;
+        let stale = self.entries.get(rel).filter(|entry| entry.sha256 == sha256);
+        let brokers = stale.and_then(|entry| entry.brokers.clone());
+        let flows = stale.and_then(|entry| entry.flows.clone());
+        self.entries.insert(
+            rel.to_string(),
+            CacheEntry {
+                sha256: sha256.clone(),
+                line_count,
+                size,
+                mtime_secs,
+                brokers,
+                flows,
+            },
+        );
+        self.dirty = true;
+        Ok((sha256, line_count))
+    }
+
+    /// Returns the broker facts cached for `rel`, if its content hash still matches what's on
+    /// disk (call `hash_and_count_lines` for `rel` first so the entry is fresh).
+    pub fn cached_brokers(&self, rel: &str) -> Option<&[CachedBroker]> {
+        self.entries
+            .get(rel)
+            .and_then(|entry| entry.brokers.as_deref())
+    }
+
+    /// Records the broker candidates derived for `rel` so the next warm scan can skip
+    /// re-parsing its config when the file is unchanged.
+    pub fn put_brokers(&mut self, rel: &str, brokers: Vec<CachedBroker>) {
+        if self.mode == CacheMode::Off {
+            return;
+        }
+        if let Some(entry) = self.entries.get_mut(rel) {
+            entry.brokers = Some(brokers);
+            self.dirty = true;
+        }
+    }
+
+    /// Returns the flow edges cached for `rel`, if its content hash still matches what's on disk
+    /// (call `hash_and_count_lines` for `rel` first so the entry is fresh).
+    pub fn cached_flows(&self, rel: &str) -> Option<&[CachedFlowEdge]> {
+        self.entries.get(rel).and_then(|entry| entry.flows.as_deref())
+    }
+
+    /// Records the flow edges parsed out of `rel` so the next warm scan can skip re-parsing its
+    /// contract body when the file is unchanged.
+    pub fn put_flows(&mut self, rel: &str, flows: Vec<CachedFlowEdge>) {
+        if self.mode == CacheMode::Off {
+            return;
+        }
+        if let Some(entry) = self.entries.get_mut(rel) {
+            entry.flows = Some(flows);
+            self.dirty = true;
+        }
+    }
+}
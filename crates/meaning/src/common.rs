@@ -6,6 +6,9 @@ use std::path::Path;
 use tokio::fs::File;
 use tokio::io::AsyncReadExt;
 
+use crate::cache::{CachedBroker, CachedFlowEdge, ScanCache};
+use crate::tokens::{estimate_pack_tokens, estimator_for_pack};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub(super) enum BoundaryKind {
     Cli,
@@ -14,6 +17,7 @@ pub(super) enum BoundaryKind {
     Env,
     Config,
     Db,
+    Dependency,
 }
 
 impl BoundaryKind {
@@ -25,6 +29,7 @@ impl BoundaryKind {
             BoundaryKind::Env => "env",
             BoundaryKind::Config => "config",
             BoundaryKind::Db => "db",
+            BoundaryKind::Dependency => "dependency",
         }
     }
 }
@@ -113,6 +118,18 @@ pub(super) struct OutlineSymbol {
     pub(super) confidence: f32,
 }
 
+/// A nested document-symbol node, shaped after the LSP `textDocument/documentSymbol`
+/// response (`kind` is an LSP `SymbolKind` name, see `ChunkType::as_lsp_symbol_kind`).
+#[derive(Debug, Clone)]
+pub(super) struct OutlineNode {
+    pub(super) kind: &'static str,
+    pub(super) name: String,
+    pub(super) qualified_name: String,
+    pub(super) start_line: usize,
+    pub(super) end_line: usize,
+    pub(super) children: Vec<OutlineNode>,
+}
+
 #[derive(Debug, Clone)]
 pub(super) struct EvidenceItem {
     pub(super) kind: EvidenceKind,
@@ -166,8 +183,10 @@ pub(super) fn classify_boundaries(
     for file in files {
         let lc = file.to_ascii_lowercase();
         let kind = match lc.as_str() {
-            "cargo.toml" | "package.json" | "pyproject.toml" | "go.mod" | "pom.xml"
-            | "build.gradle" | "build.gradle.kts" | "makefile" | "justfile" => {
+            "cargo.toml" | "package.json" | "pyproject.toml" | "go.mod" => {
+                Some(BoundaryKind::Dependency)
+            }
+            "pom.xml" | "build.gradle" | "build.gradle.kts" | "makefile" | "justfile" => {
                 Some(BoundaryKind::Config)
             }
             ".env.example" | ".env.sample" | ".env.template" | ".env.dist" => {
@@ -421,12 +440,18 @@ pub(super) fn classify_boundaries(
             || lc == "asyncapi.json"
             || lc.contains("/asyncapi.")
             || lc.ends_with(".avsc")
+            || lc.ends_with(".prs")
+            || lc.ends_with("schema.bin")
             || lc.starts_with("events/")
             || lc.contains("/events/")
             || lc.starts_with("schemas/events/")
             || lc.contains("/schemas/events/")
             || lc.starts_with("messages/")
-            || lc.contains("/messages/");
+            || lc.contains("/messages/")
+            || lc.starts_with("dataspace/")
+            || lc.contains("/dataspace/")
+            || lc.starts_with("gateway/")
+            || lc.contains("/gateway/");
         if !is_event {
             continue;
         }
@@ -435,7 +460,7 @@ pub(super) fn classify_boundaries(
         }
         let confidence = if lc.contains("asyncapi") {
             1.0
-        } else if lc.ends_with(".avsc") {
+        } else if lc.ends_with(".avsc") || lc.ends_with(".prs") || lc.ends_with("schema.bin") {
             0.9
         } else {
             0.75
@@ -464,6 +489,7 @@ fn boundary_kind_rank(kind: BoundaryKind) -> usize {
         BoundaryKind::Env => 3,
         BoundaryKind::Config => 4,
         BoundaryKind::Db => 5,
+        BoundaryKind::Dependency => 6,
     }
 }
 
@@ -601,6 +627,8 @@ pub(super) fn is_contract_candidate(file_lc: &str) -> bool {
         || file_lc.ends_with("asyncapi.yaml")
         || file_lc.ends_with("asyncapi.yml")
         || file_lc.contains("/asyncapi.")
+        || file_lc.ends_with(".prs")
+        || file_lc.ends_with("schema.bin")
 }
 
 pub(super) fn contract_kind(file: &str) -> &'static str {
@@ -626,6 +654,9 @@ pub(super) fn contract_kind(file: &str) -> &'static str {
     if lc.contains("/asyncapi.") {
         return "asyncapi";
     }
+    if lc.ends_with(".prs") || lc.ends_with("schema.bin") {
+        return "syndicate";
+    }
     "contract"
 }
 
@@ -652,6 +683,25 @@ pub(super) struct FlowEdge {
     pub(super) protocol: Option<String>,
 }
 
+impl FlowEdge {
+    fn to_cached(&self) -> CachedFlowEdge {
+        CachedFlowEdge {
+            channel: self.channel.clone(),
+            direction: self.direction.as_str().to_string(),
+            protocol: self.protocol.clone(),
+        }
+    }
+
+    fn from_cached(contract_file: &str, cached: &CachedFlowEdge) -> Option<FlowEdge> {
+        Some(FlowEdge {
+            contract_file: contract_file.to_string(),
+            channel: cached.channel.clone(),
+            direction: cached.direction()?,
+            protocol: cached.protocol.clone(),
+        })
+    }
+}
+
 #[derive(Debug, Default)]
 struct AsyncApiSummary {
     protocols: Vec<String>,
@@ -665,7 +715,29 @@ struct AsyncApiChannel {
     subscribe: bool,
 }
 
-pub(super) async fn extract_asyncapi_flows(root: &Path, contracts: &[String]) -> Vec<FlowEdge> {
+/// Looks up the flow edges cached for `contract` under `cache`, refreshing its hash first so a
+/// stale cache entry (content changed since last scan) is never reused. Returns `None` when
+/// there's no cache, the entry is cold, or the cached directions failed to round-trip.
+async fn cached_flows_for_contract(
+    root: &Path,
+    contract: &str,
+    cache: Option<&mut ScanCache>,
+) -> Option<Vec<FlowEdge>> {
+    let cache = cache?;
+    let abs = root.join(contract);
+    cache.hash_and_count_lines(contract, &abs).await.ok()?;
+    let cached = cache.cached_flows(contract)?;
+    cached
+        .iter()
+        .map(|c| FlowEdge::from_cached(contract, c))
+        .collect()
+}
+
+pub(super) async fn extract_asyncapi_flows(
+    root: &Path,
+    contracts: &[String],
+    mut cache: Option<&mut ScanCache>,
+) -> Vec<FlowEdge> {
     const MAX_READ_BYTES: usize = 256 * 1024;
     const MAX_CHANNELS: usize = 10;
 
@@ -675,6 +747,12 @@ pub(super) async fn extract_asyncapi_flows(root: &Path, contracts: &[String]) ->
             continue;
         }
 
+        if let Some(cached) = cached_flows_for_contract(root, contract, cache.as_deref_mut()).await
+        {
+            out.extend(cached);
+            continue;
+        }
+
         let Some(content) = read_file_prefix_utf8(root, contract, MAX_READ_BYTES).await else {
             continue;
         };
@@ -684,9 +762,10 @@ pub(super) async fn extract_asyncapi_flows(root: &Path, contracts: &[String]) ->
 
         let mut channels = summary.channels;
         channels.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut contract_edges: Vec<FlowEdge> = Vec::new();
         for ch in channels.into_iter().take(MAX_CHANNELS) {
             if ch.publish {
-                out.push(FlowEdge {
+                contract_edges.push(FlowEdge {
                     contract_file: contract.clone(),
                     channel: ch.name.clone(),
                     direction: FlowDirection::Publish,
@@ -694,7 +773,7 @@ pub(super) async fn extract_asyncapi_flows(root: &Path, contracts: &[String]) ->
                 });
             }
             if ch.subscribe {
-                out.push(FlowEdge {
+                contract_edges.push(FlowEdge {
                     contract_file: contract.clone(),
                     channel: ch.name.clone(),
                     direction: FlowDirection::Subscribe,
@@ -702,6 +781,13 @@ pub(super) async fn extract_asyncapi_flows(root: &Path, contracts: &[String]) ->
                 });
             }
         }
+        if let Some(cache) = cache.as_deref_mut() {
+            cache.put_flows(
+                contract,
+                contract_edges.iter().map(FlowEdge::to_cached).collect(),
+            );
+        }
+        out.extend(contract_edges);
     }
 
     out.sort_by(|a, b| {
@@ -713,225 +799,1580 @@ pub(super) async fn extract_asyncapi_flows(root: &Path, contracts: &[String]) ->
     out
 }
 
-pub(super) async fn read_file_prefix_utf8(
-    root: &Path,
-    rel: &str,
-    max_bytes: usize,
-) -> Option<String> {
-    let abs = root.join(rel);
-    let mut file = File::open(abs).await.ok()?;
-    let mut buf = vec![0u8; max_bytes];
-    let n = file.read(&mut buf).await.ok()?;
-    buf.truncate(n);
-    String::from_utf8(buf).ok()
-}
-
-fn extract_asyncapi_summary(content: &str) -> AsyncApiSummary {
-    if let Ok(json) = serde_json::from_str::<serde_json::Value>(content) {
-        return extract_asyncapi_summary_json(&json);
-    }
-    extract_asyncapi_summary_yaml_like(content)
+#[derive(Debug, Clone)]
+pub(super) struct EnvVar {
+    pub(super) file: String,
+    pub(super) key: String,
+    pub(super) has_default: bool,
 }
 
-fn extract_asyncapi_summary_json(value: &serde_json::Value) -> AsyncApiSummary {
-    let mut out = AsyncApiSummary::default();
+/// Parses `.env.example`-style templates into declared keys, mirroring
+/// `extract_asyncapi_flows`'s "read prefix, extract structured facts" shape.
+///
+/// Parsing rules: skip blank lines and `#` comments, strip a leading `export `
+/// token, split on the first `=`, and treat the left side as the key. A value
+/// (even an inline `# comment` after an unquoted one) marks `has_default`.
+pub(super) async fn extract_env_vars(root: &Path, files: &[String]) -> Vec<EnvVar> {
+    const MAX_READ_BYTES: usize = 64 * 1024;
+    const MAX_CHANNELS: usize = 50;
 
-    if let Some(servers) = value.get("servers").and_then(|v| v.as_object()) {
-        for server in servers.values() {
-            if let Some(protocol) = server.get("protocol").and_then(|v| v.as_str()) {
-                let protocol = protocol.trim().to_ascii_lowercase();
-                if protocol.is_empty() {
-                    continue;
-                }
-                if !out.protocols.iter().any(|p| p == &protocol) {
-                    out.protocols.push(protocol);
-                }
-            }
-        }
-    }
+    let mut env_files: Vec<&String> = files
+        .iter()
+        .filter(|file| {
+            let lc = file.to_ascii_lowercase();
+            matches!(
+                lc.as_str(),
+                ".env.example" | ".env.sample" | ".env.template" | ".env.dist"
+            )
+        })
+        .collect();
+    env_files.sort();
 
-    if let Some(channels) = value.get("channels").and_then(|v| v.as_object()) {
-        for (name, channel) in channels {
-            let publish = channel.get("publish").is_some();
-            let subscribe = channel.get("subscribe").is_some();
-            out.channels.push(AsyncApiChannel {
-                name: name.clone(),
-                publish,
-                subscribe,
+    let mut out: Vec<EnvVar> = Vec::new();
+    for file in env_files {
+        let Some(content) = read_file_prefix_utf8(root, file, MAX_READ_BYTES).await else {
+            continue;
+        };
+        let mut vars = parse_env_template(&content);
+        vars.sort_by(|a, b| a.0.cmp(&b.0));
+        for (key, has_default) in vars {
+            out.push(EnvVar {
+                file: file.clone(),
+                key,
+                has_default,
             });
         }
     }
 
+    out.sort_by(|a, b| a.file.cmp(&b.file).then_with(|| a.key.cmp(&b.key)));
+    out.truncate(MAX_CHANNELS);
     out
 }
 
-fn extract_asyncapi_summary_yaml_like(content: &str) -> AsyncApiSummary {
-    let mut out = AsyncApiSummary::default();
-
-    // Best-effort protocol detection: look for `protocol: <value>` lines.
-    for raw in content.lines().take(5000) {
+fn parse_env_template(content: &str) -> Vec<(String, bool)> {
+    let mut out: Vec<(String, bool)> = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    for raw in content.lines() {
         let line = raw.trim();
         if line.is_empty() || line.starts_with('#') {
             continue;
         }
-        let Some(rest) = line.strip_prefix("protocol:") else {
+        let line = line.strip_prefix("export ").unwrap_or(line).trim_start();
+        let Some((key, rest)) = line.split_once('=') else {
             continue;
         };
-        let protocol = rest.trim().trim_matches('"').trim_matches('\'');
-        if protocol.is_empty() {
+        let key = key.trim();
+        if key.is_empty() {
             continue;
         }
-        let protocol = protocol.to_ascii_lowercase();
-        if !out.protocols.iter().any(|p| p == &protocol) {
-            out.protocols.push(protocol);
+        let value = strip_inline_comment(rest.trim());
+        if !seen.insert(key.to_string()) {
+            continue;
         }
+        out.push((key.to_string(), !value.is_empty()));
     }
+    out
+}
 
-    // Best-effort channel extraction from YAML:
-    // channels:
-    //   topic.name:
-    //     publish:
-    //     subscribe:
-    let lines: Vec<&str> = content.lines().collect();
-    let mut idx = 0usize;
-    while idx < lines.len() {
-        let raw = lines[idx];
-        if raw.trim_start().starts_with("channels:") {
-            break;
+fn strip_inline_comment(value: &str) -> String {
+    if let Some(quoted) = value.strip_prefix('"') {
+        if let Some(end) = quoted.find('"') {
+            return quoted[..end].to_string();
         }
-        idx += 1;
+        return quoted.to_string();
     }
-    if idx >= lines.len() {
-        return out;
+    if let Some(quoted) = value.strip_prefix('\'') {
+        if let Some(end) = quoted.find('\'') {
+            return quoted[..end].to_string();
+        }
+        return quoted.to_string();
     }
+    // Unquoted values may carry a trailing `# comment`.
+    value.split('#').next().unwrap_or("").trim().to_string()
+}
 
-    let channels_indent = count_leading_spaces(lines[idx]);
-    idx += 1;
+#[derive(Debug, Clone)]
+pub(super) struct RouteEdge {
+    pub(super) contract_file: String,
+    pub(super) path: String,
+    pub(super) method: String,
+    pub(super) operation_id: Option<String>,
+}
 
-    let mut current: Option<AsyncApiChannel> = None;
-    let mut current_indent: usize = 0;
+const HTTP_METHODS: &[&str] = &[
+    "get", "post", "put", "delete", "patch", "head", "options",
+];
 
-    while idx < lines.len() {
-        let raw = lines[idx];
-        let trimmed = raw.trim();
-        if trimmed.is_empty() || trimmed.starts_with('#') {
-            idx += 1;
+/// REST counterpart of `extract_asyncapi_flows`: walks an OpenAPI document's `paths` object
+/// and emits one route per HTTP-method sub-key.
+pub(super) async fn extract_openapi_flows(root: &Path, contracts: &[String]) -> Vec<RouteEdge> {
+    const MAX_READ_BYTES: usize = 256 * 1024;
+    const MAX_CHANNELS: usize = 10;
+
+    let mut out: Vec<RouteEdge> = Vec::new();
+    for contract in contracts {
+        if contract_kind(contract) != "openapi" {
             continue;
         }
-        let indent = count_leading_spaces(raw);
-        if indent <= channels_indent {
-            break;
-        }
 
-        if trimmed.ends_with(':') && !trimmed.starts_with('-') {
-            let key = trimmed.trim_end_matches(':').trim();
-            let key = key.trim_matches('"').trim_matches('\'');
-            if !key.is_empty() && key != "publish" && key != "subscribe" {
-                if let Some(ch) = current.take() {
-                    out.channels.push(ch);
-                }
-                current_indent = indent;
-                current = Some(AsyncApiChannel {
-                    name: key.to_string(),
-                    publish: false,
-                    subscribe: false,
-                });
-                idx += 1;
-                continue;
-            }
+        let Some(content) = read_file_prefix_utf8(root, contract, MAX_READ_BYTES).await else {
+            continue;
+        };
+
+        let mut routes: Vec<RouteEdge> = if let Ok(json) =
+            serde_json::from_str::<serde_json::Value>(&content)
+        {
+            extract_openapi_routes_json(contract, &json)
+        } else {
+            extract_openapi_routes_yaml_like(contract, &content)
+        };
+        routes.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.method.cmp(&b.method)));
+        routes.dedup_by(|a, b| a.path == b.path && a.method == b.method);
+        out.extend(routes.into_iter().take(MAX_CHANNELS));
+    }
+
+    out.sort_by(|a, b| {
+        a.contract_file
+            .cmp(&b.contract_file)
+            .then_with(|| a.path.cmp(&b.path))
+            .then_with(|| a.method.cmp(&b.method))
+    });
+    out
+}
+
+/// Lightweight `.proto` scanner: tracks `service <Name> {` blocks by brace depth and matches
+/// `rpc <Method>(<Req>) returns (<Resp>);` within them, mirroring `extract_asyncapi_flows`'s
+/// shape but for gRPC service definitions.
+pub(super) async fn extract_proto_flows(
+    root: &Path,
+    contracts: &[String],
+    mut cache: Option<&mut ScanCache>,
+) -> Vec<FlowEdge> {
+    const MAX_READ_BYTES: usize = 256 * 1024;
+    const MAX_CHANNELS: usize = 10;
+
+    let mut out: Vec<FlowEdge> = Vec::new();
+    for contract in contracts {
+        if contract_kind(contract) != "proto" {
+            continue;
         }
 
-        if let Some(ch) = current.as_mut() {
-            if indent > current_indent {
-                if trimmed.starts_with("publish:") {
-                    ch.publish = true;
-                } else if trimmed.starts_with("subscribe:") {
-                    ch.subscribe = true;
-                }
-            }
+        if let Some(cached) = cached_flows_for_contract(root, contract, cache.as_deref_mut()).await
+        {
+            out.extend(cached);
+            continue;
         }
 
-        idx += 1;
-    }
+        let Some(content) = read_file_prefix_utf8(root, contract, MAX_READ_BYTES).await else {
+            continue;
+        };
 
-    if let Some(ch) = current.take() {
-        out.channels.push(ch);
+        let mut edges = parse_proto_rpcs(&content);
+        edges.sort_by(|a, b| a.channel.cmp(&b.channel));
+        edges.dedup_by(|a, b| a.channel == b.channel);
+        let mut contract_edges: Vec<FlowEdge> = Vec::new();
+        for edge in edges.into_iter().take(MAX_CHANNELS) {
+            contract_edges.push(FlowEdge {
+                contract_file: contract.clone(),
+                channel: edge.channel,
+                direction: edge.direction,
+                protocol: Some("grpc".to_string()),
+            });
+        }
+        if let Some(cache) = cache.as_deref_mut() {
+            cache.put_flows(
+                contract,
+                contract_edges.iter().map(FlowEdge::to_cached).collect(),
+            );
+        }
+        out.extend(contract_edges);
     }
 
+    out.sort_by(|a, b| {
+        a.contract_file
+            .cmp(&b.contract_file)
+            .then_with(|| a.channel.cmp(&b.channel))
+    });
     out
 }
 
-fn count_leading_spaces(s: &str) -> usize {
-    s.as_bytes().iter().take_while(|&&b| b == b' ').count()
+struct ProtoRpc {
+    channel: String,
+    direction: FlowDirection,
 }
 
-pub(super) async fn detect_channel_mentions(
-    root: &Path,
-    files: &[String],
-    channels: &[String],
-) -> HashMap<String, String> {
-    const MAX_SCAN_FILES: usize = 200;
-    const MAX_READ_BYTES: usize = 64 * 1024;
-    const MAX_CHANNELS: usize = 20;
-
-    let mut wanted: Vec<String> = channels.to_vec();
-    wanted.sort();
-    wanted.dedup();
-    wanted.truncate(MAX_CHANNELS);
-
-    let mut out: HashMap<String, String> = HashMap::new();
-    if wanted.is_empty() {
-        return out;
-    }
+fn parse_proto_rpcs(content: &str) -> Vec<ProtoRpc> {
+    let mut out: Vec<ProtoRpc> = Vec::new();
+    let mut depth: i32 = 0;
+    let mut service_stack: Vec<(String, i32)> = Vec::new();
 
-    let mut candidates: Vec<&String> = files
-        .iter()
-        .filter(|file| is_code_file_candidate(&file.to_ascii_lowercase()))
-        .collect();
-    candidates.sort();
+    for raw in content.lines() {
+        let line = raw.trim();
+        let service_depth_before = depth;
 
-    for file in candidates.into_iter().take(MAX_SCAN_FILES) {
-        if out.len() >= wanted.len() {
-            break;
-        }
-        let Some(content) = read_file_prefix_utf8(root, file, MAX_READ_BYTES).await else {
-            continue;
-        };
-        for channel in &wanted {
-            if out.contains_key(channel) {
-                continue;
+        if let Some(rest) = line.strip_prefix("service ") {
+            if let Some(name) = rest.split_whitespace().next() {
+                service_stack.push((name.trim_end_matches('{').to_string(), depth));
             }
-            if content.contains(channel) {
-                out.insert(channel.clone(), file.clone());
+        } else if let Some(current_service) = service_stack.last().map(|(name, _)| name.clone()) {
+            if let Some(rpc) = parse_rpc_line(line) {
+                out.push(ProtoRpc {
+                    channel: format!("{current_service}.{}", rpc.0),
+                    direction: rpc.1,
+                });
             }
         }
+
+        depth += line.matches('{').count() as i32 - line.matches('}').count() as i32;
+        let _ = service_depth_before;
+        service_stack.retain(|(_, opened_at)| depth > *opened_at);
     }
-    out
-}
 
-#[derive(Debug, Clone)]
-pub(super) struct BrokerCandidate {
-    pub(super) proto: String,
-    pub(super) file: String,
-    pub(super) confidence: f32,
+    out
 }
 
-pub(super) async fn detect_brokers(
+/// Syndicate/Preserves dataspace counterpart of `extract_proto_flows`: a dataspace has no
+/// channels, only assertions/retractions/messages published against shared patterns, so this
+/// walks a `.prs` schema line-by-line for `assert`/`retract`/`message` declarations instead of
+/// `rpc` lines, and treats the asserted/retracted/messaged pattern name as the `channel`.
+pub(super) async fn extract_syndicate_flows(
     root: &Path,
-    files: &[String],
-    flows: &[FlowEdge],
-) -> Vec<BrokerCandidate> {
-    const MAX_CANDIDATE_FILES: usize = 30;
-    const MAX_READ_BYTES: usize = 192 * 1024;
-    const MAX_BROKERS: usize = 4;
+    contracts: &[String],
+    mut cache: Option<&mut ScanCache>,
+) -> Vec<FlowEdge> {
+    const MAX_READ_BYTES: usize = 256 * 1024;
+    const MAX_CHANNELS: usize = 10;
 
-    let mut wanted: Vec<String> = flows
-        .iter()
-        .filter_map(|f| f.protocol.as_ref())
-        .map(|p| p.to_ascii_lowercase())
-        .collect();
-    wanted.sort();
-    wanted.dedup();
+    let mut out: Vec<FlowEdge> = Vec::new();
+    for contract in contracts {
+        if contract_kind(contract) != "syndicate" {
+            continue;
+        }
+
+        if let Some(cached) = cached_flows_for_contract(root, contract, cache.as_deref_mut()).await
+        {
+            out.extend(cached);
+            continue;
+        }
+
+        let Some(content) = read_file_prefix_utf8(root, contract, MAX_READ_BYTES).await else {
+            continue;
+        };
+
+        let mut edges = parse_syndicate_patterns(&content);
+        edges.sort_by(|a, b| a.0.cmp(&b.0));
+        edges.dedup_by(|a, b| a.0 == b.0);
+        let mut contract_edges: Vec<FlowEdge> = Vec::new();
+        for (pattern, direction) in edges.into_iter().take(MAX_CHANNELS) {
+            contract_edges.push(FlowEdge {
+                contract_file: contract.clone(),
+                channel: pattern,
+                direction,
+                protocol: Some("syndicate".to_string()),
+            });
+        }
+        if let Some(cache) = cache.as_deref_mut() {
+            cache.put_flows(
+                contract,
+                contract_edges.iter().map(FlowEdge::to_cached).collect(),
+            );
+        }
+        out.extend(contract_edges);
+    }
+
+    out.sort_by(|a, b| {
+        a.contract_file
+            .cmp(&b.contract_file)
+            .then_with(|| a.channel.cmp(&b.channel))
+    });
+    out
+}
+
+/// Extracts `(pattern, direction)` pairs from `assert`/`retract`/`message` declarations. A
+/// `retract` undoes a previously asserted fact, so it is modeled as the inverse (subscribe) side
+/// of the same edge; `assert` and `message` are both outbound (publish) actions from the
+/// declaring actor's perspective.
+fn parse_syndicate_patterns(content: &str) -> Vec<(String, FlowDirection)> {
+    let mut out = Vec::new();
+    for raw in content.lines() {
+        let line = raw.trim();
+        let (verb, rest) = if let Some(rest) = line.strip_prefix("assert ") {
+            ("assert", rest)
+        } else if let Some(rest) = line.strip_prefix("retract ") {
+            ("retract", rest)
+        } else if let Some(rest) = line.strip_prefix("message ") {
+            ("message", rest)
+        } else {
+            continue;
+        };
+
+        let pattern = rest
+            .trim_start_matches('<')
+            .split(|c: char| c.is_whitespace() || c == '>' || c == '(')
+            .next()
+            .unwrap_or("")
+            .trim();
+        if pattern.is_empty() {
+            continue;
+        }
+
+        let direction = if verb == "retract" {
+            FlowDirection::Subscribe
+        } else {
+            FlowDirection::Publish
+        };
+        out.push((pattern.to_string(), direction));
+    }
+    out
+}
+
+fn parse_rpc_line(line: &str) -> Option<(String, FlowDirection)> {
+    let rest = line.strip_prefix("rpc ")?.trim();
+    let paren = rest.find('(')?;
+    let method = rest[..paren].trim();
+    if method.is_empty() {
+        return None;
+    }
+    let after_paren = &rest[paren + 1..];
+    let close = after_paren.find(')')?;
+    let request = after_paren[..close].trim();
+    let returns_part = &after_paren[close + 1..];
+    let direction = if request.trim_start().starts_with("stream") {
+        FlowDirection::Subscribe
+    } else if returns_part.contains("stream") {
+        FlowDirection::Subscribe
+    } else {
+        FlowDirection::Publish
+    };
+    Some((method.to_string(), direction))
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct DependencyInfo {
+    pub(super) manifest_file: String,
+    pub(super) name: String,
+    pub(super) version: Option<String>,
+}
+
+/// Parses a package manifest's direct-dependency section into a structured list, so an agent
+/// can see "this service talks to postgres/redis/kafka via crates X, Y, Z" without opening it.
+/// Lockfiles are intentionally not consulted: direct-dependency names are already the
+/// high-signal entry point, and the top-N cap below keeps this bounded for large monorepos.
+pub(super) async fn extract_dependencies(root: &Path, manifest_file: &str) -> Vec<DependencyInfo> {
+    const MAX_READ_BYTES: usize = 256 * 1024;
+    const MAX_DEPENDENCIES: usize = 15;
+
+    let Some(content) = read_file_prefix_utf8(root, manifest_file, MAX_READ_BYTES).await else {
+        return Vec::new();
+    };
+
+    let lc = manifest_file.to_ascii_lowercase();
+    let mut deps = if lc == "cargo.toml" {
+        parse_cargo_toml_deps(&content)
+    } else if lc == "package.json" {
+        parse_package_json_deps(&content)
+    } else if lc == "go.mod" {
+        parse_go_mod_deps(&content)
+    } else if lc == "pyproject.toml" {
+        parse_pyproject_deps(&content)
+    } else {
+        Vec::new()
+    };
+
+    deps.sort_by(|a, b| a.0.cmp(&b.0));
+    deps.dedup_by(|a, b| a.0 == b.0);
+    deps.truncate(MAX_DEPENDENCIES);
+
+    deps.into_iter()
+        .map(|(name, version)| DependencyInfo {
+            manifest_file: manifest_file.to_string(),
+            name,
+            version,
+        })
+        .collect()
+}
+
+fn parse_cargo_toml_deps(content: &str) -> Vec<(String, Option<String>)> {
+    let mut out = Vec::new();
+    let mut in_deps_section = false;
+    for raw in content.lines() {
+        let line = raw.trim();
+        if line.starts_with('[') {
+            in_deps_section = line == "[dependencies]";
+            continue;
+        }
+        if !in_deps_section || line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((name, rest)) = line.split_once('=') else {
+            continue;
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            continue;
+        }
+        let rest = rest.trim();
+        let version = if let Some(v) = rest.strip_prefix('"') {
+            v.find('"').map(|end| v[..end].to_string())
+        } else {
+            // Inline table: { version = "1.0", ... }
+            rest.find("version")
+                .and_then(|_| rest.split('"').nth(1))
+                .map(str::to_string)
+        };
+        out.push((name.to_string(), version));
+    }
+    out
+}
+
+fn parse_package_json_deps(content: &str) -> Vec<(String, Option<String>)> {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+    let Some(deps) = value.get("dependencies").and_then(|v| v.as_object()) else {
+        return Vec::new();
+    };
+    deps.iter()
+        .map(|(name, version)| {
+            (
+                name.clone(),
+                version.as_str().map(|s| s.trim_start_matches('^').trim_start_matches('~').to_string()),
+            )
+        })
+        .collect()
+}
+
+fn parse_go_mod_deps(content: &str) -> Vec<(String, Option<String>)> {
+    let mut out = Vec::new();
+    let mut in_require_block = false;
+    for raw in content.lines() {
+        let line = raw.trim();
+        if line.starts_with("require (") {
+            in_require_block = true;
+            continue;
+        }
+        if in_require_block && line == ")" {
+            in_require_block = false;
+            continue;
+        }
+        let entry = if in_require_block {
+            Some(line)
+        } else {
+            line.strip_prefix("require ")
+        };
+        let Some(entry) = entry else { continue };
+        let mut parts = entry.split_whitespace();
+        let Some(name) = parts.next() else { continue };
+        if name.is_empty() {
+            continue;
+        }
+        let version = parts.next().map(str::to_string);
+        out.push((name.to_string(), version));
+    }
+    out
+}
+
+fn parse_pyproject_deps(content: &str) -> Vec<(String, Option<String>)> {
+    let mut out = Vec::new();
+    let mut in_deps_section = false;
+    for raw in content.lines() {
+        let line = raw.trim();
+        if line.starts_with('[') {
+            in_deps_section = line == "[tool.poetry.dependencies]" || line == "[project]";
+            continue;
+        }
+        if !in_deps_section || line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some((name, rest)) = line.split_once('=') {
+            let name = name.trim();
+            if name.is_empty() || name == "python" {
+                continue;
+            }
+            let rest = rest.trim();
+            let version = rest
+                .strip_prefix('"')
+                .and_then(|v| v.find('"').map(|end| v[..end].to_string()));
+            out.push((name.to_string(), version));
+        }
+    }
+    out
+}
+
+/// Query term sets for the semantic rankers below (TF-IDF and embedding tiers alike), keyed by
+/// anchor/boundary role.
+pub(super) fn semantic_role_terms(role: &str) -> &'static [&'static str] {
+    match role {
+        "canon" => &["overview", "architecture", "getting", "started", "philosophy", "design"],
+        "howto" => &["run", "test", "build", "install", "setup", "usage"],
+        "contract" => &["endpoint", "schema", "request", "response", "api", "interface"],
+        "infra" => &["deploy", "cluster", "service", "container", "provision"],
+        _ => &[],
+    }
+}
+
+/// Embedding-free content ranker: TF-IDF over the truncated content of candidate files, scored
+/// by cosine similarity against a role's query term set. This is a fallback signal layered on
+/// top of the existing path heuristics (see callers), not a replacement for them — it returns
+/// an empty map (callers keep their path-only confidence) whenever the corpus is empty or no
+/// candidate content can be read.
+pub(super) async fn tfidf_role_scores(
+    root: &Path,
+    candidates: &[String],
+    role: &str,
+) -> HashMap<String, f32> {
+    const MAX_READ_BYTES: usize = 64 * 1024;
+    const MAX_CANDIDATES: usize = 12;
+
+    let query_terms = semantic_role_terms(role);
+    if query_terms.is_empty() || candidates.is_empty() {
+        return HashMap::new();
+    }
+
+    let mut docs: Vec<(String, HashMap<String, u32>)> = Vec::new();
+    for file in candidates.iter().take(MAX_CANDIDATES) {
+        let Some(content) = read_file_prefix_utf8(root, file, MAX_READ_BYTES).await else {
+            continue;
+        };
+        docs.push((file.clone(), tokenize_term_counts(&content)));
+    }
+    if docs.is_empty() {
+        return HashMap::new();
+    }
+
+    let mut doc_freq: HashMap<&str, usize> = HashMap::new();
+    for (_, counts) in &docs {
+        for term in counts.keys() {
+            *doc_freq.entry(term.as_str()).or_insert(0) += 1;
+        }
+    }
+    let n_docs = docs.len() as f32;
+    let idf = |term: &str| -> f32 {
+        let df = doc_freq.get(term).copied().unwrap_or(0) as f32;
+        ((n_docs + 1.0) / (df + 1.0)).ln() + 1.0
+    };
+
+    let mut out: HashMap<String, f32> = HashMap::new();
+    for (file, counts) in &docs {
+        let mut dot = 0.0f32;
+        let mut doc_norm = 0.0f32;
+        for (term, count) in counts {
+            let weight = (*count as f32) * idf(term);
+            doc_norm += weight * weight;
+            if query_terms.contains(&term.as_str()) {
+                dot += weight * idf(term);
+            }
+        }
+        let query_norm = (query_terms.len() as f32).sqrt();
+        let denom = doc_norm.sqrt() * query_norm;
+        let score = if denom > 0.0 { (dot / denom).clamp(0.0, 1.0) } else { 0.0 };
+        out.insert(file.clone(), score);
+    }
+    out
+}
+
+fn tokenize_term_counts(content: &str) -> HashMap<String, u32> {
+    let mut counts: HashMap<String, u32> = HashMap::new();
+    let mut current = String::new();
+    for ch in content.chars().take(200_000) {
+        if ch.is_alphanumeric() {
+            current.push(ch.to_ascii_lowercase());
+        } else if !current.is_empty() {
+            *counts.entry(std::mem::take(&mut current)).or_insert(0) += 1;
+        }
+    }
+    if !current.is_empty() {
+        *counts.entry(current).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Splits on non-alphanumeric boundaries and lowercases. Shared tokenizer for the query-aware
+/// BM25 ranker below and the pack-shrinking one in this module.
+fn tokenize_query_terms(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|tok| !tok.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect()
+}
+
+/// Query-aware relevance ranker: scores each candidate file as a BM25 "document" whose terms are
+/// its path segments plus the prefix of its content, against the tokenized `query`. Used to
+/// surface anchors/evidence/flows/map rows that actually match what was asked, instead of relying
+/// purely on static path heuristics. Returns scores normalized to `[0, 1]` (divided by the best
+/// score in the batch) so callers can blend them with an existing `[0, 1]` confidence. Returns an
+/// empty map when the query is empty, has no recognizable terms, or no candidate content/paths
+/// score above zero — callers fall back to their existing ordering in that case.
+pub(super) async fn bm25_query_file_scores(
+    root: &Path,
+    query: &str,
+    candidates: &[String],
+) -> HashMap<String, f64> {
+    const MAX_READ_BYTES: usize = 64 * 1024;
+    const MAX_CANDIDATES: usize = 64;
+    const K1: f64 = 1.2;
+    const B: f64 = 0.75;
+
+    let query_terms = tokenize_query_terms(query);
+    if query_terms.is_empty() || candidates.is_empty() {
+        return HashMap::new();
+    }
+
+    let mut dedup: HashSet<&str> = HashSet::new();
+    let mut docs: Vec<(String, Vec<String>)> = Vec::new();
+    for file in candidates {
+        if !dedup.insert(file.as_str()) || docs.len() >= MAX_CANDIDATES {
+            continue;
+        }
+        let mut terms = tokenize_query_terms(file);
+        if let Some(content) = read_file_prefix_utf8(root, file, MAX_READ_BYTES).await {
+            terms.extend(tokenize_query_terms(&content));
+        }
+        docs.push((file.clone(), terms));
+    }
+    if docs.is_empty() {
+        return HashMap::new();
+    }
+
+    let n = docs.len() as f64;
+    let avgdl = docs.iter().map(|(_, toks)| toks.len()).sum::<usize>() as f64 / n;
+
+    let mut idf: HashMap<&str, f64> = HashMap::new();
+    for term in &query_terms {
+        if idf.contains_key(term.as_str()) {
+            continue;
+        }
+        let n_t = docs
+            .iter()
+            .filter(|(_, toks)| toks.iter().any(|tok| tok == term))
+            .count() as f64;
+        idf.insert(term.as_str(), ((n - n_t + 0.5) / (n_t + 0.5) + 1.0).ln());
+    }
+
+    let mut raw: HashMap<String, f64> = HashMap::new();
+    let mut max_score = 0.0f64;
+    for (file, toks) in &docs {
+        let dl = toks.len().max(1) as f64;
+        let mut score = 0.0;
+        for term in &query_terms {
+            let f = toks.iter().filter(|tok| *tok == term).count() as f64;
+            if f == 0.0 {
+                continue;
+            }
+            let term_idf = idf.get(term.as_str()).copied().unwrap_or(0.0);
+            score += term_idf * (f * (K1 + 1.0)) / (f + K1 * (1.0 - B + B * dl / avgdl));
+        }
+        max_score = max_score.max(score);
+        raw.insert(file.clone(), score);
+    }
+    if max_score <= 0.0 {
+        return HashMap::new();
+    }
+
+    raw.into_iter().map(|(file, score)| (file, (score / max_score).clamp(0.0, 1.0))).collect()
+}
+
+/// Pluggable embedder for the semantic tier of channel-mention/anchor-file matching: unlike
+/// `tfidf_role_scores`'s bag-of-words cosine score, this is meant to be backed by a real model
+/// when one is configured, falling back to `HashingEmbedder` for offline use.
+pub(super) trait SemanticEmbedder: Send + Sync {
+    fn embed(&self, text: &str) -> Vec<f32>;
+}
+
+/// Offline default embedder: hashes character trigrams of each identifier word into a
+/// fixed-size bucket vector and L2-normalizes it. No model download, so callers always have a
+/// working semantic tier even when no richer `SemanticEmbedder` is configured; it catches
+/// paraphrases and identifier renames (e.g. `user.signup` vs `UserSignedUp`) that plain
+/// substring matching misses, at lower fidelity than a real embedding model.
+pub(super) struct HashingEmbedder {
+    dims: usize,
+}
+
+impl HashingEmbedder {
+    pub(super) fn new(dims: usize) -> Self {
+        Self { dims: dims.max(8) }
+    }
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        Self::new(256)
+    }
+}
+
+impl SemanticEmbedder for HashingEmbedder {
+    fn embed(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0f32; self.dims];
+        for word in split_identifier_words(text) {
+            let chars: Vec<char> = word.chars().collect();
+            if chars.len() < 3 {
+                vector[(fnv1a_hash(word.as_bytes()) as usize) % self.dims] += 1.0;
+                continue;
+            }
+            for trigram in chars.windows(3) {
+                let gram: String = trigram.iter().collect();
+                vector[(fnv1a_hash(gram.as_bytes()) as usize) % self.dims] += 1.0;
+            }
+        }
+        let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            for x in vector.iter_mut() {
+                *x /= norm;
+            }
+        }
+        vector
+    }
+}
+
+/// Splits an identifier or phrase into lowercase words on camelCase boundaries and any
+/// non-alphanumeric separator (`.`, `_`, `-`, whitespace, ...), e.g. `UserSignedUp` and
+/// `user.signup` both become comparable word sets.
+fn split_identifier_words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut prev_was_lower = false;
+    for ch in text.chars() {
+        if ch.is_alphanumeric() {
+            if ch.is_uppercase() && prev_was_lower && !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            current.push(ch.to_ascii_lowercase());
+            prev_was_lower = ch.is_lowercase() || ch.is_numeric();
+        } else {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev_was_lower = false;
+        }
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+pub(super) fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    (dot / (norm_a * norm_b)).clamp(-1.0, 1.0)
+}
+
+/// Semantic tier for channel-mention/anchor-file matching: chunks each candidate via the
+/// existing `context_code_chunker` (reusing its embeddings-sized chunks), embeds every chunk
+/// and the query terms with `embedder`, and returns each candidate's best cosine score. This is
+/// deliberately a second tier behind cheap substring/path matching (see callers in `pack.rs`)
+/// — only unresolved or tied items are worth the chunking cost.
+pub(super) async fn semantic_chunk_scores(
+    root: &Path,
+    candidates: &[String],
+    query_terms: &[&str],
+    embedder: &dyn SemanticEmbedder,
+) -> HashMap<String, f32> {
+    const MAX_READ_BYTES: usize = 96 * 1024;
+    const MAX_CANDIDATES: usize = 40;
+
+    let mut out: HashMap<String, f32> = HashMap::new();
+    if query_terms.is_empty() || candidates.is_empty() {
+        return out;
+    }
+    let query_vector = embedder.embed(&query_terms.join(" "));
+
+    for file in candidates.iter().take(MAX_CANDIDATES) {
+        let Some(content) = read_file_prefix_utf8(root, file, MAX_READ_BYTES).await else {
+            continue;
+        };
+        let chunker = Chunker::new(ChunkerConfig::for_embeddings());
+        let chunks = chunker.chunk_str(&content, Some(file.as_str())).unwrap_or_default();
+        let mut best = 0.0f32;
+        for chunk in &chunks {
+            let score = cosine_similarity(&query_vector, &embedder.embed(&chunk.content));
+            if score > best {
+                best = score;
+            }
+        }
+        if best > 0.0 {
+            out.insert(file.clone(), best);
+        }
+    }
+    out
+}
+
+pub(super) async fn read_file_prefix_utf8(
+    root: &Path,
+    rel: &str,
+    max_bytes: usize,
+) -> Option<String> {
+    let abs = root.join(rel);
+    let mut file = File::open(abs).await.ok()?;
+    let mut buf = vec![0u8; max_bytes];
+    let n = file.read(&mut buf).await.ok()?;
+    buf.truncate(n);
+    String::from_utf8(buf).ok()
+}
+
+fn extract_asyncapi_summary(content: &str) -> AsyncApiSummary {
+    if let Ok(json) = serde_json::from_str::<serde_json::Value>(content) {
+        return extract_asyncapi_summary_json(&json);
+    }
+    extract_asyncapi_summary_yaml_like(content)
+}
+
+fn extract_asyncapi_summary_json(value: &serde_json::Value) -> AsyncApiSummary {
+    let mut out = AsyncApiSummary::default();
+
+    if let Some(servers) = value.get("servers").and_then(|v| v.as_object()) {
+        for server in servers.values() {
+            if let Some(protocol) = server.get("protocol").and_then(|v| v.as_str()) {
+                let protocol = protocol.trim().to_ascii_lowercase();
+                if protocol.is_empty() {
+                    continue;
+                }
+                if !out.protocols.iter().any(|p| p == &protocol) {
+                    out.protocols.push(protocol);
+                }
+            }
+        }
+    }
+
+    let is_v3 = value
+        .get("asyncapi")
+        .and_then(|v| v.as_str())
+        .map(|v| v.trim_start().starts_with('3'))
+        .unwrap_or(false);
+
+    let mut channel_names: HashMap<String, usize> = HashMap::new();
+    if let Some(channels) = value.get("channels").and_then(|v| v.as_object()) {
+        for name in channels.keys() {
+            let idx = out.channels.len();
+            out.channels.push(AsyncApiChannel {
+                name: name.clone(),
+                publish: false,
+                subscribe: false,
+            });
+            channel_names.insert(name.clone(), idx);
+        }
+    }
+
+    if !is_v3 {
+        if let Some(channels) = value.get("channels").and_then(|v| v.as_object()) {
+            for (name, channel) in channels {
+                let Some(&idx) = channel_names.get(name) else {
+                    continue;
+                };
+                out.channels[idx].publish = channel.get("publish").is_some();
+                out.channels[idx].subscribe = channel.get("subscribe").is_some();
+            }
+        }
+        return out;
+    }
+
+    // AsyncAPI 3.x: publish/subscribe moved to a top-level `operations` map, each entry
+    // carrying `action: send|receive` and `channel: { $ref: "#/channels/<key>" }`.
+    if let Some(operations) = value.get("operations").and_then(|v| v.as_object()) {
+        for op in operations.values() {
+            let Some(action) = op.get("action").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let Some(ref_ptr) = op
+                .get("channel")
+                .and_then(|c| c.get("$ref"))
+                .and_then(|v| v.as_str())
+            else {
+                continue;
+            };
+            // Only local JSON-pointer refs (`#/channels/<name>`) can be resolved here;
+            // refs into external files are skipped rather than followed.
+            let Some(channel_key) = ref_ptr
+                .strip_prefix("#/channels/")
+                .map(|s| s.to_string())
+            else {
+                continue;
+            };
+
+            let idx = *channel_names.entry(channel_key.clone()).or_insert_with(|| {
+                let idx = out.channels.len();
+                out.channels.push(AsyncApiChannel {
+                    name: channel_key,
+                    publish: false,
+                    subscribe: false,
+                });
+                idx
+            });
+
+            match action {
+                "send" => out.channels[idx].publish = true,
+                "receive" => out.channels[idx].subscribe = true,
+                _ => {}
+            }
+        }
+    }
+
+    out
+}
+
+fn extract_asyncapi_summary_yaml_like(content: &str) -> AsyncApiSummary {
+    let mut out = AsyncApiSummary::default();
+
+    // Best-effort protocol detection: look for `protocol: <value>` lines.
+    for raw in content.lines().take(5000) {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some(rest) = line.strip_prefix("protocol:") else {
+            continue;
+        };
+        let protocol = rest.trim().trim_matches('"').trim_matches('\'');
+        if protocol.is_empty() {
+            continue;
+        }
+        let protocol = protocol.to_ascii_lowercase();
+        if !out.protocols.iter().any(|p| p == &protocol) {
+            out.protocols.push(protocol);
+        }
+    }
+
+    // Best-effort channel extraction from YAML:
+    // channels:
+    //   topic.name:
+    //     publish:
+    //     subscribe:
+    let lines: Vec<&str> = content.lines().collect();
+    let mut idx = 0usize;
+    while idx < lines.len() {
+        let raw = lines[idx];
+        if raw.trim_start().starts_with("channels:") {
+            break;
+        }
+        idx += 1;
+    }
+    if idx >= lines.len() {
+        return out;
+    }
+
+    let channels_indent = count_leading_spaces(lines[idx]);
+    idx += 1;
+
+    let mut current: Option<AsyncApiChannel> = None;
+    let mut current_indent: usize = 0;
+
+    while idx < lines.len() {
+        let raw = lines[idx];
+        let trimmed = raw.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            idx += 1;
+            continue;
+        }
+        let indent = count_leading_spaces(raw);
+        if indent <= channels_indent {
+            break;
+        }
+
+        if trimmed.ends_with(':') && !trimmed.starts_with('-') {
+            let key = trimmed.trim_end_matches(':').trim();
+            let key = key.trim_matches('"').trim_matches('\'');
+            if !key.is_empty() && key != "publish" && key != "subscribe" {
+                if let Some(ch) = current.take() {
+                    out.channels.push(ch);
+                }
+                current_indent = indent;
+                current = Some(AsyncApiChannel {
+                    name: key.to_string(),
+                    publish: false,
+                    subscribe: false,
+                });
+                idx += 1;
+                continue;
+            }
+        }
+
+        if let Some(ch) = current.as_mut() {
+            if indent > current_indent {
+                if trimmed.starts_with("publish:") {
+                    ch.publish = true;
+                } else if trimmed.starts_with("subscribe:") {
+                    ch.subscribe = true;
+                }
+            }
+        }
+
+        idx += 1;
+    }
+
+    if let Some(ch) = current.take() {
+        out.channels.push(ch);
+    }
+
+    // AsyncAPI 3.x: `asyncapi: 3.x.y` moves publish/subscribe into a top-level `operations:`
+    // map keyed by action (send/receive) and a `channel: {$ref: "#/channels/<name>"}` pointer.
+    let is_v3 = content
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("asyncapi:"))
+        .map(|v| v.trim().trim_matches('"').trim_matches('\'').starts_with('3'))
+        .unwrap_or(false);
+    if is_v3 {
+        for (channel_key, action) in parse_asyncapi_operations_yaml_like(&lines) {
+            let entry = out
+                .channels
+                .iter_mut()
+                .find(|ch| ch.name == channel_key);
+            let ch = match entry {
+                Some(ch) => ch,
+                None => {
+                    out.channels.push(AsyncApiChannel {
+                        name: channel_key,
+                        publish: false,
+                        subscribe: false,
+                    });
+                    out.channels.last_mut().unwrap()
+                }
+            };
+            match action.as_str() {
+                "send" => ch.publish = true,
+                "receive" => ch.subscribe = true,
+                _ => {}
+            }
+        }
+    }
+
+    out
+}
+
+fn parse_asyncapi_operations_yaml_like(lines: &[&str]) -> Vec<(String, String)> {
+    let mut out: Vec<(String, String)> = Vec::new();
+    let mut idx = 0usize;
+    while idx < lines.len() {
+        if lines[idx].trim_start().starts_with("operations:") {
+            break;
+        }
+        idx += 1;
+    }
+    if idx >= lines.len() {
+        return out;
+    }
+    let operations_indent = count_leading_spaces(lines[idx]);
+    idx += 1;
+
+    let mut current_action: Option<String> = None;
+    while idx < lines.len() {
+        let raw = lines[idx];
+        let trimmed = raw.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            idx += 1;
+            continue;
+        }
+        let indent = count_leading_spaces(raw);
+        if indent <= operations_indent {
+            break;
+        }
+
+        if let Some(rest) = trimmed.strip_prefix("action:") {
+            current_action = Some(rest.trim().trim_matches('"').trim_matches('\'').to_string());
+        } else if let Some(rest) = trimmed.strip_prefix("$ref:") {
+            let ref_ptr = rest.trim().trim_matches('"').trim_matches('\'');
+            if let Some(channel_key) = ref_ptr.strip_prefix("#/channels/") {
+                if let Some(action) = current_action.clone() {
+                    out.push((channel_key.to_string(), action));
+                }
+            }
+        }
+
+        idx += 1;
+    }
+
+    out
+}
+
+fn extract_openapi_routes_json(contract: &str, value: &serde_json::Value) -> Vec<RouteEdge> {
+    let mut out = Vec::new();
+    let Some(paths) = value.get("paths").and_then(|v| v.as_object()) else {
+        return out;
+    };
+    for (path, methods) in paths {
+        let Some(methods) = methods.as_object() else {
+            continue;
+        };
+        for method in HTTP_METHODS {
+            let Some(op) = methods.get(*method) else {
+                continue;
+            };
+            let operation_id = op
+                .get("operationId")
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+            out.push(RouteEdge {
+                contract_file: contract.to_string(),
+                path: path.clone(),
+                method: method.to_string(),
+                operation_id,
+            });
+        }
+    }
+    out
+}
+
+fn extract_openapi_routes_yaml_like(contract: &str, content: &str) -> Vec<RouteEdge> {
+    // Best-effort line-based walk, mirroring `extract_asyncapi_summary_yaml_like`:
+    // paths:
+    //   /users/{id}:
+    //     get:
+    //       operationId: getUser
+    let mut out = Vec::new();
+    let lines: Vec<&str> = content.lines().collect();
+    let mut idx = 0usize;
+    while idx < lines.len() {
+        if lines[idx].trim_start().starts_with("paths:") {
+            break;
+        }
+        idx += 1;
+    }
+    if idx >= lines.len() {
+        return out;
+    }
+    let paths_indent = count_leading_spaces(lines[idx]);
+    idx += 1;
+
+    let mut current_path: Option<(String, usize)> = None;
+    let mut current_method: Option<(String, usize)> = None;
+
+    while idx < lines.len() {
+        let raw = lines[idx];
+        let trimmed = raw.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            idx += 1;
+            continue;
+        }
+        let indent = count_leading_spaces(raw);
+        if indent <= paths_indent {
+            break;
+        }
+
+        if trimmed.ends_with(':') && !trimmed.starts_with('-') {
+            let key = trimmed.trim_end_matches(':').trim();
+            let key = key.trim_matches('"').trim_matches('\'');
+            if key.starts_with('/') {
+                current_path = Some((key.to_string(), indent));
+                current_method = None;
+            } else if HTTP_METHODS.contains(&key.to_ascii_lowercase().as_str()) {
+                if let Some((path, path_indent)) = &current_path {
+                    if indent > *path_indent {
+                        out.push(RouteEdge {
+                            contract_file: contract.to_string(),
+                            path: path.clone(),
+                            method: key.to_ascii_lowercase(),
+                            operation_id: None,
+                        });
+                        current_method = Some((key.to_ascii_lowercase(), indent));
+                    }
+                }
+            } else if let Some(rest) = trimmed.strip_prefix("operationId:") {
+                if let Some((method, method_indent)) = &current_method {
+                    if indent > *method_indent {
+                        let op_id = rest.trim().trim_matches('"').trim_matches('\'');
+                        if !op_id.is_empty() {
+                            if let Some(route) = out.iter_mut().rfind(|r| &r.method == method) {
+                                route.operation_id = Some(op_id.to_string());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        idx += 1;
+    }
+
+    out
+}
+
+fn count_leading_spaces(s: &str) -> usize {
+    s.as_bytes().iter().take_while(|&&b| b == b' ').count()
+}
+
+pub(super) async fn detect_channel_mentions(
+    root: &Path,
+    files: &[String],
+    channels: &[String],
+) -> HashMap<String, String> {
+    const MAX_SCAN_FILES: usize = 200;
+    const MAX_READ_BYTES: usize = 64 * 1024;
+    const MAX_CHANNELS: usize = 20;
+
+    let mut wanted: Vec<String> = channels.to_vec();
+    wanted.sort();
+    wanted.dedup();
+    wanted.truncate(MAX_CHANNELS);
+
+    let mut out: HashMap<String, String> = HashMap::new();
+    if wanted.is_empty() {
+        return out;
+    }
+
+    let mut candidates: Vec<&String> = files
+        .iter()
+        .filter(|file| is_code_file_candidate(&file.to_ascii_lowercase()))
+        .collect();
+    candidates.sort();
+
+    for file in candidates.into_iter().take(MAX_SCAN_FILES) {
+        if out.len() >= wanted.len() {
+            break;
+        }
+        let Some(content) = read_file_prefix_utf8(root, file, MAX_READ_BYTES).await else {
+            continue;
+        };
+        for channel in &wanted {
+            if out.contains_key(channel) {
+                continue;
+            }
+            if content.contains(channel) {
+                out.insert(channel.clone(), file.clone());
+            }
+        }
+    }
+
+    if out.len() < wanted.len() {
+        // Semantic tier: substring matching misses paraphrased/renamed references (a channel
+        // `user.signup` referenced as `UserSignedUp` in code), so fall back to embedding
+        // cosine similarity for whatever channels the cheap tier above left unresolved.
+        const SEMANTIC_MAX_FILES: usize = 40;
+        const SEMANTIC_THRESHOLD: f32 = 0.35;
+
+        let mut semantic_candidates: Vec<String> = files
+            .iter()
+            .filter(|file| is_code_file_candidate(&file.to_ascii_lowercase()))
+            .cloned()
+            .collect();
+        semantic_candidates.sort();
+        semantic_candidates.truncate(SEMANTIC_MAX_FILES);
+
+        let embedder = HashingEmbedder::default();
+        for channel in &wanted {
+            if out.contains_key(channel) {
+                continue;
+            }
+            let terms = split_identifier_words(channel);
+            if terms.is_empty() {
+                continue;
+            }
+            let term_refs: Vec<&str> = terms.iter().map(String::as_str).collect();
+            let scores =
+                semantic_chunk_scores(root, &semantic_candidates, &term_refs, &embedder).await;
+            if let Some((file, _)) = scores
+                .into_iter()
+                .filter(|(_, score)| *score >= SEMANTIC_THRESHOLD)
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+            {
+                out.insert(channel.clone(), file);
+            }
+        }
+    }
+
+    out
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct BrokerCandidate {
+    pub(super) proto: String,
+    pub(super) file: String,
+    pub(super) confidence: f32,
+    /// Container image that matched `proto`, when detected via structured YAML parsing.
+    pub(super) image: Option<String>,
+    /// The env var (`env:NAME`) or port (`port:N`) that drove the `role` classification.
+    pub(super) via: Option<String>,
+    pub(super) role: &'static str,
+    /// The container item's line range within `file`, when known precisely; falls back to the
+    /// default file-evidence window otherwise.
+    pub(super) line_range: Option<(usize, usize)>,
+}
+
+impl BrokerCandidate {
+    fn to_cached(&self) -> CachedBroker {
+        CachedBroker {
+            proto: self.proto.clone(),
+            role: self.role.to_string(),
+            confidence: self.confidence,
+            image: self.image.clone(),
+            via: self.via.clone(),
+            line_range: self.line_range,
+        }
+    }
+
+    fn from_cached(file: &str, cached: &CachedBroker) -> BrokerCandidate {
+        BrokerCandidate {
+            proto: cached.proto.clone(),
+            file: file.to_string(),
+            confidence: cached.confidence,
+            image: cached.image.clone(),
+            via: cached.via.clone(),
+            role: broker_role_literal(&cached.role),
+            line_range: cached.line_range,
+        }
+    }
+}
+
+/// `BrokerCandidate::role` is `&'static str` so it can be matched against without allocating;
+/// `classify_broker_role` only ever produces these three values, so anything else (a cache
+/// entry from a future fingerprint we don't recognize) falls back to "broker".
+fn broker_role_literal(role: &str) -> &'static str {
+    match role {
+        "consume" => "consume",
+        "produce" => "produce",
+        _ => "broker",
+    }
+}
+
+/// Reconstructs the broker-derived flow edge (if any) a cached candidate would have produced
+/// live, mirroring the `via.strip_prefix("env:")` branch in `detect_brokers`.
+fn broker_flow_from_cached(file: &str, cached: &CachedBroker) -> Option<FlowEdge> {
+    let topic = cached.via.as_deref()?.strip_prefix("env:")?;
+    let direction = match cached.role.as_str() {
+        "consume" => FlowDirection::Subscribe,
+        "produce" => FlowDirection::Publish,
+        _ => return None,
+    };
+    Some(FlowEdge {
+        contract_file: file.to_string(),
+        channel: topic.to_string(),
+        direction,
+        protocol: Some(cached.proto.clone()),
+    })
+}
+
+/// One `containers:`/`initContainers:` list item extracted from a Kubernetes-style manifest.
+struct ContainerBlock {
+    image: Option<String>,
+    start_line: usize,
+    end_line: usize,
+    env_names: Vec<String>,
+    ports: Vec<u16>,
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn unquote_yaml_value(value: &str) -> String {
+    let value = value.trim();
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|v| v.strip_suffix(quote))
+        {
+            return inner.to_string();
+        }
+    }
+    value.to_string()
+}
+
+/// Walks a Kubernetes-style manifest by indentation (Helm/Kustomize/Argo/Flux all render to
+/// this shape), extracting each `containers:` item's image, exposed ports, and declared env /
+/// envFrom names. This is deliberately a small hand-rolled walker rather than a full YAML
+/// parser (matching how this module already hand-parses Cargo.toml/package.json/go.mod), but it
+/// tracks real document structure instead of a raw substring search over the whole file.
+fn parse_yaml_containers(content: &str) -> Vec<ContainerBlock> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut out = Vec::new();
+
+    let mut idx = 0usize;
+    while idx < lines.len() {
+        if !matches!(lines[idx].trim(), "containers:" | "initContainers:") {
+            idx += 1;
+            continue;
+        }
+        let containers_indent = indent_of(lines[idx]);
+        idx += 1;
+
+        let mut item_indent: Option<usize> = None;
+        while idx < lines.len() {
+            let line = lines[idx];
+            if line.trim().is_empty() {
+                idx += 1;
+                continue;
+            }
+            let this_indent = indent_of(line);
+            if this_indent <= containers_indent {
+                break;
+            }
+            let is_item_start = line.trim_start().starts_with("- ");
+            let indent = match item_indent {
+                None if is_item_start => *item_indent.insert(this_indent),
+                None => {
+                    // Stray line before the first list item (unexpected shape); skip it rather
+                    // than misparse it as a container.
+                    idx += 1;
+                    continue;
+                }
+                Some(indent) if this_indent == indent && is_item_start => indent,
+                Some(_) => break, // end of this `containers:` list
+            };
+
+            let start_line = idx + 1; // EvidenceItem line numbers are 1-indexed.
+            let mut end = idx + 1;
+            while end < lines.len() {
+                let next = lines[end];
+                if next.trim().is_empty() {
+                    end += 1;
+                    continue;
+                }
+                let next_indent = indent_of(next);
+                if next_indent <= containers_indent
+                    || (next_indent == indent && next.trim_start().starts_with("- "))
+                {
+                    break;
+                }
+                end += 1;
+            }
+
+            let mut image = None;
+            let mut env_names = Vec::new();
+            let mut ports = Vec::new();
+            let mut seen_own_name = false;
+            for block_line in &lines[idx..end] {
+                let key = block_line
+                    .trim_start()
+                    .strip_prefix("- ")
+                    .unwrap_or_else(|| block_line.trim_start());
+                if let Some(value) = key.strip_prefix("image:") {
+                    image.get_or_insert_with(|| unquote_yaml_value(value));
+                } else if let Some(value) = key.strip_prefix("containerPort:") {
+                    if let Ok(port) = value.trim().parse::<u16>() {
+                        ports.push(port);
+                    }
+                } else if let Some(value) = key.strip_prefix("name:") {
+                    // The first `name:` in a container item is the container's own name
+                    // (`- name: api`), not an env/secret name; skip it.
+                    if seen_own_name {
+                        env_names.push(unquote_yaml_value(value));
+                    }
+                    seen_own_name = true;
+                }
+            }
+
+            out.push(ContainerBlock {
+                image,
+                start_line,
+                end_line: end,
+                env_names,
+                ports,
+            });
+            idx = end;
+        }
+    }
+
+    out
+}
+
+fn image_mentions_proto(image_lc: &str, proto_lc: &str) -> bool {
+    match proto_lc {
+        "kafka" => image_lc.contains("kafka"),
+        "nats" => image_lc.contains("nats"),
+        "amqp" | "rabbitmq" => image_lc.contains("rabbitmq") || image_lc.contains("amqp"),
+        "mqtt" => image_lc.contains("mqtt") || image_lc.contains("mosquitto"),
+        "pulsar" => image_lc.contains("pulsar"),
+        "syndicate" => {
+            image_lc.contains("syndicate")
+                || image_lc.contains("dataspace")
+                || image_lc.contains("preserves")
+        }
+        other => image_lc.contains(other),
+    }
+}
+
+/// Classifies a container's direction of use from its declared env var names: a
+/// `*_CONSUMER_GROUP`/subscription key implies `consume`, a `*_TOPIC`/publish key implies
+/// `produce`, and a bare bootstrap/broker URL implies a connection without a clear direction.
+/// Returns the `env:NAME` that drove the call, so the caller can surface it as `via=`.
+fn classify_broker_role(env_names: &[String]) -> (&'static str, Option<String>) {
+    let upper: Vec<String> = env_names.iter().map(|n| n.to_ascii_uppercase()).collect();
+    if let Some(name) = upper
+        .iter()
+        .find(|n| n.contains("CONSUMER_GROUP") || n.contains("SUBSCRIB"))
+    {
+        return ("consume", Some(format!("env:{name}")));
+    }
+    if let Some(name) = upper
+        .iter()
+        .find(|n| n.contains("TOPIC") || n.contains("PRODUC") || n.contains("PUBLISH"))
+    {
+        return ("produce", Some(format!("env:{name}")));
+    }
+    if let Some(name) = upper
+        .iter()
+        .find(|n| n.contains("BOOTSTRAP") || n.contains("BROKER") || n.ends_with("_URL") || n.contains("_URI"))
+    {
+        return ("broker", Some(format!("env:{name}")));
+    }
+    ("broker", None)
+}
+
+/// Coarse, single-candidate broker hit for a file that wasn't (or didn't match as) a
+/// structured `containers:` manifest: the first `wanted` protocol mentioned in its content.
+fn broker_candidate_from_content(
+    file: &str,
+    file_lc: &str,
+    content_lc: &str,
+    wanted: &[String],
+) -> Option<BrokerCandidate> {
+    for proto in wanted {
+        if !content_mentions_proto(content_lc, proto) {
+            continue;
+        }
+        let mut confidence = 0.75;
+        if file_lc.contains("docker-compose")
+            || file_lc.ends_with("compose.yml")
+            || file_lc.ends_with("compose.yaml")
+        {
+            confidence = 0.9;
+        } else if content_lc.contains("image:") {
+            confidence = 0.85;
+        }
+        return Some(BrokerCandidate {
+            proto: proto.clone(),
+            file: file.to_string(),
+            confidence,
+            image: None,
+            via: None,
+            role: "broker",
+            line_range: None,
+        });
+    }
+    None
+}
+
+pub(super) async fn detect_brokers(
+    root: &Path,
+    files: &[String],
+    flows: &[FlowEdge],
+    mut cache: Option<&mut ScanCache>,
+) -> (Vec<BrokerCandidate>, Vec<FlowEdge>) {
+    const MAX_CANDIDATE_FILES: usize = 30;
+    const MAX_READ_BYTES: usize = 192 * 1024;
+    const MAX_BROKERS: usize = 4;
+
+    let mut wanted: Vec<String> = flows
+        .iter()
+        .filter_map(|f| f.protocol.as_ref())
+        .map(|p| p.to_ascii_lowercase())
+        .collect();
+    wanted.sort();
+    wanted.dedup();
     if wanted.is_empty() {
         wanted = vec!["kafka", "nats", "amqp", "mqtt", "pulsar"]
             .into_iter()
@@ -946,6 +2387,7 @@ pub(super) async fn detect_brokers(
     candidates.sort();
 
     let mut out: Vec<BrokerCandidate> = Vec::new();
+    let mut broker_flows: Vec<FlowEdge> = Vec::new();
     let mut seen_files: HashSet<&str> = HashSet::new();
 
     for file in candidates.into_iter().take(MAX_CANDIDATE_FILES) {
@@ -955,30 +2397,97 @@ pub(super) async fn detect_brokers(
         if !seen_files.insert(file.as_str()) {
             continue;
         }
+
+        // Cached broker facts assume `wanted` hasn't changed since they were recorded; that's
+        // true for repeat calls against the same contract set and only drifts if new contracts
+        // appear between scans, which a real content change elsewhere would usually also touch.
+        let abs = root.join(file);
+        if let Some(cache) = cache.as_deref_mut() {
+            if cache.hash_and_count_lines(file, &abs).await.is_ok() {
+                if let Some(cached) = cache.cached_brokers(file) {
+                    for entry in cached {
+                        if out.len() >= MAX_BROKERS {
+                            break;
+                        }
+                        if let Some(edge) = broker_flow_from_cached(file, entry) {
+                            broker_flows.push(edge);
+                        }
+                        out.push(BrokerCandidate::from_cached(file, entry));
+                    }
+                    continue;
+                }
+            }
+        }
+
+        let file_lc = file.to_ascii_lowercase();
         let Some(content) = read_file_prefix_utf8(root, file, MAX_READ_BYTES).await else {
             continue;
         };
         let content_lc = content.to_ascii_lowercase();
-        for proto in &wanted {
-            if !content_mentions_proto(&content_lc, proto) {
-                continue;
+        let mut file_candidates: Vec<BrokerCandidate> = Vec::new();
+
+        if is_structured_infra_yaml(&file_lc) {
+            let mut matched = false;
+            for container in parse_yaml_containers(&content) {
+                if out.len() + file_candidates.len() >= MAX_BROKERS {
+                    break;
+                }
+                let Some(image) = &container.image else {
+                    continue;
+                };
+                let image_lc = image.to_ascii_lowercase();
+                let Some(proto) = wanted.iter().find(|p| image_mentions_proto(&image_lc, p))
+                else {
+                    continue;
+                };
+
+                let (role, via_env) = classify_broker_role(&container.env_names);
+                let via =
+                    via_env.or_else(|| container.ports.first().map(|port| format!("port:{port}")));
+
+                file_candidates.push(BrokerCandidate {
+                    proto: proto.clone(),
+                    file: (*file).clone(),
+                    confidence: 0.9,
+                    image: Some(image.clone()),
+                    via,
+                    role,
+                    line_range: Some((container.start_line, container.end_line)),
+                });
+                matched = true;
             }
-            let mut confidence = 0.75;
-            if file.to_ascii_lowercase().contains("docker-compose")
-                || file.to_ascii_lowercase().ends_with("compose.yml")
-                || file.to_ascii_lowercase().ends_with("compose.yaml")
-            {
-                confidence = 0.9;
-            } else if content_lc.contains("image:") {
-                confidence = 0.85;
+            if !matched {
+                // Fall through: a manifest gated as "structured infra yaml" but shaped
+                // differently from a `containers:` list (e.g. a Helm values file) is still
+                // worth a coarse hit.
+                file_candidates.extend(broker_candidate_from_content(
+                    file,
+                    &file_lc,
+                    &content_lc,
+                    &wanted,
+                ));
+            }
+        } else {
+            file_candidates.extend(broker_candidate_from_content(
+                file,
+                &file_lc,
+                &content_lc,
+                &wanted,
+            ));
+        }
+
+        if let Some(cache) = cache.as_deref_mut() {
+            cache.put_brokers(
+                file,
+                file_candidates.iter().map(BrokerCandidate::to_cached).collect(),
+            );
+        }
+        for candidate in &file_candidates {
+            if let Some(edge) = broker_flow_from_cached(file, &candidate.to_cached()) {
+                broker_flows.push(edge);
             }
-            out.push(BrokerCandidate {
-                proto: proto.clone(),
-                file: file.clone(),
-                confidence,
-            });
-            break;
         }
+        out.extend(file_candidates);
     }
 
     out.sort_by(|a, b| {
@@ -988,10 +2497,10 @@ pub(super) async fn detect_brokers(
             .then_with(|| a.file.cmp(&b.file))
     });
     out.truncate(MAX_BROKERS);
-    out
+    (out, broker_flows)
 }
 
-fn is_code_file_candidate(file_lc: &str) -> bool {
+pub(super) fn is_code_file_candidate(file_lc: &str) -> bool {
     if file_lc.starts_with("target/")
         || file_lc.contains("/target/")
         || file_lc.starts_with("node_modules/")
@@ -1018,7 +2527,7 @@ fn is_code_file_candidate(file_lc: &str) -> bool {
         || file_lc.ends_with(".hpp")
 }
 
-fn is_broker_config_candidate(file_lc: &str) -> bool {
+pub(super) fn is_broker_config_candidate(file_lc: &str) -> bool {
     let is_compose = file_lc.ends_with("docker-compose.yml")
         || file_lc.ends_with("docker-compose.yaml")
         || file_lc.ends_with("compose.yml")
@@ -1032,6 +2541,17 @@ fn is_broker_config_candidate(file_lc: &str) -> bool {
         return true;
     }
 
+    let is_dataspace_config = matches!(
+        basename,
+        "dataspace.yaml" | "dataspace.yml" | "gateway.yaml" | "gateway.yml"
+    ) || file_lc.starts_with("dataspace/")
+        || file_lc.contains("/dataspace/")
+        || file_lc.starts_with("gateway/")
+        || file_lc.contains("/gateway/");
+    if is_dataspace_config && (file_lc.ends_with(".yaml") || file_lc.ends_with(".yml")) {
+        return true;
+    }
+
     let is_tf =
         file_lc.ends_with(".tf") || file_lc.ends_with(".tfvars") || file_lc.ends_with(".hcl");
     if is_tf {
@@ -1055,7 +2575,18 @@ fn is_broker_config_candidate(file_lc: &str) -> bool {
         return is_tf_dir || (is_root && is_tf_root_candidate) || basename == "terragrunt.hcl";
     }
 
-    let is_infra_dir = file_lc.starts_with("k8s/")
+    if !is_infra_dir(file_lc) {
+        return false;
+    }
+
+    file_lc.ends_with(".yaml") || file_lc.ends_with(".yml")
+}
+
+/// Matches the directory layouts and well-known filenames used by Helm/Kustomize/Argo/Flux
+/// deployment manifests, regardless of extension (callers narrow to `.yaml`/`.yml` themselves).
+pub(super) fn is_infra_dir(file_lc: &str) -> bool {
+    let basename = file_lc.rsplit('/').next().unwrap_or(file_lc);
+    file_lc.starts_with("k8s/")
         || file_lc.contains("/k8s/")
         || file_lc.starts_with("kubernetes/")
         || file_lc.contains("/kubernetes/")
@@ -1094,15 +2625,17 @@ fn is_broker_config_candidate(file_lc: &str) -> bool {
                 | "werf.yml"
                 | "devspace.yaml"
                 | "devspace.yml"
-        );
-    if !is_infra_dir {
-        return false;
-    }
+        )
+}
 
-    file_lc.ends_with(".yaml") || file_lc.ends_with(".yml")
+/// Whether `file_lc` is a Helm/Kustomize/Argo/Flux manifest we can walk as structured YAML
+/// (see [`parse_yaml_containers`]), as opposed to compose/terraform files that only support the
+/// coarser [`content_mentions_proto`] substring check.
+pub(super) fn is_structured_infra_yaml(file_lc: &str) -> bool {
+    is_infra_dir(file_lc) && (file_lc.ends_with(".yaml") || file_lc.ends_with(".yml"))
 }
 
-fn content_mentions_proto(content_lc: &str, proto_lc: &str) -> bool {
+pub(super) fn content_mentions_proto(content_lc: &str, proto_lc: &str) -> bool {
     match proto_lc {
         "kafka" => {
             content_lc.contains("kafka")
@@ -1114,6 +2647,11 @@ fn content_mentions_proto(content_lc: &str, proto_lc: &str) -> bool {
         "amqp" | "rabbitmq" => content_lc.contains("rabbitmq") || content_lc.contains("amqp"),
         "mqtt" => content_lc.contains("mqtt"),
         "pulsar" => content_lc.contains("pulsar"),
+        "syndicate" => {
+            content_lc.contains("syndicate")
+                || content_lc.contains("dataspace")
+                || content_lc.contains("preserves")
+        }
         other => content_lc.contains(other),
     }
 }
@@ -1310,8 +2848,114 @@ fn shrink_pack_simple(pack: &mut String) -> bool {
     true
 }
 
+const PRUNABLE_PREFIXES: [&str; 13] = [
+    "MAP ",
+    "ENVVAR ",
+    "ROUTE ",
+    "DEPENDENCY ",
+    "SYM ",
+    "FLOW ",
+    "BROKER ",
+    "ENTRY ",
+    "CONTRACT ",
+    "BOUNDARY ",
+    "STEP ",
+    "AREA ",
+    "ANCHOR ",
+];
+
+fn is_prunable_body_line(line: &str) -> bool {
+    PRUNABLE_PREFIXES.iter().any(|prefix| line.starts_with(prefix))
+}
+
+/// Splits on non-alphanumeric boundaries and lowercases, for BM25 tokenization.
+fn tokenize_bm25(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|tok| !tok.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect()
+}
+
+/// Finds the lowest-BM25-scoring prunable line against the pack's `QUERY`, so shrinking removes
+/// the least query-relevant evidence first instead of the first line matching a fixed prefix
+/// order. Returns `None` when there is no `QUERY` line or no prunable line to score (the caller
+/// falls back to the fixed-priority policy below).
+fn bm25_prune_target(lines: &[String], nba_idx: usize) -> Option<usize> {
+    let query_terms = tokenize_bm25(
+        lines
+            .iter()
+            .find(|line| line.starts_with("QUERY "))?
+            .strip_prefix("QUERY ")
+            .unwrap_or(""),
+    );
+    if query_terms.is_empty() {
+        return None;
+    }
+
+    let docs: Vec<(usize, Vec<String>)> = lines
+        .iter()
+        .take(nba_idx)
+        .enumerate()
+        .filter(|(_, line)| is_prunable_body_line(line))
+        .map(|(idx, line)| (idx, tokenize_bm25(line)))
+        .collect();
+    if docs.is_empty() {
+        return None;
+    }
+
+    const K1: f64 = 1.2;
+    const B: f64 = 0.75;
+
+    let n = docs.len() as f64;
+    let avgdl = docs.iter().map(|(_, toks)| toks.len()).sum::<usize>() as f64 / n;
+
+    let mut idf: HashMap<&str, f64> = HashMap::new();
+    for term in &query_terms {
+        if idf.contains_key(term.as_str()) {
+            continue;
+        }
+        let n_t = docs
+            .iter()
+            .filter(|(_, toks)| toks.iter().any(|tok| tok == term))
+            .count() as f64;
+        idf.insert(term.as_str(), ((n - n_t + 0.5) / (n_t + 0.5) + 1.0).ln());
+    }
+
+    let mut worst: Option<(usize, f64)> = None;
+    for (idx, toks) in &docs {
+        let dl = toks.len() as f64;
+        let mut score = 0.0;
+        for term in &query_terms {
+            let f = toks.iter().filter(|tok| *tok == term).count() as f64;
+            if f == 0.0 {
+                continue;
+            }
+            let term_idf = idf.get(term.as_str()).copied().unwrap_or(0.0);
+            score += term_idf * (f * (K1 + 1.0)) / (f + K1 * (1.0 - B + B * dl / avgdl));
+        }
+        let is_worse = match worst {
+            None => true,
+            Some((worst_idx, worst_score)) => {
+                score < worst_score || (score == worst_score && *idx > worst_idx)
+            }
+        };
+        if is_worse {
+            worst = Some((*idx, score));
+        }
+    }
+    worst.map(|(idx, _)| idx)
+}
+
 fn remove_one_low_priority_body_line(lines: &mut Vec<String>, nba_idx: usize) -> bool {
-    // Keep this deterministic: lowest-signal content is removed first.
+    // Relevance-driven: prefer dropping the prunable line that is least relevant to `QUERY`
+    // under BM25, so tight budgets keep the evidence that actually matches what was asked.
+    if let Some(idx) = bm25_prune_target(lines, nba_idx) {
+        lines.remove(idx);
+        return true;
+    }
+
+    // Fallback when there's no `QUERY` to rank against: deterministic fixed-priority order,
+    // lowest-signal content first.
     // Note: we intentionally do *not* remove `D ...`, `EV ...`, or headers here.
     #[derive(Clone, Copy)]
     struct PrefixPolicy {
@@ -1323,11 +2967,23 @@ fn remove_one_low_priority_body_line(lines: &mut Vec<String>, nba_idx: usize) ->
     // The most valuable navigation primitives are kept longer:
     // - `ANCHOR` (where to start), `STEP` (how to run), `AREA` (sense map).
     // - Under extreme budgets we may still fall back to the minimal CP (see shrink_pack()).
-    const POLICIES: [PrefixPolicy; 10] = [
+    const POLICIES: [PrefixPolicy; 13] = [
         PrefixPolicy {
             prefix: "MAP ",
             min_keep: 0,
         },
+        PrefixPolicy {
+            prefix: "ENVVAR ",
+            min_keep: 0,
+        },
+        PrefixPolicy {
+            prefix: "ROUTE ",
+            min_keep: 0,
+        },
+        PrefixPolicy {
+            prefix: "DEPENDENCY ",
+            min_keep: 0,
+        },
         PrefixPolicy {
             prefix: "SYM ",
             min_keep: 0,
@@ -1388,7 +3044,7 @@ fn remove_one_low_priority_body_line(lines: &mut Vec<String>, nba_idx: usize) ->
     false
 }
 
-fn remove_empty_sections(lines: &mut Vec<String>) -> bool {
+pub(super) fn remove_empty_sections(lines: &mut Vec<String>) -> bool {
     let mut changed = false;
     let mut idx = 0usize;
     while idx < lines.len() {
@@ -1414,7 +3070,7 @@ fn remove_empty_sections(lines: &mut Vec<String>) -> bool {
     changed
 }
 
-fn prune_unused_ev_lines(lines: &mut Vec<String>) -> bool {
+pub(super) fn prune_unused_ev_lines(lines: &mut Vec<String>) -> bool {
     let mut used: HashSet<String> = HashSet::new();
     for line in lines.iter().filter(|line| !line.starts_with("EV ")) {
         for token in line.split_whitespace() {
@@ -1447,7 +3103,7 @@ fn prune_unused_ev_lines(lines: &mut Vec<String>) -> bool {
     changed
 }
 
-fn prune_unused_dict_lines(lines: &mut Vec<String>) -> bool {
+pub(super) fn prune_unused_dict_lines(lines: &mut Vec<String>) -> bool {
     let mut used: HashSet<String> = HashSet::new();
     for line in lines.iter().filter(|line| !line.starts_with("D ")) {
         collect_dict_ids(line, &mut used);
@@ -1585,6 +3241,163 @@ pub(super) async fn extract_code_outline(root: &Path, focus_rel: &str) -> Vec<Ou
     outline
 }
 
+/// Like `extract_code_outline`, but reconstructs the declaration nesting (a struct/class
+/// owning its method nodes) from `chunk.metadata.qualified_name`/`parent_scope` instead of
+/// returning a flat list, and takes a caller-supplied `limit` instead of a hard cap.
+///
+/// `limit` bounds the number of declarations considered, applied *before* nesting — a
+/// selected child whose parent fell outside the limit simply surfaces as a root node, same
+/// as `extract_code_outline` already does for orphaned parent scopes.
+pub(super) async fn extract_code_outline_tree(
+    root: &Path,
+    focus_rel: &str,
+    limit: usize,
+) -> Vec<OutlineNode> {
+    const MAX_FILE_BYTES: u64 = 512 * 1024;
+
+    let focus_lc = focus_rel.to_ascii_lowercase();
+    if !is_code_file_candidate(&focus_lc) {
+        return Vec::new();
+    }
+
+    let abs = root.join(focus_rel);
+    let Ok(meta) = tokio::fs::metadata(&abs).await else {
+        return Vec::new();
+    };
+    if !meta.is_file() || meta.len() > MAX_FILE_BYTES {
+        return Vec::new();
+    }
+    let limit = limit.max(1);
+
+    tokio::task::spawn_blocking(move || {
+        let chunker = Chunker::new(ChunkerConfig {
+            // Outline is a “meaning read”: avoid pulling long docs into the metadata.
+            include_documentation: false,
+            ..ChunkerConfig::default()
+        });
+        let chunks = chunker.chunk_file(abs).ok()?;
+
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut entries: Vec<(u8, String, Option<String>, OutlineNode)> = Vec::new();
+        for chunk in chunks {
+            let Some(chunk_type) = chunk.metadata.chunk_type else {
+                continue;
+            };
+            if !chunk_type.is_declaration() {
+                continue;
+            }
+
+            let symbol = chunk.metadata.symbol_name.as_deref().unwrap_or("").trim();
+            if symbol.is_empty() {
+                continue;
+            }
+            let parent_scope = chunk
+                .metadata
+                .parent_scope
+                .as_deref()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string);
+            let qualified_name = chunk.metadata.qualified_name.clone().unwrap_or_else(|| {
+                parent_scope
+                    .as_deref()
+                    .map(|scope| format!("{scope}.{symbol}"))
+                    .unwrap_or_else(|| symbol.to_string())
+            });
+
+            let key = format!(
+                "{}:{}:{}:{}",
+                chunk.file_path, chunk.start_line, chunk.end_line, qualified_name
+            );
+            if !seen.insert(key) {
+                continue;
+            }
+
+            entries.push((
+                chunk_type.priority(),
+                qualified_name.clone(),
+                parent_scope,
+                OutlineNode {
+                    kind: chunk_type.as_lsp_symbol_kind(),
+                    name: symbol.to_string(),
+                    qualified_name,
+                    start_line: chunk.start_line,
+                    end_line: chunk.end_line,
+                    children: Vec::new(),
+                },
+            ));
+        }
+
+        entries.sort_by(|a, b| {
+            b.0.cmp(&a.0)
+                .then_with(|| a.3.start_line.cmp(&b.3.start_line))
+                .then_with(|| a.1.cmp(&b.1))
+        });
+        entries.truncate(limit);
+
+        let flat = entries
+            .into_iter()
+            .map(|(_, qualified_name, parent_scope, node)| (qualified_name, parent_scope, node))
+            .collect::<Vec<_>>();
+        Some(nest_outline_entries(flat))
+    })
+    .await
+    .ok()
+    .flatten()
+    .unwrap_or_default()
+}
+
+/// Reassembles a flat `(qualified_name, parent_scope, node)` list into a forest, attaching
+/// each entry under the sibling whose `qualified_name` matches its `parent_scope`.
+fn nest_outline_entries(mut flat: Vec<(String, Option<String>, OutlineNode)>) -> Vec<OutlineNode> {
+    let index_by_name: HashMap<String, usize> = flat
+        .iter()
+        .enumerate()
+        .map(|(i, (qualified_name, _, _))| (qualified_name.clone(), i))
+        .collect();
+
+    let mut children_by_parent: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut roots: Vec<usize> = Vec::new();
+    for (i, (_, parent_scope, _)) in flat.iter().enumerate() {
+        match parent_scope
+            .as_deref()
+            .filter(|p| index_by_name.contains_key(*p))
+        {
+            Some(parent) => children_by_parent
+                .entry(parent.to_string())
+                .or_default()
+                .push(i),
+            None => roots.push(i),
+        }
+    }
+
+    let mut slots: Vec<Option<OutlineNode>> =
+        flat.drain(..).map(|(_, _, node)| Some(node)).collect();
+
+    fn attach(
+        idx: usize,
+        slots: &mut [Option<OutlineNode>],
+        children_by_parent: &HashMap<String, Vec<usize>>,
+    ) -> OutlineNode {
+        let mut node = slots[idx].take().expect("outline node visited twice");
+        if let Some(child_idxs) = children_by_parent.get(&node.qualified_name) {
+            for &child_idx in child_idxs {
+                node.children
+                    .push(attach(child_idx, slots, children_by_parent));
+            }
+            node.children.sort_by_key(|c| c.start_line);
+        }
+        node
+    }
+
+    let mut roots: Vec<OutlineNode> = roots
+        .into_iter()
+        .map(|i| attach(i, &mut slots, &children_by_parent))
+        .collect();
+    roots.sort_by_key(|n| n.start_line);
+    roots
+}
+
 #[derive(Default)]
 pub(super) struct CognitivePack {
     dict: Vec<String>,
@@ -1618,29 +3431,38 @@ impl CognitivePack {
         self.lines.push(line.to_string());
     }
 
-    pub(super) fn render(&self) -> String {
-        if self.dict.is_empty() {
-            return self.lines.join("\n") + "\n";
-        }
-
-        let mut out = String::new();
-        let base_lines = self.lines.iter().map(String::as_str).collect::<Vec<_>>();
-        let insert_at = base_lines.len().min(3);
-        for (idx, line) in base_lines.iter().enumerate() {
-            if idx == insert_at {
+    /// Renders the pack. When `max_tokens` is `Some`, appends a trailing `BUDGET tokens=<est>`
+    /// line with this pack's own estimate at render time (a planning hint; the shrink loop's
+    /// `used_tokens` is the authoritative, post-trim figure).
+    pub(super) fn render(&self, max_tokens: Option<usize>) -> String {
+        let mut out = if self.dict.is_empty() {
+            self.lines.join("\n") + "\n"
+        } else {
+            let mut out = String::new();
+            let base_lines = self.lines.iter().map(String::as_str).collect::<Vec<_>>();
+            let insert_at = base_lines.len().min(3);
+            for (idx, line) in base_lines.iter().enumerate() {
+                if idx == insert_at {
+                    out.push_str("S DICT\n");
+                    for (d_idx, value) in self.dict.iter().enumerate() {
+                        out.push_str(&format!("D d{d_idx} {}\n", json_string(value)));
+                    }
+                }
+                out.push_str(line);
+                out.push('\n');
+            }
+            if insert_at == base_lines.len() {
                 out.push_str("S DICT\n");
                 for (d_idx, value) in self.dict.iter().enumerate() {
                     out.push_str(&format!("D d{d_idx} {}\n", json_string(value)));
                 }
             }
-            out.push_str(line);
-            out.push('\n');
-        }
-        if insert_at == base_lines.len() {
-            out.push_str("S DICT\n");
-            for (d_idx, value) in self.dict.iter().enumerate() {
-                out.push_str(&format!("D d{d_idx} {}\n", json_string(value)));
-            }
+            out
+        };
+
+        if let Some(max_tokens) = max_tokens {
+            let tokens = estimate_pack_tokens(&out, &estimator_for_pack(&out));
+            out.push_str(&format!("BUDGET tokens={tokens} max_tokens={max_tokens}\n"));
         }
         out
     }
@@ -0,0 +1,255 @@
+//! Reproducible benchmark harness for individual detectors, as opposed to `bench`'s
+//! whole-pipeline view.
+//!
+//! Each workload is a *synthetic* repo tree (file paths + small inline contents) plus the
+//! outputs we expect `detect_brokers`, `augment_k8s_manifest_boundaries`, `best_contract_file`,
+//! and `anchor_evidence_window` to find for it. The harness materializes the tree into a temp
+//! dir, runs the detectors, and reports precision/recall per detector plus wall-clock, so a
+//! regression in a heuristic shows up as a score drop instead of a silent pass.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::common::{classify_boundaries, classify_files, detect_brokers, extract_asyncapi_flows, AnchorKind};
+use crate::pack::{anchor_evidence_window, augment_k8s_manifest_boundaries, best_contract_file};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DetectorWorkloadFile {
+    pub path: String,
+    #[serde(default)]
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ExpectedBrokers {
+    #[serde(default)]
+    pub protocols: Vec<String>,
+    #[serde(default)]
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExpectedBoundary {
+    pub kind: String,
+    pub file: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExpectedAnchorWindow {
+    pub file: String,
+    pub kind: String,
+    /// A line the detected `(start, end)` window must cover.
+    pub contains_line: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DetectorWorkload {
+    pub name: String,
+    pub files: Vec<DetectorWorkloadFile>,
+    #[serde(default)]
+    pub expected_brokers: Option<ExpectedBrokers>,
+    #[serde(default)]
+    pub expected_boundaries: Vec<ExpectedBoundary>,
+    #[serde(default)]
+    pub expected_contract_file: Option<String>,
+    #[serde(default)]
+    pub expected_anchor_window: Option<ExpectedAnchorWindow>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DetectorScore {
+    pub detector: &'static str,
+    pub precision: f64,
+    pub recall: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DetectorWorkloadReport {
+    pub name: String,
+    pub wall_ms: f64,
+    pub scores: Vec<DetectorScore>,
+    pub pass: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DetectorBenchReport {
+    pub workloads: Vec<DetectorWorkloadReport>,
+    pub all_passed: bool,
+}
+
+/// Runs every `*.json` workload file in `workload_dir`, sorted by filename for determinism.
+pub async fn run_detector_benchmark(workload_dir: &Path) -> Result<DetectorBenchReport> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(workload_dir)
+        .with_context(|| format!("read workload dir {}", workload_dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("json"))
+        .collect();
+    paths.sort();
+
+    let mut workloads = Vec::new();
+    let mut all_passed = true;
+    for path in &paths {
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("read workload {}", path.display()))?;
+        let workload: DetectorWorkload = serde_json::from_str(&raw)
+            .with_context(|| format!("parse workload {}", path.display()))?;
+        let report = run_one(&workload).await?;
+        all_passed &= report.pass;
+        workloads.push(report);
+    }
+    Ok(DetectorBenchReport {
+        workloads,
+        all_passed,
+    })
+}
+
+async fn run_one(workload: &DetectorWorkload) -> Result<DetectorWorkloadReport> {
+    let temp_dir = tempfile::tempdir().context("create temp dir for workload")?;
+    let root = temp_dir.path();
+    for file in &workload.files {
+        let abs = root.join(&file.path);
+        if let Some(parent) = abs.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("create dirs for {}", abs.display()))?;
+        }
+        std::fs::write(&abs, &file.content)
+            .with_context(|| format!("write workload file {}", abs.display()))?;
+    }
+
+    let start = Instant::now();
+    let files: Vec<String> = workload.files.iter().map(|f| f.path.clone()).collect();
+    let (entrypoints, contracts) = classify_files(&files);
+    let mut boundaries = classify_boundaries(&files, &entrypoints, &contracts);
+    augment_k8s_manifest_boundaries(root, &files, &mut boundaries).await;
+    let flows = extract_asyncapi_flows(root, &contracts, None).await;
+    let (brokers, _broker_flows) = detect_brokers(root, &files, &flows, None).await;
+    let contract_file = best_contract_file(&contracts);
+
+    let mut scores = Vec::new();
+    let mut failures = Vec::new();
+
+    if let Some(expected) = &workload.expected_brokers {
+        let actual_files: HashSet<&str> = brokers.iter().map(|b| b.file.as_str()).collect();
+        let expected_files: HashSet<&str> = expected.files.iter().map(String::as_str).collect();
+        let (precision, recall) = precision_recall(&actual_files, &expected_files);
+        scores.push(DetectorScore {
+            detector: "detect_brokers",
+            precision,
+            recall,
+        });
+        if precision < 1.0 || recall < 1.0 {
+            failures.push(format!(
+                "detect_brokers files: expected {expected_files:?}, got {actual_files:?}"
+            ));
+        }
+        let actual_protocols: HashSet<&str> = brokers.iter().map(|b| b.proto.as_str()).collect();
+        for protocol in &expected.protocols {
+            if !actual_protocols.contains(protocol.as_str()) {
+                failures.push(format!("detect_brokers missing expected protocol {protocol}"));
+            }
+        }
+    }
+
+    if !workload.expected_boundaries.is_empty() {
+        let actual: HashSet<String> = boundaries
+            .iter()
+            .map(|b| format!("{}:{}", b.kind.as_str(), b.file))
+            .collect();
+        let expected: HashSet<String> = workload
+            .expected_boundaries
+            .iter()
+            .map(|e| format!("{}:{}", e.kind, e.file))
+            .collect();
+        let (precision, recall) = precision_recall(&actual, &expected);
+        scores.push(DetectorScore {
+            detector: "boundary_detection",
+            precision,
+            recall,
+        });
+        if precision < 1.0 || recall < 1.0 {
+            failures.push(format!(
+                "boundaries: expected {expected:?}, got {actual:?}"
+            ));
+        }
+    }
+
+    if let Some(expected) = &workload.expected_contract_file {
+        let hit = contract_file.as_deref() == Some(expected.as_str());
+        scores.push(DetectorScore {
+            detector: "best_contract_file",
+            precision: if hit { 1.0 } else { 0.0 },
+            recall: if hit { 1.0 } else { 0.0 },
+        });
+        if !hit {
+            failures.push(format!(
+                "best_contract_file: expected {expected}, got {contract_file:?}"
+            ));
+        }
+    }
+
+    if let Some(expected) = &workload.expected_anchor_window {
+        if let Some(kind) = anchor_kind_from_str(&expected.kind) {
+            let (window_start, window_end) =
+                anchor_evidence_window(root, &expected.file, kind, 40).await;
+            let hit = expected.contains_line >= window_start && expected.contains_line <= window_end;
+            scores.push(DetectorScore {
+                detector: "anchor_evidence_window",
+                precision: if hit { 1.0 } else { 0.0 },
+                recall: if hit { 1.0 } else { 0.0 },
+            });
+            if !hit {
+                failures.push(format!(
+                    "anchor_evidence_window: line {} not in window {window_start}..{window_end}",
+                    expected.contains_line
+                ));
+            }
+        } else {
+            failures.push(format!("unknown anchor kind {}", expected.kind));
+        }
+    }
+
+    Ok(DetectorWorkloadReport {
+        name: workload.name.clone(),
+        wall_ms: start.elapsed().as_secs_f64() * 1000.0,
+        pass: failures.is_empty(),
+        scores,
+        failures,
+    })
+}
+
+fn anchor_kind_from_str(kind: &str) -> Option<AnchorKind> {
+    match kind {
+        "canon" => Some(AnchorKind::Canon),
+        "howto" => Some(AnchorKind::HowTo),
+        "ci" => Some(AnchorKind::Ci),
+        "contract" => Some(AnchorKind::Contract),
+        "entrypoint" => Some(AnchorKind::Entrypoint),
+        "artifact" => Some(AnchorKind::Artifact),
+        "experiment" => Some(AnchorKind::Experiment),
+        _ => None,
+    }
+}
+
+fn precision_recall<T: Eq + Hash>(actual: &HashSet<T>, expected: &HashSet<T>) -> (f64, f64) {
+    if actual.is_empty() && expected.is_empty() {
+        return (1.0, 1.0);
+    }
+    let true_positives = actual.intersection(expected).count() as f64;
+    let precision = if actual.is_empty() {
+        1.0
+    } else {
+        true_positives / actual.len() as f64
+    };
+    let recall = if expected.is_empty() {
+        1.0
+    } else {
+        true_positives / expected.len() as f64
+    };
+    (precision, recall)
+}
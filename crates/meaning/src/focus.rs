@@ -4,6 +4,7 @@ use context_protocol::{enforce_max_chars, BudgetTruncation};
 use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::Path;
 
+use crate::cache::{CacheMode, ScanCache, CACHE_FINGERPRINT};
 use crate::common::{
     build_ev_file_index, classify_boundaries, classify_files, contract_kind, detect_brokers,
     detect_channel_mentions, directory_key, evidence_fetch_payload_json, extract_asyncapi_flows,
@@ -18,6 +19,8 @@ use crate::pack::{
 };
 use crate::paths::normalize_relative_path;
 use crate::secrets::is_potential_secret_path;
+use crate::semantic_vectors::{semantic_file_scores, SemanticVectorStore};
+use crate::tokens::{estimate_pack_tokens, estimator_for_pack};
 
 const VERSION: u32 = 1;
 const DEFAULT_MAX_CHARS: usize = 2_000;
@@ -33,6 +36,7 @@ const DEFAULT_MAX_CONTRACTS: usize = 8;
 const DEFAULT_MAX_FLOWS: usize = 12;
 const DEFAULT_MAX_BROKERS: usize = 6;
 const DEFAULT_EVIDENCE_END_LINE: usize = 120;
+const DEFAULT_SEMANTIC_WEIGHT: f32 = 0.5;
 
 #[derive(Debug, Clone)]
 struct AnchorCandidate {
@@ -120,9 +124,9 @@ pub async fn meaning_focus(
         .map(|q| q.to_string())
         .unwrap_or_else(|| format!("focus:{focus_rel}"));
 
-    let scanner = FileScanner::new(root);
+    let mut scanner = FileScanner::new(root);
     let mut all_files: Vec<String> = Vec::new();
-    for abs in scanner.scan() {
+    for abs in scanner.scan()? {
         let Some(rel) = normalize_relative_path(root, &abs) else {
             continue;
         };
@@ -149,6 +153,13 @@ pub async fn meaning_focus(
         &scope_files
     };
 
+    // Loaded up front, mirroring `meaning_pack`, so flow extraction and broker detection below
+    // can reuse per-file cache entries too, not just the evidence-fetch stage.
+    let mut cache = request
+        .cache_path
+        .as_deref()
+        .map(|path| ScanCache::load(path, CacheMode::ReadWrite, CACHE_FINGERPRINT));
+
     let mut dir_files: HashMap<String, usize> = HashMap::new();
     let mut dir_files_with_artifacts: HashMap<String, usize> = HashMap::new();
     let focus_is_artifact = is_artifact_scope(&focus_rel) || is_artifact_scope(&focus_dir);
@@ -173,20 +184,47 @@ pub async fn meaning_focus(
     boundaries.truncate(DEFAULT_MAX_BOUNDARIES);
 
     let artifact_store_file = best_artifact_store_evidence_file(files_for_map);
-    let anchors = select_repo_anchors(
+    let mut anchors = select_repo_anchors(
         files_for_map,
         &entrypoints,
         &contracts,
         &boundaries,
         artifact_store_file.as_deref(),
     );
+    let mut entrypoints = entrypoints;
+    let mut contracts = contracts;
+
+    // `focus` has no BM25 relevance pass like `meaning_pack` does (it's scoped to a path, not
+    // a corpus-wide query), so when the caller opts into `semantic`, embedding similarity is the
+    // only query-relevance signal blended in here.
+    if request.semantic.unwrap_or(false) {
+        let candidates: Vec<String> = anchors
+            .iter()
+            .map(|a| a.file.clone())
+            .chain(entrypoints.iter().cloned())
+            .chain(contracts.iter().cloned())
+            .chain(boundaries.iter().map(|b| b.file.clone()))
+            .collect();
+        let relevance = semantic_relevance_for_focus(root, request, &query, &candidates).await?;
+        if !relevance.is_empty() {
+            let weight = request.semantic_weight.unwrap_or(DEFAULT_SEMANTIC_WEIGHT).clamp(0.0, 1.0);
+            apply_semantic_relevance_to_anchors(&mut anchors, &relevance, weight);
+            apply_semantic_relevance_to_boundaries(&mut boundaries, &relevance, weight);
+            sort_files_by_relevance(&mut entrypoints, &relevance);
+            sort_files_by_relevance(&mut contracts, &relevance);
+        }
+    }
+    let entrypoints = entrypoints;
+    let contracts = contracts;
 
-    let flows = extract_asyncapi_flows(root, &contracts).await;
+    let mut flows = extract_asyncapi_flows(root, &contracts, cache.as_mut()).await;
 
     let channels = flows.iter().map(|f| f.channel.clone()).collect::<Vec<_>>();
     let channel_mentions = detect_channel_mentions(root, files_for_map, &channels).await;
 
-    let brokers = detect_brokers(root, files_for_map, &flows).await;
+    let (brokers, broker_flows) = detect_brokers(root, files_for_map, &flows, cache.as_mut()).await;
+    flows.extend(broker_flows);
+    let flows = flows;
 
     let evidence = collect_focus_evidence(
         root,
@@ -200,8 +238,12 @@ pub async fn meaning_focus(
             flows: &flows,
             brokers: &brokers,
         },
+        cache.as_mut(),
     )
     .await;
+    if let Some(cache) = &cache {
+        cache.save()?;
+    }
     let ev_file_index = build_ev_file_index(&evidence);
 
     let root_fp = context_indexer::root_fingerprint(root_display);
@@ -309,6 +351,9 @@ pub async fn meaning_focus(
         file: String,
         proto: String,
         confidence: f32,
+        image: Option<String>,
+        via: Option<String>,
+        role: &'static str,
         ev_id: String,
     }
 
@@ -324,6 +369,9 @@ pub async fn meaning_focus(
             file: broker.file.clone(),
             proto: broker.proto.clone(),
             confidence: broker.confidence,
+            image: broker.image.clone(),
+            via: broker.via.clone(),
+            role: broker.role,
             ev_id,
         });
     }
@@ -388,6 +436,9 @@ pub async fn meaning_focus(
     }
     for broker in &emitted_brokers {
         dict_paths.insert(broker.file.clone());
+        if let Some(image) = &broker.image {
+            dict_paths.insert(image.clone());
+        }
     }
     for (idx, ev) in evidence.iter().enumerate() {
         let ev_id = format!("ev{idx}");
@@ -526,9 +577,19 @@ pub async fn meaning_focus(
         for broker in &emitted_brokers {
             let d = cp.dict_id(&broker.file);
             let conf = format!("{:.2}", broker.confidence.clamp(0.0, 1.0));
+            let image_field = broker
+                .image
+                .as_deref()
+                .map(|image| format!(" image={}", cp.dict_id(image)))
+                .unwrap_or_default();
+            let via_field = broker
+                .via
+                .as_deref()
+                .map(|via| format!(" via={via}"))
+                .unwrap_or_default();
             cp.push_line(&format!(
-                "BROKER proto={} file={d} conf={conf} ev={}",
-                broker.proto, broker.ev_id
+                "BROKER kind={} file={d} conf={conf}{image_field}{via_field} role={} ev={}",
+                broker.proto, broker.role, broker.ev_id
             ));
         }
     }
@@ -578,14 +639,17 @@ pub async fn meaning_focus(
         .unwrap_or_else(|| "NBA map".to_string());
     cp.push_line(&nba);
 
+    let max_tokens = request.max_tokens;
     let mut result = MeaningFocusResult {
         version: VERSION,
         query,
         format: "cpv1".to_string(),
-        pack: cp.render(),
+        pack: cp.render(max_tokens),
         budget: MeaningFocusBudget {
             max_chars,
             used_chars: 0,
+            max_tokens,
+            used_tokens: None,
             truncated: false,
             truncation: None,
         },
@@ -608,9 +672,32 @@ fn trim_to_budget(result: &mut MeaningFocusResult) -> anyhow::Result<()> {
         |inner| shrink_pack(&mut inner.pack),
     )?;
     result.budget.used_chars = used;
+
+    if let Some(max_tokens) = result.budget.max_tokens {
+        enforce_max_tokens(result, max_tokens)?;
+        result.budget.used_chars = result.pack.chars().count();
+    }
     Ok(())
 }
 
+/// Mirrors `enforce_max_chars`, but drives the shrink loop off a token estimate instead of a
+/// char count: characters are a poor proxy for LLM tokens (see `tokens::estimate_pack_tokens`).
+fn enforce_max_tokens(result: &mut MeaningFocusResult, max_tokens: usize) -> anyhow::Result<()> {
+    loop {
+        let estimator = estimator_for_pack(&result.pack);
+        let tokens = estimate_pack_tokens(&result.pack, &estimator);
+        result.budget.used_tokens = Some(tokens);
+        if tokens <= max_tokens {
+            return Ok(());
+        }
+        result.budget.truncated = true;
+        result.budget.truncation = Some(BudgetTruncation::MaxTokens);
+        if !shrink_pack(&mut result.pack) {
+            anyhow::bail!("budget exceeded (used_tokens={tokens}, max_tokens={max_tokens})");
+        }
+    }
+}
+
 struct FocusEvidenceContext<'a> {
     focus_is_dir: bool,
     focus_rel: &'a str,
@@ -622,6 +709,85 @@ struct FocusEvidenceContext<'a> {
     brokers: &'a [BrokerCandidate],
 }
 
+/// Embedding-similarity scores for `candidates` against `query`, backed by the same persistent
+/// vector store sidecar `meaning_pack` uses (next to `request.cache_path` when set; otherwise
+/// built and used for this call only, never persisted).
+async fn semantic_relevance_for_focus(
+    root: &Path,
+    request: &MeaningFocusRequest,
+    query: &str,
+    candidates: &[String],
+) -> Result<HashMap<String, f64>> {
+    let vector_mode = if request.cache_path.is_some() {
+        CacheMode::ReadWrite
+    } else {
+        CacheMode::Off
+    };
+    let vector_path = request
+        .cache_path
+        .as_ref()
+        .map(|p| p.with_extension("vectors.json"))
+        .unwrap_or_default();
+    let mut store = SemanticVectorStore::load(&vector_path, vector_mode);
+    let scores = semantic_file_scores(root, &mut store, query, candidates).await;
+    store.save()?;
+    Ok(scores.into_iter().map(|(file, score)| (file, f64::from(score))).collect())
+}
+
+/// Blends each anchor's path/kind-based confidence with its semantic relevance score at
+/// `weight`, then re-sorts by the blended confidence. No-op entries (not found in `relevance`)
+/// keep their original confidence.
+fn apply_semantic_relevance_to_anchors(
+    anchors: &mut [AnchorCandidate],
+    relevance: &HashMap<String, f64>,
+    weight: f32,
+) {
+    for anchor in anchors.iter_mut() {
+        if let Some(score) = relevance.get(&anchor.file) {
+            anchor.confidence =
+                ((1.0 - weight) * anchor.confidence + weight * (*score as f32)).clamp(0.0, 1.0);
+        }
+    }
+    anchors.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then_with(|| a.kind.as_str().cmp(b.kind.as_str()))
+            .then_with(|| a.file.cmp(&b.file))
+    });
+}
+
+/// Same blend as [`apply_semantic_relevance_to_anchors`], for boundary candidates.
+fn apply_semantic_relevance_to_boundaries(
+    boundaries: &mut [BoundaryCandidate],
+    relevance: &HashMap<String, f64>,
+    weight: f32,
+) {
+    for boundary in boundaries.iter_mut() {
+        if let Some(score) = relevance.get(&boundary.file) {
+            boundary.confidence =
+                ((1.0 - weight) * boundary.confidence + weight * (*score as f32)).clamp(0.0, 1.0);
+        }
+    }
+    boundaries.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then_with(|| a.file.cmp(&b.file))
+    });
+}
+
+/// Stable-sorts `files` by `relevance` descending, keeping the original (classify-order)
+/// relative order for ties and for files with no score at all. No-op if `relevance` is empty.
+fn sort_files_by_relevance(files: &mut [String], relevance: &HashMap<String, f64>) {
+    if relevance.is_empty() {
+        return;
+    }
+    files.sort_by(|a, b| {
+        let score_a = relevance.get(a).copied().unwrap_or(0.0);
+        let score_b = relevance.get(b).copied().unwrap_or(0.0);
+        score_b.total_cmp(&score_a)
+    });
+}
+
 fn select_repo_anchors(
     files: &[String],
     entrypoints: &[String],
@@ -713,7 +879,11 @@ fn select_repo_anchors(
     out
 }
 
-async fn collect_focus_evidence(root: &Path, focus: FocusEvidenceContext<'_>) -> Vec<EvidenceItem> {
+async fn collect_focus_evidence(
+    root: &Path,
+    focus: FocusEvidenceContext<'_>,
+    mut cache: Option<&mut ScanCache>,
+) -> Vec<EvidenceItem> {
     let mut out: Vec<EvidenceItem> = Vec::new();
     let mut seen: HashSet<&str> = HashSet::new();
 
@@ -732,7 +902,11 @@ async fn collect_focus_evidence(root: &Path, focus: FocusEvidenceContext<'_>) ->
     if let Some(rel) = focus_candidate.as_deref() {
         if seen.insert(rel) {
             let abs = root.join(rel);
-            let (hash, lines) = hash_and_count_lines(&abs).await.ok().unwrap_or_default();
+            let (hash, lines) = if let Some(cache) = cache.as_deref_mut() {
+                cache.hash_and_count_lines(rel, &abs).await.ok().unwrap_or_default()
+            } else {
+                hash_and_count_lines(&abs).await.ok().unwrap_or_default()
+            };
             let kind = if let Some(anchor) = focus.anchors.iter().find(|a| a.file == rel) {
                 EvidenceKind::Anchor(anchor.kind)
             } else if focus.entrypoints.iter().any(|f| f == rel) {
@@ -806,7 +980,10 @@ async fn collect_focus_evidence(root: &Path, focus: FocusEvidenceContext<'_>) ->
         candidates.push((EvidenceKind::Entrypoint, file.clone()));
     }
 
-    // Ensure broker config claims have evidence anchors.
+    // Ensure broker config claims have evidence anchors, pointing at the exact container block
+    // when structured YAML parsing found one (see `parse_yaml_containers`) rather than the
+    // default whole-file window.
+    let mut broker_windows: HashMap<&str, (usize, usize)> = HashMap::new();
     for broker in focus.brokers.iter().take(2) {
         if !seen.insert(broker.file.as_str()) {
             continue;
@@ -815,6 +992,9 @@ async fn collect_focus_evidence(root: &Path, focus: FocusEvidenceContext<'_>) ->
             EvidenceKind::Boundary(BoundaryKind::Config),
             broker.file.clone(),
         ));
+        if let Some(range) = broker.line_range {
+            broker_windows.insert(broker.file.as_str(), range);
+        }
     }
 
     for file in focus.entrypoints.iter().take(DEFAULT_MAX_EVIDENCE) {
@@ -846,18 +1026,28 @@ async fn collect_focus_evidence(root: &Path, focus: FocusEvidenceContext<'_>) ->
 
     for (kind, rel) in candidates.into_iter().take(DEFAULT_MAX_EVIDENCE) {
         let abs = root.join(&rel);
-        let (hash, lines) = hash_and_count_lines(&abs).await.ok().unwrap_or_default();
-        let (start_line, end_line) = match kind {
-            EvidenceKind::Anchor(anchor_kind) => {
-                let (start, end) =
-                    anchor_evidence_window(root, &rel, anchor_kind, DEFAULT_EVIDENCE_END_LINE)
-                        .await;
-                let file_lines = lines.max(1);
-                let start = start.clamp(1, file_lines);
-                let end = end.clamp(start, file_lines);
-                (start, end)
+        let (hash, lines) = if let Some(cache) = cache.as_deref_mut() {
+            cache.hash_and_count_lines(&rel, &abs).await.ok().unwrap_or_default()
+        } else {
+            hash_and_count_lines(&abs).await.ok().unwrap_or_default()
+        };
+        let (start_line, end_line) = if let Some(&(start, end)) = broker_windows.get(rel.as_str())
+        {
+            let file_lines = lines.max(1);
+            (start.clamp(1, file_lines), end.clamp(start, file_lines))
+        } else {
+            match kind {
+                EvidenceKind::Anchor(anchor_kind) => {
+                    let (start, end) =
+                        anchor_evidence_window(root, &rel, anchor_kind, DEFAULT_EVIDENCE_END_LINE)
+                            .await;
+                    let file_lines = lines.max(1);
+                    let start = start.clamp(1, file_lines);
+                    let end = end.clamp(start, file_lines);
+                    (start, end)
+                }
+                _ => (1, DEFAULT_EVIDENCE_END_LINE.min(lines.max(1))),
             }
-            _ => (1, DEFAULT_EVIDENCE_END_LINE.min(lines.max(1))),
         };
         out.push(EvidenceItem {
             kind,
@@ -9,17 +9,39 @@
 //! - `context-cli` (Command API)
 //! - `context-mcp` (MCP tools)
 
+pub mod bench;
+pub mod detector_bench;
 pub mod model;
+pub mod vectors;
 
+mod cache;
 mod common;
 mod focus;
+mod outline;
 mod pack;
 mod paths;
+mod query_lang;
+mod rules;
 mod secrets;
+mod semantic_vectors;
+mod tokens;
+mod validate;
 
+pub use bench::{run_benchmark, BenchEntryReport, BenchReport, BenchWorkload, BenchWorkloadEntry};
+pub use cache::{CacheMode, CachedBroker, CachedFlowEdge, ScanCache};
+pub use detector_bench::{
+    run_detector_benchmark, DetectorBenchReport, DetectorScore, DetectorWorkload,
+    DetectorWorkloadReport,
+};
 pub use focus::meaning_focus;
 pub use model::{
-    EvidencePointer, MeaningFocusBudget, MeaningFocusRequest, MeaningFocusResult,
-    MeaningPackBudget, MeaningPackRequest, MeaningPackResult,
+    DocumentSymbol, EvidencePointer, MeaningFocusBudget, MeaningFocusRequest, MeaningFocusResult,
+    MeaningOutlineRequest, MeaningOutlineResult, MeaningPackBudget, MeaningPackRequest,
+    MeaningPackResult,
 };
+pub use outline::meaning_outline;
 pub use pack::meaning_pack;
+pub use query_lang::{QueryParseError, ResolvedFilter};
+pub use rules::UserRuleDef;
+pub use validate::{repair_pack, validate_pack, Diagnostic, Severity};
+pub use vectors::{convert_vectors, write_flattened, TestVectorCorpus};
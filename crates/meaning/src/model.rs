@@ -1,6 +1,11 @@
+use std::path::PathBuf;
+
 use context_protocol::BudgetTruncation;
 use serde::{Deserialize, Serialize};
 
+use crate::query_lang::ResolvedFilter;
+use crate::rules::UserRuleDef;
+
 /// Evidence pointer (EV): minimal, verifiable reference to exact source material.
 ///
 /// This is an internal canonical model; tool/CLI adapters can format it as CP lines.
@@ -23,12 +28,32 @@ pub struct MeaningPackRequest {
     pub map_limit: Option<usize>,
     /// Maximum UTF-8 characters for the entire meaning pack (default: 2000).
     pub max_chars: Option<usize>,
+    /// Maximum estimated LLM tokens for the entire meaning pack (no default: unenforced unless
+    /// set, so callers can target an exact context window, e.g. 8000 or 32000).
+    pub max_tokens: Option<usize>,
+    /// Sidecar path for the incremental scan cache (no default: caching is off unless set, so
+    /// callers opt in with a path under their own state directory).
+    pub cache_path: Option<PathBuf>,
+    /// User-defined classification overrides (e.g. loaded from repo config), layered on top of
+    /// the built-in entrypoint/contract/boundary/anchor heuristics (default: none).
+    #[serde(default)]
+    pub rules: Vec<UserRuleDef>,
+    /// Rank candidates by embedding similarity to `query` in addition to lexical/path signals
+    /// (default: false, i.e. lexical-only — degrades gracefully with no behavior change).
+    pub semantic: Option<bool>,
+    /// Blend weight for the semantic score against the existing lexical score, 0.0 (ignore it)
+    /// to 1.0 (semantic-only); only used when `semantic` is true (default: 0.5).
+    pub semantic_weight: Option<f32>,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct MeaningPackBudget {
     pub max_chars: usize,
     pub used_chars: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub used_tokens: Option<usize>,
     pub truncated: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub truncation: Option<BudgetTruncation>,
@@ -41,6 +66,11 @@ pub struct MeaningPackResult {
     pub format: String,
     pub pack: String,
     pub budget: MeaningPackBudget,
+    /// Typed filters (`path:`, `lang:`, `symbol:`, `ext:`) parsed out of `query`, in source
+    /// order, so callers can confirm how their query was interpreted (empty for plain
+    /// free-text queries).
+    #[serde(default)]
+    pub resolved_filters: Vec<ResolvedFilter>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -55,12 +85,28 @@ pub struct MeaningFocusRequest {
     pub map_limit: Option<usize>,
     /// Maximum UTF-8 characters for the entire meaning pack (default: 2000).
     pub max_chars: Option<usize>,
+    /// Maximum estimated LLM tokens for the entire meaning pack (no default: unenforced unless
+    /// set, so callers can target an exact context window, e.g. 8000 or 32000).
+    pub max_tokens: Option<usize>,
+    /// Sidecar path for the incremental scan cache (no default: caching is off unless set, so
+    /// callers opt in with a path under their own state directory).
+    pub cache_path: Option<PathBuf>,
+    /// Rank candidates by embedding similarity to `query` in addition to path heuristics
+    /// (default: false, i.e. lexical-only — degrades gracefully with no behavior change).
+    pub semantic: Option<bool>,
+    /// Blend weight for the semantic score against the existing confidence score, 0.0 (ignore
+    /// it) to 1.0 (semantic-only); only used when `semantic` is true (default: 0.5).
+    pub semantic_weight: Option<f32>,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct MeaningFocusBudget {
     pub max_chars: usize,
     pub used_chars: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub used_tokens: Option<usize>,
     pub truncated: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub truncation: Option<BudgetTruncation>,
@@ -74,3 +120,30 @@ pub struct MeaningFocusResult {
     pub pack: String,
     pub budget: MeaningFocusBudget,
 }
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MeaningOutlineRequest {
+    /// Repo-relative file path to extract a document-symbol tree for.
+    pub file: String,
+    /// Maximum number of declarations to include (default: 200; clamped to 1..=2000).
+    pub limit: Option<usize>,
+}
+
+/// One entry of an LSP-style `textDocument/documentSymbol` response tree (`kind` is an LSP
+/// `SymbolKind` name, see `ChunkType::as_lsp_symbol_kind`).
+#[derive(Debug, Clone, Serialize)]
+pub struct DocumentSymbol {
+    pub name: String,
+    pub kind: &'static str,
+    pub start_line: usize,
+    pub end_line: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<DocumentSymbol>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MeaningOutlineResult {
+    pub version: u32,
+    pub file: String,
+    pub symbols: Vec<DocumentSymbol>,
+}
@@ -0,0 +1,45 @@
+//! LSP-style `textDocument/documentSymbol` entry point.
+//!
+//! Thin adapter over `common::extract_code_outline_tree`: turns the internal `OutlineNode`
+//! tree into the public `DocumentSymbol` shape so an editor/LSP server can request a
+//! hierarchical symbol outline for a single indexed file.
+
+use std::path::Path;
+
+use crate::common::{extract_code_outline_tree, OutlineNode};
+use crate::model::{DocumentSymbol, MeaningOutlineRequest, MeaningOutlineResult};
+
+const VERSION: u32 = 1;
+const DEFAULT_LIMIT: usize = 200;
+const MAX_LIMIT: usize = 2_000;
+
+pub async fn meaning_outline(
+    root: &Path,
+    request: &MeaningOutlineRequest,
+) -> MeaningOutlineResult {
+    let file = request.file.replace('\\', "/");
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let nodes = extract_code_outline_tree(root, &file, limit).await;
+    MeaningOutlineResult {
+        version: VERSION,
+        file,
+        symbols: nodes.into_iter().map(DocumentSymbol::from).collect(),
+    }
+}
+
+impl From<OutlineNode> for DocumentSymbol {
+    fn from(node: OutlineNode) -> Self {
+        Self {
+            name: node.name,
+            kind: node.kind,
+            start_line: node.start_line,
+            end_line: node.end_line,
+            children: node
+                .children
+                .into_iter()
+                .map(DocumentSymbol::from)
+                .collect(),
+        }
+    }
+}
@@ -4,18 +4,25 @@ use context_protocol::{enforce_max_chars, BudgetTruncation};
 use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::Path;
 
+use crate::cache::{CacheMode, ScanCache, CACHE_FINGERPRINT};
 use crate::common::{
-    artifact_scope_rank, build_ev_file_index, classify_boundaries, classify_files, contract_kind,
-    detect_brokers, detect_channel_mentions, directory_key, extract_asyncapi_flows,
-    hash_and_count_lines, infer_actor_by_path, infer_flow_actor, is_artifact_scope,
-    is_binary_blob_path, is_ci_config_candidate, is_code_file, is_contract_candidate,
-    is_dataset_like_path, json_string, read_file_prefix_utf8, shrink_pack, AnchorKind,
-    BoundaryCandidate, BoundaryKind, BrokerCandidate, CognitivePack, EvidenceItem, EvidenceKind,
-    FlowEdge,
+    artifact_scope_rank, bm25_query_file_scores, build_ev_file_index, classify_boundaries,
+    classify_files, contract_kind, detect_brokers, detect_channel_mentions, directory_key,
+    extract_asyncapi_flows, extract_dependencies, extract_env_vars, extract_openapi_flows,
+    extract_proto_flows, extract_syndicate_flows, hash_and_count_lines, tfidf_role_scores,
+    infer_actor_by_path, infer_flow_actor, is_artifact_scope, is_binary_blob_path,
+    is_ci_config_candidate, is_code_file, is_contract_candidate, is_dataset_like_path, json_string,
+    read_file_prefix_utf8, semantic_chunk_scores, semantic_role_terms, shrink_pack,
+    AnchorKind, BoundaryCandidate, BoundaryKind, BrokerCandidate, CognitivePack, EvidenceItem,
+    EvidenceKind, FlowEdge, HashingEmbedder,
 };
 use crate::model::{MeaningPackBudget, MeaningPackRequest, MeaningPackResult};
 use crate::paths::normalize_relative_path;
+use crate::query_lang::{self, CandidateFacts, ParsedQuery};
+use crate::rules::{Candidate as RuleCandidate, CandidateKind as RuleCandidateKind, RuleSet};
 use crate::secrets::is_potential_secret_path;
+use crate::semantic_vectors::{semantic_file_scores, SemanticVectorStore};
+use crate::tokens::{estimate_pack_tokens, estimator_for_pack};
 
 const VERSION: u32 = 1;
 const DEFAULT_MAX_CHARS: usize = 2_000;
@@ -31,6 +38,7 @@ const DEFAULT_MAX_CONTRACTS: usize = 8;
 const DEFAULT_MAX_FLOWS: usize = 12;
 const DEFAULT_MAX_BROKERS: usize = 6;
 const DEFAULT_EVIDENCE_END_LINE: usize = 120;
+const DEFAULT_SEMANTIC_WEIGHT: f32 = 0.5;
 
 #[derive(Debug, Clone, Copy)]
 struct QueryHints {
@@ -406,13 +414,14 @@ pub async fn meaning_pack(
         .clamp(MIN_MAX_CHARS, MAX_MAX_CHARS);
     let hints = QueryHints::from_query(&request.query);
     let tight_budget = max_chars <= 2_200;
+    let parsed_query = query_lang::parse_query(&request.query).map_err(anyhow::Error::from)?;
 
     // v0: facts-only map derived from filesystem paths (gitignore-aware), no full-file parsing.
-    let scanner = FileScanner::new(root);
+    let mut scanner = FileScanner::new(root);
     let mut files: Vec<String> = Vec::new();
     let mut sizes: HashMap<String, u64> = HashMap::new();
     let mut signals = RepoSignals::default();
-    for abs in scanner.scan() {
+    for abs in scanner.scan()? {
         let Some(rel) = normalize_relative_path(root, &abs) else {
             continue;
         };
@@ -480,6 +489,14 @@ pub async fn meaning_pack(
     files.sort();
     files.dedup();
 
+    // Loaded up front (rather than just before `collect_evidence`, as before) so classification,
+    // flow extraction, and broker detection below can all reuse per-file hash/derived-fact cache
+    // entries too, not just the evidence-fetch stage.
+    let mut cache = request
+        .cache_path
+        .as_deref()
+        .map(|path| ScanCache::load(path, CacheMode::ReadWrite, CACHE_FINGERPRINT));
+
     // Dynamic defaults (signal-driven): allow a more useful map without requiring explicit
     // map_depth/map_limit tuning by the caller.
     let mut map_depth = request.map_depth.unwrap_or(DEFAULT_MAP_DEPTH);
@@ -518,14 +535,24 @@ pub async fn meaning_pack(
         map_rows.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
     }
 
-    let (entrypoints, contracts) = classify_files(&files);
+    let (mut entrypoints, mut contracts) = classify_files(&files);
+    let (rule_boundaries, rule_anchors) =
+        apply_user_rules(root, &files, &request.rules, &mut entrypoints, &mut contracts).await;
+
     let mut boundaries_full = classify_boundaries(&files, &entrypoints, &contracts);
+    let mut seen_boundary_files: HashSet<String> =
+        boundaries_full.iter().map(|b| b.file.clone()).collect();
+    for boundary in rule_boundaries {
+        if seen_boundary_files.insert(boundary.file.clone()) {
+            boundaries_full.push(boundary);
+        }
+    }
     augment_k8s_manifest_boundaries(root, &files, &mut boundaries_full).await;
     let artifact_store_file = best_artifact_store_evidence_file(&files);
     // Budget-aware: keep anchors stable and actionable under tight budgets by reducing
     // lower-signal sections (boundaries/entrypoints/flows) unless the query asks for them.
     let max_anchors = if tight_budget { 5 } else { DEFAULT_MAX_ANCHORS };
-    let anchors = select_repo_anchors(
+    let mut anchors = select_repo_anchors(
         &files,
         &entrypoints,
         &contracts,
@@ -534,6 +561,9 @@ pub async fn meaning_pack(
         &hints,
         max_anchors,
     );
+    merge_rule_anchors(&mut anchors, rule_anchors, max_anchors);
+    apply_semantic_anchor_boost(root, &mut anchors).await;
+    apply_semantic_anchor_fallback(root, &files, &mut anchors, max_anchors).await;
     let include_entrypoints = !tight_budget || hints.wants_entrypoints;
     // Signal-driven boundary inclusion: keep “external” boundaries (HTTP/CLI/events/DB) even when
     // the query is a broad onboarding prompt that doesn't explicitly say “infra/boundary”.
@@ -552,7 +582,19 @@ pub async fn meaning_pack(
     boundaries_full.truncate(DEFAULT_MAX_BOUNDARIES);
     let boundaries = boundaries_full;
 
-    let flows = extract_asyncapi_flows(root, &contracts).await;
+    let mut flows = extract_asyncapi_flows(root, &contracts, cache.as_mut()).await;
+    flows.extend(extract_proto_flows(root, &contracts, cache.as_mut()).await);
+    flows.extend(extract_syndicate_flows(root, &contracts, cache.as_mut()).await);
+    let env_vars = extract_env_vars(root, &files).await;
+    let routes = extract_openapi_flows(root, &contracts).await;
+
+    let mut dependencies = Vec::new();
+    for boundary in &boundaries {
+        if boundary.kind != BoundaryKind::Dependency {
+            continue;
+        }
+        dependencies.extend(extract_dependencies(root, &boundary.file).await);
+    }
 
     let channels = flows.iter().map(|f| f.channel.clone()).collect::<Vec<_>>();
     let channel_mentions = detect_channel_mentions(root, &files, &channels).await;
@@ -561,12 +603,15 @@ pub async fn meaning_pack(
     let include_flows = !flows.is_empty();
     let include_brokers =
         !tight_budget || hints.wants_brokers || hints.wants_infra || !flows.is_empty();
-    let flows = if include_flows { flows } else { Vec::new() };
+    let mut flows = if include_flows { flows } else { Vec::new() };
     let brokers = if include_brokers {
-        detect_brokers(root, &files, &flows).await
+        let (brokers, broker_flows) = detect_brokers(root, &files, &flows, cache.as_mut()).await;
+        flows.extend(broker_flows);
+        brokers
     } else {
         Vec::new()
     };
+    let flows = flows;
 
     let evidence_entrypoints: &[String] = if include_entrypoints {
         &entrypoints
@@ -581,6 +626,36 @@ pub async fn meaning_pack(
     let evidence_boundaries: &[BoundaryCandidate] =
         if include_boundaries { &boundaries } else { &[] };
 
+    // Query-aware relevance pass (BM25 over path + content prefix): blended into anchor
+    // confidence, and reused to re-rank evidence/flows/map rows below, so a query actually
+    // changes what gets surfaced instead of every query against the same repo producing the
+    // same pack. Scored once over the union of candidates these sections draw from; empty when
+    // the query has no usable terms, in which case every section below falls back to its
+    // existing (static-heuristic) ordering.
+    let relevance_candidates: Vec<String> = anchors
+        .iter()
+        .map(|a| a.file.clone())
+        .chain(entrypoints.iter().cloned())
+        .chain(contracts.iter().cloned())
+        .chain(boundaries.iter().map(|b| b.file.clone()))
+        .chain(flows.iter().map(|f| f.contract_file.clone()))
+        .collect();
+    let relevance_candidates =
+        prune_candidates_by_filters(root, &parsed_query, relevance_candidates).await;
+    let mut relevance =
+        bm25_query_file_scores(root, &parsed_query.free_text, &relevance_candidates).await;
+    if request.semantic.unwrap_or(false) {
+        blend_semantic_relevance(
+            root,
+            request,
+            &parsed_query.free_text,
+            &relevance_candidates,
+            &mut relevance,
+        )
+        .await?;
+    }
+    apply_query_relevance_to_anchors(&mut anchors, &relevance);
+
     let evidence = collect_evidence(
         root,
         &anchors,
@@ -589,33 +664,31 @@ pub async fn meaning_pack(
         evidence_boundaries,
         &flows,
         &brokers,
+        &relevance,
+        cache.as_mut(),
     )
     .await;
+    if let Some(cache) = &cache {
+        cache.save()?;
+    }
     let ev_file_index = build_ev_file_index(&evidence);
 
     // Evidence-driven map ranking: prefer directories that contain “sources of truth” over those
-    // that merely have many files (dataset-heavy repos, vendored trees, etc.).
-    let mut dir_scores: HashMap<String, i32> = HashMap::new();
+    // that merely have many files (dataset-heavy repos, vendored trees, etc.). Folds in each
+    // evidence file's query relevance too, so a directory holding the query-relevant file ranks
+    // above one that merely holds a higher-priority *kind* of evidence.
+    let mut dir_scores: HashMap<String, f64> = HashMap::new();
     for ev in &evidence {
         let dir = directory_key(&ev.file, map_depth);
-        let weight = match &ev.kind {
-            EvidenceKind::Anchor(AnchorKind::Canon) => 100,
-            EvidenceKind::Anchor(AnchorKind::HowTo) => 95,
-            EvidenceKind::Anchor(AnchorKind::Ci) => 90,
-            EvidenceKind::Contract | EvidenceKind::Anchor(AnchorKind::Contract) => 85,
-            EvidenceKind::Entrypoint | EvidenceKind::Anchor(AnchorKind::Entrypoint) => 75,
-            EvidenceKind::Anchor(AnchorKind::Experiment) => 65,
-            EvidenceKind::Anchor(AnchorKind::Artifact) => 60,
-            EvidenceKind::Anchor(AnchorKind::Infra) => 55,
-            EvidenceKind::Boundary(_) => 40,
-        };
-        *dir_scores.entry(dir).or_insert(0) += weight;
+        let weight = f64::from(evidence_kind_weight(&ev.kind));
+        let relevance_boost = relevance.get(&ev.file).copied().unwrap_or(0.0) * 100.0;
+        *dir_scores.entry(dir).or_insert(0.0) += weight + relevance_boost;
     }
     map_rows.sort_by(|a, b| {
-        let score_a = *dir_scores.get(&a.0).unwrap_or(&0);
-        let score_b = *dir_scores.get(&b.0).unwrap_or(&0);
+        let score_a = dir_scores.get(&a.0).copied().unwrap_or(0.0);
+        let score_b = dir_scores.get(&b.0).copied().unwrap_or(0.0);
         score_b
-            .cmp(&score_a)
+            .total_cmp(&score_a)
             .then_with(|| b.1.cmp(&a.1))
             .then_with(|| a.0.cmp(&b.0))
     });
@@ -671,10 +744,6 @@ pub async fn meaning_pack(
 
     let mut emitted_flows: Vec<EmittedFlow> = Vec::new();
     for flow in &flows {
-        if emitted_flows.len() >= DEFAULT_MAX_FLOWS {
-            break;
-        }
-
         let actor_from_mentions = channel_mentions
             .get(&flow.channel)
             .and_then(|hit| infer_actor_by_path(hit, &entrypoints));
@@ -705,12 +774,27 @@ pub async fn meaning_pack(
             ev_id,
         });
     }
+    // Query-relevance re-rank, same blend as anchors/evidence above; a stable sort keeps
+    // insertion order (and so today's truncation order) when there's no query to rank against.
+    if !relevance.is_empty() {
+        emitted_flows.sort_by(|a, b| {
+            let score_a = 0.5 * f64::from(a.confidence)
+                + 0.5 * relevance.get(&a.contract_file).copied().unwrap_or(0.0);
+            let score_b = 0.5 * f64::from(b.confidence)
+                + 0.5 * relevance.get(&b.contract_file).copied().unwrap_or(0.0);
+            score_b.total_cmp(&score_a)
+        });
+    }
+    emitted_flows.truncate(DEFAULT_MAX_FLOWS);
 
     #[derive(Clone)]
     struct EmittedBroker {
         file: String,
         proto: String,
         confidence: f32,
+        image: Option<String>,
+        via: Option<String>,
+        role: &'static str,
         ev_id: String,
     }
 
@@ -726,6 +810,72 @@ pub async fn meaning_pack(
             file: broker.file.clone(),
             proto: broker.proto.clone(),
             confidence: broker.confidence,
+            image: broker.image.clone(),
+            via: broker.via.clone(),
+            role: broker.role,
+            ev_id,
+        });
+    }
+
+    #[derive(Clone)]
+    struct EmittedEnvVar {
+        file: String,
+        key: String,
+        has_default: bool,
+        ev_id: String,
+    }
+
+    let mut emitted_env_vars: Vec<EmittedEnvVar> = Vec::new();
+    for var in &env_vars {
+        let Some(ev_id) = ev_file_index.get(&var.file).cloned() else {
+            continue;
+        };
+        emitted_env_vars.push(EmittedEnvVar {
+            file: var.file.clone(),
+            key: var.key.clone(),
+            has_default: var.has_default,
+            ev_id,
+        });
+    }
+
+    #[derive(Clone)]
+    struct EmittedRoute {
+        path: String,
+        method: String,
+        operation_id: Option<String>,
+        ev_id: String,
+    }
+
+    let mut emitted_routes: Vec<EmittedRoute> = Vec::new();
+    for route in routes.iter().take(DEFAULT_MAX_FLOWS) {
+        let Some(ev_id) = ev_file_index.get(&route.contract_file).cloned() else {
+            continue;
+        };
+        emitted_routes.push(EmittedRoute {
+            path: route.path.clone(),
+            method: route.method.clone(),
+            operation_id: route.operation_id.clone(),
+            ev_id,
+        });
+    }
+
+    #[derive(Clone)]
+    struct EmittedDependency {
+        manifest_file: String,
+        name: String,
+        version: Option<String>,
+        ev_id: String,
+    }
+
+    let mut emitted_dependencies: Vec<EmittedDependency> = Vec::new();
+    for dep in &dependencies {
+        let Some(ev_id) = ev_file_index.get(&dep.manifest_file).cloned() else {
+            continue;
+        };
+        emitted_dependencies.push(EmittedDependency {
+            manifest_file: dep.manifest_file.clone(),
+            name: dep.name.clone(),
+            version: dep.version.clone(),
             ev_id,
         });
     }
@@ -822,6 +972,9 @@ pub async fn meaning_pack(
     }
     for broker in &emitted_brokers {
         dict_paths.insert(broker.file.clone());
+        if let Some(image) = &broker.image {
+            dict_paths.insert(image.clone());
+        }
     }
     for (idx, ev) in evidence.iter().enumerate() {
         let ev_id = format!("ev{idx}");
@@ -886,6 +1039,51 @@ pub async fn meaning_pack(
         }
     }
 
+    if !emitted_env_vars.is_empty() {
+        cp.push_line("S ENVVARS");
+        for var in &emitted_env_vars {
+            let file_d = cp.dict_id(&var.file);
+            let key_d = cp.dict_id(&var.key);
+            cp.push_line(&format!(
+                "ENVVAR file={file_d} key={key_d} default={} ev={}",
+                var.has_default, var.ev_id
+            ));
+        }
+    }
+
+    if !emitted_dependencies.is_empty() {
+        cp.push_line("S DEPENDENCIES");
+        for dep in &emitted_dependencies {
+            let manifest_d = cp.dict_id(&dep.manifest_file);
+            let name_d = cp.dict_id(&dep.name);
+            let version_field = dep
+                .version
+                .as_deref()
+                .map(|v| format!(" version={}", cp.dict_id(v)))
+                .unwrap_or_default();
+            cp.push_line(&format!(
+                "DEPENDENCY manifest={manifest_d} name={name_d}{version_field} ev={}",
+                dep.ev_id
+            ));
+        }
+    }
+
+    if !emitted_routes.is_empty() {
+        cp.push_line("S ROUTES");
+        for route in &emitted_routes {
+            let path_d = cp.dict_id(&route.path);
+            let op_field = route
+                .operation_id
+                .as_deref()
+                .map(|op| format!(" op={}", cp.dict_id(op)))
+                .unwrap_or_default();
+            cp.push_line(&format!(
+                "ROUTE method={} path={path_d}{op_field} ev={}",
+                route.method, route.ev_id
+            ));
+        }
+    }
+
     cp.push_line("S MAP");
     for area in areas
         .iter()
@@ -997,9 +1195,19 @@ pub async fn meaning_pack(
         for broker in &emitted_brokers {
             let d = cp.dict_id(&broker.file);
             let conf = format!("{:.2}", broker.confidence.clamp(0.0, 1.0));
+            let image_field = broker
+                .image
+                .as_deref()
+                .map(|image| format!(" image={}", cp.dict_id(image)))
+                .unwrap_or_default();
+            let via_field = broker
+                .via
+                .as_deref()
+                .map(|via| format!(" via={via}"))
+                .unwrap_or_default();
             cp.push_line(&format!(
-                "BROKER proto={} file={d} conf={conf} ev={}",
-                broker.proto, broker.ev_id
+                "BROKER kind={} file={d} conf={conf}{image_field}{via_field} role={} ev={}",
+                broker.proto, broker.role, broker.ev_id
             ));
         }
     }
@@ -1046,17 +1254,21 @@ pub async fn meaning_pack(
         .unwrap_or_else(|| "NBA map".to_string());
     cp.push_line(&nba);
 
+    let max_tokens = request.max_tokens;
     let mut result = MeaningPackResult {
         version: VERSION,
         query: request.query.clone(),
         format: "cpv1".to_string(),
-        pack: cp.render(),
+        pack: cp.render(max_tokens),
         budget: MeaningPackBudget {
             max_chars,
             used_chars: 0,
+            max_tokens,
+            used_tokens: None,
             truncated: false,
             truncation: None,
         },
+        resolved_filters: parsed_query.filters,
     };
 
     trim_to_budget(&mut result)?;
@@ -1076,9 +1288,49 @@ fn trim_to_budget(result: &mut MeaningPackResult) -> anyhow::Result<()> {
         |inner| shrink_pack(&mut inner.pack),
     )?;
     result.budget.used_chars = used;
+
+    if let Some(max_tokens) = result.budget.max_tokens {
+        enforce_max_tokens(result, max_tokens)?;
+        result.budget.used_chars = result.pack.chars().count();
+    }
     Ok(())
 }
 
+/// Mirrors `enforce_max_chars`, but drives the shrink loop off a token estimate instead of a
+/// char count: characters are a poor proxy for LLM tokens (see `tokens::estimate_pack_tokens`).
+fn enforce_max_tokens(result: &mut MeaningPackResult, max_tokens: usize) -> anyhow::Result<()> {
+    loop {
+        let estimator = estimator_for_pack(&result.pack);
+        let tokens = estimate_pack_tokens(&result.pack, &estimator);
+        result.budget.used_tokens = Some(tokens);
+        if tokens <= max_tokens {
+            return Ok(());
+        }
+        result.budget.truncated = true;
+        result.budget.truncation = Some(BudgetTruncation::MaxTokens);
+        if !shrink_pack(&mut result.pack) {
+            anyhow::bail!("budget exceeded (used_tokens={tokens}, max_tokens={max_tokens})");
+        }
+    }
+}
+
+/// Static priority weight for an evidence candidate's kind, on a 0-100 scale. Drives evidence
+/// truncation order and directory map ranking; see [`bm25_query_file_scores`] for the
+/// query-relevance signal blended on top of it.
+fn evidence_kind_weight(kind: &EvidenceKind) -> i32 {
+    match kind {
+        EvidenceKind::Anchor(AnchorKind::Canon) => 100,
+        EvidenceKind::Anchor(AnchorKind::HowTo) => 95,
+        EvidenceKind::Anchor(AnchorKind::Ci) => 90,
+        EvidenceKind::Contract | EvidenceKind::Anchor(AnchorKind::Contract) => 85,
+        EvidenceKind::Entrypoint | EvidenceKind::Anchor(AnchorKind::Entrypoint) => 75,
+        EvidenceKind::Anchor(AnchorKind::Experiment) => 65,
+        EvidenceKind::Anchor(AnchorKind::Artifact) => 60,
+        EvidenceKind::Anchor(AnchorKind::Infra) => 55,
+        EvidenceKind::Boundary(_) => 40,
+    }
+}
+
 async fn collect_evidence(
     root: &Path,
     anchors: &[AnchorCandidate],
@@ -1087,6 +1339,8 @@ async fn collect_evidence(
     boundaries: &[BoundaryCandidate],
     flows: &[FlowEdge],
     brokers: &[BrokerCandidate],
+    relevance: &HashMap<String, f64>,
+    mut cache: Option<&mut ScanCache>,
 ) -> Vec<EvidenceItem> {
     let mut candidates: Vec<(EvidenceKind, String)> = Vec::new();
     let mut seen: HashSet<&str> = HashSet::new();
@@ -1129,7 +1383,10 @@ async fn collect_evidence(
         candidates.push((EvidenceKind::Entrypoint, file.clone()));
     }
 
-    // Ensure broker config claims have evidence anchors.
+    // Ensure broker config claims have evidence anchors, pointing at the exact container block
+    // when structured YAML parsing found one (see `parse_yaml_containers`) rather than the
+    // default whole-file window.
+    let mut broker_windows: HashMap<&str, (usize, usize)> = HashMap::new();
     for broker in brokers.iter().take(2) {
         if !seen.insert(broker.file.as_str()) {
             continue;
@@ -1138,6 +1395,9 @@ async fn collect_evidence(
             EvidenceKind::Boundary(BoundaryKind::Config),
             broker.file.clone(),
         ));
+        if let Some(range) = broker.line_range {
+            broker_windows.insert(broker.file.as_str(), range);
+        }
     }
 
     for file in entrypoints.iter().take(DEFAULT_MAX_EVIDENCE) {
@@ -1165,21 +1425,45 @@ async fn collect_evidence(
         candidates.push((EvidenceKind::Boundary(boundary.kind), boundary.file.clone()));
     }
 
+    // Query-relevance re-rank: when the query scored any of these candidates, surface the
+    // query-relevant ones first instead of the fixed category-priority insertion order above.
+    // A stable sort keeps that insertion order as the tie-break, and as the sole order when
+    // `relevance` is empty (no query, or no candidate matched any query term).
+    if !relevance.is_empty() {
+        candidates.sort_by(|a, b| {
+            let score_a = 0.5 * f64::from(evidence_kind_weight(&a.0)) / 100.0
+                + 0.5 * relevance.get(&a.1).copied().unwrap_or(0.0);
+            let score_b = 0.5 * f64::from(evidence_kind_weight(&b.0)) / 100.0
+                + 0.5 * relevance.get(&b.1).copied().unwrap_or(0.0);
+            score_b.total_cmp(&score_a)
+        });
+    }
+
     let mut out = Vec::new();
     for (kind, rel) in candidates.into_iter().take(DEFAULT_MAX_EVIDENCE) {
         let abs = root.join(&rel);
-        let (hash, lines) = hash_and_count_lines(&abs).await.ok().unwrap_or_default();
-        let (start_line, end_line) = match kind {
-            EvidenceKind::Anchor(anchor_kind) => {
-                let (start, end) =
-                    anchor_evidence_window(root, &rel, anchor_kind, DEFAULT_EVIDENCE_END_LINE)
-                        .await;
-                let file_lines = lines.max(1);
-                let start = start.clamp(1, file_lines);
-                let end = end.clamp(start, file_lines);
-                (start, end)
+        let (hash, lines) = if let Some(cache) = cache.as_deref_mut() {
+            cache.hash_and_count_lines(&rel, &abs).await.ok().unwrap_or_default()
+        } else {
+            hash_and_count_lines(&abs).await.ok().unwrap_or_default()
+        };
+        let (start_line, end_line) = if let Some(&(start, end)) = broker_windows.get(rel.as_str())
+        {
+            let file_lines = lines.max(1);
+            (start.clamp(1, file_lines), end.clamp(start, file_lines))
+        } else {
+            match kind {
+                EvidenceKind::Anchor(anchor_kind) => {
+                    let (start, end) =
+                        anchor_evidence_window(root, &rel, anchor_kind, DEFAULT_EVIDENCE_END_LINE)
+                            .await;
+                    let file_lines = lines.max(1);
+                    let start = start.clamp(1, file_lines);
+                    let end = end.clamp(start, file_lines);
+                    (start, end)
+                }
+                _ => (1, DEFAULT_EVIDENCE_END_LINE.min(lines.max(1))),
             }
-            _ => (1, DEFAULT_EVIDENCE_END_LINE.min(lines.max(1))),
         };
         out.push(EvidenceItem {
             kind,
@@ -1192,6 +1476,81 @@ async fn collect_evidence(
     out
 }
 
+/// Runs the caller's [`UserRuleDef`](crate::rules::UserRuleDef) overrides (if any) and folds
+/// entrypoint/contract hits directly into `entrypoints`/`contracts`, returning the
+/// boundary/anchor hits separately so callers can merge them alongside the built-in
+/// `classify_boundaries`/`select_repo_anchors` results. A no-op (and no file IO) when the
+/// request carries no rules.
+async fn apply_user_rules(
+    root: &Path,
+    files: &[String],
+    rule_defs: &[crate::rules::UserRuleDef],
+    entrypoints: &mut Vec<String>,
+    contracts: &mut Vec<String>,
+) -> (Vec<BoundaryCandidate>, Vec<AnchorCandidate>) {
+    let rule_set = RuleSet::from_user_rules(rule_defs);
+    if rule_set.is_empty() {
+        return (Vec::new(), Vec::new());
+    }
+
+    let mut rule_boundaries = Vec::new();
+    let mut rule_anchors = Vec::new();
+    for candidate in rule_set.run(root, files).await {
+        let RuleCandidate {
+            kind,
+            file,
+            confidence,
+        } = candidate;
+        match kind {
+            RuleCandidateKind::Entrypoint => {
+                if !entrypoints.contains(&file) {
+                    entrypoints.push(file);
+                }
+            }
+            RuleCandidateKind::Contract => {
+                if !contracts.contains(&file) {
+                    contracts.push(file);
+                }
+            }
+            RuleCandidateKind::Boundary(kind) => rule_boundaries.push(BoundaryCandidate {
+                kind,
+                file,
+                confidence,
+            }),
+            RuleCandidateKind::Anchor(kind) => rule_anchors.push(AnchorCandidate {
+                kind,
+                label: format!("{}: user rule", kind.as_str()),
+                file,
+                confidence,
+            }),
+        }
+    }
+    entrypoints.sort();
+    contracts.sort();
+    (rule_boundaries, rule_anchors)
+}
+
+/// Folds user-rule anchor hits into the built-in anchor list, skipping files already present,
+/// then re-applies the same confidence-first ordering and cap `select_repo_anchors` used.
+fn merge_rule_anchors(anchors: &mut Vec<AnchorCandidate>, rule_anchors: Vec<AnchorCandidate>, max_anchors: usize) {
+    if rule_anchors.is_empty() {
+        return;
+    }
+    let mut seen: HashSet<String> = anchors.iter().map(|a| a.file.clone()).collect();
+    for anchor in rule_anchors {
+        if seen.insert(anchor.file.clone()) {
+            anchors.push(anchor);
+        }
+    }
+    anchors.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then_with(|| a.kind.as_str().cmp(b.kind.as_str()))
+            .then_with(|| a.file.cmp(&b.file))
+    });
+    anchors.truncate(max_anchors.max(1));
+}
+
 fn select_repo_anchors(
     files: &[String],
     entrypoints: &[String],
@@ -1326,6 +1685,194 @@ fn select_repo_anchors(
     out
 }
 
+/// Blends each anchor's confidence with its BM25 query-relevance score, 0.5/0.5, and re-sorts
+/// by the blended confidence. No-op when `relevance` is empty (e.g. the caller's query had no
+/// usable terms), so anchor order is unchanged from the path/kind-priority ordering above.
+fn apply_query_relevance_to_anchors(anchors: &mut [AnchorCandidate], relevance: &HashMap<String, f64>) {
+    if relevance.is_empty() {
+        return;
+    }
+    for anchor in anchors.iter_mut() {
+        if let Some(score) = relevance.get(&anchor.file) {
+            anchor.confidence = (0.5 * anchor.confidence + 0.5 * (*score as f32)).clamp(0.0, 1.0);
+        }
+    }
+    anchors.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then_with(|| a.kind.as_str().cmp(b.kind.as_str()))
+            .then_with(|| a.file.cmp(&b.file))
+    });
+}
+
+/// Drops candidates that don't satisfy `parsed`'s filters (`path:`/`lang:`/`ext:`/`symbol:` and
+/// their `AND`/`OR`/`NOT` combinations). Free-text terms never prune here — they already feed
+/// `bm25_query_file_scores`/`blend_semantic_relevance` via `parsed.free_text` instead. Reads and
+/// chunks file content only when the query actually has a `symbol:` filter, since that's the only
+/// facet that needs it.
+async fn prune_candidates_by_filters(
+    root: &Path,
+    parsed: &ParsedQuery,
+    candidates: Vec<String>,
+) -> Vec<String> {
+    if candidates.is_empty() || parsed.filters.is_empty() {
+        return candidates;
+    }
+    let need_symbols = query_lang::needs_symbol_facts(parsed);
+    let mut kept = Vec::with_capacity(candidates.len());
+    for path in candidates {
+        let symbol_names = if need_symbols {
+            symbol_names_for_file(root, &path).await
+        } else {
+            None
+        };
+        let facts = CandidateFacts {
+            path: &path,
+            symbol_names: symbol_names.as_deref(),
+        };
+        if query_lang::matches(parsed, &facts) {
+            kept.push(path);
+        }
+    }
+    kept
+}
+
+/// Chunks `file` (symbol-scoped spans, same chunker used for embeddings) and collects every
+/// span's symbol name, for evaluating a `symbol:` filter against.
+async fn symbol_names_for_file(root: &Path, file: &str) -> Option<Vec<String>> {
+    const MAX_READ_BYTES: usize = 96 * 1024;
+    let content = read_file_prefix_utf8(root, file, MAX_READ_BYTES).await?;
+    let chunker =
+        context_code_chunker::Chunker::new(context_code_chunker::ChunkerConfig::for_embeddings());
+    let chunks = chunker.chunk_str(&content, Some(file)).ok()?;
+    let names: Vec<String> = chunks
+        .into_iter()
+        .filter_map(|chunk| chunk.metadata.symbol_name)
+        .collect();
+    (!names.is_empty()).then_some(names)
+}
+
+/// Folds an embedding-similarity score for each of `candidates` into `relevance`, blended with
+/// whatever lexical (BM25) score is already there at `request.semantic_weight` (default 0.5).
+/// Backed by a persistent, content-hash-addressed vector store sidecar next to
+/// `request.cache_path` when set, so a warm re-pack over an unchanged file skips re-embedding;
+/// with no `cache_path`, the store is still built and used for this call, just never persisted.
+async fn blend_semantic_relevance(
+    root: &Path,
+    request: &MeaningPackRequest,
+    query_text: &str,
+    candidates: &[String],
+    relevance: &mut HashMap<String, f64>,
+) -> Result<()> {
+    let weight = request.semantic_weight.unwrap_or(DEFAULT_SEMANTIC_WEIGHT).clamp(0.0, 1.0);
+    let vector_mode = if request.cache_path.is_some() {
+        CacheMode::ReadWrite
+    } else {
+        CacheMode::Off
+    };
+    let vector_path = request
+        .cache_path
+        .as_ref()
+        .map(|p| p.with_extension("vectors.json"))
+        .unwrap_or_default();
+    let mut store = SemanticVectorStore::load(&vector_path, vector_mode);
+    let semantic_scores = semantic_file_scores(root, &mut store, query_text, candidates).await;
+    for (file, score) in semantic_scores {
+        let lexical = relevance.get(&file).copied().unwrap_or(0.0);
+        relevance.insert(
+            file,
+            (1.0 - f64::from(weight)) * lexical + f64::from(weight) * f64::from(score),
+        );
+    }
+    store.save()
+}
+
+/// Blends each selected anchor's path-based confidence with a TF-IDF content score for its
+/// role, 0.5/0.5, so anchor confidence reflects content as well as file placement. Falls back
+/// to the original path-only confidence when the role has no query terms or the file can't be
+/// read (e.g. empty corpus).
+async fn apply_semantic_anchor_boost(root: &Path, anchors: &mut [AnchorCandidate]) {
+    let role_of = |kind: AnchorKind| -> &'static str {
+        match kind {
+            AnchorKind::Canon => "canon",
+            AnchorKind::HowTo => "howto",
+            AnchorKind::Contract => "contract",
+            AnchorKind::Infra => "infra",
+            _ => "",
+        }
+    };
+
+    for anchor in anchors.iter_mut() {
+        let role = role_of(anchor.kind);
+        if role.is_empty() {
+            continue;
+        }
+        let candidates = vec![anchor.file.clone()];
+        let scores = tfidf_role_scores(root, &candidates, role).await;
+        if let Some(score) = scores.get(&anchor.file) {
+            anchor.confidence = (0.5 * anchor.confidence + 0.5 * score).clamp(0.0, 1.0);
+        }
+    }
+}
+
+/// Semantic tier for anchor *selection*, not just confidence: when `select_repo_anchors`'
+/// path heuristics found nothing for canon/howto/infra (e.g. a quick-start doc titled
+/// "Onboarding" that no path rule recognizes), chunk the remaining files, embed them against
+/// the role's query terms, and add the best above-threshold match as a lower-confidence
+/// anchor. Only fires for roles still missing after the path tier — an anchor the path tier
+/// already found is left to `apply_semantic_anchor_boost` for confidence blending instead.
+async fn apply_semantic_anchor_fallback(
+    root: &Path,
+    files: &[String],
+    anchors: &mut Vec<AnchorCandidate>,
+    max_anchors: usize,
+) {
+    const SEMANTIC_THRESHOLD: f32 = 0.3;
+    let max_anchors = max_anchors.max(1);
+    if anchors.len() >= max_anchors {
+        return;
+    }
+
+    const ROLES: [(AnchorKind, &str, &str); 3] = [
+        (AnchorKind::Canon, "canon", "Canon: start here (semantic match)"),
+        (AnchorKind::HowTo, "howto", "How-to: run / test (semantic match)"),
+        (AnchorKind::Infra, "infra", "Infra: deploy (semantic match)"),
+    ];
+
+    let embedder = HashingEmbedder::default();
+    for (kind, role, label) in ROLES {
+        if anchors.len() >= max_anchors {
+            break;
+        }
+        if anchors.iter().any(|a| a.kind == kind) {
+            continue;
+        }
+        let terms = semantic_role_terms(role);
+        if terms.is_empty() {
+            continue;
+        }
+        let seen: HashSet<&str> = anchors.iter().map(|a| a.file.as_str()).collect();
+        let candidates: Vec<String> = files
+            .iter()
+            .filter(|f| !seen.contains(f.as_str()))
+            .cloned()
+            .collect();
+        let scores = semantic_chunk_scores(root, &candidates, terms, &embedder).await;
+        let best = scores
+            .into_iter()
+            .filter(|(_, score)| *score >= SEMANTIC_THRESHOLD)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((file, score)) = best {
+            anchors.push(AnchorCandidate {
+                kind,
+                label: label.to_string(),
+                file,
+                confidence: (0.4 + 0.3 * score).clamp(0.0, 0.7),
+            });
+        }
+    }
+}
+
 pub(crate) fn best_artifact_store_evidence_file(files: &[String]) -> Option<String> {
     let mut candidates: Vec<(usize, usize, &String)> = Vec::new();
     for file in files {
@@ -1638,7 +2185,7 @@ pub(crate) fn best_infra_file(boundaries: &[BoundaryCandidate]) -> Option<String
     })
 }
 
-async fn augment_k8s_manifest_boundaries(
+pub(crate) async fn augment_k8s_manifest_boundaries(
     root: &Path,
     files: &[String],
     boundaries: &mut Vec<BoundaryCandidate>,
@@ -1813,10 +2360,193 @@ pub(crate) async fn anchor_evidence_window(
 
     let idx = best_idx.unwrap_or(0);
     let start = idx.saturating_sub(3) + 1;
-    let end = start.saturating_add(max_window_lines.saturating_sub(1));
+    let fixed_end = start.saturating_add(max_window_lines.saturating_sub(1));
+
+    // Prefer closing the window at the next same-or-shallower heading/target instead of an
+    // arbitrary fixed size, when the folding-range scan finds one for this anchor's line.
+    let end = anchor_folding_ranges(&lines, &lc_lines, kind, 1)
+        .into_iter()
+        .find(|(region_start, _, _)| *region_start == start)
+        .map(|(_, region_end, _)| region_end.min(fixed_end))
+        .unwrap_or(fixed_end);
+
     (start.max(1), end.max(start.max(1)))
 }
 
+/// Ranked folding regions for an anchor file: unlike `anchor_evidence_window`'s single
+/// fixed-size window, this returns *every* matching region (one per markdown heading, one
+/// per Makefile/justfile target, one per CI job/step `run:`) so callers can assemble a
+/// compact multi-snippet context, or an editor could surface them as `foldingRange` hints.
+///
+/// Each region is `(start_line, end_line, confidence)`, 1-indexed and closed at the next
+/// heading/target of the same-or-shallower level (or end of file). Takes the already
+/// lower-cased lines so `anchor_evidence_window` can reuse a single read.
+pub(crate) fn anchor_folding_ranges(
+    lines: &[&str],
+    lc_lines: &[String],
+    kind: AnchorKind,
+    max_regions: usize,
+) -> Vec<(usize, usize, f32)> {
+    let needles: &[&str] = match kind {
+        AnchorKind::Canon => &[
+            "quick start",
+            "getting started",
+            "usage",
+            "install",
+            "overview",
+            "start here",
+            "architecture",
+            "goals",
+            "philosophy",
+        ],
+        AnchorKind::HowTo => &[
+            "how to run",
+            "howto",
+            "usage",
+            "run",
+            "test",
+            "build",
+            "lint",
+            "fmt",
+            "format",
+            "serve",
+        ],
+        AnchorKind::Ci => &["jobs", "job", "steps", "workflow", "pipeline", "ci"],
+        AnchorKind::Artifact => &[
+            "artifacts",
+            "artifact",
+            "results",
+            "runs",
+            "outputs",
+            "checkpoints",
+            "layout",
+            "naming",
+        ],
+        AnchorKind::Experiment => &[
+            "experiments",
+            "experiment",
+            "baselines",
+            "baseline",
+            "bench",
+            "benches",
+            "benchmark",
+            "evaluation",
+            "eval",
+            "ablation",
+            "ablations",
+            "analysis",
+        ],
+        _ => return Vec::new(),
+    };
+    let allows_commandish = matches!(
+        kind,
+        AnchorKind::HowTo | AnchorKind::Ci | AnchorKind::Experiment
+    );
+
+    let mut starts = find_all_heading_like(lc_lines, needles);
+    if allows_commandish {
+        for idx in find_all_commandish(lc_lines) {
+            if !starts.iter().any(|(existing, _)| *existing == idx) {
+                starts.push((idx, heading_level(lc_lines[idx].as_str())));
+            }
+        }
+    }
+    if starts.is_empty() {
+        return Vec::new();
+    }
+    starts.sort_by_key(|(idx, _)| *idx);
+    starts.dedup_by_key(|(idx, _)| *idx);
+
+    let last_line = lines.len().saturating_sub(1);
+    let mut regions: Vec<(usize, usize, f32)> = Vec::new();
+    for (pos, &(idx, level)) in starts.iter().enumerate() {
+        let end_idx = starts[pos + 1..]
+            .iter()
+            .find(|(_, other_level)| *other_level <= level)
+            .map(|(other_idx, _)| other_idx.saturating_sub(1))
+            .unwrap_or(last_line);
+        let start_line = idx.saturating_sub(3) + 1;
+        let end_line = (end_idx + 1).max(start_line);
+        let confidence = (0.9 - pos as f32 * 0.1).max(0.4);
+        regions.push((start_line.max(1), end_line, confidence));
+        if regions.len() >= max_regions.max(1) {
+            break;
+        }
+    }
+    regions
+}
+
+fn heading_level(line_lc: &str) -> usize {
+    let trimmed = line_lc.trim_start();
+    trimmed.chars().take_while(|c| *c == '#').count()
+}
+
+fn find_all_heading_like(lines_lc: &[String], needles: &[&str]) -> Vec<(usize, usize)> {
+    let mut hits = Vec::new();
+    for (idx, line) in lines_lc.iter().enumerate() {
+        let trimmed = line.trim_start();
+        let is_heading =
+            trimmed.starts_with('#') || trimmed.starts_with("==") || trimmed.starts_with("--");
+        if !is_heading {
+            continue;
+        }
+        if needles.iter().any(|n| trimmed.contains(n)) {
+            hits.push((idx, heading_level(line)));
+        }
+    }
+    hits
+}
+
+fn find_all_commandish(lines_lc: &[String]) -> Vec<usize> {
+    const TOKENS: [&str; 10] = [
+        "cargo test",
+        "cargo build",
+        "cargo run",
+        "npm run",
+        "pnpm",
+        "yarn",
+        "pip install",
+        "python -m",
+        "make ",
+        "just ",
+    ];
+
+    let mut hits = Vec::new();
+    for (idx, line) in lines_lc.iter().enumerate() {
+        let trimmed = line.trim_start();
+        if TOKENS.iter().any(|t| trimmed.contains(t)) {
+            hits.push(idx);
+            continue;
+        }
+        let looks_like_target = trimmed
+            .split_once(':')
+            .map(|(lhs, _)| {
+                let lhs = lhs.trim();
+                !lhs.is_empty()
+                    && lhs.len() <= 32
+                    && lhs
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+            })
+            .unwrap_or(false);
+        if looks_like_target
+            && (trimmed.starts_with("test:")
+                || trimmed.starts_with("run:")
+                || trimmed.starts_with("build:")
+                || trimmed.starts_with("lint:")
+                || trimmed.starts_with("fmt:"))
+        {
+            hits.push(idx);
+            continue;
+        }
+        let looks_like_yaml_run = trimmed.starts_with("- run:") || trimmed.starts_with("run:");
+        if looks_like_yaml_run {
+            hits.push(idx);
+        }
+    }
+    hits
+}
+
 fn find_first_heading_like(lines_lc: &[String], needles: &[&str]) -> Option<usize> {
     for (idx, line) in lines_lc.iter().enumerate() {
         let trimmed = line.trim_start();
@@ -1843,7 +2573,7 @@ fn find_first_heading_like(lines_lc: &[String], needles: &[&str]) -> Option<usiz
     None
 }
 
-fn find_first_commandish(lines_lc: &[String]) -> Option<usize> {
+pub(crate) fn find_first_commandish(lines_lc: &[String]) -> Option<usize> {
     const TOKENS: [&str; 10] = [
         "cargo test",
         "cargo build",
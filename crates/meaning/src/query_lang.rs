@@ -0,0 +1,558 @@
+//! Small filter query language for [`crate::model::MeaningPackRequest::query`].
+//!
+//! `query` is usually an opaque natural-language blob fed straight into BM25/semantic scoring.
+//! This module lets a caller additionally express structured constraints inline, e.g.
+//! `auth refresh path:src/session lang:rust symbol:Token NOT test`: typed filters (`path:`,
+//! `lang:`, `symbol:`, `ext:`) prune the candidate file list before ranking runs, while the
+//! remaining free text still feeds the existing lexical/semantic relevance pass unchanged.
+//!
+//! Grammar (`AND` is implicit between adjacent terms):
+//! ```text
+//! expr    := or_expr
+//! or_expr := and_expr ("OR" and_expr)*
+//! and_expr:= unary+
+//! unary   := "NOT" unary | primary
+//! primary := "(" expr ")" | filter | phrase | term
+//! filter  := key ":" value        (key one of path|lang|symbol|ext, value has no whitespace)
+//! phrase  := '"' ... '"'
+//! ```
+//! An unrecognized `key:value` (any other key) is kept as a plain free-text term, so the grammar
+//! stays forgiving for natural-language queries that happen to contain a colon.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum FilterKey {
+    Path,
+    Lang,
+    Symbol,
+    Ext,
+}
+
+impl FilterKey {
+    fn parse(raw: &str) -> Option<FilterKey> {
+        match raw {
+            "path" => Some(FilterKey::Path),
+            "lang" => Some(FilterKey::Lang),
+            "symbol" => Some(FilterKey::Symbol),
+            "ext" => Some(FilterKey::Ext),
+            _ => None,
+        }
+    }
+
+    pub(super) fn as_str(self) -> &'static str {
+        match self {
+            FilterKey::Path => "path",
+            FilterKey::Lang => "lang",
+            FilterKey::Symbol => "symbol",
+            FilterKey::Ext => "ext",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum QueryNode {
+    Term(String),
+    Filter(FilterKey, String),
+    And(Box<QueryNode>, Box<QueryNode>),
+    Or(Box<QueryNode>, Box<QueryNode>),
+    Not(Box<QueryNode>),
+}
+
+/// A typed filter pulled out of a query, in source order; the shape surfaced back to callers via
+/// `MeaningPackResult::resolved_filters` so they can confirm how their query was interpreted.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ResolvedFilter {
+    pub key: String,
+    pub value: String,
+    pub negated: bool,
+}
+
+/// A parsed `query`: the AST used to prune candidates, the free text handed to the existing
+/// relevance scoring unchanged, and the filters resolved for display.
+#[derive(Debug, Clone, Default)]
+pub(super) struct ParsedQuery {
+    ast: Option<QueryNode>,
+    pub(super) free_text: String,
+    pub(super) filters: Vec<ResolvedFilter>,
+}
+
+/// A query parse failure, with a 1-based column offset so callers can point at the exact spot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryParseError {
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for QueryParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "query parse error at column {}: {}", self.column, self.message)
+    }
+}
+
+impl std::error::Error for QueryParseError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    Or,
+    Not,
+    Filter(FilterKey, String),
+    Term(String),
+}
+
+struct Lexer<'a> {
+    input: &'a str,
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(input: &'a str) -> Self {
+        Lexer {
+            input,
+            chars: input.char_indices().peekable(),
+        }
+    }
+
+    fn column_at(&self, byte_idx: usize) -> usize {
+        self.input[..byte_idx].chars().count() + 1
+    }
+
+    fn tokenize(mut self) -> Result<Vec<(Token, usize)>, QueryParseError> {
+        let mut tokens = Vec::new();
+        while let Some(&(idx, ch)) = self.chars.peek() {
+            if ch.is_whitespace() {
+                self.chars.next();
+                continue;
+            }
+            match ch {
+                '(' => {
+                    self.chars.next();
+                    tokens.push((Token::LParen, idx));
+                }
+                ')' => {
+                    self.chars.next();
+                    tokens.push((Token::RParen, idx));
+                }
+                '"' => {
+                    self.chars.next();
+                    let start = idx;
+                    let mut phrase = String::new();
+                    let mut closed = false;
+                    for (_, c) in self.chars.by_ref() {
+                        if c == '"' {
+                            closed = true;
+                            break;
+                        }
+                        phrase.push(c);
+                    }
+                    if !closed {
+                        return Err(QueryParseError {
+                            column: self.column_at(start),
+                            message: "unterminated quoted phrase".to_string(),
+                        });
+                    }
+                    tokens.push((Token::Term(phrase), start));
+                }
+                _ => {
+                    let start = idx;
+                    let mut word = String::new();
+                    while let Some(&(_, c)) = self.chars.peek() {
+                        if c.is_whitespace() || c == '(' || c == ')' || c == '"' {
+                            break;
+                        }
+                        word.push(c);
+                        self.chars.next();
+                    }
+                    match word.as_str() {
+                        "AND" => {
+                            // Implicit between adjacent terms; an explicit AND is a no-op token
+                            // we simply drop, so "a AND b" and "a b" parse identically.
+                        }
+                        "OR" => tokens.push((Token::Or, start)),
+                        "NOT" => tokens.push((Token::Not, start)),
+                        _ => {
+                            if let Some((key, value)) = word.split_once(':') {
+                                if let (Some(filter_key), false) =
+                                    (FilterKey::parse(key), value.is_empty())
+                                {
+                                    tokens.push((
+                                        Token::Filter(filter_key, value.to_string()),
+                                        start,
+                                    ));
+                                    continue;
+                                }
+                            }
+                            tokens.push((Token::Term(word), start));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(tokens)
+    }
+}
+
+struct Parser<'a> {
+    lexer_input: &'a str,
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn column_at(&self, byte_idx: usize) -> usize {
+        self.lexer_input[..byte_idx].chars().count() + 1
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn next_column(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map(|(_, idx)| self.column_at(*idx))
+            .unwrap_or(self.lexer_input.chars().count() + 1)
+    }
+
+    fn bump(&mut self) -> Token {
+        let (token, _) = self.tokens[self.pos].clone();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<QueryNode, QueryParseError> {
+        let mut node = self.parse_and()?;
+        while matches!(self.peek(), Some(Token::Or)) {
+            self.bump();
+            let rhs = self.parse_and()?;
+            node = QueryNode::Or(Box::new(node), Box::new(rhs));
+        }
+        Ok(node)
+    }
+
+    fn parse_and(&mut self) -> Result<QueryNode, QueryParseError> {
+        let mut node = self.parse_unary()?;
+        while matches!(
+            self.peek(),
+            Some(Token::LParen) | Some(Token::Not) | Some(Token::Filter(..)) | Some(Token::Term(_))
+        ) {
+            let rhs = self.parse_unary()?;
+            node = QueryNode::And(Box::new(node), Box::new(rhs));
+        }
+        Ok(node)
+    }
+
+    fn parse_unary(&mut self) -> Result<QueryNode, QueryParseError> {
+        if matches!(self.peek(), Some(Token::Not)) {
+            self.bump();
+            if self.peek().is_none() {
+                return Err(QueryParseError {
+                    column: self.next_column(),
+                    message: "expected a term or filter after NOT".to_string(),
+                });
+            }
+            let inner = self.parse_unary()?;
+            return Ok(QueryNode::Not(Box::new(inner)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<QueryNode, QueryParseError> {
+        match self.peek() {
+            Some(Token::LParen) => {
+                self.bump();
+                let inner = self.parse_or()?;
+                match self.peek() {
+                    Some(Token::RParen) => {
+                        self.bump();
+                        Ok(inner)
+                    }
+                    _ => Err(QueryParseError {
+                        column: self.next_column(),
+                        message: "expected ')'".to_string(),
+                    }),
+                }
+            }
+            Some(Token::Filter(key, value)) => {
+                let (key, value) = (*key, value.clone());
+                self.bump();
+                Ok(QueryNode::Filter(key, value))
+            }
+            Some(Token::Term(text)) => {
+                let text = text.clone();
+                self.bump();
+                Ok(QueryNode::Term(text))
+            }
+            Some(Token::RParen) => Err(QueryParseError {
+                column: self.next_column(),
+                message: "unexpected ')'".to_string(),
+            }),
+            Some(Token::Or) => Err(QueryParseError {
+                column: self.next_column(),
+                message: "unexpected 'OR'".to_string(),
+            }),
+            None => Err(QueryParseError {
+                column: self.next_column(),
+                message: "expected a term, filter, or '('".to_string(),
+            }),
+        }
+    }
+}
+
+/// Walks a parsed AST, splitting it into the filters to prune candidates with and the free text
+/// (space-joined, source order) to hand to the existing relevance scoring. Negated bare terms
+/// (`NOT foo`) are kept as exclusion filters (see [`QueryNode::eval`]) rather than dropped, since
+/// that's the only way a bare term can meaningfully prune; non-negated terms never prune.
+fn collect(node: &QueryNode, free_text: &mut Vec<String>, filters: &mut Vec<ResolvedFilter>) {
+    match node {
+        QueryNode::Term(text) => free_text.push(text.clone()),
+        QueryNode::Filter(key, value) => filters.push(ResolvedFilter {
+            key: key.as_str().to_string(),
+            value: value.clone(),
+            negated: false,
+        }),
+        QueryNode::And(a, b) | QueryNode::Or(a, b) => {
+            collect(a, free_text, filters);
+            collect(b, free_text, filters);
+        }
+        QueryNode::Not(inner) => match inner.as_ref() {
+            QueryNode::Term(text) => filters.push(ResolvedFilter {
+                key: "text".to_string(),
+                value: text.clone(),
+                negated: true,
+            }),
+            QueryNode::Filter(key, value) => filters.push(ResolvedFilter {
+                key: key.as_str().to_string(),
+                value: value.clone(),
+                negated: true,
+            }),
+            other => collect(other, free_text, filters),
+        },
+    }
+}
+
+impl QueryNode {
+    /// `true` if `candidate` (a repo-relative path, optionally paired with its content for
+    /// `symbol:` filters) satisfies this node. Bare free-text terms always match here (they only
+    /// prune when wrapped in `NOT`, see [`collect`]); filters and their boolean combinations prune
+    /// for real.
+    fn eval(&self, candidate: &CandidateFacts<'_>) -> bool {
+        match self {
+            QueryNode::Term(_) => true,
+            QueryNode::Filter(key, value) => candidate.matches_filter(*key, value),
+            QueryNode::And(a, b) => a.eval(candidate) && b.eval(candidate),
+            QueryNode::Or(a, b) => a.eval(candidate) || b.eval(candidate),
+            QueryNode::Not(inner) => match inner.as_ref() {
+                QueryNode::Term(text) => !candidate.contains_text(text),
+                other => !other.eval(candidate),
+            },
+        }
+    }
+}
+
+/// Per-candidate facts the AST is evaluated against: the repo-relative path always, and the
+/// file's chunked symbol names only when a `symbol:` filter is actually present in the query (so
+/// queries without one never pay for the extra chunk pass).
+pub(super) struct CandidateFacts<'a> {
+    pub(super) path: &'a str,
+    pub(super) symbol_names: Option<&'a [String]>,
+}
+
+impl CandidateFacts<'_> {
+    fn contains_text(&self, needle: &str) -> bool {
+        self.path.to_ascii_lowercase().contains(&needle.to_ascii_lowercase())
+    }
+
+    fn matches_filter(&self, key: FilterKey, value: &str) -> bool {
+        let value_lc = value.to_ascii_lowercase();
+        match key {
+            FilterKey::Path => self.path.to_ascii_lowercase().contains(&value_lc),
+            FilterKey::Ext => self
+                .path
+                .rsplit('.')
+                .next()
+                .map(|ext| ext.eq_ignore_ascii_case(value.trim_start_matches('.')))
+                .unwrap_or(false),
+            FilterKey::Lang => language_for_path(self.path)
+                .map(|lang| lang.eq_ignore_ascii_case(&value_lc))
+                .unwrap_or(false),
+            FilterKey::Symbol => self
+                .symbol_names
+                .map(|names| {
+                    names
+                        .iter()
+                        .any(|name| name.to_ascii_lowercase().contains(&value_lc))
+                })
+                .unwrap_or(false),
+        }
+    }
+}
+
+/// Maps a repo-relative path's extension to a language name for `lang:` filters. Deliberately
+/// small: just enough coverage for the languages this repo's own chunker/classifiers know about.
+fn language_for_path(path: &str) -> Option<&'static str> {
+    let ext = path.rsplit('.').next()?.to_ascii_lowercase();
+    Some(match ext.as_str() {
+        "rs" => "rust",
+        "py" => "python",
+        "js" | "mjs" | "cjs" => "javascript",
+        "ts" | "tsx" => "typescript",
+        "go" => "go",
+        "java" => "java",
+        "rb" => "ruby",
+        "c" | "h" => "c",
+        "cc" | "cpp" | "cxx" | "hpp" => "cpp",
+        "cs" => "csharp",
+        "php" => "php",
+        "kt" | "kts" => "kotlin",
+        "swift" => "swift",
+        "sh" | "bash" => "shell",
+        "yaml" | "yml" => "yaml",
+        "json" => "json",
+        "proto" => "proto",
+        _ => return None,
+    })
+}
+
+/// `true` if the parsed query has at least one `symbol:` filter, i.e. callers need to chunk file
+/// content (not just check the path) to evaluate it.
+pub(super) fn needs_symbol_facts(query: &ParsedQuery) -> bool {
+    query.filters.iter().any(|f| f.key == "symbol" && !f.negated)
+}
+
+/// `true` if `candidate` survives every filter in `query` (free text never prunes here).
+pub(super) fn matches(query: &ParsedQuery, candidate: &CandidateFacts<'_>) -> bool {
+    query.ast.as_ref().map(|ast| ast.eval(candidate)).unwrap_or(true)
+}
+
+/// Parses `input` into filters plus free text. A query with no recognized filter/operator syntax
+/// (the common case) parses as a single conjunction of terms, so plain natural-language queries
+/// are unaffected.
+pub(super) fn parse_query(input: &str) -> Result<ParsedQuery, QueryParseError> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Ok(ParsedQuery::default());
+    }
+
+    let lexer = Lexer::new(trimmed);
+    let tokens = lexer.tokenize()?;
+    if tokens.is_empty() {
+        return Ok(ParsedQuery::default());
+    }
+
+    let mut parser = Parser {
+        lexer_input: trimmed,
+        tokens,
+        pos: 0,
+    };
+    let ast = parser.parse_or()?;
+    if parser.pos != parser.tokens.len() {
+        return Err(QueryParseError {
+            column: parser.next_column(),
+            message: "unexpected trailing input".to_string(),
+        });
+    }
+
+    let mut free_text = Vec::new();
+    let mut filters = Vec::new();
+    collect(&ast, &mut free_text, &mut filters);
+
+    Ok(ParsedQuery {
+        ast: Some(ast),
+        free_text: free_text.join(" "),
+        filters,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_query_has_no_filters() {
+        let parsed = parse_query("auth refresh flow").unwrap();
+        assert!(parsed.filters.is_empty());
+        assert_eq!(parsed.free_text, "auth refresh flow");
+    }
+
+    #[test]
+    fn extracts_typed_filters_and_free_text() {
+        let parsed =
+            parse_query("auth refresh path:src/session lang:rust symbol:Token NOT test").unwrap();
+        assert_eq!(parsed.free_text, "auth refresh");
+        assert_eq!(
+            parsed.filters,
+            vec![
+                ResolvedFilter {
+                    key: "path".to_string(),
+                    value: "src/session".to_string(),
+                    negated: false
+                },
+                ResolvedFilter {
+                    key: "lang".to_string(),
+                    value: "rust".to_string(),
+                    negated: false
+                },
+                ResolvedFilter {
+                    key: "symbol".to_string(),
+                    value: "Token".to_string(),
+                    negated: false
+                },
+                ResolvedFilter {
+                    key: "text".to_string(),
+                    value: "test".to_string(),
+                    negated: true
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_key_value_stays_a_plain_term() {
+        let parsed = parse_query("ticket:JIRA-123 fix").unwrap();
+        assert!(parsed.filters.is_empty());
+        assert_eq!(parsed.free_text, "ticket:JIRA-123 fix");
+    }
+
+    #[test]
+    fn or_and_parens_combine_filters() {
+        let parsed = parse_query("(lang:rust OR lang:go) path:crates").unwrap();
+        assert_eq!(parsed.filters.len(), 3);
+        let facts_rust = CandidateFacts {
+            path: "crates/graph/src/lib.rs",
+            symbol_names: None,
+        };
+        let facts_py = CandidateFacts {
+            path: "crates/graph/src/lib.py",
+            symbol_names: None,
+        };
+        assert!(matches(&parsed, &facts_rust));
+        assert!(!matches(&parsed, &facts_py));
+    }
+
+    #[test]
+    fn quoted_phrase_is_a_single_term() {
+        let parsed = parse_query("\"hello world\" path:src").unwrap();
+        assert_eq!(parsed.free_text, "hello world");
+    }
+
+    #[test]
+    fn reports_column_on_unterminated_quote() {
+        let err = parse_query("auth \"refresh").unwrap_err();
+        assert_eq!(err.column, 6);
+    }
+
+    #[test]
+    fn reports_column_on_unbalanced_paren() {
+        let err = parse_query("(lang:rust").unwrap_err();
+        assert_eq!(err.column, 11);
+    }
+
+    #[test]
+    fn not_without_operand_is_an_error() {
+        let err = parse_query("auth NOT").unwrap_err();
+        assert_eq!(err.message, "expected a term or filter after NOT");
+    }
+}
@@ -0,0 +1,254 @@
+//! User-configurable classification rules, layered on top of the built-in heuristics in
+//! `common.rs`. A [`Rule`] inspects one scanned file (its path) and emits zero or more
+//! [`Candidate`]s; a [`RuleSet`] runs every rule over every scanned file and returns the merged
+//! candidates for `pack.rs` to fold into the existing entrypoint/contract/boundary/anchor
+//! collections. Built-in heuristics stay the default and the only source of candidates when a
+//! caller supplies no [`UserRuleDef`]s, so this is purely additive.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::{AnchorKind, BoundaryKind};
+
+/// What a rule believes a file is, using the same vocabulary the built-in heuristics in
+/// `common.rs` already produce downstream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum CandidateKind {
+    Entrypoint,
+    Contract,
+    Boundary(BoundaryKind),
+    Anchor(AnchorKind),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Candidate {
+    pub(crate) kind: CandidateKind,
+    pub(crate) file: String,
+    pub(crate) confidence: f32,
+}
+
+/// One classification rule. [`GlobRule`] is the only implementation today (compiled from a
+/// user-supplied [`UserRuleDef`]); the trait exists so repo config can grow other rule shapes
+/// (regex, content sniffing) later without changing how `pack.rs` consumes results.
+pub(crate) trait Rule: Send + Sync {
+    fn evaluate(&self, file: &str) -> Option<Candidate>;
+}
+
+/// A user-supplied override, typically loaded from repo config, e.g.
+/// `{glob: "**/cmd/*/main.go", kind: "entrypoint", confidence: 0.9}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserRuleDef {
+    /// Glob pattern matched against the repo-relative, forward-slashed file path. Supports `*`
+    /// (any run of characters within a segment), `**` (any number of path segments), and `?`
+    /// (a single character).
+    pub glob: String,
+    /// One of `entrypoint`, `contract`, `boundary.<cli|http|event|env|config|db|dependency>`, or
+    /// `anchor.<canon|howto|infra|contract|entrypoint|artifact|experiment>`.
+    pub kind: String,
+    #[serde(default = "default_user_rule_confidence")]
+    pub confidence: f32,
+}
+
+fn default_user_rule_confidence() -> f32 {
+    0.8
+}
+
+fn parse_candidate_kind(kind: &str) -> Option<CandidateKind> {
+    if let Some(rest) = kind.strip_prefix("boundary.") {
+        let boundary = match rest {
+            "cli" => BoundaryKind::Cli,
+            "http" => BoundaryKind::Http,
+            "event" => BoundaryKind::Event,
+            "env" => BoundaryKind::Env,
+            "config" => BoundaryKind::Config,
+            "db" => BoundaryKind::Db,
+            "dependency" => BoundaryKind::Dependency,
+            _ => return None,
+        };
+        return Some(CandidateKind::Boundary(boundary));
+    }
+    if let Some(rest) = kind.strip_prefix("anchor.") {
+        let anchor = match rest {
+            "canon" => AnchorKind::Canon,
+            "howto" => AnchorKind::HowTo,
+            "infra" => AnchorKind::Infra,
+            "contract" => AnchorKind::Contract,
+            "entrypoint" => AnchorKind::Entrypoint,
+            "artifact" => AnchorKind::Artifact,
+            "experiment" => AnchorKind::Experiment,
+            _ => return None,
+        };
+        return Some(CandidateKind::Anchor(anchor));
+    }
+    match kind {
+        "entrypoint" => Some(CandidateKind::Entrypoint),
+        "contract" => Some(CandidateKind::Contract),
+        _ => None,
+    }
+}
+
+/// Compiles a glob (`*`, `**`, `?`) into path segments matched against a forward-slashed,
+/// repo-relative file path. Hand-rolled rather than pulling in a glob crate, matching how this
+/// module already hand-parses YAML/TOML manifests elsewhere (see `parse_yaml_containers`).
+struct GlobRule {
+    pattern: Vec<String>,
+    kind: CandidateKind,
+    confidence: f32,
+}
+
+impl GlobRule {
+    fn compile(def: &UserRuleDef) -> Option<Self> {
+        let kind = parse_candidate_kind(def.kind.trim())?;
+        if def.glob.trim().is_empty() {
+            return None;
+        }
+        let pattern = def
+            .glob
+            .split('/')
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        Some(Self {
+            pattern,
+            kind,
+            confidence: def.confidence.clamp(0.0, 1.0),
+        })
+    }
+}
+
+impl Rule for GlobRule {
+    fn evaluate(&self, file: &str) -> Option<Candidate> {
+        let segments: Vec<&str> = file.split('/').collect();
+        if glob_match(&self.pattern, &segments) {
+            Some(Candidate {
+                kind: self.kind.clone(),
+                file: file.to_string(),
+                confidence: self.confidence,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+fn glob_match(pattern: &[String], segments: &[&str]) -> bool {
+    match pattern.first() {
+        None => segments.is_empty(),
+        Some(head) if head == "**" => {
+            if pattern.len() == 1 {
+                return true;
+            }
+            (0..=segments.len()).any(|skip| glob_match(&pattern[1..], &segments[skip..]))
+        }
+        Some(head) => match segments.first() {
+            Some(seg) if segment_match(head, seg) => glob_match(&pattern[1..], &segments[1..]),
+            _ => false,
+        },
+    }
+}
+
+fn segment_match(pattern: &str, segment: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let segment: Vec<char> = segment.chars().collect();
+    segment_match_chars(&pattern, &segment)
+}
+
+fn segment_match_chars(pattern: &[char], segment: &[char]) -> bool {
+    match pattern.first() {
+        None => segment.is_empty(),
+        Some('*') => {
+            (0..=segment.len()).any(|skip| segment_match_chars(&pattern[1..], &segment[skip..]))
+        }
+        Some('?') => !segment.is_empty() && segment_match_chars(&pattern[1..], &segment[1..]),
+        Some(c) => segment.first() == Some(c) && segment_match_chars(&pattern[1..], &segment[1..]),
+    }
+}
+
+/// Evaluates every compiled [`UserRuleDef`] against every scanned file. Invalid rule definitions
+/// (unknown `kind`, empty `glob`) are dropped rather than failing the whole pack.
+pub(crate) struct RuleSet {
+    rules: Vec<GlobRule>,
+}
+
+impl RuleSet {
+    pub(crate) fn from_user_rules(defs: &[UserRuleDef]) -> Self {
+        Self {
+            rules: defs.iter().filter_map(GlobRule::compile).collect(),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Runs every rule against every file. Rules are pure path matches (no file IO), so this
+    /// simply iterates in-process; `root` is accepted for symmetry with the async heuristics in
+    /// `common.rs` and so a future content-aware `Rule` impl can read files without changing this
+    /// signature.
+    pub(crate) async fn run(&self, _root: &Path, files: &[String]) -> Vec<Candidate> {
+        if self.rules.is_empty() {
+            return Vec::new();
+        }
+        let mut out = Vec::new();
+        for file in files {
+            for rule in &self.rules {
+                if let Some(candidate) = rule.evaluate(file) {
+                    out.push(candidate);
+                }
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_matches_double_star_prefix() {
+        let def = UserRuleDef {
+            glob: "**/cmd/*/main.go".to_string(),
+            kind: "entrypoint".to_string(),
+            confidence: 0.9,
+        };
+        let rule = GlobRule::compile(&def).expect("valid rule");
+        assert!(rule.evaluate("services/api/cmd/server/main.go").is_some());
+        assert!(rule.evaluate("cmd/server/main.go").is_some());
+        assert!(rule.evaluate("cmd/server/other.go").is_none());
+    }
+
+    #[test]
+    fn glob_matches_single_star_segment() {
+        let def = UserRuleDef {
+            glob: "proto/*.proto".to_string(),
+            kind: "contract".to_string(),
+            confidence: 0.85,
+        };
+        let rule = GlobRule::compile(&def).expect("valid rule");
+        assert!(rule.evaluate("proto/payments.proto").is_some());
+        assert!(rule.evaluate("proto/nested/payments.proto").is_none());
+    }
+
+    #[test]
+    fn unknown_kind_is_dropped() {
+        let def = UserRuleDef {
+            glob: "**/*.proto".to_string(),
+            kind: "nonsense".to_string(),
+            confidence: 0.9,
+        };
+        assert!(GlobRule::compile(&def).is_none());
+    }
+
+    #[test]
+    fn boundary_and_anchor_kinds_parse() {
+        assert_eq!(
+            parse_candidate_kind("boundary.event"),
+            Some(CandidateKind::Boundary(BoundaryKind::Event))
+        );
+        assert_eq!(
+            parse_candidate_kind("anchor.canon"),
+            Some(CandidateKind::Anchor(AnchorKind::Canon))
+        );
+    }
+}
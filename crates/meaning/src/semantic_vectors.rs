@@ -0,0 +1,267 @@
+//! On-disk, content-hash-addressed store of symbol-scoped embedding vectors, used to rank
+//! `meaning_pack`/`meaning_focus` candidates by semantic similarity to the request's `query`
+//! alongside the existing lexical signals (BM25, TF-IDF, path heuristics).
+//!
+//! Each candidate file is split into symbol-scoped spans via the same
+//! [`context_code_chunker::Chunker`] used for embeddings elsewhere, and every span is embedded
+//! once per `source_hash`; a re-pack over an unchanged file skips re-embedding entirely. The
+//! sidecar itself is a compact columnar layout — a flat `f32` matrix plus parallel arrays of
+//! [`EvidencePointer`]s and source hashes — rather than one row struct per span, mirroring how
+//! [`crate::cache::ScanCache`] keeps its sidecar to a single content-addressed file.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use context_code_chunker::{Chunker, ChunkerConfig};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::cache::CacheMode;
+use crate::common::{cosine_similarity, HashingEmbedder, SemanticEmbedder};
+use crate::model::EvidencePointer;
+
+/// Bumped whenever the span/embedding shape changes, so stale sidecars are never mistaken for
+/// fresh ones (see [`crate::cache::CACHE_FINGERPRINT`] for the analogous scan-cache constant).
+pub(super) const VECTOR_STORE_FINGERPRINT: &str = "meaning-vectors-v1";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct VectorStoreFile {
+    fingerprint: String,
+    dims: usize,
+    pointers: Vec<EvidencePointer>,
+    source_hashes: Vec<String>,
+    /// `pointers.len()` rows of `dims` floats each, flattened row-major.
+    vectors: Vec<f32>,
+}
+
+/// Persistent, content-addressed store of symbol-scoped span embeddings for one repo. Loaded
+/// from (and saved back to) a single JSON sidecar, the same pattern [`crate::cache::ScanCache`]
+/// uses for its own sidecar.
+pub(super) struct SemanticVectorStore {
+    path: PathBuf,
+    mode: CacheMode,
+    dims: usize,
+    pointers: Vec<EvidencePointer>,
+    source_hashes: Vec<String>,
+    vectors: Vec<f32>,
+    dirty: bool,
+}
+
+impl SemanticVectorStore {
+    /// Loads the sidecar at `path` if `mode` permits reading. A missing file, an unparseable
+    /// file, or a fingerprint mismatch all start an empty (not an error) store.
+    pub(super) fn load(path: &Path, mode: CacheMode) -> SemanticVectorStore {
+        let mut store = SemanticVectorStore {
+            path: path.to_path_buf(),
+            mode,
+            dims: 0,
+            pointers: Vec::new(),
+            source_hashes: Vec::new(),
+            vectors: Vec::new(),
+            dirty: false,
+        };
+        if mode == CacheMode::Off {
+            return store;
+        }
+        let Ok(raw) = std::fs::read_to_string(path) else {
+            return store;
+        };
+        let Ok(file) = serde_json::from_str::<VectorStoreFile>(&raw) else {
+            return store;
+        };
+        if file.fingerprint == VECTOR_STORE_FINGERPRINT {
+            store.dims = file.dims;
+            store.pointers = file.pointers;
+            store.source_hashes = file.source_hashes;
+            store.vectors = file.vectors;
+        }
+        store
+    }
+
+    /// Persists the store to its sidecar path, atomically via a same-directory temp file.
+    /// A no-op under `Off`/`ReadOnly` or when nothing changed since `load`.
+    pub(super) fn save(&self) -> Result<()> {
+        if self.mode != CacheMode::ReadWrite || !self.dirty {
+            return Ok(());
+        }
+        let file = VectorStoreFile {
+            fingerprint: VECTOR_STORE_FINGERPRINT.to_string(),
+            dims: self.dims,
+            pointers: self.pointers.clone(),
+            source_hashes: self.source_hashes.clone(),
+            vectors: self.vectors.clone(),
+        };
+        let serialized = serde_json::to_string(&file).context("serialize vector store sidecar")?;
+        let tmp_path = self.path.with_extension("tmp");
+        std::fs::write(&tmp_path, serialized)
+            .with_context(|| format!("write vector store sidecar {}", tmp_path.display()))?;
+        std::fs::rename(&tmp_path, &self.path)
+            .with_context(|| format!("install vector store sidecar {}", self.path.display()))?;
+        Ok(())
+    }
+
+    /// `true` if every span already stored for `file` was embedded under `source_hash` (and at
+    /// least one span is stored at all).
+    fn is_fresh(&self, file: &str, source_hash: &str) -> bool {
+        let mut any = false;
+        for (pointer, hash) in self.pointers.iter().zip(self.source_hashes.iter()) {
+            if pointer.file != file {
+                continue;
+            }
+            any = true;
+            if hash != source_hash {
+                return false;
+            }
+        }
+        any
+    }
+
+    /// Splits `content` into symbol-scoped spans and embeds each one, replacing any spans
+    /// already stored for `file`. No-op if `file` is already fresh under `source_hash`.
+    fn index_file(
+        &mut self,
+        embedder: &dyn SemanticEmbedder,
+        file: &str,
+        source_hash: &str,
+        content: &str,
+    ) {
+        if self.is_fresh(file, source_hash) {
+            return;
+        }
+
+        let keep: Vec<usize> = self
+            .pointers
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| (p.file != file).then_some(i))
+            .collect();
+        self.pointers = keep.iter().map(|&i| self.pointers[i].clone()).collect();
+        self.source_hashes = keep.iter().map(|&i| self.source_hashes[i].clone()).collect();
+        self.vectors = keep
+            .iter()
+            .flat_map(|&i| self.vectors[i * self.dims..(i + 1) * self.dims].to_vec())
+            .collect();
+
+        let chunker = Chunker::new(ChunkerConfig::for_embeddings());
+        let spans = chunker.chunk_str(content, Some(file)).unwrap_or_default();
+        if spans.is_empty() {
+            self.dirty = true;
+            return;
+        }
+
+        for span in spans {
+            let vector = embedder.embed(&span.content);
+            if self.dims == 0 {
+                self.dims = vector.len();
+            }
+            if vector.len() != self.dims {
+                continue;
+            }
+            self.pointers.push(EvidencePointer {
+                file: span.file_path,
+                start_line: span.start_line,
+                end_line: span.end_line,
+                source_hash: Some(source_hash.to_string()),
+            });
+            self.source_hashes.push(source_hash.to_string());
+            self.vectors.extend(vector);
+        }
+        self.dirty = true;
+    }
+
+    /// Embeds `query` and returns each stored span's cosine similarity, highest first.
+    fn rank(&self, embedder: &dyn SemanticEmbedder, query: &str) -> Vec<(&EvidencePointer, f32)> {
+        if self.pointers.is_empty() || self.dims == 0 {
+            return Vec::new();
+        }
+        let query_vector = embedder.embed(query);
+        let mut scored: Vec<(&EvidencePointer, f32)> = self
+            .pointers
+            .iter()
+            .enumerate()
+            .map(|(i, pointer)| {
+                let row = &self.vectors[i * self.dims..(i + 1) * self.dims];
+                (pointer, cosine_similarity(&query_vector, row))
+            })
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored
+    }
+}
+
+fn sha256_hex(content: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(content.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Embeds every candidate's content (re-embedding only those whose content changed since the
+/// last call) and returns each candidate's best-matching span score against `query`, for the
+/// caller to blend into its own lexical ranking. Candidates that can't be read are skipped.
+pub(super) async fn semantic_file_scores(
+    root: &Path,
+    store: &mut SemanticVectorStore,
+    query: &str,
+    candidates: &[String],
+) -> std::collections::HashMap<String, f32> {
+    const MAX_READ_BYTES: usize = 96 * 1024;
+    const MAX_CANDIDATES: usize = 40;
+
+    let mut out = std::collections::HashMap::new();
+    if query.trim().is_empty() || candidates.is_empty() {
+        return out;
+    }
+
+    let embedder = HashingEmbedder::default();
+    for file in candidates.iter().take(MAX_CANDIDATES) {
+        let Some(content) = crate::common::read_file_prefix_utf8(root, file, MAX_READ_BYTES).await
+        else {
+            continue;
+        };
+        let hash = sha256_hex(&content);
+        store.index_file(&embedder, file, &hash, &content);
+    }
+
+    for (pointer, score) in store.rank(&embedder, query) {
+        let entry = out.entry(pointer.file.clone()).or_insert(0.0f32);
+        if score > *entry {
+            *entry = score;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_file_skips_reembedding_unchanged_content() {
+        let embedder = HashingEmbedder::default();
+        let mut store = SemanticVectorStore::load(Path::new("/nonexistent"), CacheMode::Off);
+        store.index_file(&embedder, "a.rs", "hash1", "fn a() {}\nfn b() {}\n");
+        let spans_before = store.pointers.len();
+        store.index_file(&embedder, "a.rs", "hash1", "fn a() {}\nfn b() {}\n");
+        assert_eq!(store.pointers.len(), spans_before);
+    }
+
+    #[test]
+    fn index_file_reindexes_on_hash_change() {
+        let embedder = HashingEmbedder::default();
+        let mut store = SemanticVectorStore::load(Path::new("/nonexistent"), CacheMode::Off);
+        store.index_file(&embedder, "a.rs", "hash1", "fn a() {}\n");
+        store.index_file(&embedder, "a.rs", "hash2", "fn a() {}\nfn b() {}\n");
+        assert!(store.is_fresh("a.rs", "hash2"));
+        assert!(!store.is_fresh("a.rs", "hash1"));
+    }
+
+    #[test]
+    fn rank_prefers_the_matching_span() {
+        let embedder = HashingEmbedder::default();
+        let mut store = SemanticVectorStore::load(Path::new("/nonexistent"), CacheMode::Off);
+        store.index_file(&embedder, "a.rs", "h", "fn login_user() {}\n");
+        store.index_file(&embedder, "b.rs", "h", "fn render_widget() {}\n");
+        let ranked = store.rank(&embedder, "login_user");
+        assert_eq!(ranked[0].0.file, "a.rs");
+    }
+}
@@ -0,0 +1,202 @@
+//! A pluggable LLM token estimator for budgeting meaning packs, plus the default
+//! byte-pair-merge approximation.
+//!
+//! Characters are a poor proxy for tokens: a path-heavy `D` line tokenizes very differently
+//! from prose. [`ByteMergeTokenEstimator`] seeds a small BPE-style vocabulary from the pack's
+//! own dictionary values (the interned paths/strings it actually carries) plus common code
+//! punctuation, then estimates a line's token count by greedily applying the learned merges.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// Estimates how many LLM tokens a line of pack text would cost.
+pub(super) trait TokenEstimator {
+    fn estimate_line(&self, line: &str) -> usize;
+
+    /// Estimates a dictionary value's token cost, keyed by its interned `dN` id so repeated
+    /// lookups of the same id (across shrink iterations) don't redo the estimate.
+    fn estimate_dict_value(&self, _dict_id: usize, value: &str) -> usize {
+        self.estimate_line(value)
+    }
+}
+
+/// Punctuation clusters common in code/paths, used to seed the merge vocabulary alongside the
+/// pack's own dictionary values.
+const SEED_PUNCTUATION: &[&str] = &[
+    "::", "->", "=>", "//", "/*", "*/", "==", "!=", "<=", ">=", "&&", "||", "..", "..=",
+];
+
+const MAX_MERGES: usize = 48;
+
+/// Natural split points for BPE-style tokenization: whitespace plus the punctuation this repo's
+/// heuristics already treat as path/identifier boundaries.
+fn is_boundary_char(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '/' | '.' | '_' | '=')
+}
+
+/// Splits a line into word runs (merged via the learned vocabulary) and single-char boundary
+/// tokens (whitespace is dropped, since it costs no token in practice).
+fn split_natural(line: &str) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut word = String::new();
+    for c in line.chars() {
+        if is_boundary_char(c) {
+            if !word.is_empty() {
+                pieces.push(std::mem::take(&mut word));
+            }
+            if !c.is_whitespace() {
+                pieces.push(c.to_string());
+            }
+        } else {
+            word.push(c);
+        }
+    }
+    if !word.is_empty() {
+        pieces.push(word);
+    }
+    pieces
+}
+
+fn merge_pair(seq: &[String], pair: &(String, String)) -> Vec<String> {
+    let mut out = Vec::with_capacity(seq.len());
+    let mut i = 0;
+    while i < seq.len() {
+        if i + 1 < seq.len() && seq[i] == pair.0 && seq[i + 1] == pair.1 {
+            out.push(format!("{}{}", seq[i], seq[i + 1]));
+            i += 2;
+        } else {
+            out.push(seq[i].clone());
+            i += 1;
+        }
+    }
+    out
+}
+
+/// A default, dependency-free token estimator: trains a small BPE-style merge list from the
+/// pack's own dictionary values, then applies those merges greedily to estimate each line.
+pub(super) struct ByteMergeTokenEstimator {
+    merge_rank: HashMap<(String, String), usize>,
+    dict_cache: RefCell<HashMap<usize, usize>>,
+}
+
+impl ByteMergeTokenEstimator {
+    /// Runs a frequency-merge (BPE training) pass over `dict_values` plus common code
+    /// punctuation, recording up to `MAX_MERGES` merges in frequency order.
+    pub(super) fn build(dict_values: &[String]) -> Self {
+        let mut sequences: Vec<Vec<String>> = dict_values
+            .iter()
+            .map(String::as_str)
+            .chain(SEED_PUNCTUATION.iter().copied())
+            .flat_map(split_natural_for_training)
+            .filter(|seq| seq.len() > 1)
+            .collect();
+
+        let mut merge_rank: HashMap<(String, String), usize> = HashMap::new();
+        for rank in 0..MAX_MERGES {
+            let mut pair_counts: HashMap<(String, String), usize> = HashMap::new();
+            for seq in &sequences {
+                for window in seq.windows(2) {
+                    let pair = (window[0].clone(), window[1].clone());
+                    *pair_counts.entry(pair).or_insert(0) += 1;
+                }
+            }
+            let best = pair_counts
+                .into_iter()
+                .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)));
+            let Some((best_pair, best_count)) = best else {
+                break;
+            };
+            if best_count < 2 {
+                break;
+            }
+            merge_rank.insert(best_pair.clone(), rank);
+            for seq in &mut sequences {
+                *seq = merge_pair(seq, &best_pair);
+            }
+        }
+
+        Self {
+            merge_rank,
+            dict_cache: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Greedily applies the learned merges (earliest-trained merge first) to one word run.
+    fn encode_word(&self, word: &str) -> usize {
+        let mut symbols: Vec<String> = word.chars().map(|c| c.to_string()).collect();
+        loop {
+            let mut best: Option<(usize, usize)> = None;
+            for i in 0..symbols.len().saturating_sub(1) {
+                let pair = (symbols[i].clone(), symbols[i + 1].clone());
+                if let Some(&rank) = self.merge_rank.get(&pair) {
+                    if best.map(|(best_rank, _)| rank < best_rank).unwrap_or(true) {
+                        best = Some((rank, i));
+                    }
+                }
+            }
+            let Some((_, idx)) = best else { break };
+            let merged = format!("{}{}", symbols[idx], symbols[idx + 1]);
+            symbols.splice(idx..idx + 2, [merged]);
+        }
+        symbols.len().max(1)
+    }
+}
+
+/// Splits training input into char-level symbol sequences, one per natural-boundary piece.
+fn split_natural_for_training(text: &str) -> Vec<Vec<String>> {
+    split_natural(text)
+        .into_iter()
+        .map(|piece| piece.chars().map(|c| c.to_string()).collect())
+        .collect()
+}
+
+impl TokenEstimator for ByteMergeTokenEstimator {
+    fn estimate_line(&self, line: &str) -> usize {
+        split_natural(line)
+            .iter()
+            .map(|piece| {
+                if piece.chars().next().is_some_and(|c| c.is_alphanumeric()) {
+                    self.encode_word(piece)
+                } else {
+                    1
+                }
+            })
+            .sum()
+    }
+
+    fn estimate_dict_value(&self, dict_id: usize, value: &str) -> usize {
+        if let Some(&cached) = self.dict_cache.borrow().get(&dict_id) {
+            return cached;
+        }
+        let estimate = self.estimate_line(value);
+        self.dict_cache.borrow_mut().insert(dict_id, estimate);
+        estimate
+    }
+}
+
+/// Rebuilds an estimator from a rendered pack's own `D <id> <value>` lines, so the shrink loop's
+/// token estimates stay grounded in this pack's actual vocabulary.
+pub(super) fn estimator_for_pack(pack: &str) -> ByteMergeTokenEstimator {
+    let dict_values: Vec<String> = pack
+        .lines()
+        .filter_map(|line| line.strip_prefix("D "))
+        .filter_map(|rest| rest.split_once(' ').map(|(_, value)| value.to_string()))
+        .collect();
+    ByteMergeTokenEstimator::build(&dict_values)
+}
+
+/// Estimates the total token cost of a rendered pack, caching dictionary-value lookups by id.
+pub(super) fn estimate_pack_tokens(pack: &str, estimator: &dyn TokenEstimator) -> usize {
+    pack.lines()
+        .map(|line| {
+            let Some(rest) = line.strip_prefix("D ") else {
+                return estimator.estimate_line(line);
+            };
+            let Some((id_token, value)) = rest.split_once(' ') else {
+                return estimator.estimate_line(line);
+            };
+            let dict_id = id_token.strip_prefix('d').and_then(|s| s.parse().ok()).unwrap_or(0);
+            estimator.estimate_dict_value(dict_id, value)
+        })
+        .sum()
+}
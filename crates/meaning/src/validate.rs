@@ -0,0 +1,189 @@
+//! Validates a rendered CP pack's evidence invariants, turning what used to be test-only
+//! assertions and `CognitivePack::dict_id` panics into an inspectable, recoverable result.
+//!
+//! [`validate_pack`] reports every broken reference without mutating anything; [`repair_pack`]
+//! is the paired Fixer: it deterministically deletes what it can't keep consistent, re-runs the
+//! existing prune/cleanup passes, and guarantees a trailing `NBA` line survives.
+
+use std::collections::HashSet;
+
+use crate::common::{prune_unused_dict_lines, prune_unused_ev_lines, remove_empty_sections};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: &'static str,
+    /// 1-indexed line number within the pack, matching editor/diagnostic conventions.
+    pub line: usize,
+    pub message: String,
+}
+
+fn line_dict_id(line: &str) -> Option<&str> {
+    line.strip_prefix("D ")
+        .and_then(|rest| rest.split_whitespace().next())
+}
+
+fn line_ev_id(line: &str) -> Option<&str> {
+    line.strip_prefix("EV ")
+        .and_then(|rest| rest.split_whitespace().next())
+}
+
+/// Checks a rendered pack's evidence invariants: every claim's `ev=` must resolve to a declared
+/// `EV` line, every `EV`'s `file=dN` must resolve to a declared `D` line, every other `dN`
+/// reference must resolve to a declared `D` line, `S` sections must carry at least one data
+/// line, and the trailing `NBA evidence_fetch` (if present) must not point at a deleted `EV`.
+pub fn validate_pack(pack: &str) -> Vec<Diagnostic> {
+    let lines: Vec<&str> = pack.lines().collect();
+
+    let dict_ids: HashSet<&str> = lines.iter().filter_map(|line| line_dict_id(line)).collect();
+    let ev_ids: HashSet<&str> = lines.iter().filter_map(|line| line_ev_id(line)).collect();
+
+    let mut diagnostics = Vec::new();
+
+    for (idx, line) in lines.iter().enumerate() {
+        let line_no = idx + 1;
+
+        if let Some(file_id) = line
+            .strip_prefix("EV ")
+            .and_then(|rest| rest.split_whitespace().find_map(|tok| tok.strip_prefix("file=")))
+        {
+            if !dict_ids.contains(file_id) {
+                diagnostics.push(Diagnostic {
+                    severity: Severity::Error,
+                    code: "EV_DICT_MISSING",
+                    line: line_no,
+                    message: format!("EV line references missing dict id {file_id}"),
+                });
+            }
+            continue;
+        }
+
+        if line.starts_with("D ") {
+            continue;
+        }
+
+        if let Some(ev_id) = line.split_whitespace().find_map(|tok| tok.strip_prefix("ev=")) {
+            if !ev_ids.contains(ev_id) {
+                let code = if line.starts_with("NBA ") {
+                    "NBA_EV_MISSING"
+                } else {
+                    "CLAIM_EV_MISSING"
+                };
+                let tag = line.split_whitespace().next().unwrap_or("line");
+                diagnostics.push(Diagnostic {
+                    severity: Severity::Error,
+                    code,
+                    line: line_no,
+                    message: format!("{tag} references missing ev={ev_id}"),
+                });
+            }
+        }
+
+        for tok in line.split_whitespace().skip(1) {
+            let Some((key, value)) = tok.split_once('=') else {
+                continue;
+            };
+            if key == "ev" || !value.starts_with('d') || !value[1..].chars().all(|c| c.is_ascii_digit()) {
+                continue;
+            }
+            if !dict_ids.contains(value) {
+                diagnostics.push(Diagnostic {
+                    severity: Severity::Warning,
+                    code: "DANGLING_DICT_REF",
+                    line: line_no,
+                    message: format!("{key}={value} references a dict id that was never declared"),
+                });
+            }
+        }
+    }
+
+    let mut idx = 0usize;
+    while idx < lines.len() {
+        if !lines[idx].starts_with("S ") {
+            idx += 1;
+            continue;
+        }
+        let start = idx;
+        let mut end = start + 1;
+        while end < lines.len() && !lines[end].starts_with("S ") && !lines[end].starts_with("NBA ") {
+            end += 1;
+        }
+        if end == start + 1 {
+            diagnostics.push(Diagnostic {
+                severity: Severity::Warning,
+                code: "EMPTY_SECTION",
+                line: start + 1,
+                message: format!("section {:?} has no data lines", lines[start]),
+            });
+        }
+        idx = end;
+    }
+
+    diagnostics
+}
+
+/// Repairs a pack in place: deletes claims/EVs it can no longer keep consistent, re-runs the
+/// shrink loop's existing prune passes until they stop changing anything, and re-adds a
+/// fallback `NBA map` if the trailing next-action line was lost along the way. Returns the
+/// diagnostics from before the repair that no longer apply afterward.
+pub fn repair_pack(pack: &mut String) -> Vec<Diagnostic> {
+    let before = validate_pack(pack);
+    if before.is_empty() {
+        return before;
+    }
+
+    let mut lines: Vec<String> = pack
+        .lines()
+        .map(|line| line.trim_end_matches('\r').to_string())
+        .collect();
+
+    let ev_ids: HashSet<String> = lines
+        .iter()
+        .filter_map(|line| line_ev_id(line).map(str::to_string))
+        .collect();
+    lines.retain(|line| {
+        if line.starts_with("EV ") || line.starts_with("D ") {
+            return true;
+        }
+        match line.split_whitespace().find_map(|tok| tok.strip_prefix("ev=")) {
+            Some(ev_id) => ev_ids.contains(ev_id),
+            None => true,
+        }
+    });
+
+    let dict_ids: HashSet<String> = lines
+        .iter()
+        .filter_map(|line| line_dict_id(line).map(str::to_string))
+        .collect();
+    lines.retain(|line| {
+        line.strip_prefix("EV ")
+            .and_then(|rest| rest.split_whitespace().find_map(|tok| tok.strip_prefix("file=")))
+            .map(|file_id| dict_ids.contains(file_id))
+            .unwrap_or(true)
+    });
+
+    loop {
+        let mut changed = prune_unused_ev_lines(&mut lines);
+        changed |= prune_unused_dict_lines(&mut lines);
+        changed |= remove_empty_sections(&mut lines);
+        if !changed {
+            break;
+        }
+    }
+
+    if !lines.last().is_some_and(|line| line.starts_with("NBA ")) {
+        lines.retain(|line| !line.starts_with("NBA "));
+        lines.push("NBA map".to_string());
+    }
+
+    *pack = lines.join("\n") + "\n";
+
+    let after = validate_pack(pack);
+    before.into_iter().filter(|d| !after.contains(d)).collect()
+}
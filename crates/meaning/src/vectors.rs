@@ -0,0 +1,175 @@
+//! Golden test-vector corpus for the brittle string heuristics in `common` and `pack`.
+//!
+//! Following the approach crypto suites use for known-answer tests, contributors author
+//! cases by hand as small, readable JSON files under `fixtures/vectors/cases/` (one file
+//! per heuristic). A converter flattens those files into a single `fixtures/vectors/corpus.json`
+//! fixture, and the test suite below replays that flattened fixture exactly against the real
+//! functions. Pinning to the flattened form (rather than re-reading the human files at test
+//! time) keeps the replayed corpus stable even if the human-authored layout changes.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::common::{
+    content_mentions_proto, contract_kind, is_broker_config_candidate, is_code_file_candidate,
+};
+use crate::pack::find_first_commandish;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoolFileCase {
+    pub name: String,
+    pub file: String,
+    pub expect: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentProtoCase {
+    pub name: String,
+    pub content: String,
+    pub proto: String,
+    pub expect: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractKindCase {
+    pub name: String,
+    pub file: String,
+    pub expect: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandishCase {
+    pub name: String,
+    pub lines: Vec<String>,
+    pub expect: Option<usize>,
+}
+
+/// The flattened corpus: every heuristic's cases under one serialized fixture.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TestVectorCorpus {
+    #[serde(default)]
+    pub is_broker_config_candidate: Vec<BoolFileCase>,
+    #[serde(default)]
+    pub is_code_file_candidate: Vec<BoolFileCase>,
+    #[serde(default)]
+    pub content_mentions_proto: Vec<ContentProtoCase>,
+    #[serde(default)]
+    pub contract_kind: Vec<ContractKindCase>,
+    #[serde(default)]
+    pub find_first_commandish: Vec<CommandishCase>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawCaseFile<T> {
+    function: String,
+    cases: Vec<T>,
+}
+
+fn read_case_file<T: for<'de> Deserialize<'de>>(
+    cases_dir: &Path,
+    expected_function: &str,
+) -> Result<Vec<T>> {
+    let path = cases_dir.join(format!("{expected_function}.json"));
+    let raw = std::fs::read_to_string(&path)
+        .with_context(|| format!("read vector case file {}", path.display()))?;
+    let parsed: RawCaseFile<T> = serde_json::from_str(&raw)
+        .with_context(|| format!("parse vector case file {}", path.display()))?;
+    anyhow::ensure!(
+        parsed.function == expected_function,
+        "{}: function field {:?} does not match filename {expected_function:?}",
+        path.display(),
+        parsed.function,
+    );
+    Ok(parsed.cases)
+}
+
+/// Reads the human-authored case files in `cases_dir` and flattens them into one corpus.
+pub fn convert_vectors(cases_dir: &Path) -> Result<TestVectorCorpus> {
+    Ok(TestVectorCorpus {
+        is_broker_config_candidate: read_case_file(cases_dir, "is_broker_config_candidate")?,
+        is_code_file_candidate: read_case_file(cases_dir, "is_code_file_candidate")?,
+        content_mentions_proto: read_case_file(cases_dir, "content_mentions_proto")?,
+        contract_kind: read_case_file(cases_dir, "contract_kind")?,
+        find_first_commandish: read_case_file(cases_dir, "find_first_commandish")?,
+    })
+}
+
+/// Serializes a flattened corpus to the compact fixture the test suite replays.
+pub fn write_flattened(corpus: &TestVectorCorpus, out_path: &Path) -> Result<()> {
+    let json = serde_json::to_string_pretty(corpus).context("serialize flattened corpus")?;
+    std::fs::write(out_path, json)
+        .with_context(|| format!("write flattened corpus to {}", out_path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FLATTENED: &str = include_str!("../fixtures/vectors/corpus.json");
+
+    fn corpus() -> TestVectorCorpus {
+        serde_json::from_str(FLATTENED).expect("flattened vector corpus must parse")
+    }
+
+    #[test]
+    fn replays_is_broker_config_candidate_vectors() {
+        for case in corpus().is_broker_config_candidate {
+            let lc = case.file.to_ascii_lowercase();
+            assert_eq!(
+                is_broker_config_candidate(&lc),
+                case.expect,
+                "case: {}",
+                case.name
+            );
+        }
+    }
+
+    #[test]
+    fn replays_is_code_file_candidate_vectors() {
+        for case in corpus().is_code_file_candidate {
+            let lc = case.file.to_ascii_lowercase();
+            assert_eq!(
+                is_code_file_candidate(&lc),
+                case.expect,
+                "case: {}",
+                case.name
+            );
+        }
+    }
+
+    #[test]
+    fn replays_content_mentions_proto_vectors() {
+        for case in corpus().content_mentions_proto {
+            let content_lc = case.content.to_ascii_lowercase();
+            let proto_lc = case.proto.to_ascii_lowercase();
+            assert_eq!(
+                content_mentions_proto(&content_lc, &proto_lc),
+                case.expect,
+                "case: {}",
+                case.name
+            );
+        }
+    }
+
+    #[test]
+    fn replays_contract_kind_vectors() {
+        for case in corpus().contract_kind {
+            assert_eq!(contract_kind(&case.file), case.expect, "case: {}", case.name);
+        }
+    }
+
+    #[test]
+    fn replays_find_first_commandish_vectors() {
+        for case in corpus().find_first_commandish {
+            let lines_lc: Vec<String> =
+                case.lines.iter().map(|l| l.to_ascii_lowercase()).collect();
+            assert_eq!(
+                find_first_commandish(&lines_lc),
+                case.expect,
+                "case: {}",
+                case.name
+            );
+        }
+    }
+}
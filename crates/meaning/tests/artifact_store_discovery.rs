@@ -15,6 +15,11 @@ async fn meaning_pack_discovers_artifact_store_file_under_ignored_data_scope() -
         map_depth: None,
         map_limit: None,
         max_chars: Some(4_000),
+        max_tokens: None,
+        cache_path: None,
+        rules: Vec::new(),
+        semantic: None,
+        semantic_weight: None,
     };
     let result = meaning_pack(root, &root.to_string_lossy(), &request).await?;
 
@@ -10,6 +10,7 @@ pub const CAPABILITIES_SCHEMA_VERSION: u32 = 1;
 #[serde(rename_all = "snake_case")]
 pub enum BudgetTruncation {
     MaxChars,
+    MaxTokens,
     MaxLines,
     MaxMatches,
     MaxHunks,
@@ -135,3 +136,140 @@ pub fn enforce_max_chars<T: Serialize>(
 pub fn serialize_json<T: Serialize>(value: &T) -> Result<String> {
     serde_json::to_string(value).map_err(Into::into)
 }
+
+/// Counts (an estimate of) how many LLM tokens `text` would cost. Pluggable so a full BPE
+/// vocabulary can be swapped in later without touching callers; see [`HeuristicTokenCounter`] for
+/// the default, dependency-free implementation.
+pub trait TokenCounter {
+    fn count(&self, text: &str) -> usize;
+}
+
+/// Default [`TokenCounter`]: each run of alphanumeric/`_` characters and each other
+/// non-whitespace character counts as roughly one token. This is deliberately simple (no real BPE
+/// vocabulary, unlike e.g. a cl100k table) so it has no model/dictionary dependency; it is meant
+/// for tools that need *a* token-shaped budget knob, not an exact count.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HeuristicTokenCounter;
+
+impl TokenCounter for HeuristicTokenCounter {
+    fn count(&self, text: &str) -> usize {
+        let mut count = 0usize;
+        let mut in_word = false;
+        for ch in text.chars() {
+            if ch.is_alphanumeric() || ch == '_' {
+                if !in_word {
+                    count += 1;
+                    in_word = true;
+                }
+            } else {
+                in_word = false;
+                if !ch.is_whitespace() {
+                    count += 1;
+                }
+            }
+        }
+        count.max(1)
+    }
+}
+
+/// Convenience wrapper around [`HeuristicTokenCounter`] for call sites that don't need a
+/// pluggable counter.
+pub fn estimate_tokens_heuristic(text: &str) -> usize {
+    HeuristicTokenCounter.count(text)
+}
+
+/// Sibling of a `truncate_to_chars` helper (several tools have their own char-boundary-safe copy),
+/// but shrinks `input` until `counter` reports it fits in `max_tokens` instead of a char budget.
+/// Binary-searches over char boundaries so the result is always a valid UTF-8 prefix of `input`,
+/// without needing the counter to support encode/decode.
+pub fn truncate_to_tokens(input: &str, max_tokens: usize, counter: &dyn TokenCounter) -> String {
+    let total_chars = input.chars().count();
+    if counter.count(input) <= max_tokens || total_chars <= 1 {
+        return input.to_string();
+    }
+
+    let char_at = |n: usize| -> usize {
+        input
+            .char_indices()
+            .nth(n)
+            .map(|(idx, _)| idx)
+            .unwrap_or(input.len())
+    };
+
+    let (mut lo, mut hi) = (0usize, total_chars);
+    while lo < hi {
+        let mid = lo + (hi - lo + 1) / 2;
+        let candidate = &input[..char_at(mid)];
+        if counter.count(candidate) <= max_tokens {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    input[..char_at(lo)].to_string()
+}
+
+/// Mirrors `enforce_max_chars`, but drives the shrink loop off a [`TokenCounter`] instead of a
+/// char count: characters are a poor proxy for LLM tokens.
+pub fn enforce_max_tokens<T: Serialize>(
+    value: &mut T,
+    max_tokens: usize,
+    mut set_used: impl FnMut(&mut T, usize),
+    mut on_truncate: impl FnMut(&mut T),
+    mut shrink: impl FnMut(&mut T) -> bool,
+) -> Result<usize> {
+    enforce_max_tokens_with(
+        value,
+        max_tokens,
+        &HeuristicTokenCounter,
+        set_used,
+        on_truncate,
+        shrink,
+    )
+}
+
+/// Same as [`enforce_max_tokens`], but takes an explicit [`TokenCounter`] instead of always using
+/// [`HeuristicTokenCounter`].
+pub fn enforce_max_tokens_with<T: Serialize>(
+    value: &mut T,
+    max_tokens: usize,
+    counter: &dyn TokenCounter,
+    mut set_used: impl FnMut(&mut T, usize),
+    mut on_truncate: impl FnMut(&mut T),
+    mut shrink: impl FnMut(&mut T) -> bool,
+) -> Result<usize> {
+    loop {
+        let raw = serde_json::to_string(value)?;
+        let tokens = counter.count(&raw);
+        set_used(value, tokens);
+        if tokens <= max_tokens {
+            return Ok(tokens);
+        }
+        on_truncate(value);
+        if !shrink(value) {
+            anyhow::bail!("budget exceeded (used_tokens={tokens}, max_tokens={max_tokens})");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heuristic_counter_counts_words_and_punctuation_as_tokens() {
+        let counter = HeuristicTokenCounter;
+        assert_eq!(counter.count("hello world"), 2);
+        assert_eq!(counter.count("a, b."), 4);
+    }
+
+    #[test]
+    fn truncate_to_tokens_keeps_a_valid_utf8_prefix_within_budget() {
+        let counter = HeuristicTokenCounter;
+        let input = "αβγ δεζ ηθι".repeat(20);
+        let truncated = truncate_to_tokens(&input, 5, &counter);
+        assert!(counter.count(&truncated) <= 5);
+        assert!(input.starts_with(&truncated));
+        assert!(truncated.is_char_boundary(truncated.len()));
+    }
+}
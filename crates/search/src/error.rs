@@ -16,3 +16,16 @@ pub enum SearchError {
     #[error("{0}")]
     Other(String),
 }
+
+impl SearchError {
+    /// True for failures worth retrying (transient vector-store/graph contention), false for
+    /// failures that stem from the request itself (`EmptyQuery`) or are otherwise not expected to
+    /// resolve on their own.
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            Self::VectorStoreError(err) => err.is_retriable(),
+            Self::GraphError(err) => err.is_retriable(),
+            Self::EmptyQuery | Self::Other(_) => false,
+        }
+    }
+}
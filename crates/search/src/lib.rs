@@ -16,13 +16,17 @@ mod error;
 mod fusion;
 mod fuzzy;
 mod hybrid;
+mod line_index;
 mod query_expansion;
+mod ranking;
 
 pub use error::{Result, SearchError};
 pub use fusion::{AstBooster, RRFFusion};
 pub use fuzzy::FuzzySearch;
 pub use hybrid::HybridSearch;
+pub use line_index::{LineIndex, OffsetEncoding};
 pub use query_expansion::QueryExpander;
+pub use ranking::{apply_ranking_rules, RankingBreakdown, DEFAULT_RANKING_RULES};
 
 // Re-export for convenience
 pub use context_vector_store::SearchResult;
@@ -0,0 +1,197 @@
+//! Reusable line/offset conversion over chunk or file content.
+//!
+//! `CodeChunk`/`SearchResult` only carry 1-indexed line numbers, which is all a human reading the
+//! content needs but is lossy for editors and LSP clients that address text by byte, Unicode
+//! scalar (char), or UTF-16 code-unit offsets (the encoding the LSP spec and VS Code use).
+//! [`LineIndex`] precomputes line-start byte offsets once so converting between `(line, column)`,
+//! byte offset, char offset, and UTF-16 offset only needs a lookup plus a scan of the matched
+//! span, handling multi-byte UTF-8 and CRLF line endings correctly.
+
+use std::fmt;
+
+/// Offset encoding requested by a caller, selected via `SearchRequest::offset_encoding`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OffsetEncoding {
+    /// Raw UTF-8 byte offsets.
+    Utf8,
+    /// UTF-16 code-unit offsets, as used by the LSP spec and VS Code.
+    Utf16,
+    /// Unicode scalar value (`char`) offsets.
+    Char,
+}
+
+impl OffsetEncoding {
+    /// Parses the `"utf8" | "utf16" | "char"` request values (case-insensitive, `-` optional).
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "utf8" | "utf-8" => Some(Self::Utf8),
+            "utf16" | "utf-16" => Some(Self::Utf16),
+            "char" | "chars" => Some(Self::Char),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Utf8 => "utf8",
+            Self::Utf16 => "utf16",
+            Self::Char => "char",
+        }
+    }
+}
+
+impl fmt::Display for OffsetEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Precomputed line-start byte offsets over some content, for converting between `(line,
+/// column)`, byte offset, char offset, and UTF-16 offset.
+pub struct LineIndex<'a> {
+    content: &'a str,
+    /// Byte offset where each 0-indexed line starts; always has at least one entry (`0`).
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    #[must_use]
+    pub fn new(content: &'a str) -> Self {
+        let mut line_starts = vec![0];
+        for (idx, byte) in content.bytes().enumerate() {
+            if byte == b'\n' {
+                line_starts.push(idx + 1);
+            }
+        }
+        Self {
+            content,
+            line_starts,
+        }
+    }
+
+    /// Byte offset for a 0-indexed `(line, column)` pair, where `column` is counted in UTF-8
+    /// bytes from the start of the line. A `\r` immediately before a `\n` is just another byte on
+    /// the preceding line, so CRLF content needs no special-casing here.
+    #[must_use]
+    pub fn byte_offset(&self, line: usize, column: usize) -> Option<usize> {
+        let line_start = *self.line_starts.get(line)?;
+        let offset = line_start + column;
+        (offset <= self.content.len()).then_some(offset)
+    }
+
+    /// Converts a byte offset into a char (Unicode scalar value) offset by counting the chars
+    /// before it. Clamps to content length rather than panicking on out-of-range input.
+    #[must_use]
+    pub fn to_char_offset(&self, byte_offset: usize) -> usize {
+        let clamped = byte_offset.min(self.content.len());
+        self.content[..clamped].chars().count()
+    }
+
+    /// Converts a byte offset into a UTF-16 code-unit offset by summing each preceding char's
+    /// UTF-16 length -- the encoding LSP/editor clients need for highlight ranges.
+    #[must_use]
+    pub fn to_utf16_offset(&self, byte_offset: usize) -> usize {
+        let clamped = byte_offset.min(self.content.len());
+        self.content[..clamped].chars().map(char::len_utf16).sum()
+    }
+
+    /// Converts a byte offset into the requested encoding; `Utf8` passes the (clamped) byte
+    /// offset through unchanged.
+    #[must_use]
+    pub fn convert(&self, byte_offset: usize, encoding: OffsetEncoding) -> usize {
+        match encoding {
+            OffsetEncoding::Utf8 => byte_offset.min(self.content.len()),
+            OffsetEncoding::Char => self.to_char_offset(byte_offset),
+            OffsetEncoding::Utf16 => self.to_utf16_offset(byte_offset),
+        }
+    }
+
+    /// Byte offset span covering 1-indexed, inclusive lines `[start_line, end_line]` -- the
+    /// convention `CodeChunk`/`SearchResult` already use -- trimmed of the line-ending bytes at
+    /// the end so the span doesn't include a trailing newline.
+    #[must_use]
+    pub fn line_range_byte_offsets(
+        &self,
+        start_line: usize,
+        end_line: usize,
+    ) -> Option<(usize, usize)> {
+        let start = *self.line_starts.get(start_line.checked_sub(1)?)?;
+        let raw_end = self
+            .line_starts
+            .get(end_line)
+            .copied()
+            .unwrap_or(self.content.len());
+        let mut end = raw_end.min(self.content.len());
+        while end > start && matches!(self.content.as_bytes().get(end - 1), Some(b'\n' | b'\r')) {
+            end -= 1;
+        }
+        Some((start, end))
+    }
+
+    /// Converts the byte-offset span for 1-indexed, inclusive lines `[start_line, end_line]` into
+    /// the requested encoding.
+    #[must_use]
+    pub fn line_range(
+        &self,
+        start_line: usize,
+        end_line: usize,
+        encoding: OffsetEncoding,
+    ) -> Option<(usize, usize)> {
+        let (start, end) = self.line_range_byte_offsets(start_line, end_line)?;
+        Some((self.convert(start, encoding), self.convert(end, encoding)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_known_encodings_case_insensitively() {
+        assert_eq!(OffsetEncoding::parse("UTF8"), Some(OffsetEncoding::Utf8));
+        assert_eq!(OffsetEncoding::parse("utf-16"), Some(OffsetEncoding::Utf16));
+        assert_eq!(OffsetEncoding::parse("Char"), Some(OffsetEncoding::Char));
+        assert_eq!(OffsetEncoding::parse("ebcdic"), None);
+    }
+
+    #[test]
+    fn byte_offset_handles_multiple_lines() {
+        let index = LineIndex::new("fn a() {}\nfn b() {}\n");
+        assert_eq!(index.byte_offset(0, 0), Some(0));
+        assert_eq!(index.byte_offset(1, 3), Some(13));
+        assert_eq!(index.byte_offset(5, 0), None);
+    }
+
+    #[test]
+    fn multi_byte_chars_diverge_between_encodings() {
+        // "héllo" -- 'é' is 2 UTF-8 bytes, 1 char, 1 UTF-16 unit.
+        let index = LineIndex::new("héllo world");
+        let byte_offset = "héllo".len();
+        assert_eq!(index.to_char_offset(byte_offset), 5);
+        assert_eq!(index.to_utf16_offset(byte_offset), 5);
+        assert_eq!(
+            index.convert(byte_offset, OffsetEncoding::Utf8),
+            byte_offset
+        );
+    }
+
+    #[test]
+    fn astral_chars_take_two_utf16_units_but_one_char() {
+        // U+1F600 GRINNING FACE is 4 UTF-8 bytes, 1 char, 2 UTF-16 code units (a surrogate pair).
+        let index = LineIndex::new("😀!");
+        let byte_offset = "😀".len();
+        assert_eq!(index.to_char_offset(byte_offset), 1);
+        assert_eq!(index.to_utf16_offset(byte_offset), 2);
+    }
+
+    #[test]
+    fn line_range_trims_crlf_and_lf_line_endings() {
+        let index = LineIndex::new("one\r\ntwo\r\nthree");
+        assert_eq!(index.line_range_byte_offsets(1, 1), Some((0, 3)));
+        assert_eq!(index.line_range_byte_offsets(2, 2), Some((5, 8)));
+        assert_eq!(index.line_range_byte_offsets(1, 3), Some((0, 15)));
+        assert_eq!(index.line_range_byte_offsets(4, 4), None);
+    }
+}
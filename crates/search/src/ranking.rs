@@ -0,0 +1,254 @@
+//! Configurable lexicographic ranking pipeline for the `search` tool.
+//!
+//! Complements the single fused `score` [`HybridSearch`](crate::HybridSearch) already produces
+//! with an ordered list of tie-breaking rules, the way a full-text search engine's ranking
+//! pipeline works: each rule only decides between results the rules before it couldn't separate.
+//! `semantic` (the existing hybrid score) is the default catch-all, so a pipeline that keeps it
+//! last degrades to today's single-score ordering whenever the sharper rules tie.
+
+use context_vector_store::SearchResult;
+
+/// Rule names understood by [`apply_ranking_rules`], in the order `search` applies by default.
+pub const DEFAULT_RANKING_RULES: &[&str] =
+    &["exactness", "words", "proximity", "attribute", "semantic"];
+
+/// Per-rule contribution for one result, returned alongside the reordered results so callers can
+/// see why a result ranked where it did rather than just trusting an opaque fused score.
+#[derive(Debug, Clone, Copy, PartialEq, Default, serde::Serialize, schemars::JsonSchema)]
+pub struct RankingBreakdown {
+    /// 1.0 for a whole-identifier match on the chunk's symbol, 0.5 for a partial one, else 0.
+    pub exactness: f32,
+    /// Fraction of query tokens present anywhere in the chunk content.
+    pub words: f32,
+    /// How tightly the matched query tokens cluster together in the content (1.0 = adjacent).
+    pub proximity: f32,
+    /// Match location weighted by importance: symbol name beats docs beats body.
+    pub attribute: f32,
+    /// The existing hybrid (semantic + fuzzy + RRF + AST boost) fused score.
+    pub semantic: f32,
+}
+
+impl RankingBreakdown {
+    fn compute(tokens: &[String], result: &SearchResult) -> Self {
+        Self {
+            exactness: exactness_score(tokens, result),
+            words: words_score(tokens, result),
+            proximity: proximity_score(tokens, result),
+            attribute: attribute_score(tokens, result),
+            semantic: result.score,
+        }
+    }
+
+    fn value(&self, rule: &str) -> Option<f32> {
+        match rule {
+            "exactness" => Some(self.exactness),
+            "words" => Some(self.words),
+            "proximity" => Some(self.proximity),
+            "attribute" => Some(self.attribute),
+            "semantic" => Some(self.semantic),
+            _ => None,
+        }
+    }
+}
+
+fn query_tokens(query: &str) -> Vec<String> {
+    query
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|token| !token.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn exactness_score(tokens: &[String], result: &SearchResult) -> f32 {
+    let Some(symbol) = result.chunk.metadata.symbol_name.as_deref() else {
+        return 0.0;
+    };
+    if tokens.is_empty() {
+        return 0.0;
+    }
+    let symbol_lower = symbol.to_lowercase();
+    let joined = tokens.join("_");
+    if symbol_lower == joined {
+        1.0
+    } else if symbol_lower.contains(&joined) {
+        0.5
+    } else {
+        0.0
+    }
+}
+
+fn words_score(tokens: &[String], result: &SearchResult) -> f32 {
+    if tokens.is_empty() {
+        return 0.0;
+    }
+    let content_lower = result.chunk.content.to_lowercase();
+    let hits = tokens
+        .iter()
+        .filter(|token| content_lower.contains(token.as_str()))
+        .count();
+    hits as f32 / tokens.len() as f32
+}
+
+/// Tighter clusters of matched tokens score higher; chunks matching none or only one token can't
+/// be judged on proximity, so they fall back to a neutral score rather than being penalized twice
+/// on top of a low `words` score.
+fn proximity_score(tokens: &[String], result: &SearchResult) -> f32 {
+    if tokens.len() < 2 {
+        return 1.0;
+    }
+    let content_lower = result.chunk.content.to_lowercase();
+    let mut positions: Vec<usize> = tokens
+        .iter()
+        .filter_map(|token| content_lower.find(token.as_str()))
+        .collect();
+    if positions.len() < 2 {
+        return 0.0;
+    }
+    positions.sort_unstable();
+    let span = (positions[positions.len() - 1] - positions[0]) as f32;
+    1.0 / (1.0 + span / 100.0)
+}
+
+fn attribute_score(tokens: &[String], result: &SearchResult) -> f32 {
+    if tokens.is_empty() {
+        return 0.0;
+    }
+    let hit_fraction = |haystack: &str| -> f32 {
+        let haystack_lower = haystack.to_lowercase();
+        let hits = tokens
+            .iter()
+            .filter(|token| haystack_lower.contains(token.as_str()))
+            .count();
+        hits as f32 / tokens.len() as f32
+    };
+
+    let symbol_hit = hit_fraction(result.chunk.metadata.symbol_name.as_deref().unwrap_or(""));
+    let doc_hit = hit_fraction(result.chunk.metadata.documentation.as_deref().unwrap_or(""));
+    let body_hit = hit_fraction(&result.chunk.content);
+
+    // Symbol name matches outrank documentation matches, which outrank a plain body match.
+    (symbol_hit * 1.0 + doc_hit * 0.5 + body_hit * 0.1) / 1.6
+}
+
+fn resolve_rules(rules: &[String]) -> Vec<&'static str> {
+    let recognized: Vec<&'static str> = rules
+        .iter()
+        .filter_map(|requested| {
+            DEFAULT_RANKING_RULES
+                .iter()
+                .find(|known| known.eq_ignore_ascii_case(requested))
+                .copied()
+        })
+        .collect();
+    if recognized.is_empty() {
+        DEFAULT_RANKING_RULES.to_vec()
+    } else {
+        recognized
+    }
+}
+
+/// Reorders `results` by applying `rules` in order, each rule only breaking ties left by the
+/// rules before it. Unrecognized rule names are skipped rather than rejected -- and if every
+/// requested name is unrecognized, falls back to [`DEFAULT_RANKING_RULES`] -- so a caller on a
+/// different client version never turns a search into a hard error. The sort is stable, so
+/// results tied on every applied rule keep the order they arrived in (today's fused-score order).
+#[must_use]
+pub fn apply_ranking_rules(
+    query: &str,
+    results: Vec<SearchResult>,
+    rules: &[String],
+) -> Vec<(SearchResult, RankingBreakdown)> {
+    let tokens = query_tokens(query);
+    let rules_to_apply = resolve_rules(rules);
+
+    let mut scored: Vec<(SearchResult, RankingBreakdown)> = results
+        .into_iter()
+        .map(|result| {
+            let breakdown = RankingBreakdown::compute(&tokens, &result);
+            (result, breakdown)
+        })
+        .collect();
+
+    scored.sort_by(|(_, a), (_, b)| {
+        for rule in &rules_to_apply {
+            let (Some(value_a), Some(value_b)) = (a.value(rule), b.value(rule)) else {
+                continue;
+            };
+            match value_b
+                .partial_cmp(&value_a)
+                .unwrap_or(std::cmp::Ordering::Equal)
+            {
+                std::cmp::Ordering::Equal => continue,
+                ordering => return ordering,
+            }
+        }
+        std::cmp::Ordering::Equal
+    });
+
+    scored
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use context_code_chunker::{ChunkMetadata, CodeChunk};
+
+    fn result(symbol: &str, content: &str, score: f32) -> SearchResult {
+        SearchResult {
+            chunk: CodeChunk::new(
+                "test.rs".to_string(),
+                1,
+                10,
+                content.to_string(),
+                ChunkMetadata::default().symbol_name(symbol),
+            ),
+            score,
+            id: format!("test.rs:1:10:{symbol}"),
+        }
+    }
+
+    #[test]
+    fn exactness_beats_semantic_when_listed_first() {
+        let results = vec![
+            result("process_data", "fn process_data() {}", 0.4),
+            result("handle_process", "fn handle_process() {}", 0.9),
+        ];
+
+        let ranked = apply_ranking_rules(
+            "process_data",
+            results,
+            &["exactness".to_string(), "semantic".to_string()],
+        );
+
+        assert_eq!(
+            ranked[0].0.chunk.metadata.symbol_name.as_deref(),
+            Some("process_data")
+        );
+        assert_eq!(ranked[0].1.exactness, 1.0);
+    }
+
+    #[test]
+    fn falls_back_to_semantic_order_without_exactness_rule() {
+        let results = vec![
+            result("process_data", "fn process_data() {}", 0.4),
+            result("handle_process", "fn handle_process() {}", 0.9),
+        ];
+
+        let ranked = apply_ranking_rules("process_data", results, &["semantic".to_string()]);
+
+        assert_eq!(
+            ranked[0].0.chunk.metadata.symbol_name.as_deref(),
+            Some("handle_process")
+        );
+    }
+
+    #[test]
+    fn unknown_rules_fall_back_to_defaults() {
+        let results = vec![result("a", "a", 0.1), result("b", "b", 0.9)];
+
+        let ranked = apply_ranking_rules("a", results, &["made_up_rule".to_string()]);
+
+        // DEFAULT_RANKING_RULES ends with `semantic`, so the higher-score chunk still wins.
+        assert_eq!(ranked[0].0.chunk.metadata.symbol_name.as_deref(), Some("b"));
+    }
+}
@@ -25,3 +25,12 @@ pub enum VectorStoreError {
     #[error("{0}")]
     Other(String),
 }
+
+impl VectorStoreError {
+    /// True for failures that are plausibly transient (e.g. a momentarily locked/contended index,
+    /// a one-off I/O hiccup) and therefore worth retrying; false for errors that will recur
+    /// deterministically no matter how many times the same call is retried.
+    pub fn is_retriable(&self) -> bool {
+        matches!(self, Self::IndexError(_) | Self::IoError(_))
+    }
+}